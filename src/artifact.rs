@@ -0,0 +1,213 @@
+//! Recognizing build outputs, fetched dependencies and caches by their paths.
+
+use std::collections::BTreeMap;
+use std::path::{Component, Path};
+use std::sync::OnceLock;
+
+use crate::name;
+
+/// Why a path looks like something a build produced rather than a source.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ArtifactKind {
+    /// Inside a build output directory, like `target/` or `dist/`.
+    Output,
+    /// Inside a directory of fetched dependencies, like `node_modules/` or `.venv/`.
+    Dependencies,
+    /// Inside a tool's cache, like `__pycache__/` or `.gradle/`.
+    Cache,
+    /// A compiled file, like `.o`, `.obj`, `.class` or `.pyc`.
+    Compiled,
+}
+
+/// The directories [`ArtifactRules::default`] recognizes, also in
+/// [`NameClassifier::build_artifacts`](crate::NameClassifier::build_artifacts).
+pub(crate) const DIRECTORIES: &[(&[&str], ArtifactKind)] = &[
+    (
+        &[
+            "target",
+            "build",
+            "dist",
+            "obj",
+            "cmake-build-debug",
+            "cmake-build-release",
+            "_build",
+            ".next",
+        ],
+        ArtifactKind::Output,
+    ),
+    (
+        &[
+            "node_modules",
+            "bower_components",
+            ".venv",
+            "venv",
+            ".bundle",
+            "Pods",
+            ".yarn",
+        ],
+        ArtifactKind::Dependencies,
+    ),
+    (
+        &[
+            "__pycache__",
+            ".pytest_cache",
+            ".mypy_cache",
+            ".ruff_cache",
+            ".gradle",
+            ".parcel-cache",
+            ".cache",
+            ".tox",
+        ],
+        ArtifactKind::Cache,
+    ),
+];
+
+pub(crate) const COMPILED_EXTENSIONS: &[&str] = &[
+    "o", "obj", "a", "lib", "class", "pyc", "pyo", "rlib", "rmeta", "pdb", "ilk", "gch", "pch",
+];
+
+/// The rules [`PathComponentExt::artifact_kind`](crate::PathComponentExt::artifact_kind) recognizes
+/// artifacts by, to start from and change.
+///
+/// Directory names match any component exactly, the outermost match deciding, so
+/// `node_modules/x/dist` is [`ArtifactKind::Dependencies`]. Only if none matches are the file name's
+/// extensions tried, ignoring ASCII case and longest first. Later rules for the same name or
+/// extension replace earlier ones.
+///
+/// ```rust
+/// use pathext::{ArtifactKind, ArtifactRules};
+///
+/// let rules = ArtifactRules::default()
+///     .directory("out", ArtifactKind::Output)
+///     .remove_directory("build");
+/// assert_eq!(rules.kind_of("app/out/bundle.js"), Some(ArtifactKind::Output));
+/// assert_eq!(rules.kind_of("docs/build/index.md"), None);
+/// assert_eq!(rules.kind_of("src/main.o"), Some(ArtifactKind::Compiled));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ArtifactRules {
+    directories: BTreeMap<String, ArtifactKind>,
+    extensions: BTreeMap<String, ArtifactKind>,
+}
+
+impl Default for ArtifactRules {
+    /// The built-in rules for common build systems and package managers.
+    fn default() -> Self {
+        let mut rules = ArtifactRules::new();
+        for (directories, kind) in DIRECTORIES {
+            for directory in *directories {
+                rules = rules.directory(*directory, *kind);
+            }
+        }
+        for extension in COMPILED_EXTENSIONS {
+            rules = rules.extension(extension, ArtifactKind::Compiled);
+        }
+        rules
+    }
+}
+
+impl ArtifactRules {
+    /// Rules that recognize nothing.
+    pub fn new() -> Self {
+        ArtifactRules {
+            directories: BTreeMap::new(),
+            extensions: BTreeMap::new(),
+        }
+    }
+
+    /// Paths with a component named exactly `name` are `kind`.
+    pub fn directory<S: Into<String>>(mut self, name: S, kind: ArtifactKind) -> Self {
+        self.directories.insert(name.into(), kind);
+        self
+    }
+
+    /// Files ending in `.extension` are `kind`. A leading `.` is ignored, and the extension may be
+    /// compound.
+    pub fn extension<S: AsRef<str>>(mut self, extension: S, kind: ArtifactKind) -> Self {
+        let extension = extension.as_ref();
+        let extension = extension.strip_prefix('.').unwrap_or(extension);
+        self.extensions.insert(extension.to_ascii_lowercase(), kind);
+        self
+    }
+
+    /// Stops recognizing components named `name`, e.g. `bin` in a tree that keeps scripts there.
+    pub fn remove_directory(mut self, name: &str) -> Self {
+        self.directories.remove(name);
+        self
+    }
+
+    /// Stops recognizing files ending in `.extension`.
+    pub fn remove_extension(mut self, extension: &str) -> Self {
+        let extension = extension.strip_prefix('.').unwrap_or(extension);
+        self.extensions.remove(&extension.to_ascii_lowercase());
+        self
+    }
+
+    /// What kind of artifact the path is, `None` if no rule matches.
+    pub fn kind_of<P: AsRef<Path>>(&self, path: P) -> Option<ArtifactKind> {
+        let path = path.as_ref();
+        let directory = path.components().find_map(|component| match component {
+            Component::Normal(name) => self.directories.get(name.to_str()?).copied(),
+            _ => None,
+        });
+        if directory.is_some() {
+            return directory;
+        }
+        let name = path.file_name()?.to_string_lossy().to_ascii_lowercase();
+        let kind = name::extension_suffixes(&name)
+            .find_map(|extension| self.extensions.get(extension))
+            .copied();
+        kind
+    }
+}
+
+pub(crate) fn builtin() -> &'static ArtifactRules {
+    static BUILTIN: OnceLock<ArtifactRules> = OnceLock::new();
+    BUILTIN.get_or_init(ArtifactRules::default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{builtin, ArtifactKind, ArtifactRules};
+
+    #[test]
+    fn test_kind_of() {
+        let tests = &[
+            ("project/target/debug/app", Some(ArtifactKind::Output)),
+            ("target", Some(ArtifactKind::Output)),
+            (
+                "web/node_modules/react/dist/index.js",
+                Some(ArtifactKind::Dependencies),
+            ),
+            (
+                "pkg/__pycache__/mod.cpython-312.pyc",
+                Some(ArtifactKind::Cache),
+            ),
+            ("src/main.o", Some(ArtifactKind::Compiled)),
+            ("classes/App.CLASS", Some(ArtifactKind::Compiled)),
+            ("src/targets.rs", None),
+            ("src/main.rs", None),
+            ("build.rs", None),
+            (".obj", None),
+        ];
+
+        for test in tests {
+            assert_eq!(builtin().kind_of(test.0), test.1, "{}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_custom_rules() {
+        let rules = ArtifactRules::new()
+            .directory("gen", ArtifactKind::Output)
+            .extension(".PB.go", ArtifactKind::Compiled);
+
+        assert_eq!(rules.kind_of("gen/a.go"), Some(ArtifactKind::Output));
+        assert_eq!(rules.kind_of("api/a.pb.go"), Some(ArtifactKind::Compiled));
+        assert_eq!(rules.kind_of("api/a.go"), None);
+        assert_eq!(rules.kind_of("target/a"), None);
+
+        let rules = rules.remove_extension("pb.go").remove_directory("gen");
+        assert_eq!(rules, ArtifactRules::new());
+    }
+}
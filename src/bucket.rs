@@ -0,0 +1,95 @@
+//! Spreading files named by numeric ID over directories of their leading digits.
+
+use std::path::{Path, PathBuf};
+
+fn numeric_stem(path: &Path) -> Option<(&str, &str)> {
+    let name = path.file_name()?.to_str()?;
+    let end = name.find('.').unwrap_or(name.len());
+    let (stem, extensions) = name.split_at(end);
+    if stem.is_empty() || !stem.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some((stem, extensions))
+}
+
+pub(crate) fn bucket_by_number(path: &Path, width: usize, group: usize) -> Option<PathBuf> {
+    let (stem, extensions) = numeric_stem(path)?;
+    if group == 0 {
+        return None;
+    }
+    let digits = stem.trim_start_matches('0');
+    if digits.len() > width {
+        return None;
+    }
+    let padded = format!("{digits:0>width$}");
+    let mut bucketed = path.parent().map(Path::to_path_buf).unwrap_or_default();
+    for start in (0..width)
+        .step_by(group)
+        .take_while(|start| start + group < width)
+    {
+        bucketed.push(&padded[start..start + group]);
+    }
+    bucketed.push(padded + extensions);
+    Some(bucketed)
+}
+
+pub(crate) fn bucketed_number(path: &Path) -> Option<u64> {
+    numeric_stem(path)?.0.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bucket_by_number, bucketed_number};
+
+    use std::path::Path;
+
+    #[test]
+    fn test_bucket_by_number() {
+        let tests = &[
+            (
+                "export/123456.json",
+                9,
+                3,
+                Some("export/000/123/000123456.json"),
+            ),
+            (
+                "export/000123456.json",
+                9,
+                3,
+                Some("export/000/123/000123456.json"),
+            ),
+            ("42", 6, 2, Some("00/00/000042")),
+            ("42.tar.gz", 8, 3, Some("000/000/00000042.tar.gz")),
+            ("7.txt", 3, 3, Some("007.txt")),
+            ("0", 4, 2, Some("00/0000")),
+            ("1234567890", 9, 3, None),
+            ("12a.json", 9, 3, None),
+            (".json", 9, 3, None),
+            ("1.json", 9, 0, None),
+        ];
+
+        for test in tests {
+            assert_eq!(
+                bucket_by_number(Path::new(test.0), test.1, test.2).as_deref(),
+                test.3.map(Path::new),
+                "{}",
+                test.0
+            );
+        }
+    }
+
+    #[test]
+    fn test_bucketed_number() {
+        let tests = &[
+            ("export/000/123/000123456.json", Some(123456)),
+            ("000000000", Some(0)),
+            ("18446744073709551616", None),
+            ("export/000/123", Some(123)),
+            ("readme.md", None),
+        ];
+
+        for test in tests {
+            assert_eq!(bucketed_number(Path::new(test.0)), test.1, "{}", test.0);
+        }
+    }
+}
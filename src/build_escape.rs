@@ -0,0 +1,107 @@
+//! Writing paths into Makefiles, depfiles and Ninja build files.
+
+use std::path::Path;
+
+/// Escapes as GCC's `-MT` and `-MD` do: `$` doubled, `#`, `:`, spaces and tabs after a `\`, and the
+/// backslashes before those (or ending the path) doubled, other backslashes staying so Windows paths
+/// read back. The colon of a drive like `C:\` isn't escaped, since make reads those as paths.
+pub(crate) fn escape_for_make(path: &Path) -> Option<String> {
+    let path = path.to_str()?;
+    if path.contains(['\n', '\r']) {
+        return None;
+    }
+    let bytes = path.as_bytes();
+    let is_drive = |i: usize| {
+        i == 1 && bytes[0].is_ascii_alphabetic() && matches!(bytes.get(2), Some(b'\\' | b'/'))
+    };
+    let mut escaped = String::with_capacity(path.len());
+    let mut backslashes = 0;
+    for (i, c) in path.char_indices() {
+        if c == '\\' {
+            backslashes += 1;
+            continue;
+        }
+        let special = matches!(c, ' ' | '\t' | '#') || (c == ':' && !is_drive(i));
+        let doubled = if special { 2 } else { 1 };
+        escaped.extend(std::iter::repeat_n('\\', backslashes * doubled));
+        backslashes = 0;
+        match c {
+            '$' => escaped.push_str("$$"),
+            c if special => escaped.extend(['\\', c]),
+            c => escaped.push(c),
+        }
+    }
+    escaped.extend(std::iter::repeat_n('\\', backslashes * 2));
+    Some(escaped)
+}
+
+/// Escapes with `$` as Ninja build files do: `$$`, `$ ` and `$:`. Ninja's own depfile parser reads
+/// the make syntax, for which there is [`escape_for_make`].
+pub(crate) fn escape_for_ninja(path: &Path) -> Option<String> {
+    let path = path.to_str()?;
+    if path.contains(['\n', '\r']) {
+        return None;
+    }
+    let mut escaped = String::with_capacity(path.len());
+    for c in path.chars() {
+        if matches!(c, '$' | ' ' | ':') {
+            escaped.push('$');
+        }
+        escaped.push(c);
+    }
+    Some(escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{escape_for_make, escape_for_ninja};
+
+    use std::path::Path;
+
+    #[test]
+    fn test_escape_for_make() {
+        let tests = &[
+            ("src/main.c", Some("src/main.c")),
+            ("My Documents/a.c", Some(r"My\ Documents/a.c")),
+            ("cost$.h", Some("cost$$.h")),
+            ("issue#1.c", Some(r"issue\#1.c")),
+            ("a:b", Some(r"a\:b")),
+            (r"C:\src\a.c", Some(r"C:\src\a.c")),
+            (r"C:\My Files\a.c", Some(r"C:\My\ Files\a.c")),
+            (r"odd\ name", Some(r"odd\\\ name")),
+            (r"dir\", Some(r"dir\\")),
+            ("tab\there", Some("tab\\\there")),
+            ("two\nlines", None),
+        ];
+
+        for test in tests {
+            assert_eq!(
+                escape_for_make(Path::new(test.0)).as_deref(),
+                test.1,
+                "{}",
+                test.0
+            );
+        }
+    }
+
+    #[test]
+    fn test_escape_for_ninja() {
+        let tests = &[
+            ("src/main.c", Some("src/main.c")),
+            ("My Documents/a.c", Some("My$ Documents/a.c")),
+            ("cost$.h", Some("cost$$.h")),
+            (r"C:\src\a.c", Some(r"C$:\src\a.c")),
+            ("issue#1.c", Some("issue#1.c")),
+            ("two\nlines", None),
+        ];
+
+        for test in tests {
+            assert_eq!(
+                escape_for_ninja(Path::new(test.0)).as_deref(),
+                test.1,
+                "{}",
+                test.0
+            );
+        }
+    }
+}
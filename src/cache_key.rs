@@ -0,0 +1,126 @@
+//! Whole paths as single file names, for caches keyed by the path of their source.
+
+use std::path::{Path, PathBuf};
+
+use crate::encoded;
+
+/// Bytes written as they are. Uppercase letters are escaped too, so keys stay distinct on
+/// filesystems that ignore case.
+fn is_kept(byte: u8) -> bool {
+    matches!(byte, b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.')
+}
+
+pub(crate) fn to_cache_key(path: &Path) -> String {
+    let bytes = path.as_os_str().as_encoded_bytes();
+    let mut key = String::with_capacity(bytes.len());
+    for (i, &byte) in bytes.iter().enumerate() {
+        // a leading `.` would make `.` and `..` keys, or hide the file
+        if is_kept(byte) && !(i == 0 && byte == b'.') {
+            key.push(byte as char);
+        } else {
+            key.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    key
+}
+
+/// Turns a key from [`PathStrExt::to_cache_key`](crate::PathStrExt::to_cache_key) back into the path.
+///
+/// Keys hold only `a-z 0-9 - _ .` and uppercase `%XX` escapes of the path's bytes, with
+/// uppercase letters and a leading `.` escaped, so apart from the empty path's empty key they are
+/// valid file names everywhere, and distinct even where case is ignored. They can still be longer
+/// than a file name may be. Returns `None` for any other character, a bad escape, or on Windows a
+/// path that wasn't Unicode.
+///
+/// ```rust
+/// use pathext::{from_cache_key, PathStrExt};
+/// use std::path::Path;
+///
+/// let key = Path::new("/src/Main.rs").to_cache_key();
+/// assert_eq!(key, "%2Fsrc%2F%4Dain.rs");
+/// assert_eq!(from_cache_key(&key).unwrap(), Path::new("/src/Main.rs"));
+/// ```
+pub fn from_cache_key(key: &str) -> Option<PathBuf> {
+    let mut bytes = Vec::with_capacity(key.len());
+    let mut rest = key.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = tail.get(..2)?;
+            if !hex.iter().all(u8::is_ascii_hexdigit) {
+                return None;
+            }
+            bytes.push(u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()?);
+            rest = &tail[2..];
+        } else if is_kept(byte) {
+            bytes.push(byte);
+            rest = tail;
+        } else {
+            return None;
+        }
+    }
+    encoded::path_from_bytes(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_cache_key, to_cache_key};
+
+    use std::path::Path;
+
+    #[test]
+    fn test_cache_key_roundtrip() {
+        let tests = &[
+            "/usr/local/lib/libfoo.so.1",
+            "relative/../dir/./file.txt",
+            "100% done/ünïcödé",
+            "README",
+            ".",
+            "..",
+            ".hidden",
+            "",
+            r"C:\Users\me",
+        ];
+
+        for test in tests {
+            let key = to_cache_key(Path::new(test));
+            assert!(key.is_ascii() && !key.contains(['/', '\\']), "{key}");
+            assert!(!key.starts_with('.'), "{key}");
+            assert_eq!(from_cache_key(&key).unwrap(), Path::new(test), "{test}");
+        }
+    }
+
+    #[test]
+    fn test_to_cache_key() {
+        let tests = &[
+            ("/tmp/a.txt", "%2Ftmp%2Fa.txt"),
+            ("Makefile", "%4Dakefile"),
+            ("..", "%2E."),
+            ("a b", "a%20b"),
+            ("é", "%C3%A9"),
+        ];
+
+        for test in tests {
+            assert_eq!(to_cache_key(Path::new(test.0)), test.1);
+        }
+    }
+
+    #[test]
+    fn test_from_cache_key_rejects() {
+        let tests = &["a/b", "UPPER", "bad%zz", "cut%2", "%+1"];
+
+        for test in tests {
+            assert_eq!(from_cache_key(test), None, "{test}");
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_cache_key_non_utf8() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let path = Path::new(OsStr::from_bytes(b"a/\xff"));
+        assert_eq!(to_cache_key(path), "a%2F%FF");
+        assert_eq!(from_cache_key("a%2F%FF").unwrap(), path);
+    }
+}
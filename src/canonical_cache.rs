@@ -0,0 +1,185 @@
+//! Canonicalization that remembers the directories it has already resolved.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+/// Canonicalizes paths like [`Path::canonicalize`], caching the canonical form of every directory
+/// on the way so paths sharing ancestors only pay for the components they don't share.
+///
+/// A cached directory that is later replaced by a symlink, or moved, keeps resolving to its old
+/// location until the entry expires or [`clear`](CanonicalizeCache::clear) is called. Without a
+/// TTL entries never expire, which suits one scan of a tree that isn't changing underneath it.
+/// The cache can be shared between threads.
+///
+/// ```rust
+/// use pathext::CanonicalizeCache;
+/// use std::path::Path;
+///
+/// let cache = CanonicalizeCache::new();
+/// let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+/// for name in ["src/lib.rs", "src/fs.rs", "src/../Cargo.toml"] {
+///     assert_eq!(cache.canonicalize(root.join(name)).unwrap(), root.join(name).canonicalize().unwrap());
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct CanonicalizeCache {
+    dirs: RwLock<HashMap<PathBuf, (PathBuf, Instant)>>,
+    ttl: Option<Duration>,
+}
+
+impl CanonicalizeCache {
+    pub fn new() -> Self {
+        CanonicalizeCache::default()
+    }
+
+    /// A cache whose entries are resolved again once they are older than `ttl`.
+    pub fn with_ttl(ttl: Duration) -> Self {
+        CanonicalizeCache {
+            ttl: Some(ttl),
+            ..CanonicalizeCache::default()
+        }
+    }
+
+    /// How many directories are cached, including expired ones not yet looked up again.
+    pub fn len(&self) -> usize {
+        self.dirs.read().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        self.dirs.write().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+
+    /// The canonical, absolute form of `path` with all symlinks resolved, which must exist.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(path = %path.as_ref().display()))
+    )]
+    pub fn canonicalize<P: AsRef<Path>>(&self, path: P) -> io::Result<PathBuf> {
+        let path = path.as_ref();
+        let absolute = if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            std::env::current_dir()?.join(path)
+        };
+        self.resolve(&absolute, false)
+    }
+
+    fn cached(&self, dir: &Path) -> Option<PathBuf> {
+        let dirs = self.dirs.read().unwrap_or_else(|e| e.into_inner());
+        let (canonical, at) = dirs.get(dir)?;
+        let fresh = self.ttl.is_none_or(|ttl| at.elapsed() < ttl);
+        fresh.then(|| canonical.clone())
+    }
+
+    /// Resolves the absolute `path` from its parent's canonical form, caching it if it's a directory on
+    /// the way to something else.
+    fn resolve(&self, path: &Path, is_ancestor: bool) -> io::Result<PathBuf> {
+        if let Some(canonical) = self.cached(path) {
+            debug!(dir = %path.display(), "canonicalize cache hit");
+            return Ok(canonical);
+        }
+        let (Some(parent), Some(last)) = (path.parent(), path.components().next_back()) else {
+            return fs::canonicalize(path);
+        };
+
+        let parent = self.resolve(parent, true)?;
+        let canonical = match last {
+            // the parent is free of symlinks, so `..` is lexical from here
+            Component::ParentDir => parent.parent().map_or(parent.clone(), Path::to_path_buf),
+            Component::Normal(name) => {
+                let candidate = parent.join(name);
+                if fs::symlink_metadata(&candidate)?.is_symlink() {
+                    fs::canonicalize(&candidate)?
+                } else {
+                    candidate
+                }
+            }
+            _ => parent,
+        };
+
+        if is_ancestor {
+            debug!(dir = %path.display(), "canonicalize cache miss");
+            self.dirs
+                .write()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(path.to_path_buf(), (canonical.clone(), Instant::now()));
+        }
+        Ok(canonical)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CanonicalizeCache;
+
+    use std::ops::Not;
+    use std::path::Path;
+
+    #[test]
+    fn test_canonicalize_cache() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let cache = CanonicalizeCache::new();
+        let tests = &["src/lib.rs", "src", "src/../src/./fs.rs", "Cargo.toml", "."];
+
+        for test in tests {
+            let path = root.join(test);
+            assert_eq!(
+                cache.canonicalize(&path).unwrap(),
+                path.canonicalize().unwrap(),
+                "{test}"
+            );
+        }
+        assert!(cache.is_empty().not());
+        assert!(cache.canonicalize(root.join("src/missing.rs")).is_err());
+        assert!(cache.canonicalize(root.join("Cargo.toml/x")).is_err());
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_canonicalize_cache_symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let real = dir.path().canonicalize().unwrap();
+        std::fs::create_dir_all(real.join("a/b")).unwrap();
+        std::fs::create_dir(real.join("c")).unwrap();
+        std::fs::write(real.join("a/b/file"), "").unwrap();
+        std::os::unix::fs::symlink("a/b", real.join("link")).unwrap();
+        std::os::unix::fs::symlink("../a/b/file", real.join("c/to-file")).unwrap();
+
+        let cache = CanonicalizeCache::new();
+        let tests = &["link/file", "link/../b/file", "c/to-file", "link/./file"];
+        for test in tests {
+            let path = dir.path().join(test);
+            assert_eq!(
+                cache.canonicalize(&path).unwrap(),
+                path.canonicalize().unwrap(),
+                "{test}"
+            );
+        }
+
+        // the cached `link` goes stale when it's repointed, until it expires
+        std::fs::remove_file(real.join("link")).unwrap();
+        std::os::unix::fs::symlink("c", real.join("link")).unwrap();
+        let through_link = dir.path().join("link/to-file");
+        assert!(cache.canonicalize(&through_link).is_err());
+        let expiring = CanonicalizeCache::with_ttl(std::time::Duration::ZERO);
+        assert_eq!(
+            expiring.canonicalize(&through_link).unwrap(),
+            real.join("a/b/file")
+        );
+        cache.clear();
+        assert_eq!(
+            cache.canonicalize(&through_link).unwrap(),
+            real.join("a/b/file")
+        );
+    }
+}
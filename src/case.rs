@@ -0,0 +1,144 @@
+//! Slug and case conversions of file stems, leaving extensions alone.
+
+use std::path::{Path, PathBuf};
+
+use crate::hash::fnv1a;
+use crate::name::split_extensions;
+
+/// Splits on anything that isn't alphanumeric and on case changes, `HTTPServer` giving `HTTP` and `Server`.
+fn words(s: &str) -> Vec<String> {
+    let chars: Vec<char> = s.chars().collect();
+    let mut words = Vec::new();
+    let mut current = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        if let Some(&prev) = i.checked_sub(1).and_then(|p| chars.get(p)) {
+            let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            let boundary = c.is_uppercase()
+                && (prev.is_lowercase()
+                    || prev.is_numeric()
+                    || (prev.is_uppercase() && next_is_lower));
+            if boundary && !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+        }
+        current.push(c);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+fn join_lowercase(s: &str, separator: &str) -> String {
+    words(s)
+        .iter()
+        .map(|w| w.to_lowercase())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+/// Lowercases and joins runs of alphanumerics with `-`, keeping no other characters.
+pub(crate) fn slugify(s: &str) -> String {
+    let mut slug = String::with_capacity(s.len());
+    for c in s.chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let trimmed = slug.trim_end_matches('-').len();
+    slug.truncate(trimmed);
+    slug
+}
+
+pub(crate) fn snake_case(s: &str) -> String {
+    join_lowercase(s, "_")
+}
+
+pub(crate) fn kebab_case(s: &str) -> String {
+    join_lowercase(s, "-")
+}
+
+/// Replaces the file stem (up to the first `.`, so `.tar.gz` stays whole) with `f` of it.
+/// A leading `.` is kept so hidden files stay hidden, and a stem that converts to nothing,
+/// like `!!!`, becomes a short hash of itself instead.
+pub(crate) fn map_stem(path: &Path, f: fn(&str) -> String) -> PathBuf {
+    let Some(name) = path.file_name() else {
+        return path.to_path_buf();
+    };
+    let name = name.to_string_lossy();
+    let (stem, extension) = split_extensions(&name);
+    let (dot, stem) = match stem.strip_prefix('.') {
+        Some(stem) => (".", stem),
+        None => ("", stem),
+    };
+    let mut converted = f(stem);
+    if converted.is_empty() {
+        converted = format!("{:08x}", fnv1a(stem.as_bytes()) as u32);
+    }
+    path.with_file_name(format!("{dot}{converted}{extension}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{kebab_case, map_stem, slugify, snake_case};
+
+    use std::path::Path;
+
+    #[test]
+    fn test_slugify() {
+        let tests = &[
+            ("Hello, World!", "hello-world"),
+            ("  --Already-slugged--  ", "already-slugged"),
+            ("Crème Brûlée 2", "crème-brûlée-2"),
+            ("!!!", ""),
+        ];
+
+        for test in tests {
+            assert_eq!(slugify(test.0), test.1);
+        }
+    }
+
+    #[test]
+    fn test_case_conversions() {
+        let tests = &[
+            ("MyFileName", "my_file_name", "my-file-name"),
+            ("HTTPServer", "http_server", "http-server"),
+            ("some name-here", "some_name_here", "some-name-here"),
+            ("version2Final", "version2_final", "version2-final"),
+            ("already_snake", "already_snake", "already-snake"),
+            ("MP3Player", "mp3_player", "mp3-player"),
+        ];
+
+        for test in tests {
+            assert_eq!(snake_case(test.0), test.1);
+            assert_eq!(kebab_case(test.0), test.2);
+        }
+    }
+
+    #[test]
+    fn test_map_stem() {
+        let tests = &[
+            ("posts/My First Post.md", "posts/my-first-post.md"),
+            ("Backup Of Site.tar.gz", "backup-of-site.tar.gz"),
+            ("/a/.Hidden File", "/a/.hidden-file"),
+            ("/a/b/", "/a/b"),
+        ];
+
+        for test in tests {
+            assert_eq!(map_stem(Path::new(test.0), slugify), Path::new(test.1));
+        }
+
+        let hashed = map_stem(Path::new("!!!.md"), slugify);
+        let name = hashed.to_str().unwrap();
+        assert_eq!(name.len(), 11);
+        assert!(name.ends_with(".md"));
+    }
+}
@@ -0,0 +1,518 @@
+//! Sets of name rules behind the yes-or-no predicates on file names, to tune or load from config.
+
+use std::collections::BTreeSet;
+use std::path::{Component, Path};
+use std::sync::OnceLock;
+
+use crate::{artifact, name};
+
+/// One rule of a [`NameClassifier`]. With the `serde` feature rules (de)serialize externally
+/// tagged, e.g. `{ extension = "swp" }` in TOML.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum NameRule {
+    /// File names equal to this.
+    Name(String),
+    /// File names starting with this.
+    Prefix(String),
+    /// File names ending with this.
+    Suffix(String),
+    /// File names whose extensions, compound or not, end in this, ignoring ASCII case. A leading
+    /// `.` is ignored.
+    Extension(String),
+    /// Paths with a component equal to this, so everything inside such a directory.
+    Directory(String),
+    /// File names matching this shell pattern, where `*` is any run of characters and `?` any one.
+    /// A pattern with a `/` matches the path's components joined with `/` instead, a leading `/`
+    /// being dropped, where `*` and `?` stay within a component and `**` spans any number of them:
+    /// `docs/**/*.md`. There are no character classes.
+    Glob(String),
+    /// File names equal to this never match, whatever the other rules say.
+    Except(String),
+}
+
+impl NameRule {
+    /// The rule as stored, so equal rules compare equal however they were written.
+    fn normalized(self) -> Self {
+        match self {
+            NameRule::Extension(extension) => {
+                let extension = extension.strip_prefix('.').unwrap_or(&extension);
+                NameRule::Extension(extension.to_ascii_lowercase())
+            }
+            rule => rule,
+        }
+    }
+}
+
+/// A set of [`NameRule`]s a path matches if any rule does and no [`NameRule::Except`] does.
+///
+/// The presets are the rules behind [`is_os_metadata_junk`](crate::PathStrExt::is_os_metadata_junk),
+/// [`is_transient_file`](crate::PathStrExt::is_transient_file),
+/// [`looks_sensitive`](crate::PathStrExt::looks_sensitive) and
+/// [`is_build_artifact`](crate::PathComponentExt::is_build_artifact), to start from and tune. With
+/// the `serde` feature a classifier (de)serializes as its list of rules, so it can come from a
+/// config file.
+///
+/// ```rust
+/// use pathext::{NameClassifier, NameRule};
+///
+/// let ignored = NameClassifier::transient()
+///     .with(NameRule::Extension("bak".into()))
+///     .without(&NameRule::Extension("lock".into()));
+/// assert!(ignored.matches("notes.txt.bak"));
+/// assert!(ignored.matches("~$report.docx"));
+/// assert!(!ignored.matches("db.lock"));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(from = "Vec<NameRule>", into = "Vec<NameRule>")
+)]
+pub struct NameClassifier {
+    rules: BTreeSet<NameRule>,
+}
+
+impl NameClassifier {
+    /// A classifier without rules, matching nothing.
+    pub fn new() -> Self {
+        NameClassifier::default()
+    }
+
+    /// Files operating systems leave for their own bookkeeping: `.DS_Store`, `._` resource forks,
+    /// `Thumbs.db`, `desktop.ini`, and directories like `__MACOSX` and `$RECYCLE.BIN`.
+    pub fn os_metadata_junk() -> Self {
+        let names = [
+            ".DS_Store",
+            ".localized",
+            ".directory",
+            "Thumbs.db",
+            "ehthumbs.db",
+            "ehthumbs_vista.db",
+            "desktop.ini",
+            "Desktop.ini",
+            "Icon\r",
+        ];
+        let directories = [
+            "__MACOSX",
+            ".Spotlight-V100",
+            ".Trashes",
+            ".fseventsd",
+            ".TemporaryItems",
+            ".DocumentRevisions-V100",
+            "$RECYCLE.BIN",
+            "System Volume Information",
+        ];
+        names
+            .into_iter()
+            .map(|name| NameRule::Name(name.to_string()))
+            .chain(std::iter::once(NameRule::Prefix("._".to_string())))
+            .chain(
+                directories
+                    .into_iter()
+                    .map(|directory| NameRule::Directory(directory.to_string())),
+            )
+            .collect()
+    }
+
+    /// Files editors, office suites and downloaders leave while working: swap and temp files,
+    /// backups, locks and partial downloads. Lock files that belong in a repository, like
+    /// `Cargo.lock`, are exceptions.
+    pub fn transient() -> Self {
+        let extensions = [
+            "!qb",
+            "!ut",
+            "crdownload",
+            "download",
+            "lck",
+            "lock",
+            "opdownload",
+            "part",
+            "partial",
+            "swo",
+            "swp",
+            "swx",
+            "temp",
+            "tmp",
+        ];
+        // Office owner files, LibreOffice and Emacs locks, NFS silly renames
+        let prefixes = ["~$", ".~lock.", ".#", ".nfs", ".fuse_hidden"];
+        // Emacs auto-saves `#notes.txt#` and backs up to `notes.txt~`
+        let suffixes = ["#", "~"];
+        let kept_locks = [
+            "Cargo.lock",
+            "Gemfile.lock",
+            "Pipfile.lock",
+            "composer.lock",
+            "flake.lock",
+            "mix.lock",
+            "package-lock.json",
+            "packages.lock.json",
+            "pnpm-lock.yaml",
+            "poetry.lock",
+            "pubspec.lock",
+            "uv.lock",
+            "yarn.lock",
+        ];
+        extensions
+            .into_iter()
+            .map(|extension| NameRule::Extension(extension.to_string()))
+            .chain(prefixes.map(|prefix| NameRule::Prefix(prefix.to_string())))
+            .chain(suffixes.map(|suffix| NameRule::Suffix(suffix.to_string())))
+            .chain(kept_locks.map(|name| NameRule::Except(name.to_string())))
+            .collect()
+    }
+
+    /// Files likely to hold secrets: private keys and certificates, `.env` files, credential and
+    /// password stores, and everything in directories like `.ssh`, `.gnupg` and `Keychains`.
+    /// Example and template `.env` files are exceptions.
+    pub fn sensitive() -> Self {
+        let names = [
+            ".env",
+            ".git-credentials",
+            ".htpasswd",
+            ".netrc",
+            "_netrc",
+            ".npmrc",
+            ".pgpass",
+            ".pypirc",
+            "credentials",
+            "credentials.json",
+            "id_dsa",
+            "id_ecdsa",
+            "id_ed25519",
+            "id_rsa",
+            "master.key",
+            "secrets.json",
+            "secrets.yaml",
+            "secrets.yml",
+            "shadow",
+        ];
+        let extensions = ["jks", "kdbx", "key", "keystore", "p12", "pem", "pfx", "ppk"];
+        let directories = [
+            ".aws",
+            ".azure",
+            ".docker",
+            ".gnupg",
+            ".kube",
+            ".password-store",
+            ".ssh",
+            "Keychains",
+            "gcloud",
+        ];
+        let kept = [".env.dist", ".env.example", ".env.sample", ".env.template"];
+        names
+            .into_iter()
+            .map(|name| NameRule::Name(name.to_string()))
+            .chain(std::iter::once(NameRule::Prefix(".env.".to_string())))
+            .chain(extensions.map(|extension| NameRule::Extension(extension.to_string())))
+            .chain(directories.map(|directory| NameRule::Directory(directory.to_string())))
+            .chain(kept.map(|name| NameRule::Except(name.to_string())))
+            .collect()
+    }
+
+    /// The directories and extensions of [`ArtifactRules::default`](crate::ArtifactRules::default),
+    /// without telling the kinds apart.
+    pub fn build_artifacts() -> Self {
+        artifact::DIRECTORIES
+            .iter()
+            .flat_map(|(directories, _)| directories.iter())
+            .map(|directory| NameRule::Directory(directory.to_string()))
+            .chain(
+                artifact::COMPILED_EXTENSIONS
+                    .iter()
+                    .map(|extension| NameRule::Extension(extension.to_string())),
+            )
+            .collect()
+    }
+
+    /// Adds `rule`, returning whether it wasn't there yet.
+    pub fn insert(&mut self, rule: NameRule) -> bool {
+        self.rules.insert(rule.normalized())
+    }
+
+    /// Removes `rule`, returning whether it was there.
+    pub fn remove(&mut self, rule: &NameRule) -> bool {
+        self.rules.remove(&rule.clone().normalized())
+    }
+
+    /// The classifier with `rule` added.
+    pub fn with(mut self, rule: NameRule) -> Self {
+        self.insert(rule);
+        self
+    }
+
+    /// The classifier with `rule` removed.
+    pub fn without(mut self, rule: &NameRule) -> Self {
+        self.remove(rule);
+        self
+    }
+
+    pub fn rules(&self) -> impl Iterator<Item = &NameRule> {
+        self.rules.iter()
+    }
+
+    /// Whether any rule matches the path and no exception matches its file name.
+    pub fn matches<P: AsRef<Path>>(&self, path: P) -> bool {
+        let path = path.as_ref();
+        let name = path.file_name().map(|name| name.to_string_lossy());
+        let Some(name) = name.as_deref() else {
+            return self.rules.iter().any(|rule| in_directory(path, rule));
+        };
+        if self.rules.contains(&NameRule::Except(name.to_string())) {
+            return false;
+        }
+        let lowercase = name.to_ascii_lowercase();
+        self.rules.iter().any(|rule| match rule {
+            NameRule::Name(wanted) => name == wanted,
+            NameRule::Prefix(prefix) => name.starts_with(prefix.as_str()),
+            NameRule::Suffix(suffix) => name.ends_with(suffix.as_str()),
+            NameRule::Extension(wanted) => {
+                name::extension_suffixes(&lowercase).any(|extension| extension == wanted)
+            }
+            NameRule::Directory(_) => in_directory(path, rule),
+            NameRule::Glob(pattern) => match pattern.strip_prefix('/') {
+                Some(pattern) => glob_matches(pattern, &slash_joined(path)),
+                None if pattern.contains('/') => glob_matches(pattern, &slash_joined(path)),
+                None => glob_matches(pattern, name),
+            },
+            NameRule::Except(_) => false,
+        })
+    }
+}
+
+/// The normal components of `path` joined with `/`, for path globs to match.
+fn slash_joined(path: &Path) -> String {
+    let mut joined = String::new();
+    for component in path.components() {
+        if let Component::Normal(name) = component {
+            if !joined.is_empty() {
+                joined.push('/');
+            }
+            joined.push_str(&name.to_string_lossy());
+        }
+    }
+    joined
+}
+
+/// Whether `text` matches the glob `pattern`, see [`NameRule::Glob`].
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    glob_matches_at(&pattern, &text)
+}
+
+fn glob_matches_at(pattern: &[char], text: &[char]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        ['*', '*', rest @ ..] => {
+            // `**/` also matches no directories at all
+            if let ['/', after @ ..] = rest {
+                if glob_matches_at(after, text) {
+                    return true;
+                }
+            }
+            (0..=text.len()).any(|skip| glob_matches_at(rest, &text[skip..]))
+        }
+        ['*', rest @ ..] => {
+            let within = text.iter().position(|&c| c == '/').unwrap_or(text.len());
+            (0..=within).any(|skip| glob_matches_at(rest, &text[skip..]))
+        }
+        ['?', rest @ ..] => {
+            matches!(text, [c, ..] if *c != '/') && glob_matches_at(rest, &text[1..])
+        }
+        [c, rest @ ..] => text.first() == Some(c) && glob_matches_at(rest, &text[1..]),
+    }
+}
+
+fn in_directory(path: &Path, rule: &NameRule) -> bool {
+    let NameRule::Directory(directory) = rule else {
+        return false;
+    };
+    path.components().any(|component| {
+        matches!(component, Component::Normal(name) if name.to_str() == Some(directory.as_str()))
+    })
+}
+
+impl FromIterator<NameRule> for NameClassifier {
+    fn from_iter<I: IntoIterator<Item = NameRule>>(rules: I) -> Self {
+        let mut classifier = NameClassifier::new();
+        classifier.extend(rules);
+        classifier
+    }
+}
+
+impl Extend<NameRule> for NameClassifier {
+    fn extend<I: IntoIterator<Item = NameRule>>(&mut self, rules: I) {
+        for rule in rules {
+            self.insert(rule);
+        }
+    }
+}
+
+impl From<Vec<NameRule>> for NameClassifier {
+    fn from(rules: Vec<NameRule>) -> Self {
+        rules.into_iter().collect()
+    }
+}
+
+impl From<NameClassifier> for Vec<NameRule> {
+    fn from(classifier: NameClassifier) -> Self {
+        classifier.rules.into_iter().collect()
+    }
+}
+
+pub(crate) fn os_metadata_junk() -> &'static NameClassifier {
+    static JUNK: OnceLock<NameClassifier> = OnceLock::new();
+    JUNK.get_or_init(NameClassifier::os_metadata_junk)
+}
+
+pub(crate) fn sensitive() -> &'static NameClassifier {
+    static SENSITIVE: OnceLock<NameClassifier> = OnceLock::new();
+    SENSITIVE.get_or_init(NameClassifier::sensitive)
+}
+
+pub(crate) fn transient() -> &'static NameClassifier {
+    static TRANSIENT: OnceLock<NameClassifier> = OnceLock::new();
+    TRANSIENT.get_or_init(NameClassifier::transient)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NameClassifier, NameRule};
+
+    #[test]
+    fn test_os_metadata_junk() {
+        let tests = &[
+            ("photos/.DS_Store", true),
+            ("photos/._IMG_0001.JPG", true),
+            ("C:/Users/me/Pictures/Thumbs.db", true),
+            ("export/__MACOSX/photos/IMG_0001.JPG", true),
+            ("D:/$RECYCLE.BIN/S-1-5-21/file", true),
+            ("photos/IMG_0001.JPG", false),
+            ("docs/thumbs.db.md", false),
+        ];
+
+        let junk = NameClassifier::os_metadata_junk();
+        for test in tests {
+            assert_eq!(junk.matches(test.0), test.1, "{}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_transient() {
+        let tests = &[
+            (".main.rs.swp", true),
+            ("report.TMP", true),
+            ("~$report.docx", true),
+            (".~lock.budget.ods#", true),
+            ("video.mp4.part", true),
+            ("installer.exe.crdownload", true),
+            ("db.lock", true),
+            (".#notes.org", true),
+            ("#notes.org#", true),
+            ("notes.txt~", true),
+            (".nfs000000000123", true),
+            ("Cargo.lock", false),
+            ("yarn.lock", false),
+            ("report.docx", false),
+            (".swp", false),
+            ("part", false),
+        ];
+
+        let transient = NameClassifier::transient();
+        for test in tests {
+            assert_eq!(transient.matches(test.0), test.1, "{}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_sensitive() {
+        let tests = &[
+            ("/home/me/.ssh/config", true),
+            ("/home/me/.ssh/id_rsa.pub", true),
+            ("backup/id_ed25519", true),
+            ("app/.env", true),
+            ("app/.env.production", true),
+            ("/home/me/.aws/credentials", true),
+            (
+                "/home/me/.config/gcloud/application_default_credentials.json",
+                true,
+            ),
+            ("/Users/me/Library/Keychains/login.keychain-db", true),
+            ("certs/server.PEM", true),
+            ("vault.kdbx", true),
+            ("app/.env.example", false),
+            ("docs/environment.md", false),
+            ("src/keyboard.rs", false),
+            ("monkey", false),
+        ];
+
+        let sensitive = NameClassifier::sensitive();
+        for test in tests {
+            assert_eq!(sensitive.matches(test.0), test.1, "{}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_build_artifacts() {
+        let artifacts = NameClassifier::build_artifacts();
+        assert!(artifacts.matches("project/target/debug/app"));
+        assert!(artifacts.matches("web/node_modules/react/index.js"));
+        assert!(artifacts.matches("src/main.O"));
+        assert!(!artifacts.matches("src/main.rs"));
+    }
+
+    #[test]
+    fn test_rules() {
+        let mut classifier = NameClassifier::new()
+            .with(NameRule::Extension(".LOG".into()))
+            .with(NameRule::Directory("logs".into()))
+            .with(NameRule::Except("keep.log".into()));
+
+        assert!(classifier.matches("app.log"));
+        assert!(classifier.matches("logs/today.txt"));
+        assert!(!classifier.matches("logs/keep.log"));
+        assert!(!classifier.matches("app.txt"));
+
+        assert!(!classifier.insert(NameRule::Extension("log".into())));
+        assert!(classifier.remove(&NameRule::Extension(".Log".into())));
+        assert!(!classifier.matches("app.log"));
+        assert_eq!(classifier.rules().count(), 2);
+
+        let rules: Vec<NameRule> = classifier.clone().into();
+        assert_eq!(NameClassifier::from(rules), classifier);
+        assert!(!NameClassifier::new().matches("anything"));
+    }
+
+    #[test]
+    fn test_glob() {
+        let tests = &[
+            ("*.log", "app.log", true),
+            ("*.log", "logs/app.log", true),
+            ("*.log", "app.log.gz", false),
+            ("report-??.pdf", "out/report-03.pdf", true),
+            ("report-??.pdf", "out/report-3.pdf", false),
+            ("build*", "build-cache/x", false),
+            ("build*", "build-cache", true),
+            ("docs/*.md", "docs/intro.md", true),
+            ("docs/*.md", "docs/guide/intro.md", false),
+            ("docs/**/*.md", "docs/intro.md", true),
+            ("docs/**/*.md", "docs/guide/deep/intro.md", true),
+            ("docs/**/*.md", "src/docs/intro.md", false),
+            ("/target", "target", true),
+            ("/target", "crate/target", false),
+            ("**/target", "crate/target", true),
+            ("a?b", "a/b", false),
+        ];
+
+        for test in tests {
+            let classifier = NameClassifier::new().with(NameRule::Glob(test.0.into()));
+            assert_eq!(classifier.matches(test.1), test.2, "{} {}", test.0, test.1);
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! Locale-aware ordering of paths for display.
+
+use std::cmp::Ordering;
+use std::path::Path;
+
+use icu_collator::options::CollatorOptions;
+use icu_collator::{Collator, CollatorBorrowed};
+use icu_locale_core::Locale;
+use icu_provider::DataError;
+
+/// Compares paths component by component using a locale's collation rules.
+///
+/// Build one and reuse it when sorting, since creating the collator is the expensive part.
+///
+/// ```rust
+/// use pathext::icu_locale_core::locale;
+/// use pathext::PathCollator;
+///
+/// let collator = PathCollator::new(&locale!("de")).unwrap();
+/// let mut names = vec!["zebra.txt", "Äpfel.txt", "apfel.txt", "birne.txt"];
+/// collator.sort(&mut names);
+/// assert_eq!(names, ["apfel.txt", "Äpfel.txt", "birne.txt", "zebra.txt"]);
+/// ```
+#[derive(Debug)]
+pub struct PathCollator {
+    collator: CollatorBorrowed<'static>,
+}
+
+impl PathCollator {
+    pub fn new(locale: &Locale) -> Result<Self, DataError> {
+        Ok(PathCollator {
+            collator: Collator::try_new(locale.into(), CollatorOptions::default())?,
+        })
+    }
+
+    /// Compares the components pairwise, a path sorting before any path it is a prefix of.
+    pub fn compare<A: AsRef<Path>, B: AsRef<Path>>(&self, a: A, b: B) -> Ordering {
+        let mut a = a.as_ref().components();
+        let mut b = b.as_ref().components();
+        loop {
+            match (a.next(), b.next()) {
+                (None, None) => return Ordering::Equal,
+                (None, Some(_)) => return Ordering::Less,
+                (Some(_), None) => return Ordering::Greater,
+                (Some(x), Some(y)) => {
+                    let x = x.as_os_str().to_string_lossy();
+                    let y = y.as_os_str().to_string_lossy();
+                    match self.collator.compare(&x, &y) {
+                        Ordering::Equal => continue,
+                        other => return other,
+                    }
+                }
+            }
+        }
+    }
+
+    /// Sorts the paths in this collation order.
+    pub fn sort<P: AsRef<Path>>(&self, paths: &mut [P]) {
+        paths.sort_by(|a, b| self.compare(a, b));
+    }
+}
+
+/// Sorts paths for display in `locale`'s collation order, falling back to byte order if the
+/// collation data can't be loaded.
+pub fn sort_collated<P: AsRef<Path>>(paths: &mut [P], locale: &Locale) {
+    match PathCollator::new(locale) {
+        Ok(collator) => collator.sort(paths),
+        Err(_) => paths.sort_by(|a, b| a.as_ref().cmp(b.as_ref())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sort_collated, PathCollator};
+
+    use icu_locale_core::locale;
+    use std::cmp::Ordering;
+
+    #[test]
+    fn test_compare() {
+        let collator = PathCollator::new(&locale!("en")).unwrap();
+        let tests = &[
+            ("a/b", "a/b", Ordering::Equal),
+            ("a", "a/b", Ordering::Less),
+            ("B/x", "a/y", Ordering::Greater),
+            ("dir/ä", "dir/b", Ordering::Less),
+            ("a b/z", "a/z", Ordering::Greater),
+        ];
+
+        for test in tests {
+            assert_eq!(
+                collator.compare(test.0, test.1),
+                test.2,
+                "{} {}",
+                test.0,
+                test.1
+            );
+        }
+    }
+
+    #[test]
+    fn test_sort_collated() {
+        let mut german = vec!["z", "ä", "b", "a"];
+        sort_collated(&mut german, &locale!("de"));
+        assert_eq!(german, ["a", "ä", "b", "z"]);
+
+        // Swedish puts ä after z
+        let mut swedish = vec!["z", "ä", "b", "a"];
+        sort_collated(&mut swedish, &locale!("sv"));
+        assert_eq!(swedish, ["a", "b", "z", "ä"]);
+    }
+}
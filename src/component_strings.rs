@@ -0,0 +1,196 @@
+//! Paths as arrays of strings, for storing in databases and message formats.
+
+use std::path::{Component, Path, PathBuf, MAIN_SEPARATOR_STR};
+
+const ROOT: &str = "/";
+
+fn escape(name: &str) -> String {
+    let mut escaped = String::with_capacity(name.len());
+    for c in name.chars() {
+        match c {
+            '%' => escaped.push_str("%25"),
+            '/' => escaped.push_str("%2F"),
+            '\\' => escaped.push_str("%5C"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape(escaped: &str) -> Option<String> {
+    let mut name = String::with_capacity(escaped.len());
+    let mut rest = escaped;
+    while let Some(at) = rest.find('%') {
+        name.push_str(&rest[..at]);
+        let decoded = match rest.get(at + 1..at + 3)?.to_ascii_uppercase().as_str() {
+            "25" => '%',
+            "2F" => '/',
+            "5C" => '\\',
+            _ => return None,
+        };
+        name.push(decoded);
+        rest = &rest[at + 3..];
+    }
+    name.push_str(rest);
+    Some(name)
+}
+
+pub(crate) fn to_component_strings(path: &Path) -> Option<Vec<String>> {
+    path.components()
+        .map(|component| match component {
+            Component::Prefix(prefix) => prefix.as_os_str().to_str().map(String::from),
+            Component::RootDir => Some(ROOT.to_string()),
+            Component::CurDir => Some(".".to_string()),
+            Component::ParentDir => Some("..".to_string()),
+            Component::Normal(name) => name.to_str().map(escape),
+        })
+        .collect()
+}
+
+/// Rebuilds a path from the strings of [`PathComponentExt::to_component_strings`](crate::PathComponentExt::to_component_strings).
+///
+/// Every element but a leading root or Windows prefix is a single component with `%`, `/` and
+/// `\` written as `%25`, `%2F` and `%5C`, so no element can be mistaken for several. A root is
+/// always `/`, whatever the platform's separator; a prefix such as `C:` or `\\server\share` is
+/// stored as is. Returns `None` for an empty element, a bad escape, or an unescaped separator
+/// anywhere but in a leading element that is exactly one Windows prefix.
+///
+/// The path comes back equal to the original as [`Path`] compares them, not byte for byte: like
+/// [`Path::components`], the strings leave out repeated separators, `.` anywhere but at the
+/// start and a trailing separator, so `a//./b/` comes back as `a/b`.
+///
+/// ```rust
+/// use pathext::{from_component_strings, PathComponentExt};
+/// use std::path::Path;
+///
+/// let parts = Path::new("/srv/100%/a\\b").to_component_strings().unwrap();
+/// # #[cfg(unix)]
+/// assert_eq!(parts, ["/", "srv", "100%25", "a%5Cb"]);
+/// assert_eq!(from_component_strings(&parts).unwrap(), Path::new("/srv/100%/a\\b"));
+/// ```
+pub fn from_component_strings<I, S>(parts: I) -> Option<PathBuf>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut path = PathBuf::new();
+    for (i, part) in parts.into_iter().enumerate() {
+        let part = part.as_ref();
+        let after_prefix = i == 1
+            && path
+                .components()
+                .next()
+                .is_some_and(|c| matches!(c, Component::Prefix(_)));
+        if part.is_empty() {
+            return None;
+        } else if part == ROOT {
+            if i != 0 && !after_prefix {
+                return None;
+            }
+            path.push(MAIN_SEPARATOR_STR);
+        } else if part.contains(['/', '\\']) {
+            let mut components = Path::new(part).components();
+            let is_prefix = matches!(components.next(), Some(Component::Prefix(_)));
+            if i != 0 || !is_prefix || components.next().is_some() {
+                return None;
+            }
+            path.push(part);
+        } else {
+            path.push(unescape(part)?);
+        }
+    }
+    Some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_component_strings, to_component_strings};
+
+    use std::path::Path;
+
+    #[test]
+    fn test_component_strings_roundtrip() {
+        let tests = &[
+            "/usr/local/bin",
+            "relative/./dir/../file.txt",
+            "100%/done",
+            "%2F-literally",
+            ".",
+            "",
+            #[cfg(unix)]
+            "/back\\slash/name",
+            #[cfg(windows)]
+            r"C:\Users\me",
+            #[cfg(windows)]
+            r"\\server\share\dir",
+            #[cfg(windows)]
+            r"\\?\C:\verbatim",
+        ];
+
+        for test in tests {
+            let parts = to_component_strings(Path::new(test)).unwrap();
+            assert_eq!(
+                from_component_strings(&parts).unwrap(),
+                Path::new(test),
+                "{test}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_component_strings_drop_redundant_parts() {
+        let parts = to_component_strings(Path::new("a//./b/")).unwrap();
+        assert_eq!(parts, ["a", "b"]);
+        assert_eq!(
+            from_component_strings(&parts).unwrap().as_os_str(),
+            Path::new("a").join("b").as_os_str()
+        );
+    }
+
+    #[test]
+    fn test_to_component_strings() {
+        let tests: &[(&str, &[&str])] = &[
+            ("/srv/www", &["/", "srv", "www"]),
+            ("a/100%", &["a", "100%25"]),
+            #[cfg(unix)]
+            ("a\\b", &["a%5Cb"]),
+            #[cfg(windows)]
+            (r"C:\a", &["C:", "/", "a"]),
+        ];
+
+        for test in tests {
+            assert_eq!(to_component_strings(Path::new(test.0)).unwrap(), test.1);
+        }
+    }
+
+    #[test]
+    fn test_from_component_strings_rejects() {
+        let tests: &[&[&str]] = &[
+            &["a", ""],
+            &["a", "b/c"],
+            &["a/b"],
+            &["a\\b", "c"],
+            &["/a"],
+            &["a", "/"],
+            &["/", "/"],
+            &["bad%zz"],
+            &["cut%2"],
+        ];
+
+        for test in tests {
+            assert_eq!(from_component_strings(*test), None, "{test:?}");
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_to_component_strings_non_utf8() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        assert_eq!(
+            to_component_strings(Path::new(OsStr::from_bytes(b"a/\xff"))),
+            None
+        );
+    }
+}
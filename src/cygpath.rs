@@ -0,0 +1,341 @@
+//! Converting paths between the Unix forms of Cygwin and MSYS2 and Windows ones, as `cygpath` does.
+
+use std::path::{Path, PathBuf};
+
+use crate::fat;
+
+/// The form [`convert_path`](crate::PathComponentExt::convert_path) writes a path in, one per
+/// `cygpath` output flag.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PathStyle {
+    /// `/cygdrive/c/Users/me`, `cygpath -u`.
+    Unix,
+    /// `C:\Users\me`, `cygpath -w`.
+    Windows,
+    /// `C:/Users/me`, `cygpath -m`, which Windows and Cygwin programs both take.
+    Mixed,
+    /// `C:\PROGRA~1\App`, `cygpath -d`. Short names are made up the way Windows first would, since
+    /// only the volume knows the ones it gave out.
+    Dos,
+}
+
+/// Where a Cygwin-like environment mounts things, for [`convert_path_with`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CygpathOptions {
+    /// The directory drives are mounted under: `/cygdrive` for Cygwin, `/` for MSYS2's `/c/Users`.
+    pub cygdrive: &'static str,
+    /// The Windows directory `/` is, like `C:\msys64`, for converting the other absolute Unix paths.
+    /// Without it those have no Windows form.
+    pub root: Option<PathBuf>,
+}
+
+impl CygpathOptions {
+    /// Cygwin's default mounts: drives under `/cygdrive`.
+    pub const CYGWIN: CygpathOptions = CygpathOptions {
+        cygdrive: "/cygdrive",
+        root: None,
+    };
+    /// MSYS2 and Git for Windows: drives right under `/`, as `/c/Users`.
+    pub const MSYS2: CygpathOptions = CygpathOptions {
+        cygdrive: "/",
+        root: None,
+    };
+}
+
+impl Default for CygpathOptions {
+    /// [`CygpathOptions::CYGWIN`], drives under `/cygdrive` and no root.
+    fn default() -> Self {
+        CygpathOptions::CYGWIN
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Anchor<'a> {
+    Drive(char),
+    Unc(&'a str, &'a str),
+}
+
+impl Anchor<'_> {
+    fn eq_ignore_case(&self, other: &Anchor<'_>) -> bool {
+        match (self, other) {
+            (Anchor::Drive(a), Anchor::Drive(b)) => a.eq_ignore_ascii_case(b),
+            (Anchor::Unc(server, share), Anchor::Unc(other_server, other_share)) => {
+                server.eq_ignore_ascii_case(other_server) && share.eq_ignore_ascii_case(other_share)
+            }
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Parsed<'a> {
+    /// An absolute Windows path, a drive or share and what's below it.
+    Windows(Anchor<'a>, Vec<&'a str>),
+    /// An absolute Unix path that isn't a drive mount.
+    Unix(Vec<&'a str>),
+    Relative(Vec<&'a str>),
+}
+
+fn components(s: &str) -> Vec<&str> {
+    s.split(['/', '\\']).filter(|c| !c.is_empty()).collect()
+}
+
+fn drive_letter(s: &str) -> Option<char> {
+    let mut chars = s.chars();
+    let letter = chars.next().filter(char::is_ascii_alphabetic)?;
+    chars.next().is_none().then_some(letter)
+}
+
+/// Splits `s` up, `None` for forms with no absolute counterpart, like the drive-relative `C:x`.
+fn parse<'a>(s: &'a str, cygdrive: &str) -> Option<Parsed<'a>> {
+    let mut chars = s.chars();
+    let first = chars.next();
+    let second = chars.next();
+    let is_separator = |c: Option<char>| matches!(c, Some('/' | '\\'));
+    if is_separator(first) && is_separator(second) {
+        let mut rest = components(s).into_iter();
+        let anchor = Anchor::Unc(rest.next()?, rest.next()?);
+        return Some(Parsed::Windows(anchor, rest.collect()));
+    }
+    if second == Some(':') {
+        let letter = first.filter(char::is_ascii_alphabetic)?;
+        let rest = &s[2..];
+        if !rest.is_empty() && !rest.starts_with(['/', '\\']) {
+            return None;
+        }
+        return Some(Parsed::Windows(Anchor::Drive(letter), components(rest)));
+    }
+    if !is_separator(first) {
+        return Some(Parsed::Relative(components(s)));
+    }
+    let mut rest = components(s);
+    let mount = components(cygdrive);
+    if rest.len() > mount.len() && rest[..mount.len()] == mount[..] {
+        if let Some(letter) = drive_letter(rest[mount.len()]) {
+            let below = rest.split_off(mount.len() + 1);
+            return Some(Parsed::Windows(Anchor::Drive(letter), below));
+        }
+    }
+    Some(Parsed::Unix(rest))
+}
+
+/// [`PathComponentExt::convert_path`](crate::PathComponentExt::convert_path) with the mounts in
+/// `options`, so with [`CygpathOptions::MSYS2`] `C:\x` becomes `/c/x`, and with a `root` of
+/// `C:\msys64` `/usr/bin` becomes `C:\msys64\usr\bin` and back.
+///
+/// ```rust
+/// use pathext::{convert_path_with, CygpathOptions, PathStyle};
+/// use std::path::{Path, PathBuf};
+///
+/// let msys = CygpathOptions {
+///     root: Some(PathBuf::from(r"C:\msys64")),
+///     ..CygpathOptions::MSYS2
+/// };
+/// let unix = convert_path_with(r"C:\Users\me", PathStyle::Unix, &msys).unwrap();
+/// assert_eq!(unix, Path::new("/c/Users/me"));
+/// let windows = convert_path_with("/usr/bin", PathStyle::Windows, &msys).unwrap();
+/// assert_eq!(windows, Path::new(r"C:\msys64\usr\bin"));
+/// let mixed = convert_path_with("/c/Program Files/App", PathStyle::Mixed, &msys).unwrap();
+/// assert_eq!(mixed, Path::new("C:/Program Files/App"));
+/// ```
+pub fn convert_path_with<P: AsRef<Path>>(
+    path: P,
+    style: PathStyle,
+    options: &CygpathOptions,
+) -> Option<PathBuf> {
+    let s = path.as_ref().to_str()?;
+    let root = match &options.root {
+        Some(root) => match parse(root.to_str()?, options.cygdrive)? {
+            Parsed::Windows(anchor, components) => Some((anchor, components)),
+            _ => None,
+        },
+        None => None,
+    };
+    let parsed = match parse(s, options.cygdrive)? {
+        Parsed::Unix(below) if style != PathStyle::Unix => {
+            let (anchor, mut components) = root?;
+            components.extend(below);
+            Parsed::Windows(anchor, components)
+        }
+        Parsed::Windows(anchor, components) if style == PathStyle::Unix => match root {
+            Some((root_anchor, root_components))
+                if anchor.eq_ignore_case(&root_anchor)
+                    && components.len() >= root_components.len()
+                    && components
+                        .iter()
+                        .zip(&root_components)
+                        .all(|(a, b)| a.eq_ignore_ascii_case(b)) =>
+            {
+                Parsed::Unix(components[root_components.len()..].to_vec())
+            }
+            _ => Parsed::Windows(anchor, components),
+        },
+        parsed => parsed,
+    };
+
+    let separator = if style == PathStyle::Mixed || style == PathStyle::Unix {
+        "/"
+    } else {
+        "\\"
+    };
+    let (mut converted, components) = match parsed {
+        Parsed::Windows(Anchor::Drive(letter), components) if style == PathStyle::Unix => {
+            let mount = options.cygdrive.trim_end_matches('/');
+            let letter = letter.to_ascii_lowercase();
+            (format!("{mount}/{letter}"), components)
+        }
+        Parsed::Windows(Anchor::Drive(letter), components) => {
+            let letter = letter.to_ascii_uppercase();
+            (format!("{letter}:{separator}"), components)
+        }
+        Parsed::Windows(Anchor::Unc(server, share), components) => (
+            format!("{separator}{separator}{server}{separator}{share}"),
+            components,
+        ),
+        Parsed::Unix(components) => ("/".to_string(), components),
+        Parsed::Relative(components) => (String::new(), components),
+    };
+    for component in components {
+        if !converted.is_empty() && !converted.ends_with(separator) {
+            converted.push_str(separator);
+        }
+        match component {
+            "." | ".." => converted.push_str(component),
+            _ if style == PathStyle::Dos => {
+                let short = fat::short_name(component, [""; 0]);
+                if short.eq_ignore_ascii_case(component) {
+                    converted.push_str(component);
+                } else {
+                    converted.push_str(&short);
+                }
+            }
+            _ => converted.push_str(component),
+        }
+    }
+    Some(PathBuf::from(converted))
+}
+
+pub(crate) fn convert_path(path: &Path, style: PathStyle) -> Option<PathBuf> {
+    convert_path_with(path, style, &CygpathOptions::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{convert_path_with, CygpathOptions, PathStyle};
+
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_convert_path_with() {
+        let cygwin = CygpathOptions::CYGWIN;
+        let msys = CygpathOptions {
+            root: Some(PathBuf::from(r"C:\msys64")),
+            ..CygpathOptions::MSYS2
+        };
+        let tests = &[
+            (
+                r"C:\Users\me",
+                PathStyle::Unix,
+                &cygwin,
+                Some("/cygdrive/c/Users/me"),
+            ),
+            (
+                "c:/Users/me",
+                PathStyle::Windows,
+                &cygwin,
+                Some(r"C:\Users\me"),
+            ),
+            (
+                "/cygdrive/d/src/x.c",
+                PathStyle::Windows,
+                &cygwin,
+                Some(r"D:\src\x.c"),
+            ),
+            (
+                "/cygdrive/d/src/x.c",
+                PathStyle::Mixed,
+                &cygwin,
+                Some("D:/src/x.c"),
+            ),
+            ("/cygdrive/d", PathStyle::Windows, &cygwin, Some(r"D:\")),
+            (r"C:\", PathStyle::Unix, &cygwin, Some("/cygdrive/c")),
+            ("/usr/bin", PathStyle::Windows, &cygwin, None),
+            ("/usr/bin", PathStyle::Unix, &cygwin, Some("/usr/bin")),
+            ("/c/x", PathStyle::Windows, &cygwin, None),
+            (
+                r"\\server\share\dir",
+                PathStyle::Unix,
+                &cygwin,
+                Some("//server/share/dir"),
+            ),
+            (
+                "//server/share/dir",
+                PathStyle::Windows,
+                &cygwin,
+                Some(r"\\server\share\dir"),
+            ),
+            (
+                r"src\..\lib\x.rs",
+                PathStyle::Unix,
+                &cygwin,
+                Some("src/../lib/x.rs"),
+            ),
+            (
+                "src/lib/x.rs",
+                PathStyle::Windows,
+                &cygwin,
+                Some(r"src\lib\x.rs"),
+            ),
+            ("C:relative", PathStyle::Unix, &cygwin, None),
+            (r"C:\Users\me", PathStyle::Unix, &msys, Some("/c/Users/me")),
+            (
+                "/c/Users/me",
+                PathStyle::Windows,
+                &msys,
+                Some(r"C:\Users\me"),
+            ),
+            (
+                "/usr//bin/",
+                PathStyle::Windows,
+                &msys,
+                Some(r"C:\msys64\usr\bin"),
+            ),
+            ("/", PathStyle::Mixed, &msys, Some("C:/msys64")),
+            (
+                r"c:\MSYS64\usr\bin",
+                PathStyle::Unix,
+                &msys,
+                Some("/usr/bin"),
+            ),
+            (r"C:\msys64", PathStyle::Unix, &msys, Some("/")),
+            (
+                r"C:\msys64x\y",
+                PathStyle::Unix,
+                &msys,
+                Some("/c/msys64x/y"),
+            ),
+            (
+                r"C:\Program Files\My App\readme.txt",
+                PathStyle::Dos,
+                &cygwin,
+                Some(r"C:\PROGRA~1\MYAPP~1\readme.txt"),
+            ),
+            (
+                "/usr/share",
+                PathStyle::Dos,
+                &msys,
+                Some(r"C:\msys64\usr\share"),
+            ),
+        ];
+
+        for test in tests {
+            assert_eq!(
+                convert_path_with(test.0, test.1, test.2),
+                test.3.map(PathBuf::from),
+                "{} {:?}",
+                test.0,
+                test.1
+            );
+        }
+    }
+}
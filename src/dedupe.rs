@@ -0,0 +1,215 @@
+//! Dropping paths that are other spellings of one already seen.
+
+#[cfg(feature = "unicode")]
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::{Component, Path};
+use std::str;
+
+#[cfg(feature = "unicode")]
+use unicode_normalization::UnicodeNormalization;
+
+use crate::{normalize, Platform};
+
+/// What makes two spellings of a path the same for [`dedupe_equivalent`]. Separators, repeated
+/// separators and `.` components never count.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DedupeOptions {
+    /// Whether `..` is resolved lexically first, so `a/b/../c` is `a/c`. Wrong if `b` is a symlink.
+    pub normalize: bool,
+    /// Whether case is ignored, as Windows and macOS filesystems do by default.
+    pub case_fold: bool,
+    /// Whether names are compared NFC normalized, so an `é` written as `e` and a combining accent is `é`.
+    /// Without the `unicode` feature names are always compared as written.
+    #[cfg(feature = "unicode")]
+    pub unicode_normalize: bool,
+}
+
+impl Default for DedupeOptions {
+    /// Normalizing, comparing NFC with the `unicode` feature, and ignoring case on Windows and macOS.
+    fn default() -> Self {
+        DedupeOptions::for_platform(Platform::Native)
+    }
+}
+
+impl DedupeOptions {
+    /// Normalizing, comparing NFC with the `unicode` feature, and ignoring case if `platform` does.
+    pub fn for_platform(platform: Platform) -> Self {
+        DedupeOptions {
+            normalize: true,
+            case_fold: platform.ignores_case(),
+            #[cfg(feature = "unicode")]
+            unicode_normalize: true,
+        }
+    }
+}
+
+/// The result of [`dedupe_equivalent`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Deduped<P> {
+    /// The first spelling of every distinct path, in input order.
+    pub unique: Vec<P>,
+    /// The inputs that were dropped, each with the index in `unique` of the path it is a spelling of.
+    pub merged: Vec<(P, usize)>,
+}
+
+/// The components' bytes, with those that are Unicode NFC normalized and case folded as `options`
+/// say. Other components are compared byte for byte, so distinct non-UTF-8 names stay distinct.
+fn key(path: &Path, options: &DedupeOptions) -> Vec<Vec<u8>> {
+    let normalized;
+    let path = if options.normalize {
+        normalized = normalize::normalize(path);
+        &normalized
+    } else {
+        path
+    };
+    path.components()
+        .filter(|component| !matches!(component, Component::CurDir))
+        .map(|component| {
+            let bytes = match component {
+                Component::RootDir => b"/",
+                other => other.as_os_str().as_encoded_bytes(),
+            };
+            let Ok(part) = str::from_utf8(bytes) else {
+                return bytes.to_vec();
+            };
+            #[cfg(feature = "unicode")]
+            let part = if options.unicode_normalize {
+                part.nfc().collect::<String>().into()
+            } else {
+                Cow::Borrowed(part)
+            };
+            if options.case_fold {
+                part.to_lowercase().into_bytes()
+            } else {
+                part.as_bytes().to_vec()
+            }
+        })
+        .collect()
+}
+
+/// Keeps the first of every group of paths that are spellings of the same path under `options`,
+/// reporting which inputs were merged into which, for combining path lists from several sources.
+///
+/// Comparison is lexical: symlinks and hard links to the same file are not found, and with
+/// [`normalize`](DedupeOptions::normalize) a `..` after a symlink can merge paths that differ.
+///
+/// ```rust
+/// use pathext::{dedupe_equivalent, DedupeOptions};
+///
+/// let options = DedupeOptions { case_fold: true, ..DedupeOptions::default() };
+/// let deduped = dedupe_equivalent(["src/lib.rs", "src/../src/lib.rs", "SRC/Lib.rs", "src/main.rs"], options);
+/// assert_eq!(deduped.unique, ["src/lib.rs", "src/main.rs"]);
+/// assert_eq!(deduped.merged, [("src/../src/lib.rs", 0), ("SRC/Lib.rs", 0)]);
+/// ```
+pub fn dedupe_equivalent<I, P>(paths: I, options: DedupeOptions) -> Deduped<P>
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    let mut seen = HashMap::new();
+    let mut deduped = Deduped {
+        unique: Vec::new(),
+        merged: Vec::new(),
+    };
+    for path in paths {
+        let key = key(path.as_ref(), &options);
+        match seen.get(&key) {
+            Some(&index) => deduped.merged.push((path, index)),
+            None => {
+                seen.insert(key, deduped.unique.len());
+                deduped.unique.push(path);
+            }
+        }
+    }
+    deduped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{dedupe_equivalent, DedupeOptions};
+    #[cfg(feature = "unicode")]
+    use crate::Platform;
+
+    use std::path::{Path, PathBuf};
+
+    const EXACT: DedupeOptions = DedupeOptions {
+        normalize: false,
+        case_fold: false,
+        #[cfg(feature = "unicode")]
+        unicode_normalize: false,
+    };
+
+    #[test]
+    fn test_dedupe_exact() {
+        let deduped =
+            dedupe_equivalent(["a/b", "a//b", "./a/b/", "a/c/../b", "/a/b", "A/b"], EXACT);
+        assert_eq!(deduped.unique, ["a/b", "a/c/../b", "/a/b", "A/b"]);
+        assert_eq!(deduped.merged, [("a//b", 0), ("./a/b/", 0)]);
+    }
+
+    #[cfg(feature = "unicode")]
+    #[test]
+    fn test_dedupe_options() {
+        let paths = ["café/x", "cafe\u{301}/x", "CAFÉ/x", "café/y/../x"];
+        let tests: &[(DedupeOptions, &[&str])] = &[
+            (EXACT, &paths),
+            (
+                DedupeOptions {
+                    unicode_normalize: true,
+                    ..EXACT
+                },
+                &["café/x", "CAFÉ/x", "café/y/../x"],
+            ),
+            (
+                DedupeOptions {
+                    normalize: true,
+                    unicode_normalize: true,
+                    ..EXACT
+                },
+                &["café/x", "CAFÉ/x"],
+            ),
+            (DedupeOptions::for_platform(Platform::Windows), &["café/x"]),
+            (
+                DedupeOptions::for_platform(Platform::Unix),
+                &["café/x", "CAFÉ/x"],
+            ),
+        ];
+
+        for test in tests {
+            assert_eq!(
+                dedupe_equivalent(paths, test.0).unique,
+                test.1,
+                "{:?}",
+                test.0
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_dedupe_non_utf8() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let paths = [b"a/\xff".as_slice(), b"a/\xfe", b"A/\xff", b"a//\xff"]
+            .map(|bytes| Path::new(OsStr::from_bytes(bytes)));
+        let deduped = dedupe_equivalent(
+            paths,
+            DedupeOptions {
+                case_fold: true,
+                ..EXACT
+            },
+        );
+        assert_eq!(deduped.unique, paths[..2]);
+        assert_eq!(deduped.merged, [(paths[2], 0), (paths[3], 0)]);
+    }
+
+    #[test]
+    fn test_dedupe_owned() {
+        let paths = vec![PathBuf::from("x"), PathBuf::from("./x"), PathBuf::from("y")];
+        let deduped = dedupe_equivalent(paths, DedupeOptions::default());
+        assert_eq!(deduped.unique, [PathBuf::from("x"), PathBuf::from("y")]);
+        assert_eq!(deduped.merged, [(PathBuf::from("./x"), 0)]);
+    }
+}
@@ -0,0 +1,296 @@
+//! Makefile-style dependency files, the `.d` files `gcc -MD`, `clang -MD` and `rustc --emit=dep-info`
+//! write and make and Ninja read.
+//!
+//! Reading follows what those tools write: `\ `, `\#` and `\:` for literal spaces, `#` and
+//! colons, backslashes before those and line ends doubled, `$$` for `$`, and a `\` ending a line continuing the
+//! rule on the next. Other backslashes are literal, so Windows paths read as they are, and a colon
+//! only ends the targets when whitespace or the end of the line follows it, so `C:\x` stays a
+//! path. `#` starts a comment. Writing escapes with
+//! [`escape_for_make`](crate::PathStrExt::escape_for_make).
+//!
+//! ```rust
+//! use pathext::depfile;
+//! use std::path::PathBuf;
+//!
+//! let rules = depfile::parse("obj/main.o: src/main.c \\\n  include/My\\ Header.h\n").unwrap();
+//! assert_eq!(rules[0].targets, [PathBuf::from("obj/main.o")]);
+//! assert_eq!(
+//!     rules[0].prerequisites,
+//!     [PathBuf::from("src/main.c"), PathBuf::from("include/My Header.h")]
+//! );
+//!
+//! let mut written = Vec::new();
+//! depfile::write(&mut written, &rules).unwrap();
+//! assert_eq!(depfile::parse(std::str::from_utf8(&written).unwrap()).unwrap(), rules);
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Write};
+use std::iter::Peekable;
+use std::path::PathBuf;
+use std::str::Chars;
+
+use crate::PathStrExt;
+
+/// One rule: every target depends on every prerequisite. `gcc -MP` adds rules without
+/// prerequisites for each header, so deleted headers don't break the build.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DepRule {
+    pub targets: Vec<PathBuf>,
+    pub prerequisites: Vec<PathBuf>,
+}
+
+/// Why a depfile couldn't be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DepfileError {
+    /// The 1-based line the rule starts on.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for DepfileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "depfile line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for DepfileError {}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Colon,
+    Newline,
+}
+
+struct Lexer<'a> {
+    chars: Peekable<Chars<'a>>,
+    word: String,
+    /// Each token with the line it's on.
+    tokens: Vec<(Token, usize)>,
+    line: usize,
+}
+
+impl Lexer<'_> {
+    fn end_word(&mut self) {
+        if !self.word.is_empty() {
+            let word = std::mem::take(&mut self.word);
+            self.tokens.push((Token::Word(word), self.line));
+        }
+    }
+
+    fn at_separator(&mut self) -> bool {
+        matches!(self.chars.peek(), None | Some(' ' | '\t' | '\n' | '\r'))
+    }
+
+    fn backslashes(&mut self) {
+        let mut count = 1;
+        while self.chars.next_if_eq(&'\\').is_some() {
+            count += 1;
+        }
+        match self.chars.peek() {
+            Some(' ' | '\t' | '#' | ':') => {
+                self.word.extend(std::iter::repeat_n('\\', count / 2));
+                if count % 2 == 1 {
+                    self.word.extend(self.chars.next());
+                }
+            }
+            Some('\n' | '\r') => {
+                self.word.extend(std::iter::repeat_n('\\', count / 2));
+                if count % 2 == 1 {
+                    self.chars.next_if_eq(&'\r');
+                    if self.chars.next_if_eq(&'\n').is_some() {
+                        self.end_word();
+                        self.line += 1;
+                    }
+                }
+            }
+            _ => self.word.extend(std::iter::repeat_n('\\', count)),
+        }
+    }
+
+    fn run(mut self) -> Vec<(Token, usize)> {
+        while let Some(c) = self.chars.next() {
+            match c {
+                '\\' => self.backslashes(),
+                '$' => {
+                    self.chars.next_if_eq(&'$');
+                    self.word.push('$');
+                }
+                ':' if self.at_separator() => {
+                    self.end_word();
+                    self.tokens.push((Token::Colon, self.line));
+                }
+                '#' => while self.chars.next_if(|&c| c != '\n').is_some() {},
+                '\n' => {
+                    self.end_word();
+                    self.tokens.push((Token::Newline, self.line));
+                    self.line += 1;
+                }
+                ' ' | '\t' | '\r' => self.end_word(),
+                c => self.word.push(c),
+            }
+        }
+        self.end_word();
+        self.tokens
+    }
+}
+
+/// Reads the rules of a depfile, in order. Rules for the same target aren't merged.
+pub fn parse(text: &str) -> Result<Vec<DepRule>, DepfileError> {
+    let tokens = Lexer {
+        chars: text.chars().peekable(),
+        word: String::new(),
+        tokens: Vec::new(),
+        line: 1,
+    }
+    .run();
+    let mut rules = Vec::new();
+    for tokens in tokens.split(|(token, _)| *token == Token::Newline) {
+        let Some((_, line)) = tokens.first() else {
+            continue;
+        };
+        let error = |message: &str| DepfileError {
+            line: *line,
+            message: message.to_string(),
+        };
+        let colon = tokens
+            .iter()
+            .position(|(token, _)| *token == Token::Colon)
+            .ok_or_else(|| error("no `:` after the targets"))?;
+        let paths = |tokens: &[(Token, usize)]| -> Result<Vec<PathBuf>, DepfileError> {
+            tokens
+                .iter()
+                .map(|(token, _)| match token {
+                    Token::Word(word) => Ok(PathBuf::from(word)),
+                    _ => Err(error("more than one `:`")),
+                })
+                .collect()
+        };
+        let rule = DepRule {
+            targets: paths(&tokens[..colon])?,
+            prerequisites: paths(&tokens[colon + 1..])?,
+        };
+        if rule.targets.is_empty() {
+            return Err(error("no targets before the `:`"));
+        }
+        rules.push(rule);
+    }
+    Ok(rules)
+}
+
+/// Writes the rules, each prerequisite after the first on its own continued line. Paths make can't
+/// read back, with a line break or not Unicode, are an [`io::ErrorKind::InvalidData`] error.
+pub fn write<W: Write>(mut writer: W, rules: &[DepRule]) -> io::Result<()> {
+    let escape = |path: &PathBuf| {
+        path.escape_for_make().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{} can't be written to a depfile", path.display()),
+            )
+        })
+    };
+    for rule in rules {
+        let targets: Vec<String> = rule.targets.iter().map(escape).collect::<Result<_, _>>()?;
+        write!(writer, "{}:", targets.join(" "))?;
+        for (i, prerequisite) in rule.prerequisites.iter().enumerate() {
+            let separator = if i == 0 { " " } else { " \\\n  " };
+            write!(writer, "{separator}{}", escape(prerequisite)?)?;
+        }
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, write, DepRule, DepfileError};
+
+    use std::path::PathBuf;
+
+    fn rule(targets: &[&str], prerequisites: &[&str]) -> DepRule {
+        DepRule {
+            targets: targets.iter().map(PathBuf::from).collect(),
+            prerequisites: prerequisites.iter().map(PathBuf::from).collect(),
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let tests: &[(&str, &[DepRule])] = &[
+            ("", &[]),
+            ("a.o: a.c a.h", &[rule(&["a.o"], &["a.c", "a.h"])]),
+            (
+                "a.o b.o : \\\n  a.c \\\r\n  b.c\n\nb.h:\n",
+                &[rule(&["a.o", "b.o"], &["a.c", "b.c"]), rule(&["b.h"], &[])],
+            ),
+            (
+                r"x.o: My\ Files/x.c issue\#1.h cost$$.h odd\\\ name.h dir\\ next",
+                &[rule(
+                    &["x.o"],
+                    &[
+                        "My Files/x.c",
+                        "issue#1.h",
+                        "cost$.h",
+                        r"odd\ name.h",
+                        r"dir\",
+                        "next",
+                    ],
+                )],
+            ),
+            (
+                r"C:\out\x.obj: C:\src\x.c c:/inc/y.h a\:b",
+                &[rule(
+                    &[r"C:\out\x.obj"],
+                    &[r"C:\src\x.c", "c:/inc/y.h", "a:b"],
+                )],
+            ),
+            (
+                "# generated\nx.o: x.c # trailing\n",
+                &[rule(&["x.o"], &["x.c"])],
+            ),
+        ];
+
+        for test in tests {
+            assert_eq!(parse(test.0).unwrap(), test.1, "{}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let tests = &[
+            ("x.o x.c", 1),
+            ("a: b\n\n: c", 3),
+            ("a: b: c", 1),
+            ("a: \\\nb\nc", 3),
+        ];
+
+        for test in tests {
+            let DepfileError { line, .. } = parse(test.0).unwrap_err();
+            assert_eq!(line, test.1, "{}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_write() {
+        let rules = [
+            rule(
+                &["out/my app.o"],
+                &["src/my app.c", "inc/$x.h", "C:\\inc\\"],
+            ),
+            rule(&["inc/$x.h"], &[]),
+        ];
+        let mut written = Vec::new();
+        write(&mut written, &rules).unwrap();
+        let written = String::from_utf8(written).unwrap();
+        assert_eq!(
+            written,
+            "out/my\\ app.o: src/my\\ app.c \\\n  inc/$$x.h \\\n  C:\\inc\\\\\ninc/$$x.h:\n"
+        );
+        assert_eq!(parse(&written).unwrap(), rules);
+
+        let error = write(Vec::new(), &[rule(&["a\nb"], &[])]).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,184 @@
+//! Mapping dotted package and module names, as in Java and Python, to paths and back.
+
+use std::path::{Component, Path, PathBuf};
+
+/// How names are written for [`dotted_to_path_with`] and [`path_to_dotted_with`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DottedOptions {
+    /// What goes between names: `.` for Java and Python, `::` for C++ namespaces, `\` for PHP ones.
+    pub separator: &'static str,
+    /// A file stem standing for its directory, like Python's `__init__`, so `pkg/__init__.py` is `pkg`.
+    pub package_file: Option<&'static str>,
+}
+
+impl DottedOptions {
+    /// Java packages and classes: `com.example.App` is `com/example/App.java`.
+    pub const JAVA: DottedOptions = DottedOptions {
+        separator: ".",
+        package_file: None,
+    };
+    /// Python modules: `pkg.mod` is `pkg/mod.py`, and `pkg/__init__.py` is `pkg`.
+    pub const PYTHON: DottedOptions = DottedOptions {
+        separator: ".",
+        package_file: Some("__init__"),
+    };
+}
+
+impl Default for DottedOptions {
+    /// [`DottedOptions::JAVA`], dots and no package files.
+    fn default() -> Self {
+        DottedOptions::JAVA
+    }
+}
+
+fn is_name(name: &str, separator: &str) -> bool {
+    !name.is_empty()
+        && name != "."
+        && name != ".."
+        && !name.contains(['/', '\\'])
+        && !name.contains(separator)
+}
+
+/// [`dotted_to_path_with`] with dots between names.
+///
+/// ```rust
+/// use pathext::{dotted_to_path, path_to_dotted};
+/// use std::path::PathBuf;
+///
+/// assert_eq!(dotted_to_path("com.example.App", "java"), Some(PathBuf::from("com/example/App.java")));
+/// assert_eq!(dotted_to_path("com.example", ""), Some(PathBuf::from("com/example")));
+/// assert_eq!(path_to_dotted("com/example/App.java").as_deref(), Some("com.example.App"));
+/// ```
+pub fn dotted_to_path(dotted: &str, extension: &str) -> Option<PathBuf> {
+    dotted_to_path_with(dotted, extension, &DottedOptions::default())
+}
+
+/// The relative path of the file named `dotted`, its names split on the separator and the last
+/// given `extension`, with or without its `.`. An empty extension gives the directory of a package.
+/// `None` if a name is empty or couldn't be a file name, like `..` or one with a path separator.
+///
+/// A Python module may also be a package, at `dotted_to_path("pkg", "")` joined with
+/// `__init__.py`: which of the two exists only the filesystem says.
+pub fn dotted_to_path_with(
+    dotted: &str,
+    extension: &str,
+    options: &DottedOptions,
+) -> Option<PathBuf> {
+    let mut path = PathBuf::new();
+    for name in dotted.split(options.separator) {
+        if !is_name(name, "/") {
+            return None;
+        }
+        path.push(name);
+    }
+    let extension = extension.strip_prefix('.').unwrap_or(extension);
+    if !extension.is_empty() {
+        let mut name = path.file_name()?.to_os_string();
+        name.push(".");
+        name.push(extension);
+        path.set_file_name(name);
+    }
+    Some(path)
+}
+
+/// [`path_to_dotted_with`] with dots between names.
+pub fn path_to_dotted<P: AsRef<Path>>(path: P) -> Option<String> {
+    path_to_dotted_with(path, &DottedOptions::default())
+}
+
+/// The dotted name of the relative path of a source file or package directory, the last extension
+/// of its file name dropped, along with the name itself if it's the package file. `None` for absolute
+/// paths, ones with `..` or that aren't Unicode, and names holding the separator, which wouldn't map back.
+pub fn path_to_dotted_with<P: AsRef<Path>>(path: P, options: &DottedOptions) -> Option<String> {
+    let mut names = Vec::new();
+    for component in path.as_ref().components() {
+        match component {
+            Component::Normal(name) => names.push(name.to_str()?),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    let last = names.pop()?;
+    let stem = match last.rsplit_once('.') {
+        Some((stem, _)) if !stem.is_empty() => stem,
+        _ => last,
+    };
+    if options.package_file != Some(stem) {
+        names.push(stem);
+    }
+    if names.is_empty() || !names.iter().all(|name| is_name(name, options.separator)) {
+        return None;
+    }
+    Some(names.join(options.separator))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{dotted_to_path_with, path_to_dotted_with, DottedOptions};
+
+    use std::path::PathBuf;
+
+    const NAMESPACES: DottedOptions = DottedOptions {
+        separator: "::",
+        package_file: None,
+    };
+
+    #[test]
+    fn test_dotted_to_path() {
+        let tests = &[
+            (
+                "com.example.App",
+                "java",
+                DottedOptions::JAVA,
+                Some("com/example/App.java"),
+            ),
+            ("pkg.mod", ".py", DottedOptions::PYTHON, Some("pkg/mod.py")),
+            ("pkg", "", DottedOptions::PYTHON, Some("pkg")),
+            ("std::vector", "hpp", NAMESPACES, Some("std/vector.hpp")),
+            ("a.b", "rs", NAMESPACES, Some("a.b.rs")),
+            ("com..App", "java", DottedOptions::JAVA, None),
+            ("", "java", DottedOptions::JAVA, None),
+            ("a::..::b", "h", NAMESPACES, None),
+            ("a/b.c", "py", DottedOptions::PYTHON, None),
+        ];
+
+        for test in tests {
+            assert_eq!(
+                dotted_to_path_with(test.0, test.1, &test.2),
+                test.3.map(PathBuf::from),
+                "{}",
+                test.0
+            );
+        }
+    }
+
+    #[test]
+    fn test_path_to_dotted() {
+        let tests = &[
+            (
+                "com/example/App.java",
+                DottedOptions::JAVA,
+                Some("com.example.App"),
+            ),
+            ("./com/example", DottedOptions::JAVA, Some("com.example")),
+            ("pkg/__init__.py", DottedOptions::PYTHON, Some("pkg")),
+            ("pkg/__init__.py", DottedOptions::JAVA, Some("pkg.__init__")),
+            ("pkg/mod.pyi", DottedOptions::PYTHON, Some("pkg.mod")),
+            ("__init__.py", DottedOptions::PYTHON, None),
+            ("std/vector.hpp", NAMESPACES, Some("std::vector")),
+            ("v1.2/App.java", DottedOptions::JAVA, None),
+            ("pkg/.hidden", DottedOptions::PYTHON, None),
+            ("/abs/App.java", DottedOptions::JAVA, None),
+            ("../App.java", DottedOptions::JAVA, None),
+        ];
+
+        for test in tests {
+            assert_eq!(
+                path_to_dotted_with(test.0, &test.1).as_deref(),
+                test.2,
+                "{}",
+                test.0
+            );
+        }
+    }
+}
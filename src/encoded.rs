@@ -0,0 +1,100 @@
+//! The platform's encoded bytes of a path, for byte-level algorithms written once for every OS.
+
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+pub(crate) fn as_encoded_bytes(path: &Path) -> &[u8] {
+    path.as_os_str().as_encoded_bytes()
+}
+
+/// The path's bytes for storing or sending: what the OS has on Unix, UTF-8 elsewhere, `None` there
+/// for a path that isn't Unicode.
+#[cfg(unix)]
+pub(crate) fn portable_bytes(path: &Path) -> Option<&[u8]> {
+    use std::os::unix::ffi::OsStrExt;
+
+    Some(path.as_os_str().as_bytes())
+}
+
+#[cfg(not(unix))]
+pub(crate) fn portable_bytes(path: &Path) -> Option<&[u8]> {
+    path.to_str().map(str::as_bytes)
+}
+
+/// The path back from [`portable_bytes`], `None` off Unix for bytes that aren't UTF-8.
+#[cfg(unix)]
+pub(crate) fn path_from_bytes(bytes: Vec<u8>) -> Option<PathBuf> {
+    use std::ffi::OsString;
+    use std::os::unix::ffi::OsStringExt;
+
+    Some(PathBuf::from(OsString::from_vec(bytes)))
+}
+
+#[cfg(not(unix))]
+pub(crate) fn path_from_bytes(bytes: Vec<u8>) -> Option<PathBuf> {
+    String::from_utf8(bytes).ok().map(PathBuf::from)
+}
+
+/// Turns bytes from [`PathStrExt::as_encoded_bytes_compat`](crate::PathStrExt::as_encoded_bytes_compat)
+/// back into a path.
+///
+/// The bytes are UTF-8 wherever the path is valid Unicode, on every platform, with whatever the
+/// OS allows beyond that (arbitrary bytes on Unix, unpaired surrogates on Windows) encoded in an
+/// unspecified way. So they can be searched and split at ASCII characters or UTF-8 sequences, but
+/// aren't a format: don't store them, send them to another process or compare them across
+/// platforms or Rust versions.
+///
+/// # Safety
+///
+/// `bytes` must have come from `as_encoded_bytes_compat` (or [`OsStr::as_encoded_bytes`]) in the
+/// same program, either whole or split immediately before or after a non-empty UTF-8 substring,
+/// and possibly joined to other such bytes.
+///
+/// ```rust
+/// use pathext::{from_encoded_bytes_compat, PathStrExt};
+/// use std::path::Path;
+///
+/// let path = Path::new("dir/file.txt");
+/// let bytes = path.as_encoded_bytes_compat();
+/// let dot = bytes.iter().rposition(|&b| b == b'.').unwrap();
+/// // SAFETY: split right before the ASCII `.`
+/// let stem = unsafe { from_encoded_bytes_compat(&bytes[..dot]) };
+/// assert_eq!(stem, Path::new("dir/file"));
+/// ```
+pub unsafe fn from_encoded_bytes_compat(bytes: &[u8]) -> &Path {
+    // SAFETY: the caller upholds `from_encoded_bytes_unchecked`'s contract, which ours restates.
+    Path::new(unsafe { OsStr::from_encoded_bytes_unchecked(bytes) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{as_encoded_bytes, from_encoded_bytes_compat};
+
+    use std::path::Path;
+
+    #[test]
+    fn test_encoded_bytes_roundtrip() {
+        let tests = &["", "plain/ascii.txt", "ünïcödé/名前", r"C:\windows\path"];
+
+        for test in tests {
+            let bytes = as_encoded_bytes(Path::new(test));
+            assert_eq!(bytes, test.as_bytes());
+            // SAFETY: the bytes are whole
+            assert_eq!(unsafe { from_encoded_bytes_compat(bytes) }, Path::new(test));
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_encoded_bytes_non_utf8() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let path = Path::new(OsStr::from_bytes(b"bad\xff/name"));
+        let bytes = as_encoded_bytes(path);
+        let slash = bytes.iter().position(|&b| b == b'/').unwrap();
+        // SAFETY: split right before the ASCII `/`
+        let first = unsafe { from_encoded_bytes_compat(&bytes[..slash]) };
+        assert_eq!(first, Path::new(OsStr::from_bytes(b"bad\xff")));
+    }
+}
@@ -0,0 +1,142 @@
+//! Archive entry names: always `/`-separated, whatever the host uses.
+
+use std::path::{Path, PathBuf};
+
+use crate::VirtualPath;
+
+/// Path operations for zip and tar entry names, which archive crates hand out as strings.
+///
+/// This is implemented for `str` rather than `AsRef<str>` so that with both this and the
+/// [`prelude`](crate::prelude) imported, string values get these entry name semantics.
+///
+/// ```rust
+/// use pathext::EntryNameExt;
+/// use std::path::Path;
+///
+/// assert!("docs/guide/intro.md".has_component("guide"));
+/// assert_eq!("docs.v2/intro.tar.gz".strip_extensions(), "docs.v2/intro");
+/// assert_eq!("docs/intro.md".secure_join("/tmp/out"), Some(Path::new("/tmp/out/docs/intro.md").to_path_buf()));
+/// assert_eq!("../../etc/passwd".secure_join("/tmp/out"), None);
+/// ```
+pub trait EntryNameExt {
+    /// The entry name as a `/`-separated [`VirtualPath`].
+    fn entry_path(&self) -> VirtualPath<'_>;
+    /// Checks if the supplied component is present in total in the entry name
+    fn has_component<S: AsRef<str>>(&self, component: S) -> bool;
+    /// Whether the entry name ends in `/`, which archives use to mark directories
+    fn is_dir_entry(&self) -> bool;
+    /// Joins the entry name onto `base`, or `None` if extracting it there would be unsafe.
+    ///
+    /// Absolute names, `..` climbing out of `base` and components containing `\`, `:` or NUL
+    /// (which Windows would read as separators, drives or streams) are all rejected.
+    fn secure_join<P: AsRef<Path>>(&self, base: P) -> Option<PathBuf>;
+    /// Strips all extensions from the final segment, leaving dots in directory names alone
+    fn strip_extensions(&self) -> &str;
+}
+
+impl EntryNameExt for str {
+    fn entry_path(&self) -> VirtualPath<'_> {
+        VirtualPath::slash(self)
+    }
+
+    fn has_component<S: AsRef<str>>(&self, component: S) -> bool {
+        self.entry_path().has_component(component)
+    }
+
+    fn is_dir_entry(&self) -> bool {
+        self.ends_with('/')
+    }
+
+    fn secure_join<P: AsRef<Path>>(&self, base: P) -> Option<PathBuf> {
+        if self.starts_with('/') {
+            return None;
+        }
+
+        let mut parts: Vec<&str> = Vec::new();
+        for component in self.split('/') {
+            match component {
+                "" | "." => {}
+                ".." => {
+                    parts.pop()?;
+                }
+                c if c.contains(['\\', ':', '\0']) => return None,
+                c => parts.push(c),
+            }
+        }
+
+        let mut joined = base.as_ref().to_path_buf();
+        joined.extend(parts);
+        Some(joined)
+    }
+
+    fn strip_extensions(&self) -> &str {
+        self.entry_path().strip_extensions()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EntryNameExt;
+
+    use std::path::Path;
+
+    #[test]
+    fn test_has_component() {
+        let tests = &[
+            ("a/b/c.txt", "b", true),
+            ("a/b/c.txt", "c.txt", true),
+            ("a\\b/c.txt", "b", false),
+            ("a/b/", "b", true),
+        ];
+
+        for test in tests {
+            assert_eq!(test.0.has_component(test.1), test.2);
+        }
+    }
+
+    #[test]
+    fn test_is_dir_entry() {
+        assert!("a/b/".is_dir_entry());
+        assert!(!"a/b".is_dir_entry());
+    }
+
+    #[test]
+    fn test_secure_join() {
+        let tests = &[
+            ("a/b.txt", Some("/out/a/b.txt")),
+            ("./a//b.txt", Some("/out/a/b.txt")),
+            ("a/../b.txt", Some("/out/b.txt")),
+            ("dir/", Some("/out/dir")),
+            ("", Some("/out")),
+            ("../b.txt", None),
+            ("a/../../b.txt", None),
+            ("/etc/passwd", None),
+            ("..\\..\\evil", None),
+            ("C:/evil", None),
+            ("a/file.txt:stream", None),
+        ];
+
+        for test in tests {
+            assert_eq!(
+                test.0.secure_join("/out"),
+                test.1.map(|p| Path::new(p).to_path_buf()),
+                "{}",
+                test.0
+            );
+        }
+    }
+
+    #[test]
+    fn test_strip_extensions() {
+        let tests = &[
+            ("something.tar.gz", "something"),
+            ("v1.2/notes.txt", "v1.2/notes"),
+            ("v1.2/", "v1"),
+            ("noext", "noext"),
+        ];
+
+        for test in tests {
+            assert_eq!(test.0.strip_extensions(), test.1);
+        }
+    }
+}
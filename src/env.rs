@@ -0,0 +1,319 @@
+//! Environment variable references in path strings.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Replaces `$NAME`, `${NAME}` and `%NAME%` with what `lookup` gives for `NAME`, leaving
+/// references to unknown variables as they were written.
+pub(crate) fn expand_vars(s: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let is_name = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let mut expanded = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(at) = rest.find(['$', '%']) {
+        expanded.push_str(&rest[..at]);
+        let after = &rest[at + 1..];
+        let (name, len) = if rest[at..].starts_with('%') {
+            match after.find('%') {
+                Some(end) if end > 0 && after[..end].chars().all(is_name) => {
+                    (&after[..end], end + 2)
+                }
+                _ => ("", 1),
+            }
+        } else if let Some(braced) = after.strip_prefix('{') {
+            match braced.find('}') {
+                Some(end) if end > 0 => (&braced[..end], end + 3),
+                _ => ("", 1),
+            }
+        } else {
+            let end = after.find(|c: char| !is_name(c)).unwrap_or(after.len());
+            let starts_well = after.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_');
+            (if starts_well { &after[..end] } else { "" }, end + 1)
+        };
+        match (!name.is_empty()).then(|| lookup(name)).flatten() {
+            Some(value) => expanded.push_str(&value),
+            None => expanded.push_str(&rest[at..at + len]),
+        }
+        rest = &rest[at + len..];
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+/// What expanding a path depends on, captured so the same path always expands the same way: for
+/// tests, and for servers expanding paths in each request's environment rather than their own.
+///
+/// ```rust
+/// use pathext::{expand_env, expand_path, expand_tilde, ExpansionContext};
+/// use std::collections::HashMap;
+/// use std::path::{Path, PathBuf};
+///
+/// let context = ExpansionContext {
+///     vars: HashMap::from([("APP".to_string(), "tool".to_string())]),
+///     home: Some(PathBuf::from("/home/me")),
+///     cwd: Some(PathBuf::from("/srv")),
+/// };
+/// assert_eq!(expand_env("$APP/${APP}.toml", &context), "tool/tool.toml");
+/// assert_eq!(expand_tilde("~/.config", &context), Path::new("/home/me/.config"));
+/// assert_eq!(expand_path("~/.$APP", &context), Path::new("/home/me/.tool"));
+/// assert_eq!(expand_path("data/$APP", &context), Path::new("/srv/data/tool"));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExpansionContext {
+    /// The variables `$NAME`, `${NAME}` and `%NAME%` expand from.
+    pub vars: HashMap<String, String>,
+    /// What `~` stands for, left alone without one.
+    pub home: Option<PathBuf>,
+    /// What relative paths are relative to, kept relative without one.
+    pub cwd: Option<PathBuf>,
+}
+
+impl ExpansionContext {
+    /// An empty context, expanding nothing.
+    pub fn new() -> Self {
+        ExpansionContext::default()
+    }
+
+    /// The process's environment, home and working directory as they are now. Variables whose
+    /// name or value isn't Unicode are left out.
+    pub fn from_process() -> Self {
+        ExpansionContext {
+            vars: std::env::vars_os()
+                .filter_map(|(name, value)| {
+                    Some((name.into_string().ok()?, value.into_string().ok()?))
+                })
+                .collect(),
+            home: std::env::home_dir(),
+            cwd: std::env::current_dir().ok(),
+        }
+    }
+}
+
+/// Replaces `$NAME`, `${NAME}` and `%NAME%` with the variables in `context`, leaving references
+/// to unknown variables as they were written.
+pub fn expand_env(s: &str, context: &ExpansionContext) -> String {
+    expand_vars(s, |name| context.vars.get(name).cloned())
+}
+
+/// Replaces a leading `~` with the home directory in `context`, when it's all of the first
+/// component. `~user` and paths in a context without a home are returned as they are.
+pub fn expand_tilde<P: AsRef<Path>>(path: P, context: &ExpansionContext) -> PathBuf {
+    let path = path.as_ref();
+    let (Some(home), Ok(rest)) = (&context.home, path.strip_prefix("~")) else {
+        return path.to_path_buf();
+    };
+    if rest.as_os_str().is_empty() {
+        home.clone()
+    } else {
+        home.join(rest)
+    }
+}
+
+/// [`expand_tilde`], then [`expand_env`] if the path is Unicode, then a relative result joined onto
+/// the working directory in `context`. Variables holding a `~` aren't expanded again, as in a shell.
+pub fn expand_path<P: AsRef<Path>>(path: P, context: &ExpansionContext) -> PathBuf {
+    let path = expand_tilde(path, context);
+    let path = match path.to_str() {
+        Some(s) => PathBuf::from(expand_env(s, context)),
+        None => path,
+    };
+    match &context.cwd {
+        Some(cwd) if path.is_relative() => cwd.join(path),
+        _ => path,
+    }
+}
+
+/// Replaces `%NAME%` with what `lookup` gives for `NAME`, as Windows expands `REG_EXPAND_SZ` values.
+/// Names may hold anything but `%` and `=`, as in `%ProgramFiles(x86)%`, and unknown ones stay.
+pub(crate) fn expand_percent_vars(s: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let mut expanded = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find('%') {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('%') else {
+            rest = &rest[start..];
+            break;
+        };
+        let name = &after[..end];
+        match (!name.is_empty() && !name.contains('='))
+            .then(|| lookup(name))
+            .flatten()
+        {
+            Some(value) => {
+                expanded.push_str(&value);
+                rest = &after[end + 1..];
+            }
+            // the closing `%` may open the next reference
+            None => {
+                expanded.push('%');
+                expanded.push_str(name);
+                rest = &after[end..];
+            }
+        }
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+/// Decodes a `.reg` export's `hex(2):` value, the UTF-16LE bytes of a `REG_EXPAND_SZ` string.
+fn decode_reg_hex(hex: &str) -> Option<String> {
+    let bytes = hex
+        .split(',')
+        .map(|byte| byte.trim_matches(|c: char| c == '\\' || c.is_whitespace()))
+        .filter(|byte| !byte.is_empty())
+        .map(|byte| u8::from_str_radix(byte, 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .collect();
+    let value = String::from_utf16(&units).ok()?;
+    Some(value.trim_end_matches('\0').to_string())
+}
+
+/// Expands `%NAME%` references in a `REG_EXPAND_SZ` registry value, like `%SystemRoot%\system32`,
+/// from the environment on Windows, as `ExpandEnvironmentStrings` does. Elsewhere there's nothing to
+/// expand from, so the value comes back as it is. A value as a `.reg` file exports it,
+/// `hex(2):25,00,...`, is decoded first, on every platform.
+///
+/// ```rust
+/// use pathext::expand_registry_string;
+///
+/// let exported = "hex(2):25,00,41,00,25,00,5c,00,78,00,00,00";
+/// if cfg!(windows) {
+///     std::env::set_var("A", r"C:\a");
+///     assert_eq!(expand_registry_string(exported), r"C:\a\x");
+/// } else {
+///     assert_eq!(expand_registry_string(exported), r"%A%\x");
+/// }
+/// ```
+pub fn expand_registry_string(value: &str) -> String {
+    let decoded = value.strip_prefix("hex(2):").and_then(decode_reg_hex);
+    let value = decoded.as_deref().unwrap_or(value);
+    if cfg!(windows) {
+        expand_percent_vars(value, |name| std::env::var(name).ok())
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        decode_reg_hex, expand_path, expand_percent_vars, expand_tilde, expand_vars,
+        ExpansionContext,
+    };
+
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_expand_vars() {
+        let lookup = |name: &str| match name {
+            "HOME" => Some("/home/me".to_string()),
+            "APPDATA" => Some(r"C:\Users\me\AppData".to_string()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        };
+        let tests = &[
+            ("$HOME/.config", "/home/me/.config"),
+            ("${HOME}x", "/home/mex"),
+            (r"%APPDATA%\app", r"C:\Users\me\AppData\app"),
+            ("$MISSING/x", "$MISSING/x"),
+            ("${MISSING}", "${MISSING}"),
+            ("%MISSING%", "%MISSING%"),
+            ("100% sure $", "100% sure $"),
+            ("$1/a", "$1/a"),
+            ("a${EMPTY}b", "ab"),
+            ("${unclosed", "${unclosed"),
+            ("%%", "%%"),
+            ("ünï$HOME", "ünï/home/me"),
+        ];
+
+        for test in tests {
+            assert_eq!(expand_vars(test.0, lookup), test.1, "{}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_expand_percent_vars() {
+        let lookup = |name: &str| match name {
+            "SystemRoot" => Some(r"C:\Windows".to_string()),
+            "ProgramFiles(x86)" => Some(r"C:\Program Files (x86)".to_string()),
+            _ => None,
+        };
+        let tests = &[
+            (r"%SystemRoot%\system32", r"C:\Windows\system32"),
+            (r"%ProgramFiles(x86)%\App", r"C:\Program Files (x86)\App"),
+            ("%MISSING%", "%MISSING%"),
+            ("100%%SystemRoot%", r"100%C:\Windows"),
+            ("50% of %SystemRoot%", r"50% of C:\Windows"),
+            ("%a=b%", "%a=b%"),
+            ("trailing %", "trailing %"),
+            ("%%", "%%"),
+        ];
+
+        for test in tests {
+            assert_eq!(expand_percent_vars(test.0, lookup), test.1, "{}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_decode_reg_hex() {
+        let exported = "25,00,53,00,25,00,5c,00,\\\n  78,00,00,00";
+        assert_eq!(decode_reg_hex(exported).as_deref(), Some(r"%S%\x"));
+        assert_eq!(decode_reg_hex("zz,00"), None);
+        assert_eq!(decode_reg_hex("").as_deref(), Some(""));
+    }
+
+    #[test]
+    fn test_expand_tilde() {
+        let context = ExpansionContext {
+            home: Some(PathBuf::from("/home/me")),
+            ..ExpansionContext::new()
+        };
+        let tests = &[
+            ("~", Some("/home/me")),
+            ("~/", Some("/home/me")),
+            ("~/.config/app", Some("/home/me/.config/app")),
+            ("~user/x", None),
+            ("a/~", None),
+            ("/~/x", None),
+        ];
+
+        for test in tests {
+            let expected = PathBuf::from(test.1.unwrap_or(test.0));
+            assert_eq!(expand_tilde(test.0, &context), expected, "{}", test.0);
+            let empty = ExpansionContext::new();
+            assert_eq!(expand_tilde(test.0, &empty), PathBuf::from(test.0));
+        }
+    }
+
+    #[test]
+    fn test_expand_path() {
+        let context = ExpansionContext {
+            vars: HashMap::from([
+                ("OUT".to_string(), "/build".to_string()),
+                ("TILDE".to_string(), "~".to_string()),
+            ]),
+            home: Some(PathBuf::from("/home/me")),
+            cwd: Some(PathBuf::from("/work")),
+        };
+        let tests = &[
+            ("$OUT/x", "/build/x"),
+            ("~/.cache/$MISSING", "/home/me/.cache/$MISSING"),
+            ("src/$MISSING", "/work/src/$MISSING"),
+            ("$TILDE/x", "/work/~/x"),
+            ("/abs", "/abs"),
+        ];
+
+        for test in tests {
+            assert_eq!(
+                expand_path(test.0, &context),
+                PathBuf::from(test.1),
+                "{}",
+                test.0
+            );
+        }
+    }
+}
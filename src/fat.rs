@@ -0,0 +1,178 @@
+//! Names for FAT and exFAT volumes: 8.3 short names and long file name rules.
+
+use std::path::{Component, Path, PathBuf};
+
+/// The longest long file name in UTF-16 units, for both VFAT and exFAT.
+const MAX_LONG_NAME: usize = 255;
+
+/// Characters a short name may hold as they are, besides `A-Z` and `0-9`.
+fn is_short_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric()
+        || matches!(
+            c,
+            '!' | '#'
+                | '$'
+                | '%'
+                | '&'
+                | '\''
+                | '('
+                | ')'
+                | '-'
+                | '@'
+                | '^'
+                | '_'
+                | '`'
+                | '{'
+                | '}'
+                | '~'
+        )
+}
+
+/// Uppercases and maps `s` to short name characters, dropping spaces and dots as Windows does.
+/// The flag says whether anything had to change beyond the case.
+fn short_name_chars(s: &str) -> (String, bool) {
+    let mut lossy = false;
+    let mut mapped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c.to_ascii_uppercase() {
+            ' ' | '.' => lossy = true,
+            c if is_short_name_char(c) => mapped.push(c),
+            _ => {
+                lossy = true;
+                mapped.push('_');
+            }
+        }
+    }
+    (mapped, lossy)
+}
+
+/// The 8.3 name Windows would generate for `name` in a directory already holding `existing`,
+/// compared without case, adding a `~N` tail when the name had to change or collides.
+pub(crate) fn short_name<I, S>(name: &str, existing: I) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let trimmed = name.trim_start_matches('.');
+    let (stem, extension) = match trimmed.rfind('.') {
+        Some(dot) => (&trimmed[..dot], &trimmed[dot + 1..]),
+        None => (trimmed, ""),
+    };
+    let (stem, stem_lossy) = short_name_chars(stem);
+    let (extension, extension_lossy) = short_name_chars(extension);
+    let lossy = stem_lossy
+        || extension_lossy
+        || trimmed.len() != name.len()
+        || stem.len() > 8
+        || extension.len() > 3;
+    let stem = if stem.is_empty() {
+        "_"
+    } else {
+        &stem[..stem.len().min(8)]
+    };
+    let extension = &extension[..extension.len().min(3)];
+    let with_extension = |stem: &str| {
+        if extension.is_empty() {
+            stem.to_string()
+        } else {
+            format!("{stem}.{extension}")
+        }
+    };
+
+    let existing: Vec<String> = existing
+        .into_iter()
+        .map(|name| name.as_ref().to_ascii_uppercase())
+        .collect();
+    let taken = |candidate: &str| existing.iter().any(|name| name == candidate);
+    let plain = with_extension(stem);
+    if !lossy && !taken(&plain) {
+        return plain;
+    }
+    (1..)
+        .map(|n| {
+            let tail = format!("~{n}");
+            let keep = stem.len().min(8 - tail.len());
+            with_extension(&format!("{}{tail}", &stem[..keep]))
+        })
+        .find(|candidate| !taken(candidate))
+        .expect("fewer than 10 million names in a directory")
+}
+
+pub(crate) fn to_fat_8_3<I, S>(path: &Path, existing: I) -> Option<PathBuf>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let name = path.file_name()?.to_string_lossy();
+    Some(path.with_file_name(short_name(&name, existing)))
+}
+
+fn is_long_name(name: &str) -> bool {
+    name.encode_utf16().count() <= MAX_LONG_NAME
+        && !name.ends_with(['.', ' '])
+        && !name
+            .chars()
+            .any(|c| c < ' ' || matches!(c, '"' | '*' | '/' | ':' | '<' | '>' | '?' | '\\' | '|'))
+}
+
+pub(crate) fn is_fat_safe(path: &Path) -> bool {
+    path.components().all(|component| match component {
+        Component::Normal(name) => name.to_str().is_some_and(is_long_name),
+        _ => true,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_fat_safe, short_name};
+
+    use std::path::Path;
+
+    #[test]
+    fn test_short_name() {
+        let none: &[&str] = &[];
+        let tests = &[
+            ("README.TXT", none, "README.TXT"),
+            ("readme.txt", none, "README.TXT"),
+            ("Makefile", none, "MAKEFILE"),
+            ("LongFileName.txt", none, "LONGFI~1.TXT"),
+            ("LongFileName.txt", &["LONGFI~1.TXT"], "LONGFI~2.TXT"),
+            (
+                "LongFileName.txt",
+                &["longfi~1.txt", "LONGFI~2.TXT"],
+                "LONGFI~3.TXT",
+            ),
+            ("report.html", none, "REPORT~1.HTM"),
+            ("my file.c", none, "MYFILE~1.C"),
+            ("a+b.txt", none, "A_B~1.TXT"),
+            (".bashrc", none, "BASHRC~1"),
+            ("archive.tar.gz", none, "ARCHIV~1.GZ"),
+            ("README.TXT", &["readme.txt"], "README~1.TXT"),
+        ];
+
+        for test in tests {
+            assert_eq!(short_name(test.0, test.1), test.2, "{}", test.0);
+        }
+
+        let existing: Vec<String> = (1..10).map(|n| format!("LONGFI~{n}.TXT")).collect();
+        assert_eq!(short_name("LongFileName.txt", &existing), "LONGF~10.TXT");
+    }
+
+    #[test]
+    fn test_is_fat_safe() {
+        let long = "x".repeat(256);
+        let tests = &[
+            ("/DCIM/100CANON/IMG_0001.JPG", true),
+            ("Música/año nuevo.mp3", true),
+            ("what?.txt", false),
+            ("trailing.", false),
+            ("a<b", false),
+            (long.as_str(), false),
+            (&long[1..], true),
+        ];
+
+        for test in tests {
+            assert_eq!(is_fat_safe(Path::new(test.0)), test.1, "{}", test.0);
+        }
+    }
+}
@@ -0,0 +1,847 @@
+//! Helpers that query or change the real filesystem, behind the `fs` feature.
+
+use std::collections::hash_map::RandomState;
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::io;
+use std::path::{Component, Path, PathBuf, Prefix};
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+use std::time::SystemTime;
+
+use crate::sanitize::is_windows_reserved;
+use crate::{PathIoError, Platform};
+
+/// What a symlink points at, see [`PathState::Symlink`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LinkTarget {
+    File,
+    Dir,
+    /// A device, socket, fifo or anything else that's neither a file nor a directory.
+    Other,
+    /// The target doesn't exist.
+    Dangling,
+}
+
+/// What, if anything, is at a path, from [`PathFsExt::state`](crate::PathFsExt::state).
+#[derive(Debug)]
+pub enum PathState {
+    /// The parent directory doesn't exist, or is something other than a directory.
+    MissingParent,
+    /// The parent directory exists but has nothing by this name.
+    MissingLeaf,
+    File,
+    Dir,
+    Symlink(LinkTarget),
+    /// A device, socket, fifo or anything else that's neither a file nor a directory.
+    Other,
+    /// Looking the path up failed, usually for lack of permission.
+    Denied(io::Error),
+}
+
+impl PathState {
+    /// Whether something is there, counting dangling symlinks.
+    pub fn exists(&self) -> bool {
+        !matches!(
+            self,
+            PathState::MissingParent | PathState::MissingLeaf | PathState::Denied(_)
+        )
+    }
+}
+
+pub(crate) fn is_missing(e: &io::Error) -> bool {
+    matches!(
+        e.kind(),
+        io::ErrorKind::NotFound | io::ErrorKind::NotADirectory
+    )
+}
+
+pub(crate) fn state(path: &Path) -> PathState {
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.is_symlink() => match fs::metadata(path) {
+            Ok(target) if target.is_file() => PathState::Symlink(LinkTarget::File),
+            Ok(target) if target.is_dir() => PathState::Symlink(LinkTarget::Dir),
+            Ok(_) => PathState::Symlink(LinkTarget::Other),
+            Err(e) if is_missing(&e) => PathState::Symlink(LinkTarget::Dangling),
+            Err(e) => PathState::Denied(e),
+        },
+        Ok(metadata) if metadata.is_file() => PathState::File,
+        Ok(metadata) if metadata.is_dir() => PathState::Dir,
+        Ok(_) => PathState::Other,
+        Err(e) if is_missing(&e) => {
+            let parent = match path.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => parent,
+                Some(_) => Path::new("."),
+                None => return PathState::MissingLeaf,
+            };
+            match fs::metadata(parent) {
+                Ok(metadata) if metadata.is_dir() => PathState::MissingLeaf,
+                Ok(_) => PathState::MissingParent,
+                Err(e) if is_missing(&e) => PathState::MissingParent,
+                Err(e) => PathState::Denied(e),
+            }
+        }
+        Err(e) => PathState::Denied(e),
+    }
+}
+
+/// Wraps `e` in a [`PathIoError`] saying what `operation` on `path` failed, keeping its kind. Errors
+/// already carrying one keep theirs, which is about the step that failed.
+pub(crate) fn with_path(e: io::Error, operation: &'static str, path: &Path) -> io::Error {
+    if PathIoError::of(&e).is_some() {
+        return e;
+    }
+    PathIoError::new(operation, path, e).into()
+}
+
+pub(crate) fn ensure_dir(path: &Path) -> io::Result<bool> {
+    if path.is_dir() {
+        return Ok(false);
+    }
+    fs::create_dir_all(path).map_err(|e| with_path(e, "create dir", path))?;
+    Ok(true)
+}
+
+pub(crate) fn ensure_parent_dir(path: &Path) -> io::Result<bool> {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => ensure_dir(parent),
+        _ => Ok(false),
+    }
+}
+
+/// How [`PathFsExt::touch_with`](crate::PathFsExt::touch_with) touches a file.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TouchOptions {
+    /// Create missing parent directories instead of failing.
+    pub create_parents: bool,
+    /// The modification time to set, now if `None`.
+    pub modified: Option<SystemTime>,
+}
+
+pub(crate) fn touch(path: &Path, options: TouchOptions) -> io::Result<()> {
+    if options.create_parents {
+        ensure_parent_dir(path)?;
+    }
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| with_path(e, "touch", path))?;
+    file.set_modified(options.modified.unwrap_or_else(SystemTime::now))
+        .map_err(|e| with_path(e, "set the modification time of", path))
+}
+
+/// Names tried before giving up on finding an unused one.
+const UNIQUE_ATTEMPTS: usize = 100;
+
+/// 16 hex digits that differ between calls and processes.
+fn random_hex() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let mut hasher = RandomState::new().build_hasher();
+    COUNTER
+        .fetch_add(1, AtomicOrdering::Relaxed)
+        .hash(&mut hasher);
+    std::process::id().hash(&mut hasher);
+    SystemTime::now().hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// Calls `try_name` on random children of `dir` until it succeeds with something other than `AlreadyExists`.
+fn with_unique_child(
+    dir: &Path,
+    prefix: &str,
+    suffix: &str,
+    try_name: impl Fn(&Path) -> io::Result<()>,
+) -> io::Result<PathBuf> {
+    for _ in 0..UNIQUE_ATTEMPTS {
+        let child = dir.join(format!("{prefix}{}{suffix}", random_hex()));
+        match try_name(&child) {
+            Ok(()) => return Ok(child),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(with_path(e, "create", &child)),
+        }
+    }
+    Err(with_path(
+        io::Error::new(io::ErrorKind::AlreadyExists, "no unused name found"),
+        "create a unique child in",
+        dir,
+    ))
+}
+
+pub(crate) fn unique_child(dir: &Path, prefix: &str, suffix: &str) -> io::Result<PathBuf> {
+    with_unique_child(dir, prefix, suffix, |child| {
+        match fs::symlink_metadata(child) {
+            Ok(_) => Err(io::ErrorKind::AlreadyExists.into()),
+            Err(e) if is_missing(&e) => Ok(()),
+            Err(e) => Err(e),
+        }
+    })
+}
+
+pub(crate) fn create_unique_child(dir: &Path, prefix: &str, suffix: &str) -> io::Result<PathBuf> {
+    with_unique_child(dir, prefix, suffix, |child| fs::create_dir(child))
+}
+
+/// The closest of `path` and its ancestors that exists, as free space can be asked about before creating.
+fn existing_ancestor(path: &Path) -> &Path {
+    path.ancestors()
+        .find(|ancestor| !ancestor.as_os_str().is_empty() && ancestor.exists())
+        .unwrap_or(Path::new("."))
+}
+
+#[cfg(unix)]
+pub(crate) fn available_space(path: &Path) -> io::Result<u64> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let existing = existing_ancestor(path);
+    let c_path = CString::new(existing.as_os_str().as_bytes()).map_err(|e| {
+        let e = io::Error::new(io::ErrorKind::InvalidInput, e);
+        with_path(e, "get the available space of", path)
+    })?;
+    let mut stat = std::mem::MaybeUninit::<libc::statvfs>::uninit();
+    // SAFETY: `c_path` is NUL terminated and `stat` is only read after statvfs filled it in.
+    let stat = unsafe {
+        if libc::statvfs(c_path.as_ptr(), stat.as_mut_ptr()) != 0 {
+            return Err(with_path(
+                io::Error::last_os_error(),
+                "get the available space of",
+                existing,
+            ));
+        }
+        stat.assume_init()
+    };
+    // the field types differ between platforms
+    #[allow(clippy::unnecessary_cast)]
+    Ok(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+#[cfg(windows)]
+pub(crate) fn available_space(path: &Path) -> io::Result<u64> {
+    use std::os::windows::ffi::OsStrExt;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetDiskFreeSpaceExW(
+            directory: *const u16,
+            free_to_caller: *mut u64,
+            total: *mut u64,
+            total_free: *mut u64,
+        ) -> i32;
+    }
+
+    let mut existing = existing_ancestor(path);
+    if !existing.is_dir() {
+        existing = existing.parent().unwrap_or(Path::new("."));
+    }
+    let wide: Vec<u16> = existing.as_os_str().encode_wide().chain([0]).collect();
+    let mut free_to_caller = 0;
+    // SAFETY: `wide` is NUL terminated and the other pointers may be null.
+    let ok = unsafe {
+        GetDiskFreeSpaceExW(
+            wide.as_ptr(),
+            &mut free_to_caller,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    };
+    if ok == 0 {
+        return Err(with_path(
+            io::Error::last_os_error(),
+            "get the available space of",
+            existing,
+        ));
+    }
+    Ok(free_to_caller)
+}
+
+#[cfg(not(any(unix, windows)))]
+pub(crate) fn available_space(path: &Path) -> io::Result<u64> {
+    Err(with_path(
+        io::ErrorKind::Unsupported.into(),
+        "read the link count of",
+        path,
+    ))
+}
+
+#[cfg(unix)]
+pub(crate) fn make_executable(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut permissions = fs::metadata(path)
+        .map_err(|e| with_path(e, "read the permissions of", path))?
+        .permissions();
+    let mode = permissions.mode();
+    // executable by whoever can read it, as `chmod +x` does under the usual umask
+    permissions.set_mode(mode | (mode & 0o444) >> 2);
+    fs::set_permissions(path, permissions).map_err(|e| with_path(e, "set the permissions of", path))
+}
+
+#[cfg(not(unix))]
+pub(crate) fn make_executable(path: &Path) -> io::Result<()> {
+    fs::metadata(path)
+        .map(drop)
+        .map_err(|e| with_path(e, "read the metadata of", path))
+}
+
+pub(crate) fn make_readonly(path: &Path) -> io::Result<()> {
+    let mut permissions = fs::metadata(path)
+        .map_err(|e| with_path(e, "read the permissions of", path))?
+        .permissions();
+    permissions.set_readonly(true);
+    fs::set_permissions(path, permissions).map_err(|e| with_path(e, "set the permissions of", path))
+}
+
+#[cfg(unix)]
+pub(crate) fn is_writable_by_current_user(path: &Path) -> io::Result<bool> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes()).map_err(|e| {
+        let e = io::Error::new(io::ErrorKind::InvalidInput, e);
+        with_path(e, "check write access to", path)
+    })?;
+    // SAFETY: `c_path` is NUL terminated.
+    if unsafe { libc::access(c_path.as_ptr(), libc::W_OK) } == 0 {
+        return Ok(true);
+    }
+    let e = io::Error::last_os_error();
+    match e.raw_os_error() {
+        Some(libc::EACCES | libc::EROFS | libc::EPERM) => Ok(false),
+        _ => Err(with_path(e, "check write access to", path)),
+    }
+}
+
+#[cfg(not(unix))]
+pub(crate) fn is_writable_by_current_user(path: &Path) -> io::Result<bool> {
+    let metadata = fs::metadata(path).map_err(|e| with_path(e, "read the metadata of", path))?;
+    // the read-only attribute is all there is without reading ACLs, and directories ignore it
+    Ok(metadata.is_dir() || !metadata.permissions().readonly())
+}
+
+/// The link count and the (volume, file) pair identifying the file at `path`.
+#[cfg(unix)]
+fn link_info(path: &Path) -> io::Result<(u64, (u64, u64))> {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = fs::metadata(path).map_err(|e| with_path(e, "read the metadata of", path))?;
+    Ok((metadata.nlink(), (metadata.dev(), metadata.ino())))
+}
+
+#[cfg(windows)]
+fn link_info(path: &Path) -> io::Result<(u64, (u64, u64))> {
+    use std::os::windows::fs::OpenOptionsExt;
+    use std::os::windows::io::AsRawHandle;
+
+    /// `BY_HANDLE_FILE_INFORMATION`
+    #[repr(C)]
+    #[derive(Default)]
+    struct FileInformation {
+        attributes: u32,
+        // three FILETIMEs, which are pairs of u32 and only 4-byte aligned
+        times: [u32; 6],
+        volume_serial_number: u32,
+        size_high: u32,
+        size_low: u32,
+        number_of_links: u32,
+        index_high: u32,
+        index_low: u32,
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetFileInformationByHandle(
+            file: *mut std::ffi::c_void,
+            info: *mut FileInformation,
+        ) -> i32;
+    }
+
+    const FILE_FLAG_BACKUP_SEMANTICS: u32 = 0x0200_0000;
+
+    // backup semantics so directories open too
+    let file = fs::OpenOptions::new()
+        .read(true)
+        .custom_flags(FILE_FLAG_BACKUP_SEMANTICS)
+        .open(path)
+        .map_err(|e| with_path(e, "open", path))?;
+    let mut info = FileInformation::default();
+    // SAFETY: the handle is open for the duration of the call and `info` has the expected layout.
+    if unsafe { GetFileInformationByHandle(file.as_raw_handle(), &mut info) } == 0 {
+        return Err(with_path(
+            io::Error::last_os_error(),
+            "read the file information of",
+            path,
+        ));
+    }
+    let index = (u64::from(info.index_high) << 32) | u64::from(info.index_low);
+    Ok((
+        u64::from(info.number_of_links),
+        (u64::from(info.volume_serial_number), index),
+    ))
+}
+
+#[cfg(not(any(unix, windows)))]
+fn link_info(path: &Path) -> io::Result<(u64, (u64, u64))> {
+    Err(with_path(
+        io::ErrorKind::Unsupported.into(),
+        "read the link count of",
+        path,
+    ))
+}
+
+pub(crate) fn hardlink_count(path: &Path) -> io::Result<u64> {
+    link_info(path).map(|(count, _)| count)
+}
+
+pub(crate) fn is_hardlinked_with(path: &Path, other: &Path) -> io::Result<bool> {
+    Ok(link_info(path)?.1 == link_info(other)?.1)
+}
+
+pub(crate) fn hardlink_to(path: &Path, dest: &Path) -> io::Result<()> {
+    fs::hard_link(path, dest).map_err(|e| {
+        let reason = match e.kind() {
+            io::ErrorKind::CrossesDevices => "hard links can't cross filesystems".to_string(),
+            io::ErrorKind::AlreadyExists => "the destination already exists".to_string(),
+            _ => e.to_string(),
+        };
+        let source = io::Error::new(e.kind(), format!("{reason}, linking to {}", path.display()));
+        PathIoError::new("create hard link", dest, source).into()
+    })
+}
+
+/// A special kind of file, see [`is_special`].
+#[derive(Clone, Copy)]
+pub(crate) enum Special {
+    Fifo,
+    Socket,
+    BlockDevice,
+    CharDevice,
+}
+
+pub(crate) fn is_regular_file(path: &Path) -> bool {
+    fs::metadata(path).is_ok_and(|metadata| metadata.file_type().is_file())
+}
+
+/// Whether `path` is a `kind` of special file, following symlinks, which only Unix has.
+#[cfg(unix)]
+pub(crate) fn is_special(path: &Path, kind: Special) -> bool {
+    use std::os::unix::fs::FileTypeExt;
+
+    fs::metadata(path).is_ok_and(|metadata| {
+        let file_type = metadata.file_type();
+        match kind {
+            Special::Fifo => file_type.is_fifo(),
+            Special::Socket => file_type.is_socket(),
+            Special::BlockDevice => file_type.is_block_device(),
+            Special::CharDevice => file_type.is_char_device(),
+        }
+    })
+}
+
+#[cfg(not(unix))]
+pub(crate) fn is_special(_path: &Path, _kind: Special) -> bool {
+    false
+}
+
+/// The entry of `dir` named `name`, ignoring case if `ignore_case` but preferring an exact match.
+fn stored_name(dir: &Path, name: &OsStr, ignore_case: bool) -> io::Result<Option<OsString>> {
+    if !ignore_case {
+        fs::symlink_metadata(dir.join(name))?;
+        return Ok(Some(name.to_os_string()));
+    }
+    debug!(dir = %dir.display(), "reading directory for stored name");
+    let folded = name.to_string_lossy().to_lowercase();
+    let mut found = None;
+    for entry in fs::read_dir(dir)? {
+        let entry_name = entry?.file_name();
+        if entry_name == *name {
+            return Ok(Some(entry_name));
+        }
+        if found.is_none() && entry_name.to_string_lossy().to_lowercase() == folded {
+            found = Some(entry_name);
+        }
+    }
+    Ok(found)
+}
+
+/// The prefix with its drive letter, if any, uppercased as Windows stores it. Server and share
+/// names are kept as given.
+fn prefix_case(prefix: Prefix<'_>, given: &OsStr) -> OsString {
+    match prefix {
+        Prefix::Disk(letter) => format!("{}:", letter.to_ascii_uppercase() as char).into(),
+        Prefix::VerbatimDisk(letter) => {
+            format!(r"\\?\{}:", letter.to_ascii_uppercase() as char).into()
+        }
+        _ => given.to_os_string(),
+    }
+}
+
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", fields(path = %path.display()), skip(path))
+)]
+pub(crate) fn actual_case(path: &Path) -> io::Result<PathBuf> {
+    let ignore_case = Platform::Native.ignores_case();
+    let mut actual = PathBuf::new();
+    let mut components = path.components();
+    while let Some(component) = components.next() {
+        match component {
+            Component::Prefix(prefix) => {
+                actual.push(prefix_case(prefix.kind(), prefix.as_os_str()));
+            }
+            Component::Normal(name) => {
+                let dir = if actual.as_os_str().is_empty() {
+                    Path::new(".")
+                } else {
+                    actual.as_path()
+                };
+                let stored = match stored_name(dir, name, ignore_case) {
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+                    other => other?,
+                };
+                match stored {
+                    Some(stored) => actual.push(stored),
+                    None => {
+                        // nothing stored from here on, keep the rest as given
+                        actual.push(name);
+                        actual.push(components.as_path());
+                        break;
+                    }
+                }
+            }
+            other => actual.push(other),
+        }
+    }
+    Ok(actual)
+}
+
+/// Whether `rest` (after a drive or UNC prefix) means the same thing without `\\?\`, i.e. has none
+/// of the components that only verbatim paths allow and fits in `MAX_PATH`.
+fn is_plain_representable(full: &str, rest: &str) -> bool {
+    full.encode_utf16().count() < 260
+        && rest.split('\\').all(|name| {
+            !matches!(name, "." | "..")
+                && !name.ends_with(['.', ' '])
+                && !name.chars().any(|c| {
+                    c.is_control() || matches!(c, '<' | '>' | ':' | '"' | '/' | '|' | '?' | '*')
+                })
+                && !is_windows_reserved(name)
+        })
+}
+
+/// Drops a `\\?\` prefix, or turns `\\?\UNC\` into `\\`, when the path means the same without it.
+pub(crate) fn simplify_verbatim(path: &Path) -> PathBuf {
+    let Some(s) = path.to_str() else {
+        return path.to_path_buf();
+    };
+    if let Some(unc) = s.strip_prefix(r"\\?\UNC\") {
+        let plain = format!(r"\\{unc}");
+        if is_plain_representable(&plain, unc) {
+            return PathBuf::from(plain);
+        }
+    } else if let Some(plain) = s.strip_prefix(r"\\?\") {
+        let bytes = plain.as_bytes();
+        let is_drive = bytes.len() >= 3
+            && bytes[0].is_ascii_alphabetic()
+            && bytes[1] == b':'
+            && bytes[2] == b'\\';
+        if is_drive && (bytes.len() == 3 || is_plain_representable(plain, &plain[3..])) {
+            return PathBuf::from(plain);
+        }
+    }
+    path.to_path_buf()
+}
+
+pub(crate) fn canonicalize_simplified(path: &Path) -> io::Result<PathBuf> {
+    fs::canonicalize(path).map(|canonical| simplify_verbatim(&canonical))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        actual_case, available_space, create_unique_child, ensure_dir, ensure_parent_dir,
+        hardlink_count, hardlink_to, is_hardlinked_with, is_regular_file, is_special,
+        is_writable_by_current_user, make_executable, make_readonly, prefix_case,
+        simplify_verbatim, state, touch, unique_child, Special, TouchOptions,
+    };
+    use crate::Platform;
+
+    use std::ffi::OsStr;
+    use std::ops::Not;
+    use std::path::{Path, Prefix};
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn test_actual_case() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        // what case-insensitive and case-sensitive platforms give
+        let tests = &[
+            ("SRC/Lib.RS", "src/lib.rs", "SRC/Lib.RS"),
+            ("src/lib.rs", "src/lib.rs", "src/lib.rs"),
+            ("Cargo.TOML", "Cargo.toml", "Cargo.TOML"),
+            ("SRC/../readme.md", "src/../README.md", "SRC/../readme.md"),
+            ("src/Not/Here.rs", "src/Not/Here.rs", "src/Not/Here.rs"),
+        ];
+
+        for test in tests {
+            let expected = if Platform::Native.ignores_case() {
+                test.1
+            } else {
+                test.2
+            };
+            assert_eq!(
+                actual_case(&root.join(test.0)).unwrap(),
+                root.join(expected),
+                "{}",
+                test.0
+            );
+        }
+    }
+
+    #[test]
+    fn test_prefix_case() {
+        let tests = &[
+            (Prefix::Disk(b'c'), "c:", "C:"),
+            (Prefix::VerbatimDisk(b'd'), r"\\?\d:", r"\\?\D:"),
+            (
+                Prefix::UNC(OsStr::new("server"), OsStr::new("share")),
+                r"\\server\share",
+                r"\\server\share",
+            ),
+            (
+                Prefix::VerbatimUNC(OsStr::new("Server"), OsStr::new("Share")),
+                r"\\?\UNC\Server\Share",
+                r"\\?\UNC\Server\Share",
+            ),
+        ];
+
+        for test in tests {
+            assert_eq!(
+                prefix_case(test.0, OsStr::new(test.1)),
+                test.2,
+                "{}",
+                test.1
+            );
+        }
+    }
+
+    #[test]
+    fn test_simplify_verbatim() {
+        let tests = &[
+            (r"\\?\C:\Users\me", r"C:\Users\me"),
+            (r"\\?\C:\", r"C:\"),
+            (r"\\?\UNC\server\share\dir", r"\\server\share\dir"),
+            (r"\\?\C:\dir\trailing.", r"\\?\C:\dir\trailing."),
+            (r"\\?\C:\dir\space ", r"\\?\C:\dir\space "),
+            (r"\\?\C:\dir\CON", r"\\?\C:\dir\CON"),
+            (r"\\?\C:\dir\nul.txt", r"\\?\C:\dir\nul.txt"),
+            (r"\\?\C:\dir\..\x", r"\\?\C:\dir\..\x"),
+            (r"\\?\C:\a:b", r"\\?\C:\a:b"),
+            (r"\\?\GLOBALROOT\Device", r"\\?\GLOBALROOT\Device"),
+            (r"\\?\Volume{1234}\x", r"\\?\Volume{1234}\x"),
+            ("/unix/path", "/unix/path"),
+            (r"C:\plain", r"C:\plain"),
+        ];
+
+        for test in tests {
+            assert_eq!(
+                simplify_verbatim(Path::new(test.0)),
+                Path::new(test.1),
+                "{}",
+                test.0
+            );
+        }
+
+        let long = format!(r"\\?\C:\{}", "a\\".repeat(130));
+        assert_eq!(simplify_verbatim(Path::new(&long)), Path::new(&long));
+    }
+
+    #[test]
+    fn test_state() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let tests = &[
+            ("Cargo.toml", "File"),
+            ("src", "Dir"),
+            ("src/not-here.rs", "MissingLeaf"),
+            ("src/not/here.rs", "MissingParent"),
+            ("Cargo.toml/nested", "MissingParent"),
+        ];
+
+        for test in tests {
+            let state = format!("{:?}", state(&root.join(test.0)));
+            assert_eq!(state, test.1, "{}", test.0);
+        }
+        assert!(state(root).exists());
+        assert!(state(&root.join("nope")).exists().not());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_state_symlinks() {
+        use super::{LinkTarget, PathState};
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("file"), "").unwrap();
+        std::os::unix::fs::symlink("file", dir.path().join("to-file")).unwrap();
+        std::os::unix::fs::symlink(".", dir.path().join("to-dir")).unwrap();
+        std::os::unix::fs::symlink("gone", dir.path().join("dangling")).unwrap();
+        let tests = &[
+            ("to-file", LinkTarget::File),
+            ("to-dir", LinkTarget::Dir),
+            ("dangling", LinkTarget::Dangling),
+        ];
+
+        for test in tests {
+            match state(&dir.path().join(test.0)) {
+                PathState::Symlink(target) => assert_eq!(target, test.1),
+                other => panic!("{}: {other:?}", test.0),
+            }
+        }
+    }
+
+    #[test]
+    fn test_ensure_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("a/b/c");
+        assert!(ensure_dir(&nested).unwrap());
+        assert!(nested.is_dir());
+        assert!(ensure_dir(&nested).unwrap().not());
+
+        let file = nested.join("file.txt");
+        assert!(ensure_parent_dir(&file).unwrap().not());
+        assert!(ensure_parent_dir(&dir.path().join("d/file.txt")).unwrap());
+        assert!(ensure_parent_dir(Path::new("file.txt")).unwrap().not());
+
+        std::fs::write(&file, "").unwrap();
+        let e = ensure_dir(&file.join("under-a-file")).unwrap_err();
+        assert!(e.to_string().contains("under-a-file"));
+    }
+
+    #[test]
+    fn test_touch() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("file.txt");
+        touch(&file, TouchOptions::default()).unwrap();
+        assert!(file.is_file());
+
+        std::fs::write(&file, "kept").unwrap();
+        let past = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+        let options = TouchOptions {
+            modified: Some(past),
+            ..TouchOptions::default()
+        };
+        touch(&file, options).unwrap();
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "kept");
+        assert_eq!(file.metadata().unwrap().modified().unwrap(), past);
+
+        let nested = dir.path().join("a/b/file.txt");
+        assert!(touch(&nested, TouchOptions::default()).is_err());
+        let options = TouchOptions {
+            create_parents: true,
+            ..TouchOptions::default()
+        };
+        touch(&nested, options).unwrap();
+        assert!(nested.is_file());
+    }
+
+    #[test]
+    fn test_unique_child() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = unique_child(dir.path(), ".staging-", ".tmp").unwrap();
+        let second = unique_child(dir.path(), ".staging-", ".tmp").unwrap();
+        assert_ne!(first, second);
+        assert!(first.exists().not());
+        assert_eq!(first.parent(), Some(dir.path()));
+        let name = first.file_name().unwrap().to_str().unwrap();
+        assert!(name.starts_with(".staging-") && name.ends_with(".tmp"));
+        assert_eq!(name.len(), ".staging-".len() + 16 + ".tmp".len());
+
+        let created = create_unique_child(dir.path(), "stage-", "").unwrap();
+        assert!(created.is_dir());
+        assert!(create_unique_child(&dir.path().join("missing"), "x", "").is_err());
+    }
+
+    #[test]
+    fn test_available_space() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let space = available_space(root).unwrap();
+        assert!(space > 0);
+        assert!(available_space(&root.join("Cargo.toml")).is_ok());
+        assert!(available_space(&root.join("not/created/yet")).is_ok());
+    }
+
+    #[test]
+    fn test_permissions() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("script.sh");
+        std::fs::write(&file, "").unwrap();
+        assert!(is_writable_by_current_user(&file).unwrap());
+
+        make_executable(&file).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = file.metadata().unwrap().permissions().mode();
+            assert_eq!(mode & 0o100, 0o100);
+        }
+
+        make_readonly(&file).unwrap();
+        assert!(file.metadata().unwrap().permissions().readonly());
+        // root can write anything, so only check when that isn't who we are
+        #[cfg(unix)]
+        if unsafe { libc::geteuid() } != 0 {
+            assert!(is_writable_by_current_user(&file).unwrap().not());
+        }
+        assert!(is_writable_by_current_user(&dir.path().join("missing")).is_err());
+        assert!(make_readonly(&dir.path().join("missing")).is_err());
+
+        let mut permissions = file.metadata().unwrap().permissions();
+        #[allow(clippy::permissions_set_readonly_false)]
+        permissions.set_readonly(false);
+        std::fs::set_permissions(&file, permissions).unwrap();
+    }
+
+    #[test]
+    fn test_hardlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let original = dir.path().join("original");
+        let link = dir.path().join("link");
+        let other = dir.path().join("other");
+        std::fs::write(&original, "").unwrap();
+        std::fs::write(&other, "").unwrap();
+        assert_eq!(hardlink_count(&original).unwrap(), 1);
+        assert!(is_hardlinked_with(&original, &other).unwrap().not());
+
+        hardlink_to(&original, &link).unwrap();
+        assert_eq!(hardlink_count(&original).unwrap(), 2);
+        assert_eq!(hardlink_count(&link).unwrap(), 2);
+        assert!(is_hardlinked_with(&original, &link).unwrap());
+        assert!(is_hardlinked_with(&original, &original).unwrap());
+
+        let e = hardlink_to(&original, &other).unwrap_err();
+        assert_eq!(e.kind(), std::io::ErrorKind::AlreadyExists);
+        assert!(e.to_string().contains("the destination already exists"));
+        assert!(hardlink_count(&dir.path().join("missing")).is_err());
+    }
+
+    #[test]
+    fn test_file_type_predicates() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        assert!(is_regular_file(&root.join("Cargo.toml")));
+        assert!(is_regular_file(&root.join("src")).not());
+        assert!(is_regular_file(&root.join("missing")).not());
+        assert!(is_special(&root.join("Cargo.toml"), Special::Fifo).not());
+
+        #[cfg(unix)]
+        {
+            assert!(is_special(Path::new("/dev/null"), Special::CharDevice));
+            assert!(is_special(Path::new("/dev/null"), Special::BlockDevice).not());
+
+            let dir = tempfile::tempdir().unwrap();
+            let socket = dir.path().join("socket");
+            let _listener = std::os::unix::net::UnixListener::bind(&socket).unwrap();
+            assert!(is_special(&socket, Special::Socket));
+            assert!(is_regular_file(&socket).not());
+        }
+    }
+}
@@ -0,0 +1,729 @@
+//! Every extension trait method as a free function, for codebases that keep trait imports out.
+//!
+//! ```rust
+//! use std::path::Path;
+//!
+//! assert!(pathext::has_component("/some/path", "path"));
+//! assert_eq!(pathext::strip_extensions("archive.tar.gz"), Some("archive"));
+//! assert_eq!(pathext::absolutize_from("src/../lib.rs", "/work"), Path::new("/work/lib.rs"));
+//! ```
+
+use std::cmp::Ordering;
+use std::ffi::OsStr;
+#[cfg(feature = "fs")]
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use crate::{
+    encoded, name, sequence, ArtifactKind, CharPolicy, Language, LengthLimits, NameKind,
+    NormalizeStyle, ObjectStore, PathComponentExt, PathStrExt, PathStyle, PosixViolation,
+    PromptOptions, RedactOptions, StripOptions,
+};
+#[cfg(feature = "fs")]
+use crate::{LockFile, PathFsExt, PathState, PidFile, TouchOptions};
+
+// The methods that borrow from the path can't go through the blanket impls, as those are for the
+// reference itself, so these take the path by reference and do the work directly.
+
+/// [`PathStrExt::as_encoded_bytes_compat`] as a free function.
+pub fn as_encoded_bytes_compat<T: AsRef<Path> + ?Sized>(path: &T) -> &[u8] {
+    encoded::as_encoded_bytes(path.as_ref())
+}
+
+/// [`PathStrExt::strip_extensions`] as a free function.
+pub fn strip_extensions<T: AsRef<Path> + ?Sized>(path: &T) -> Option<&str> {
+    name::strip_extensions(path.as_ref())
+}
+
+/// [`PathStrExt::strip_extensions_opts`] as a free function.
+pub fn strip_extensions_opts<T: AsRef<Path> + ?Sized>(
+    path: &T,
+    options: StripOptions,
+) -> Option<&str> {
+    name::strip_extensions_opts(path.as_ref(), &options)
+}
+
+/// [`PathComponentExt::strip_prefix_if_needed`] as a free function.
+pub fn strip_prefix_if_needed<T: AsRef<Path> + ?Sized, S: AsRef<str>>(
+    path: &T,
+    prefix: S,
+) -> &Path {
+    let path = path.as_ref();
+    path.strip_prefix(prefix.as_ref()).unwrap_or(path)
+}
+
+/// [`PathComponentExt::component_windows`] as a free function.
+pub fn component_windows<T: AsRef<Path> + ?Sized>(
+    path: &T,
+    n: usize,
+) -> impl Iterator<Item = Vec<Component<'_>>> {
+    sequence::component_windows(path.as_ref(), n)
+}
+
+/// [`PathComponentExt::nth_parent`] as a free function.
+pub fn nth_parent<T: AsRef<Path> + ?Sized>(path: &T, n: usize) -> Option<&Path> {
+    path.as_ref().ancestors().nth(n)
+}
+
+/// [`PathComponentExt::parent_or_self`] as a free function.
+pub fn parent_or_self<T: AsRef<Path> + ?Sized>(path: &T) -> &Path {
+    let path = path.as_ref();
+    path.parent().unwrap_or(path)
+}
+
+/// [`PathStrExt::byte_len`] as a free function.
+pub fn byte_len<T: AsRef<Path>>(path: T) -> usize {
+    path.byte_len()
+}
+
+/// [`PathStrExt::char_len_lossy`] as a free function.
+pub fn char_len_lossy<T: AsRef<Path>>(path: T) -> usize {
+    path.char_len_lossy()
+}
+
+/// [`PathStrExt::contains`] as a free function.
+pub fn contains<T: AsRef<Path>, S: AsRef<str>>(path: T, pattern: S) -> bool {
+    path.contains(pattern)
+}
+
+/// [`PathStrExt::display_contracted`] as a free function.
+#[cfg(feature = "dirs")]
+pub fn display_contracted<T: AsRef<Path>>(path: T) -> String {
+    path.display_contracted()
+}
+
+/// [`PathStrExt::escape_for_make`] as a free function.
+pub fn escape_for_make<T: AsRef<Path>>(path: T) -> Option<String> {
+    path.escape_for_make()
+}
+
+/// [`PathStrExt::escape_for_ninja`] as a free function.
+pub fn escape_for_ninja<T: AsRef<Path>>(path: T) -> Option<String> {
+    path.escape_for_ninja()
+}
+
+/// [`PathStrExt::ends_with_extensions`] as a free function.
+pub fn ends_with_extensions<T: AsRef<Path>, S: AsRef<str>>(path: T, pattern: S) -> bool {
+    path.ends_with_extensions(pattern)
+}
+
+/// [`PathStrExt::extension_is`] as a free function.
+pub fn extension_is<T: AsRef<Path>, S: AsRef<str>>(path: T, extension: S) -> bool {
+    path.extension_is(extension)
+}
+
+/// [`PathStrExt::extension_is_any`] as a free function.
+pub fn extension_is_any<T: AsRef<Path>, I: IntoIterator<Item = S>, S: AsRef<str>>(
+    path: T,
+    extensions: I,
+) -> bool {
+    path.extension_is_any(extensions)
+}
+
+/// [`PathStrExt::extension_is_any_ignore_case`] as a free function.
+pub fn extension_is_any_ignore_case<T: AsRef<Path>, I: IntoIterator<Item = S>, S: AsRef<str>>(
+    path: T,
+    extensions: I,
+) -> bool {
+    path.extension_is_any_ignore_case(extensions)
+}
+
+/// [`PathStrExt::extension_is_ignore_case`] as a free function.
+pub fn extension_is_ignore_case<T: AsRef<Path>, S: AsRef<str>>(path: T, extension: S) -> bool {
+    path.extension_is_ignore_case(extension)
+}
+
+/// [`PathStrExt::fits_length_limits`] as a free function.
+pub fn fits_length_limits<T: AsRef<Path>>(path: T, limits: LengthLimits) -> bool {
+    path.fits_length_limits(limits)
+}
+
+/// [`PathStrExt::format_for_prompt`] as a free function.
+pub fn format_for_prompt<T: AsRef<Path>>(path: T, options: &PromptOptions) -> String {
+    path.format_for_prompt(options)
+}
+
+/// [`PathStrExt::has_extensions_exact`] as a free function.
+pub fn has_extensions_exact<T: AsRef<Path>, S: AsRef<str>>(path: T, extensions: S) -> bool {
+    path.has_extensions_exact(extensions)
+}
+
+/// [`PathStrExt::inferred_kind`] as a free function.
+pub fn inferred_kind<T: AsRef<Path>>(path: T) -> NameKind {
+    path.inferred_kind()
+}
+
+/// [`PathStrExt::inner_mime_type`] as a free function.
+#[cfg(feature = "mime")]
+pub fn inner_mime_type<T: AsRef<Path>>(path: T) -> Option<&'static str> {
+    path.inner_mime_type()
+}
+
+/// [`PathStrExt::is_empty_path`] as a free function.
+pub fn is_empty_path<T: AsRef<Path>>(path: T) -> bool {
+    path.is_empty_path()
+}
+
+/// [`PathStrExt::is_os_metadata_junk`] as a free function.
+pub fn is_os_metadata_junk<T: AsRef<Path>>(path: T) -> bool {
+    path.is_os_metadata_junk()
+}
+
+/// [`PathStrExt::is_transient_file`] as a free function.
+pub fn is_transient_file<T: AsRef<Path>>(path: T) -> bool {
+    path.is_transient_file()
+}
+
+/// [`PathStrExt::lock_path`] as a free function.
+pub fn lock_path<T: AsRef<Path>>(path: T) -> Option<PathBuf> {
+    path.lock_path()
+}
+
+/// [`PathStrExt::looks_sensitive`] as a free function.
+pub fn looks_sensitive<T: AsRef<Path>>(path: T) -> bool {
+    path.looks_sensitive()
+}
+
+/// [`PathStrExt::mime_type`] as a free function.
+#[cfg(feature = "mime")]
+pub fn mime_type<T: AsRef<Path>>(path: T) -> Option<&'static str> {
+    path.mime_type()
+}
+
+/// [`PathStrExt::portable_sort_key`] as a free function.
+#[cfg(feature = "unicode")]
+pub fn portable_sort_key<T: AsRef<Path>>(path: T) -> Vec<u8> {
+    path.portable_sort_key()
+}
+
+/// [`PathStrExt::portable_sort_key_case_folded`] as a free function.
+#[cfg(feature = "unicode")]
+pub fn portable_sort_key_case_folded<T: AsRef<Path>>(path: T) -> Vec<u8> {
+    path.portable_sort_key_case_folded()
+}
+
+/// [`PathStrExt::short_id`] as a free function.
+pub fn short_id<T: AsRef<Path>>(path: T, len: usize) -> String {
+    path.short_id(len)
+}
+
+/// [`PathStrExt::shorten_to_limit`] as a free function.
+pub fn shorten_to_limit<T: AsRef<Path>>(path: T, limits: LengthLimits) -> Option<PathBuf> {
+    path.shorten_to_limit(limits)
+}
+
+/// [`PathStrExt::sibling`] as a free function.
+pub fn sibling<T: AsRef<Path>, S: AsRef<OsStr>>(path: T, name: S) -> Option<PathBuf> {
+    path.sibling(name)
+}
+
+/// [`PathStrExt::sibling_with_extension`] as a free function.
+pub fn sibling_with_extension<T: AsRef<Path>, S: AsRef<OsStr>>(
+    path: T,
+    extension: S,
+) -> Option<PathBuf> {
+    path.sibling_with_extension(extension)
+}
+
+/// [`PathStrExt::slugify_stem`] as a free function.
+#[cfg(feature = "slug")]
+pub fn slugify_stem<T: AsRef<Path>>(path: T) -> PathBuf {
+    path.slugify_stem()
+}
+
+/// [`PathStrExt::source_language`] as a free function.
+pub fn source_language<T: AsRef<Path>>(path: T) -> Option<Language> {
+    path.source_language()
+}
+
+/// [`PathStrExt::starts_or_ends_with`] as a free function.
+pub fn starts_or_ends_with<T: AsRef<Path>, S: AsRef<str>>(path: T, pattern: S) -> bool {
+    path.starts_or_ends_with(pattern)
+}
+
+/// [`PathStrExt::stem_to_kebab_case`] as a free function.
+#[cfg(feature = "slug")]
+pub fn stem_to_kebab_case<T: AsRef<Path>>(path: T) -> PathBuf {
+    path.stem_to_kebab_case()
+}
+
+/// [`PathStrExt::stem_to_snake_case`] as a free function.
+#[cfg(feature = "slug")]
+pub fn stem_to_snake_case<T: AsRef<Path>>(path: T) -> PathBuf {
+    path.stem_to_snake_case()
+}
+
+/// [`PathStrExt::to_cache_key`] as a free function.
+pub fn to_cache_key<T: AsRef<Path>>(path: T) -> String {
+    path.to_cache_key()
+}
+
+/// [`PathStrExt::transliterate_file_name`] as a free function.
+#[cfg(feature = "translit")]
+pub fn transliterate_file_name<T: AsRef<Path>>(path: T) -> PathBuf {
+    path.transliterate_file_name()
+}
+
+/// [`PathStrExt::transliterate_file_name_hashed`] as a free function.
+#[cfg(feature = "translit")]
+pub fn transliterate_file_name_hashed<T: AsRef<Path>>(path: T) -> PathBuf {
+    path.transliterate_file_name_hashed()
+}
+
+/// [`PathStrExt::truncate_file_name`] as a free function.
+pub fn truncate_file_name<T: AsRef<Path>>(path: T, max_bytes: usize) -> Option<PathBuf> {
+    path.truncate_file_name(max_bytes)
+}
+
+/// [`PathStrExt::truncate_file_name_hashed`] as a free function.
+pub fn truncate_file_name_hashed<T: AsRef<Path>>(path: T, max_bytes: usize) -> Option<PathBuf> {
+    path.truncate_file_name_hashed(max_bytes)
+}
+
+/// [`PathComponentExt::absolutize_from`] as a free function.
+pub fn absolutize_from<T: AsRef<Path>, P: AsRef<Path>>(path: T, base: P) -> PathBuf {
+    path.absolutize_from(base)
+}
+
+/// [`PathComponentExt::artifact_kind`] as a free function.
+pub fn artifact_kind<T: AsRef<Path>>(path: T) -> Option<ArtifactKind> {
+    path.artifact_kind()
+}
+
+/// [`PathComponentExt::bucket_by_number`] as a free function.
+pub fn bucket_by_number<T: AsRef<Path>>(path: T, width: usize, group: usize) -> Option<PathBuf> {
+    path.bucket_by_number(width, group)
+}
+
+/// [`PathComponentExt::bucketed_number`] as a free function.
+pub fn bucketed_number<T: AsRef<Path>>(path: T) -> Option<u64> {
+    path.bucketed_number()
+}
+
+/// [`PathComponentExt::closest_match`] as a free function.
+pub fn closest_match<T, I, P>(path: T, candidates: I, max_distance: usize) -> Option<P>
+where
+    T: AsRef<Path>,
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    path.closest_match(candidates, max_distance)
+}
+
+/// [`PathComponentExt::cmp_dirs_first`] as a free function.
+pub fn cmp_dirs_first<T: AsRef<Path>, P: AsRef<Path>, F: Fn(&Path) -> bool>(
+    path: T,
+    other: P,
+    is_dir: F,
+) -> Ordering {
+    path.cmp_dirs_first(other, is_dir)
+}
+
+/// [`PathComponentExt::collate_cmp`] as a free function.
+#[cfg(feature = "icu")]
+pub fn collate_cmp<T: AsRef<Path>, P: AsRef<Path>>(
+    path: T,
+    other: P,
+    locale: &icu_locale_core::Locale,
+) -> Ordering {
+    path.collate_cmp(other, locale)
+}
+
+/// [`PathComponentExt::convert_path`] as a free function.
+pub fn convert_path<T: AsRef<Path>>(path: T, style: PathStyle) -> Option<PathBuf> {
+    path.convert_path(style)
+}
+
+/// [`PathComponentExt::ends_with_components`] as a free function.
+pub fn ends_with_components<T, I, S>(path: T, suffix: I) -> bool
+where
+    T: AsRef<Path>,
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    path.ends_with_components(suffix)
+}
+
+/// [`PathComponentExt::fan_in`] as a free function.
+pub fn fan_in<T: AsRef<Path>>(path: T) -> PathBuf {
+    path.fan_in()
+}
+
+/// [`PathComponentExt::fan_out`] as a free function.
+pub fn fan_out<T: AsRef<Path>>(path: T, levels: usize) -> Option<PathBuf> {
+    path.fan_out(levels)
+}
+
+/// [`PathComponentExt::has_component`] as a free function.
+pub fn has_component<T: AsRef<Path>, S: AsRef<str>>(path: T, component: S) -> bool {
+    path.has_component(component)
+}
+
+/// [`PathComponentExt::has_component_sequence`] as a free function.
+pub fn has_component_sequence<T, I, S>(path: T, sequence: I) -> bool
+where
+    T: AsRef<Path>,
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    path.has_component_sequence(sequence)
+}
+
+/// [`PathComponentExt::in_cache_dir`] as a free function.
+#[cfg(feature = "dirs")]
+pub fn in_cache_dir<T: AsRef<Path>, A: AsRef<Path>>(path: T, app: A) -> Option<PathBuf> {
+    path.in_cache_dir(app)
+}
+
+/// [`PathComponentExt::in_data_dir`] as a free function.
+#[cfg(feature = "dirs")]
+pub fn in_data_dir<T: AsRef<Path>, A: AsRef<Path>>(path: T, app: A) -> Option<PathBuf> {
+    path.in_data_dir(app)
+}
+
+/// [`PathComponentExt::is_build_artifact`] as a free function.
+pub fn is_build_artifact<T: AsRef<Path>>(path: T) -> bool {
+    path.is_build_artifact()
+}
+
+/// [`PathComponentExt::is_fat_safe`] as a free function.
+pub fn is_fat_safe<T: AsRef<Path>>(path: T) -> bool {
+    path.is_fat_safe()
+}
+
+/// [`PathComponentExt::is_joliet_safe`] as a free function.
+pub fn is_joliet_safe<T: AsRef<Path>>(path: T) -> bool {
+    path.is_joliet_safe()
+}
+
+/// [`PathComponentExt::is_normalized`] as a free function.
+pub fn is_normalized<T: AsRef<Path>>(path: T) -> bool {
+    path.is_normalized()
+}
+
+/// [`PathComponentExt::is_object_key_safe`] as a free function.
+pub fn is_object_key_safe<T: AsRef<Path>>(path: T, store: ObjectStore) -> bool {
+    path.is_object_key_safe(store)
+}
+
+/// [`PathComponentExt::is_posix_portable`] as a free function.
+pub fn is_posix_portable<T: AsRef<Path>>(path: T) -> bool {
+    path.is_posix_portable()
+}
+
+/// [`PathComponentExt::natural_cmp`] as a free function.
+pub fn natural_cmp<T: AsRef<Path>, P: AsRef<Path>>(path: T, other: P) -> Ordering {
+    path.natural_cmp(other)
+}
+
+/// [`PathComponentExt::normalize`] as a free function.
+pub fn normalize<T: AsRef<Path>>(path: T) -> PathBuf {
+    path.normalize()
+}
+
+/// [`PathComponentExt::normalize_into`] as a free function.
+pub fn normalize_into<T: AsRef<Path>>(path: T, buf: &mut PathBuf) {
+    path.normalize_into(buf)
+}
+
+/// [`PathComponentExt::normalize_uri_style`] as a free function.
+pub fn normalize_uri_style<T: AsRef<Path>>(path: T) -> PathBuf {
+    path.normalize_uri_style()
+}
+
+/// [`PathComponentExt::normalize_with_style`] as a free function.
+pub fn normalize_with_style<T: AsRef<Path>>(path: T, style: NormalizeStyle) -> PathBuf {
+    path.normalize_with_style(style)
+}
+
+/// [`PathComponentExt::normalize_with_style_into`] as a free function.
+pub fn normalize_with_style_into<T: AsRef<Path>>(
+    path: T,
+    style: NormalizeStyle,
+    buf: &mut PathBuf,
+) {
+    path.normalize_with_style_into(style, buf)
+}
+
+/// [`PathComponentExt::posix_portability_violations`] as a free function.
+pub fn posix_portability_violations<T: AsRef<Path>>(
+    path: T,
+    limits: LengthLimits,
+) -> Vec<PosixViolation> {
+    path.posix_portability_violations(limits)
+}
+
+/// [`PathComponentExt::rebase_into`] as a free function.
+pub fn rebase_into<T: AsRef<Path>, F: AsRef<Path>, U: AsRef<Path>>(
+    path: T,
+    from: F,
+    to: U,
+    buf: &mut PathBuf,
+) {
+    path.rebase_into(from, to, buf)
+}
+
+/// [`PathComponentExt::redact`] as a free function.
+pub fn redact<T: AsRef<Path>>(path: T, options: &RedactOptions) -> PathBuf {
+    path.redact(options)
+}
+
+/// [`PathComponentExt::relative_to_config_dir`] as a free function.
+#[cfg(feature = "dirs")]
+pub fn relative_to_config_dir<T: AsRef<Path>>(path: T) -> Option<PathBuf> {
+    path.relative_to_config_dir()
+}
+
+/// [`PathComponentExt::sanitize`] as a free function.
+pub fn sanitize<T: AsRef<Path>>(path: T) -> PathBuf {
+    path.sanitize()
+}
+
+/// [`PathComponentExt::sanitize_with`] as a free function.
+pub fn sanitize_with<T: AsRef<Path>>(path: T, policy: &CharPolicy) -> PathBuf {
+    path.sanitize_with(policy)
+}
+
+/// [`PathComponentExt::starts_with_components`] as a free function.
+pub fn starts_with_components<T, I, S>(path: T, prefix: I) -> bool
+where
+    T: AsRef<Path>,
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    path.starts_with_components(prefix)
+}
+
+/// [`PathComponentExt::to_component_strings`] as a free function.
+pub fn to_component_strings<T: AsRef<Path>>(path: T) -> Option<Vec<String>> {
+    path.to_component_strings()
+}
+
+/// [`PathComponentExt::to_fat_8_3`] as a free function.
+pub fn to_fat_8_3<T, I, S>(path: T, existing: I) -> Option<PathBuf>
+where
+    T: AsRef<Path>,
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    path.to_fat_8_3(existing)
+}
+
+/// [`PathComponentExt::to_iso9660_level1`] as a free function.
+pub fn to_iso9660_level1<T: AsRef<Path>>(path: T) -> PathBuf {
+    path.to_iso9660_level1()
+}
+
+/// [`PathComponentExt::to_object_key`] as a free function.
+pub fn to_object_key<T: AsRef<Path>>(path: T) -> Option<String> {
+    path.to_object_key()
+}
+
+/// [`PathComponentExt::with_root`] as a free function.
+pub fn with_root<T: AsRef<Path>, O: AsRef<Path>, N: AsRef<Path>>(
+    path: T,
+    old_root: O,
+    new_root: N,
+) -> PathBuf {
+    path.with_root(old_root, new_root)
+}
+
+/// [`PathFsExt::absolutize`] as a free function.
+#[cfg(feature = "fs")]
+pub fn absolutize<T: AsRef<Path>>(path: T) -> io::Result<PathBuf> {
+    path.absolutize()
+}
+
+/// [`PathFsExt::actual_case`] as a free function.
+#[cfg(feature = "fs")]
+pub fn actual_case<T: AsRef<Path>>(path: T) -> io::Result<PathBuf> {
+    path.actual_case()
+}
+
+/// [`PathFsExt::available_space`] as a free function.
+#[cfg(feature = "fs")]
+pub fn available_space<T: AsRef<Path>>(path: T) -> io::Result<u64> {
+    path.available_space()
+}
+
+/// [`PathFsExt::canonicalize_simplified`] as a free function.
+#[cfg(feature = "fs")]
+pub fn canonicalize_simplified<T: AsRef<Path>>(path: T) -> io::Result<PathBuf> {
+    path.canonicalize_simplified()
+}
+
+/// [`PathFsExt::cmp_dirs_first_fs`] as a free function.
+#[cfg(feature = "fs")]
+pub fn cmp_dirs_first_fs<T: AsRef<Path>, P: AsRef<Path>>(path: T, other: P) -> Ordering {
+    path.cmp_dirs_first_fs(other)
+}
+
+/// [`PathFsExt::create_unique_child`] as a free function.
+#[cfg(feature = "fs")]
+pub fn create_unique_child<T: AsRef<Path>>(
+    path: T,
+    prefix: &str,
+    suffix: &str,
+) -> io::Result<PathBuf> {
+    path.create_unique_child(prefix, suffix)
+}
+
+/// [`PathFsExt::ensure_dir`] as a free function.
+#[cfg(feature = "fs")]
+pub fn ensure_dir<T: AsRef<Path>>(path: T) -> io::Result<bool> {
+    path.ensure_dir()
+}
+
+/// [`PathFsExt::ensure_parent_dir`] as a free function.
+#[cfg(feature = "fs")]
+pub fn ensure_parent_dir<T: AsRef<Path>>(path: T) -> io::Result<bool> {
+    path.ensure_parent_dir()
+}
+
+/// [`PathFsExt::find_up`] as a free function.
+#[cfg(feature = "fs")]
+pub fn find_up<T: AsRef<Path>, N: AsRef<Path>>(path: T, name: N) -> io::Result<Option<PathBuf>> {
+    path.find_up(name)
+}
+
+/// [`PathFsExt::hardlink_count`] as a free function.
+#[cfg(feature = "fs")]
+pub fn hardlink_count<T: AsRef<Path>>(path: T) -> io::Result<u64> {
+    path.hardlink_count()
+}
+
+/// [`PathFsExt::hardlink_to`] as a free function.
+#[cfg(feature = "fs")]
+pub fn hardlink_to<T: AsRef<Path>, P: AsRef<Path>>(path: T, dest: P) -> io::Result<()> {
+    path.hardlink_to(dest)
+}
+
+/// [`PathFsExt::is_block_device`] as a free function.
+#[cfg(feature = "fs")]
+pub fn is_block_device<T: AsRef<Path>>(path: T) -> bool {
+    path.is_block_device()
+}
+
+/// [`PathFsExt::is_char_device`] as a free function.
+#[cfg(feature = "fs")]
+pub fn is_char_device<T: AsRef<Path>>(path: T) -> bool {
+    path.is_char_device()
+}
+
+/// [`PathFsExt::is_fifo`] as a free function.
+#[cfg(feature = "fs")]
+pub fn is_fifo<T: AsRef<Path>>(path: T) -> bool {
+    path.is_fifo()
+}
+
+/// [`PathFsExt::is_hardlinked_with`] as a free function.
+#[cfg(feature = "fs")]
+pub fn is_hardlinked_with<T: AsRef<Path>, P: AsRef<Path>>(path: T, other: P) -> io::Result<bool> {
+    path.is_hardlinked_with(other)
+}
+
+/// [`PathFsExt::is_regular_file`] as a free function.
+#[cfg(feature = "fs")]
+pub fn is_regular_file<T: AsRef<Path>>(path: T) -> bool {
+    path.is_regular_file()
+}
+
+/// [`PathFsExt::is_socket`] as a free function.
+#[cfg(feature = "fs")]
+pub fn is_socket<T: AsRef<Path>>(path: T) -> bool {
+    path.is_socket()
+}
+
+/// [`PathFsExt::is_writable_by_current_user`] as a free function.
+#[cfg(feature = "fs")]
+pub fn is_writable_by_current_user<T: AsRef<Path>>(path: T) -> io::Result<bool> {
+    path.is_writable_by_current_user()
+}
+
+/// [`PathFsExt::make_executable`] as a free function.
+#[cfg(feature = "fs")]
+pub fn make_executable<T: AsRef<Path>>(path: T) -> io::Result<()> {
+    path.make_executable()
+}
+
+/// [`PathFsExt::make_readonly`] as a free function.
+#[cfg(feature = "fs")]
+pub fn make_readonly<T: AsRef<Path>>(path: T) -> io::Result<()> {
+    path.make_readonly()
+}
+
+/// [`PathFsExt::move_to_trash`] as a free function.
+#[cfg(feature = "trash")]
+pub fn move_to_trash<T: AsRef<Path>>(path: T) -> io::Result<()> {
+    path.move_to_trash()
+}
+
+/// [`PathFsExt::read_pid_file`] as a free function.
+#[cfg(feature = "fs")]
+pub fn read_pid_file<T: AsRef<Path>>(path: T) -> io::Result<Option<PidFile>> {
+    path.read_pid_file()
+}
+
+/// [`PathFsExt::resolve_lenient`] as a free function.
+#[cfg(feature = "fs")]
+pub fn resolve_lenient<T: AsRef<Path>>(path: T) -> io::Result<PathBuf> {
+    path.resolve_lenient()
+}
+
+/// [`PathFsExt::state`] as a free function.
+#[cfg(feature = "fs")]
+pub fn state<T: AsRef<Path>>(path: T) -> PathState {
+    path.state()
+}
+
+/// [`PathFsExt::touch`] as a free function.
+#[cfg(feature = "fs")]
+pub fn touch<T: AsRef<Path>>(path: T) -> io::Result<()> {
+    path.touch()
+}
+
+/// [`PathFsExt::touch_with`] as a free function.
+#[cfg(feature = "fs")]
+pub fn touch_with<T: AsRef<Path>>(path: T, options: TouchOptions) -> io::Result<()> {
+    path.touch_with(options)
+}
+
+/// [`PathFsExt::try_lock`] as a free function.
+#[cfg(feature = "fs")]
+pub fn try_lock<T: AsRef<Path>>(path: T) -> io::Result<Option<LockFile>> {
+    path.try_lock()
+}
+
+/// [`PathFsExt::unique_child`] as a free function.
+#[cfg(feature = "fs")]
+pub fn unique_child<T: AsRef<Path>>(path: T, prefix: &str, suffix: &str) -> io::Result<PathBuf> {
+    path.unique_child(prefix, suffix)
+}
+
+/// [`PathFsExt::watch`] as a free function.
+#[cfg(feature = "notify")]
+pub fn watch<T: AsRef<Path>, F>(path: T, callback: F) -> notify::Result<notify::RecommendedWatcher>
+where
+    F: FnMut(notify::Result<notify::Event>) + Send + 'static,
+{
+    path.watch(callback)
+}
+
+/// [`PathFsExt::watch_matching`] as a free function.
+#[cfg(feature = "notify")]
+pub fn watch_matching<T: AsRef<Path>, F>(
+    path: T,
+    globs: globset::GlobSet,
+    callback: F,
+) -> notify::Result<notify::RecommendedWatcher>
+where
+    F: FnMut(notify::Result<notify::Event>) + Send + 'static,
+{
+    path.watch_matching(globs, callback)
+}
+
+/// [`PathFsExt::write_pid_file`] as a free function.
+#[cfg(feature = "fs")]
+pub fn write_pid_file<T: AsRef<Path>>(path: T) -> io::Result<()> {
+    path.write_pid_file()
+}
@@ -0,0 +1,313 @@
+//! Generators for adversarial paths: deep nesting, non-UTF-8 bytes, Windows prefixes,
+//! reserved device names and mixed separators.
+//!
+//! With the `proptest` feature the functions here return proptest strategies, and with the
+//! `arbitrary` feature [`ArbitraryPath`] can be built from fuzzer input.
+
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+/// Prefixes that change how Windows interprets the rest of the path.
+pub const WINDOWS_PREFIXES: &[&str] = &[
+    "C:",
+    "C:\\",
+    "c:/",
+    "\\",
+    "\\\\?\\C:\\",
+    "\\\\server\\share\\",
+    "\\\\?\\UNC\\server\\share\\",
+    "\\\\.\\COM1\\",
+];
+
+/// Names Windows reserves for devices, with and without extensions.
+pub const RESERVED_NAMES: &[&str] = &[
+    "CON",
+    "PRN",
+    "AUX",
+    "NUL",
+    "COM1",
+    "COM9",
+    "LPT1",
+    "nul.txt",
+    "con.tar.gz",
+    "CONIN$",
+];
+
+/// Components that tend to trip up path handling code.
+pub const INTERESTING_COMPONENTS: &[&str] = &[
+    ".",
+    "..",
+    "...",
+    " ",
+    "trailing.",
+    "trailing ",
+    ".hidden",
+    "archive.tar.gz",
+    "lastdot.",
+    "ünïcödé",
+    "日本語",
+    "crab🦀",
+    "with space",
+    "a*b?",
+    "new\nline",
+    "-dash",
+];
+
+/// Separators mixed between components.
+pub const SEPARATORS: &[&str] = &["/", "\\", "//"];
+
+/// Builds a component that is not valid UTF-8 (or, on Windows, not valid UTF-16).
+pub fn non_utf8_component(seed: u8) -> OsString {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStringExt;
+        // a lone continuation byte is never valid UTF-8
+        OsString::from_vec(vec![b'f', 0x80 | (seed & 0x3f), b'x'])
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::ffi::OsStringExt;
+        // an unpaired surrogate is never valid UTF-16
+        OsString::from_wide(&[
+            u16::from(b'f'),
+            0xD800 | u16::from(seed & 0x3f),
+            u16::from(b'x'),
+        ])
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = seed;
+        OsString::from("f\u{FFFD}x")
+    }
+}
+
+fn assemble(prefix: Option<&str>, parts: Vec<(OsString, &str)>) -> PathBuf {
+    let mut path = OsString::from(prefix.unwrap_or_default());
+    for (i, (component, separator)) in parts.into_iter().enumerate() {
+        if i > 0 {
+            path.push(separator);
+        }
+        path.push(component);
+    }
+    PathBuf::from(path)
+}
+
+#[cfg(feature = "proptest")]
+mod strategies {
+    use super::{
+        assemble, non_utf8_component, INTERESTING_COMPONENTS, RESERVED_NAMES, SEPARATORS,
+        WINDOWS_PREFIXES,
+    };
+
+    use proptest::prelude::*;
+    use std::ffi::OsString;
+    use std::ops::Range;
+    use std::path::PathBuf;
+
+    /// A single component, which may be reserved, odd, random or not valid UTF-8.
+    pub fn component() -> impl Strategy<Value = OsString> {
+        prop_oneof![
+            prop::sample::select(INTERESTING_COMPONENTS).prop_map(OsString::from),
+            prop::sample::select(RESERVED_NAMES).prop_map(OsString::from),
+            "[a-zA-Z0-9._ -]{1,12}".prop_map(OsString::from),
+            any::<u8>().prop_map(non_utf8_component),
+        ]
+    }
+
+    /// Any of the separators in [`SEPARATORS`](super::SEPARATORS).
+    pub fn separator() -> impl Strategy<Value = &'static str> {
+        prop::sample::select(SEPARATORS)
+    }
+
+    /// Any of the prefixes in [`WINDOWS_PREFIXES`](super::WINDOWS_PREFIXES).
+    pub fn windows_prefix() -> impl Strategy<Value = &'static str> {
+        prop::sample::select(WINDOWS_PREFIXES)
+    }
+
+    /// An optionally prefixed path with a component count drawn from `depth`.
+    pub fn path_with_depth(depth: Range<usize>) -> impl Strategy<Value = PathBuf> {
+        (
+            prop::option::of(windows_prefix()),
+            prop::collection::vec((component(), separator()), depth),
+        )
+            .prop_map(|(prefix, parts)| assemble(prefix, parts))
+    }
+
+    /// A deeply nested path.
+    pub fn deep_path() -> impl Strategy<Value = PathBuf> {
+        path_with_depth(32..128)
+    }
+
+    /// A backslash separated path behind a Windows prefix.
+    pub fn windows_path() -> impl Strategy<Value = PathBuf> {
+        (
+            windows_prefix(),
+            prop::collection::vec(component().prop_map(|c| (c, "\\")), 0..8),
+        )
+            .prop_map(|(prefix, parts)| assemble(Some(prefix), parts))
+    }
+
+    /// A path that contains at least one component that is not valid UTF-8.
+    pub fn non_utf8_path() -> impl Strategy<Value = PathBuf> {
+        (
+            prop::collection::vec((component(), separator()), 0..6),
+            any::<u8>(),
+            any::<prop::sample::Index>(),
+        )
+            .prop_map(|(mut parts, seed, index)| {
+                let at = index.index(parts.len() + 1);
+                parts.insert(at, (non_utf8_component(seed), "/"));
+                assemble(None, parts)
+            })
+    }
+
+    /// A path ending in a reserved device name.
+    pub fn reserved_name_path() -> impl Strategy<Value = PathBuf> {
+        (
+            prop::collection::vec((component(), separator()), 0..4),
+            prop::sample::select(RESERVED_NAMES),
+        )
+            .prop_map(|(mut parts, name)| {
+                parts.push((OsString::from(name), "/"));
+                assemble(None, parts)
+            })
+    }
+
+    /// A path alternating between `/` and `\`.
+    pub fn mixed_separator_path() -> impl Strategy<Value = PathBuf> {
+        prop::collection::vec("[a-z]{1,6}", 2..10).prop_map(|names| {
+            let parts = names
+                .into_iter()
+                .enumerate()
+                .map(|(i, n)| (OsString::from(n), if i % 2 == 0 { "\\" } else { "/" }))
+                .collect();
+            assemble(None, parts)
+        })
+    }
+
+    /// Any of the paths above.
+    pub fn path() -> impl Strategy<Value = PathBuf> {
+        prop_oneof![
+            path_with_depth(0..8),
+            deep_path(),
+            windows_path(),
+            non_utf8_path(),
+            reserved_name_path(),
+            mixed_separator_path(),
+        ]
+    }
+}
+
+#[cfg(feature = "proptest")]
+pub use strategies::*;
+
+#[cfg(feature = "arbitrary")]
+mod fuzz {
+    use super::{
+        assemble, non_utf8_component, INTERESTING_COMPONENTS, RESERVED_NAMES, SEPARATORS,
+        WINDOWS_PREFIXES,
+    };
+
+    use arbitrary::{Arbitrary, Unstructured};
+    use std::ffi::OsString;
+    use std::path::{Path, PathBuf};
+
+    /// A path built from fuzzer input out of the same pieces as the proptest strategies.
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    pub struct ArbitraryPath(pub PathBuf);
+
+    impl<'a> Arbitrary<'a> for ArbitraryPath {
+        fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+            let prefix = if u.arbitrary()? {
+                Some(*u.choose(WINDOWS_PREFIXES)?)
+            } else {
+                None
+            };
+            let depth = u.int_in_range(0..=64)?;
+            let mut parts = Vec::with_capacity(depth);
+            for _ in 0..depth {
+                let component = match u.int_in_range(0..=3)? {
+                    0 => OsString::from(*u.choose(INTERESTING_COMPONENTS)?),
+                    1 => OsString::from(*u.choose(RESERVED_NAMES)?),
+                    2 => OsString::from(<&str>::arbitrary(u)?),
+                    _ => non_utf8_component(u.arbitrary()?),
+                };
+                parts.push((component, *u.choose(SEPARATORS)?));
+            }
+            Ok(ArbitraryPath(assemble(prefix, parts)))
+        }
+    }
+
+    impl AsRef<Path> for ArbitraryPath {
+        fn as_ref(&self) -> &Path {
+            &self.0
+        }
+    }
+
+    impl From<ArbitraryPath> for PathBuf {
+        fn from(path: ArbitraryPath) -> Self {
+            path.0
+        }
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+pub use fuzz::ArbitraryPath;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_non_utf8_component() {
+        for seed in [0, 1, 0x3f, 0xff] {
+            assert_eq!(non_utf8_component(seed).to_str(), None);
+        }
+    }
+
+    #[test]
+    fn test_assemble() {
+        let parts = vec![
+            (OsString::from("a"), "/"),
+            (OsString::from("b"), "\\"),
+            (OsString::from("c"), "/"),
+        ];
+        assert_eq!(assemble(Some("C:\\"), parts), PathBuf::from("C:\\a\\b/c"));
+        assert_eq!(assemble(None, vec![]), PathBuf::from(""));
+    }
+
+    #[cfg(feature = "proptest")]
+    proptest::proptest! {
+        #[test]
+        fn test_methods_do_not_panic(p in path()) {
+            let _ = p.contains("a");
+            let _ = p.has_component("..");
+            let _ = p.starts_or_ends_with("/");
+            let _ = p.strip_extensions();
+            let _ = p.ends_with_extensions(".gz");
+            let _ = p.strip_prefix_if_needed("C:\\");
+        }
+
+        #[test]
+        fn test_non_utf8_path_is_not_utf8(p in non_utf8_path()) {
+            proptest::prop_assert!(p.to_str().is_none());
+        }
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn test_arbitrary_path() {
+        use arbitrary::{Arbitrary, Unstructured};
+
+        let data: Vec<u8> = (0..=255).cycle().take(4096).collect();
+        let mut u = Unstructured::new(&data);
+        while let Ok(p) = ArbitraryPath::arbitrary(&mut u) {
+            let _ = p.has_component("..");
+            let _ = p.strip_extensions();
+            if u.is_empty() {
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,78 @@
+//! A small stable hash for deriving names, not for anything security sensitive.
+
+use std::path::{Component, Path};
+
+use crate::normalize;
+
+/// 64-bit FNV-1a, which is stable across platforms, releases and runs.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+/// The first `len` hex digits, at most 16, of the hash of the lexically normalized path with its
+/// components joined by `/`, so `a\b` on Windows and `a/b` elsewhere get the same id.
+pub(crate) fn short_id(path: &Path, len: usize) -> String {
+    let mut bytes = Vec::new();
+    for component in normalize::normalize(path).components() {
+        match component {
+            Component::RootDir => {}
+            component => bytes.extend_from_slice(component.as_os_str().as_encoded_bytes()),
+        }
+        bytes.push(b'/');
+    }
+    let mut id = format!("{:016x}", fnv1a(&bytes));
+    id.truncate(len);
+    id
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fnv1a, short_id};
+
+    use std::path::Path;
+
+    #[test]
+    fn test_fnv1a() {
+        // reference values from the FNV test suite
+        let tests: &[(&[u8], u64)] = &[
+            (b"", 0xcbf29ce484222325),
+            (b"a", 0xaf63dc4c8601ec8c),
+            (b"foobar", 0x85944171f73967e8),
+        ];
+
+        for test in tests {
+            assert_eq!(fnv1a(test.0), test.1);
+        }
+    }
+
+    #[test]
+    fn test_short_id() {
+        let id = short_id(Path::new("/srv/app/data"), 16);
+        assert_eq!(id.len(), 16);
+        assert!(id
+            .bytes()
+            .all(|b| b.is_ascii_hexdigit() && !b.is_ascii_uppercase()));
+
+        let tests = &[
+            ("/srv/app/data", true),
+            ("/srv/app/data/", true),
+            ("/srv/./app//data", true),
+            ("/srv/app/x/../data", true),
+            ("/srv/app", false),
+            ("srv/app/data", false),
+            ("/srv/app/Data", false),
+        ];
+
+        for test in tests {
+            assert_eq!(short_id(Path::new(test.0), 16) == id, test.1, "{}", test.0);
+        }
+        assert_eq!(short_id(Path::new("/srv/app/data"), 8), id[..8]);
+        assert_eq!(short_id(Path::new("/srv/app/data"), 40), id);
+        assert_eq!(short_id(Path::new("/srv/app/data"), 0), "");
+    }
+}
@@ -0,0 +1,239 @@
+//! Finding the files `#include`-like directives name, for preprocessors of C, GLSL and config
+//! languages.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::resolve::PathResolver;
+
+/// Where an [`IncludeResolver`] looks first.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum IncludeOrder {
+    /// The including file's directory, then the search directories, as C's `#include "x.h"`.
+    #[default]
+    IncludingFileFirst,
+    /// The search directories, then the including file's directory.
+    SearchPathFirst,
+    /// Only the search directories, as C's `#include <x.h>`.
+    SearchPathOnly,
+}
+
+/// Resolves included names against an ordered list of search directories, the including file's
+/// directory first or last or not at all as its [`IncludeOrder`] says.
+///
+/// Names are joined as they are, so `sub/x.h` and `../x.h` work, and an absolute name is only ever
+/// itself. Nothing is normalized, so `..` after a symlinked directory goes where the OS takes it.
+///
+/// ```rust
+/// use pathext::resolve::MockFs;
+/// use pathext::{IncludeOrder, IncludeResolver};
+/// use std::path::Path;
+///
+/// let fs = MockFs::builder()
+///     .file("/proj/src/util.h")
+///     .file("/proj/include/util.h")
+///     .file("/usr/include/stdio.h")
+///     .build();
+/// let resolver = IncludeResolver::new().dir("/proj/include").dir("/usr/include");
+/// let main = Path::new("/proj/src/main.c");
+///
+/// let found = resolver.resolve_with("util.h", main, &fs).unwrap();
+/// assert_eq!(found.as_deref(), Some(Path::new("/proj/src/util.h")));
+/// let system = resolver.clone().order(IncludeOrder::SearchPathOnly);
+/// let found = system.resolve_with("util.h", main, &fs).unwrap();
+/// assert_eq!(found.as_deref(), Some(Path::new("/proj/include/util.h")));
+/// assert_eq!(resolver.resolve_with("missing.h", main, &fs).unwrap(), None);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IncludeResolver {
+    dirs: Vec<PathBuf>,
+    order: IncludeOrder,
+}
+
+impl IncludeResolver {
+    /// A resolver without search directories, looking next to the including file.
+    pub fn new() -> Self {
+        IncludeResolver::default()
+    }
+
+    /// Adds a search directory after the ones added before, like another `-I`.
+    pub fn dir<P: Into<PathBuf>>(mut self, dir: P) -> Self {
+        self.dirs.push(dir.into());
+        self
+    }
+
+    pub fn order(mut self, order: IncludeOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    pub fn dirs(&self) -> impl Iterator<Item = &Path> {
+        self.dirs.iter().map(PathBuf::as_path)
+    }
+
+    /// Every path tried for `name` included from `including_file`, in order.
+    pub fn candidates<N, I>(&self, name: N, including_file: I) -> Vec<PathBuf>
+    where
+        N: AsRef<Path>,
+        I: AsRef<Path>,
+    {
+        let name = name.as_ref();
+        if name.is_absolute() {
+            return vec![name.to_path_buf()];
+        }
+        let beside = including_file
+            .as_ref()
+            .parent()
+            .map(|dir| dir.join(name))
+            .filter(|_| self.order != IncludeOrder::SearchPathOnly);
+        let searched = self.dirs.iter().map(|dir| dir.join(name));
+        match self.order {
+            IncludeOrder::IncludingFileFirst => beside.into_iter().chain(searched).collect(),
+            _ => searched.chain(beside).collect(),
+        }
+    }
+
+    /// The first of the [`candidates`](IncludeResolver::candidates) that exists in `resolver`.
+    pub fn resolve_with<N, I, R>(
+        &self,
+        name: N,
+        including_file: I,
+        resolver: &R,
+    ) -> io::Result<Option<PathBuf>>
+    where
+        N: AsRef<Path>,
+        I: AsRef<Path>,
+        R: PathResolver,
+    {
+        for candidate in self.candidates(name, including_file) {
+            if resolver.exists(&candidate)? {
+                return Ok(Some(candidate));
+            }
+        }
+        Ok(None)
+    }
+
+    /// [`resolve_with`](IncludeResolver::resolve_with) on the real filesystem.
+    #[cfg(feature = "fs")]
+    pub fn resolve<N, I>(&self, name: N, including_file: I) -> io::Result<Option<PathBuf>>
+    where
+        N: AsRef<Path>,
+        I: AsRef<Path>,
+    {
+        self.resolve_with(name, including_file, &crate::resolve::RealFs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IncludeOrder, IncludeResolver};
+    use crate::resolve::MockFs;
+
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn test_candidates() {
+        let resolver = IncludeResolver::new().dir("inc").dir("/sys");
+        let tests: &[(IncludeOrder, &str, &[&str])] = &[
+            (
+                IncludeOrder::IncludingFileFirst,
+                "a.h",
+                &["src/a.h", "inc/a.h", "/sys/a.h"],
+            ),
+            (
+                IncludeOrder::SearchPathFirst,
+                "sub/a.h",
+                &["inc/sub/a.h", "/sys/sub/a.h", "src/sub/a.h"],
+            ),
+            (
+                IncludeOrder::SearchPathOnly,
+                "a.h",
+                &["inc/a.h", "/sys/a.h"],
+            ),
+            (IncludeOrder::IncludingFileFirst, "/abs/a.h", &["/abs/a.h"]),
+        ];
+
+        for test in tests {
+            let candidates = resolver
+                .clone()
+                .order(test.0)
+                .candidates(test.1, "src/main.c");
+            let expected: Vec<PathBuf> = test.2.iter().map(PathBuf::from).collect();
+            assert_eq!(candidates, expected, "{:?} {}", test.0, test.1);
+        }
+        assert_eq!(
+            resolver.dirs().collect::<Vec<_>>(),
+            [Path::new("inc"), Path::new("/sys")]
+        );
+    }
+
+    #[test]
+    fn test_resolve_with() {
+        let fs = MockFs::builder()
+            .file("/p/src/local.h")
+            .file("/p/src/both.h")
+            .file("/p/inc/both.h")
+            .file("/p/inc/nested/deep.h")
+            .file("/p/shared.h")
+            .build();
+        let resolver = IncludeResolver::new().dir("/p/inc");
+        let tests = &[
+            (
+                IncludeOrder::IncludingFileFirst,
+                "both.h",
+                Some("/p/src/both.h"),
+            ),
+            (
+                IncludeOrder::SearchPathFirst,
+                "both.h",
+                Some("/p/inc/both.h"),
+            ),
+            (
+                IncludeOrder::SearchPathFirst,
+                "local.h",
+                Some("/p/src/local.h"),
+            ),
+            (IncludeOrder::SearchPathOnly, "local.h", None),
+            (
+                IncludeOrder::SearchPathOnly,
+                "nested/deep.h",
+                Some("/p/inc/nested/deep.h"),
+            ),
+            (
+                IncludeOrder::IncludingFileFirst,
+                "../shared.h",
+                Some("/p/src/../shared.h"),
+            ),
+            (
+                IncludeOrder::IncludingFileFirst,
+                "/p/shared.h",
+                Some("/p/shared.h"),
+            ),
+        ];
+
+        for test in tests {
+            let found = resolver
+                .clone()
+                .order(test.0)
+                .resolve_with(test.1, "/p/src/main.c", &fs)
+                .unwrap();
+            assert_eq!(found, test.2.map(PathBuf::from), "{:?} {}", test.0, test.1);
+        }
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn test_resolve() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("inc")).unwrap();
+        std::fs::write(dir.path().join("inc/x.h"), "").unwrap();
+        let resolver = IncludeResolver::new().dir(dir.path().join("inc"));
+        let main = dir.path().join("main.c");
+
+        assert_eq!(
+            resolver.resolve("x.h", &main).unwrap(),
+            Some(dir.path().join("inc/x.h"))
+        );
+        assert_eq!(resolver.resolve("y.h", &main).unwrap(), None);
+    }
+}
@@ -0,0 +1,164 @@
+//! I/O errors that say which path they're about and what was being done to it.
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// An [`io::Error`] with the path it happened on and the operation that failed, shown as
+/// `failed to create dir /a/b: Permission denied (os error 13)`.
+///
+/// The `fs` helpers return [`io::Result`]s so they fit in with std's, their errors carrying one of
+/// these, which [`PathIoError::of`] gets back out. Converting into an [`io::Error`] keeps the kind.
+///
+/// ```rust
+/// use pathext::PathIoError;
+/// use std::io;
+/// use std::path::Path;
+///
+/// let denied = io::Error::from(io::ErrorKind::PermissionDenied);
+/// let e: io::Error = PathIoError::new("create dir", "/srv/data", denied).into();
+/// assert_eq!(e.kind(), io::ErrorKind::PermissionDenied);
+/// let context = PathIoError::of(&e).unwrap();
+/// assert_eq!(context.operation, "create dir");
+/// assert_eq!(context.path, Path::new("/srv/data"));
+/// assert!(e.to_string().starts_with("failed to create dir /srv/data: "));
+/// ```
+#[derive(Debug)]
+pub struct PathIoError {
+    /// What was being done, worded to follow "failed to", like `create dir`.
+    pub operation: &'static str,
+    pub path: PathBuf,
+    pub source: io::Error,
+}
+
+impl PathIoError {
+    pub fn new<P: Into<PathBuf>>(operation: &'static str, path: P, source: io::Error) -> Self {
+        PathIoError {
+            operation,
+            path: path.into(),
+            source,
+        }
+    }
+
+    /// The kind of the underlying error.
+    pub fn kind(&self) -> io::ErrorKind {
+        self.source.kind()
+    }
+
+    /// The `PathIoError` `error` carries, if it came from one.
+    pub fn of(error: &io::Error) -> Option<&PathIoError> {
+        error.get_ref()?.downcast_ref()
+    }
+}
+
+impl fmt::Display for PathIoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed to {} {}: {}",
+            self.operation,
+            self.path.display(),
+            self.source
+        )
+    }
+}
+
+impl Error for PathIoError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Labels the errors of plain `io::Result`s, like those of [`std::fs`], with the path they're about.
+///
+/// ```rust
+/// use pathext::IoResultExt;
+/// use std::path::Path;
+///
+/// let path = Path::new("/no/such/file.toml");
+/// let e = std::fs::read_to_string(path).with_path(path).unwrap_err();
+/// assert_eq!(e.path, path);
+/// assert!(e.to_string().starts_with("failed to access /no/such/file.toml: "));
+/// let e = std::fs::read(path).with_operation("read", path).unwrap_err();
+/// assert!(e.to_string().starts_with("failed to read /no/such/file.toml: "));
+/// ```
+pub trait IoResultExt<T> {
+    /// The error as a [`PathIoError`] about `path`, the operation given as `access`. Errors that
+    /// already carry one, as those of the `fs` helpers do, are unwrapped to it instead.
+    fn with_path<P: AsRef<Path>>(self, path: P) -> Result<T, PathIoError>;
+    /// The error as a [`PathIoError`] saying `operation` on `path` failed, with the same unwrapping
+    /// as [`with_path`](IoResultExt::with_path).
+    fn with_operation<P: AsRef<Path>>(
+        self,
+        operation: &'static str,
+        path: P,
+    ) -> Result<T, PathIoError>;
+}
+
+impl<T> IoResultExt<T> for io::Result<T> {
+    fn with_path<P: AsRef<Path>>(self, path: P) -> Result<T, PathIoError> {
+        self.with_operation("access", path)
+    }
+
+    fn with_operation<P: AsRef<Path>>(
+        self,
+        operation: &'static str,
+        path: P,
+    ) -> Result<T, PathIoError> {
+        self.map_err(|e| {
+            if PathIoError::of(&e).is_none() {
+                return PathIoError::new(operation, path.as_ref(), e);
+            }
+            let inner = e.into_inner().expect("carries a PathIoError");
+            *inner.downcast().expect("carries a PathIoError")
+        })
+    }
+}
+
+impl From<PathIoError> for io::Error {
+    fn from(error: PathIoError) -> Self {
+        io::Error::new(error.kind(), error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IoResultExt, PathIoError};
+
+    use std::error::Error;
+    use std::io;
+
+    #[test]
+    fn test_path_io_error() {
+        let source = io::Error::from(io::ErrorKind::PermissionDenied);
+        let error = PathIoError::new("create dir", "/a/b", source);
+        assert_eq!(
+            error.to_string(),
+            "failed to create dir /a/b: permission denied"
+        );
+        assert!(error.source().is_some());
+
+        let wrapped = io::Error::from(error);
+        assert_eq!(wrapped.kind(), io::ErrorKind::PermissionDenied);
+        assert_eq!(
+            wrapped.to_string(),
+            "failed to create dir /a/b: permission denied"
+        );
+        assert_eq!(PathIoError::of(&wrapped).unwrap().operation, "create dir");
+        assert!(PathIoError::of(&io::Error::other("bare")).is_none());
+    }
+
+    #[test]
+    fn test_io_result_ext() {
+        let bare: io::Result<()> = Err(io::ErrorKind::NotFound.into());
+        let e = bare.with_path("/x").unwrap_err();
+        assert_eq!((e.operation, e.kind()), ("access", io::ErrorKind::NotFound));
+        assert_eq!(e.path.to_str(), Some("/x"));
+
+        let labeled: io::Result<()> = Err(PathIoError::new("touch", "/inner", e.source).into());
+        let e = labeled.with_operation("read", "/outer").unwrap_err();
+        assert_eq!((e.operation, e.path.to_str()), ("touch", Some("/inner")));
+        assert_eq!(Ok::<_, io::Error>(1).with_path("/x").unwrap(), 1);
+    }
+}
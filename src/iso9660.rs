@@ -0,0 +1,148 @@
+//! Names for CD and DVD images: ISO 9660 level 1 and the Joliet extension.
+
+use std::path::{Component, Path, PathBuf};
+
+/// The longest Joliet name in UCS-2 characters, and the longest path in bytes.
+const JOLIET_MAX_NAME: usize = 64;
+const JOLIET_MAX_PATH_BYTES: usize = 240;
+
+/// Uppercases `s` and replaces anything that isn't a d-character (`A-Z 0-9 _`) with `_`, keeping
+/// at most `max` characters.
+fn d_characters(s: &str, max: usize) -> String {
+    s.chars()
+        .map(|c| match c.to_ascii_uppercase() {
+            c @ ('A'..='Z' | '0'..='9') => c,
+            _ => '_',
+        })
+        .take(max)
+        .collect()
+}
+
+/// `NAME.EXT` with at most eight and three d-characters, splitting at the last `.`.
+fn file_identifier(name: &str) -> String {
+    let (stem, extension) = match name.rfind('.') {
+        Some(dot) if dot > 0 => (&name[..dot], &name[dot + 1..]),
+        _ => (name, ""),
+    };
+    let stem = d_characters(stem, 8);
+    let extension = d_characters(extension, 3);
+    match (stem.is_empty(), extension.is_empty()) {
+        (true, true) => "_".to_string(),
+        (_, true) => stem,
+        _ => format!("{stem}.{extension}"),
+    }
+}
+
+fn directory_identifier(name: &str) -> String {
+    let identifier = d_characters(name, 8);
+    if identifier.is_empty() {
+        "_".to_string()
+    } else {
+        identifier
+    }
+}
+
+/// Maps the last normal component to a file identifier and the others to directory identifiers.
+pub(crate) fn to_level1(path: &Path) -> PathBuf {
+    let components: Vec<Component> = path.components().collect();
+    let last_normal = components
+        .iter()
+        .rposition(|c| matches!(c, Component::Normal(_)));
+    components
+        .iter()
+        .enumerate()
+        .map(|(i, component)| match component {
+            Component::Normal(name) if Some(i) == last_normal => {
+                PathBuf::from(file_identifier(&name.to_string_lossy()))
+            }
+            Component::Normal(name) => PathBuf::from(directory_identifier(&name.to_string_lossy())),
+            other => PathBuf::from(other.as_os_str()),
+        })
+        .collect()
+}
+
+fn is_joliet_name(name: &str) -> bool {
+    let mut units = 0;
+    for c in name.chars() {
+        if c.len_utf16() > 1 || c < ' ' || matches!(c, '*' | '/' | ':' | ';' | '?' | '\\') {
+            return false;
+        }
+        units += 1;
+    }
+    units <= JOLIET_MAX_NAME
+}
+
+pub(crate) fn is_joliet_safe(path: &Path) -> bool {
+    let mut path_bytes = 0;
+    for component in path.components() {
+        if let Component::Normal(name) = component {
+            let Some(name) = name.to_str() else {
+                return false;
+            };
+            if !is_joliet_name(name) {
+                return false;
+            }
+            // UCS-2 names plus a separator each
+            path_bytes += 2 * name.chars().count() + 2;
+        }
+    }
+    path_bytes <= JOLIET_MAX_PATH_BYTES
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{file_identifier, is_joliet_safe, to_level1};
+
+    use std::path::Path;
+
+    #[test]
+    fn test_file_identifier() {
+        let tests = &[
+            ("readme.txt", "README.TXT"),
+            ("firmware-update.bin", "FIRMWARE.BIN"),
+            ("archive.tar.gz", "ARCHIVE_.GZ"),
+            ("page.html", "PAGE.HTM"),
+            ("Makefile", "MAKEFILE"),
+            (".bashrc", "_BASHRC"),
+            ("naïve.c", "NA_VE.C"),
+            (".", "_"),
+        ];
+
+        for test in tests {
+            assert_eq!(file_identifier(test.0), test.1, "{}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_to_level1() {
+        let tests = &[
+            ("/boot/grub2/grub.cfg", "/BOOT/GRUB2/GRUB.CFG"),
+            ("release_notes/v1.2/changes.md", "RELEASE_/V1_2/CHANGES.MD"),
+            ("docs/", "DOCS"),
+        ];
+
+        for test in tests {
+            assert_eq!(to_level1(Path::new(test.0)), Path::new(test.1));
+        }
+    }
+
+    #[test]
+    fn test_is_joliet_safe() {
+        let long = "x".repeat(65);
+        let deep = "directory/".repeat(13);
+        let tests = &[
+            ("/Program Files/Ünïcode Name.txt", true),
+            ("what?.txt", false),
+            ("a;1", false),
+            ("emoji 🎉.txt", false),
+            (long.as_str(), false),
+            (&long[1..], true),
+            (deep.as_str(), false),
+            (&deep[10..], true),
+        ];
+
+        for test in tests {
+            assert_eq!(is_joliet_safe(Path::new(test.0)), test.1, "{}", test.0);
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! Adapters for iterators of paths, such as the output of a directory walk.
+
+use std::path::{Path, PathBuf};
+
+use crate::{normalize, rebase, PathStrExt};
+
+/// Path-aware adapters for any iterator of paths, so pipelines over walked files read as a chain.
+///
+/// ```rust
+/// use pathext::PathIterExt;
+/// use std::path::PathBuf;
+///
+/// let walked = ["src/./lib.rs", "src/util/../main.rs", "docs/intro.md", "src/notes.txt"];
+/// let sources: Vec<PathBuf> = walked
+///     .into_iter()
+///     .normalize_all()
+///     .filter_under("src")
+///     .filter_extensions(["rs"])
+///     .map_rebase("src", "/build/src")
+///     .collect();
+/// assert_eq!(sources, [PathBuf::from("/build/src/lib.rs"), PathBuf::from("/build/src/main.rs")]);
+/// ```
+pub trait PathIterExt: Iterator + Sized
+where
+    Self::Item: AsRef<Path>,
+{
+    /// Keeps the paths whose final extension is one of `extensions`, each given with or without a
+    /// leading `.`, as [`extension_is_any`](PathStrExt::extension_is_any) decides.
+    fn filter_extensions<E, S>(self, extensions: E) -> impl Iterator<Item = Self::Item>
+    where
+        E: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let extensions: Vec<String> = extensions
+            .into_iter()
+            .map(|extension| extension.as_ref().to_string())
+            .collect();
+        self.filter(move |path| path.extension_is_any(&extensions))
+    }
+
+    /// Keeps the paths that are `prefix` or under it, comparing whole components as
+    /// [`Path::starts_with`] does.
+    fn filter_under<P: AsRef<Path>>(self, prefix: P) -> impl Iterator<Item = Self::Item> {
+        self.filter(move |path| path.as_ref().starts_with(prefix.as_ref()))
+    }
+
+    /// Moves the paths under `from` to the same place under `to`, passing the others through as they are.
+    fn map_rebase<F, T>(self, from: F, to: T) -> impl Iterator<Item = PathBuf>
+    where
+        F: AsRef<Path>,
+        T: AsRef<Path>,
+    {
+        self.map(move |path| {
+            let mut rebased = PathBuf::new();
+            rebase::rebase_into(path.as_ref(), from.as_ref(), to.as_ref(), &mut rebased);
+            rebased
+        })
+    }
+
+    /// Normalizes every path lexically, resolving `.` and `..` as
+    /// [`normalize`](crate::PathComponentExt::normalize) does, without touching the filesystem.
+    fn normalize_all(self) -> impl Iterator<Item = PathBuf> {
+        self.map(|path| normalize::normalize(path.as_ref()))
+    }
+}
+
+impl<I> PathIterExt for I
+where
+    I: Iterator,
+    I::Item: AsRef<Path>,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PathIterExt;
+
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn test_filter_extensions() {
+        let paths = ["a.rs", "b.RS", "c.tar.gz", "d", ".rs", "e.toml"];
+        let kept: Vec<&str> = paths
+            .into_iter()
+            .filter_extensions([".rs", "gz", "toml"])
+            .collect();
+        assert_eq!(kept, ["a.rs", "c.tar.gz", "e.toml"]);
+    }
+
+    #[test]
+    fn test_filter_under() {
+        let paths = vec![
+            PathBuf::from("/srv/www/index.html"),
+            PathBuf::from("/srv/www"),
+            PathBuf::from("/srv/www2/index.html"),
+            PathBuf::from("/etc/hosts"),
+        ];
+        let kept: Vec<PathBuf> = paths.into_iter().filter_under("/srv/www").collect();
+        assert_eq!(
+            kept,
+            [Path::new("/srv/www/index.html"), Path::new("/srv/www")]
+        );
+    }
+
+    #[test]
+    fn test_map_rebase() {
+        let paths = ["src/lib.rs", "src", "srcs/x.rs", "README.md"];
+        let rebased: Vec<PathBuf> = paths.iter().map_rebase("src", "out").collect();
+        assert_eq!(
+            rebased,
+            [
+                Path::new("out/lib.rs"),
+                Path::new("out"),
+                Path::new("srcs/x.rs"),
+                Path::new("README.md")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_normalize_all() {
+        let paths = ["a/./b/../c", "/../x", "../up", "."];
+        let normalized: Vec<PathBuf> = paths.into_iter().normalize_all().collect();
+        assert_eq!(
+            normalized,
+            [
+                Path::new("a/c"),
+                Path::new("/x"),
+                Path::new("../up"),
+                Path::new(".")
+            ]
+        );
+    }
+}
@@ -0,0 +1,241 @@
+//! Coarse classification of files by name alone, for listings and icons.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::{language, name, Language};
+
+/// What a file probably is, judging by its name. See [`NameKindTable`] for the rules.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum NameKind {
+    /// Compressed or bundled files, like `.zip`, `.tar.gz` or `.7z`.
+    Archive,
+    /// Raster and vector images, like `.png`, `.jpg` or `.svg`.
+    Image,
+    /// Source code in `language`, by the extensions [`LanguageRegistry::default`](crate::LanguageRegistry::default) knows.
+    SourceCode { language: Language },
+    /// Documents for reading, like `.pdf`, `.docx` or `.md`.
+    Document,
+    /// Programs and scripts run directly, like `.exe`, `.sh` or `.bat`.
+    Executable,
+    /// Temporary files and backups, like `.tmp`, `.bak` or `notes.txt~`.
+    Temporary,
+    /// Lock files, like `Cargo.lock` or Office's `~$report.docx`.
+    Lock,
+    /// Nothing in the table matched.
+    Unknown,
+}
+
+/// The rules [`PathStrExt::inferred_kind`](crate::PathStrExt::inferred_kind) classifies names by, to start from
+/// and extend.
+///
+/// Prefixes and suffixes of the whole file name are tried first, longest first, so `~$report.docx`
+/// is a lock file rather than a document. Then extensions, ignoring ASCII case and trying compound
+/// ones like `tar.gz` before `gz`. Later rules for the same key replace earlier ones.
+///
+/// ```rust
+/// use pathext::{NameKind, NameKindTable};
+///
+/// let table = NameKindTable::default()
+///     .extension("blend", NameKind::Document)
+///     .prefix("core.", NameKind::Temporary);
+/// assert_eq!(table.kind_of("scene.BLEND"), NameKind::Document);
+/// assert_eq!(table.kind_of("core.12345"), NameKind::Temporary);
+/// assert_eq!(table.kind_of("backup.tar.gz"), NameKind::Archive);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NameKindTable {
+    extensions: BTreeMap<String, NameKind>,
+    prefixes: Vec<(String, NameKind)>,
+    suffixes: Vec<(String, NameKind)>,
+}
+
+impl Default for NameKindTable {
+    /// The built-in rules for common archives, images, source files, documents, executables,
+    /// temporary files and lock files.
+    fn default() -> Self {
+        let mut table = NameKindTable::new();
+        // first, so that e.g. `sh` ends up an executable
+        for (extensions, language) in language::EXTENSIONS {
+            for extension in *extensions {
+                let kind = NameKind::SourceCode {
+                    language: language.clone(),
+                };
+                table = table.extension(extension, kind);
+            }
+        }
+        for (extensions, kind) in [
+            (
+                &[
+                    "zip", "tar", "gz", "tgz", "tar.gz", "bz2", "tar.bz2", "xz", "tar.xz", "zst",
+                    "tar.zst", "7z", "rar", "jar",
+                ][..],
+                NameKind::Archive,
+            ),
+            (
+                &[
+                    "png", "jpg", "jpeg", "gif", "bmp", "webp", "svg", "ico", "tif", "tiff",
+                    "heic", "avif",
+                ][..],
+                NameKind::Image,
+            ),
+            (
+                &[
+                    "pdf", "doc", "docx", "odt", "rtf", "txt", "md", "rst", "xls", "xlsx", "ods",
+                    "ppt", "pptx", "odp", "epub",
+                ][..],
+                NameKind::Document,
+            ),
+            (
+                &[
+                    "exe", "msi", "app", "appimage", "sh", "bash", "zsh", "fish", "bat", "cmd",
+                    "ps1", "com",
+                ][..],
+                NameKind::Executable,
+            ),
+            (
+                &["tmp", "temp", "bak", "old", "orig", "swp", "swo"][..],
+                NameKind::Temporary,
+            ),
+            (&["lock", "lck"][..], NameKind::Lock),
+        ] {
+            for extension in extensions {
+                table = table.extension(extension, kind.clone());
+            }
+        }
+        table
+            .prefix("~$", NameKind::Lock)
+            .prefix(".~lock.", NameKind::Lock)
+            .suffix("~", NameKind::Temporary)
+    }
+}
+
+impl NameKindTable {
+    /// A table without any rules, classifying everything as [`NameKind::Unknown`].
+    pub fn new() -> Self {
+        NameKindTable {
+            extensions: BTreeMap::new(),
+            prefixes: Vec::new(),
+            suffixes: Vec::new(),
+        }
+    }
+
+    /// Classifies names ending in `.extension` as `kind`. A leading `.` is ignored, and the
+    /// extension may be compound, like `tar.gz`.
+    pub fn extension<S: AsRef<str>>(mut self, extension: S, kind: NameKind) -> Self {
+        let extension = extension.as_ref();
+        let extension = extension.strip_prefix('.').unwrap_or(extension);
+        self.extensions.insert(extension.to_ascii_lowercase(), kind);
+        self
+    }
+
+    /// Classifies file names starting with `prefix` as `kind`, before looking at extensions.
+    pub fn prefix<S: Into<String>>(mut self, prefix: S, kind: NameKind) -> Self {
+        insert_affix(&mut self.prefixes, prefix.into(), kind);
+        self
+    }
+
+    /// Classifies file names ending with `suffix` as `kind`, before looking at extensions.
+    pub fn suffix<S: Into<String>>(mut self, suffix: S, kind: NameKind) -> Self {
+        insert_affix(&mut self.suffixes, suffix.into(), kind);
+        self
+    }
+
+    /// The kind of the path's file name, [`NameKind::Unknown`] if no rule matches or it has none.
+    pub fn kind_of<P: AsRef<Path>>(&self, path: P) -> NameKind {
+        let Some(name) = path.as_ref().file_name() else {
+            return NameKind::Unknown;
+        };
+        let name = name.to_string_lossy();
+        let affix = self
+            .prefixes
+            .iter()
+            .find(|(prefix, _)| name.starts_with(prefix.as_str()))
+            .or_else(|| {
+                self.suffixes
+                    .iter()
+                    .find(|(suffix, _)| name.ends_with(suffix.as_str()))
+            });
+        if let Some((_, kind)) = affix {
+            return kind.clone();
+        }
+        let name = name.to_ascii_lowercase();
+        let kind = name::extension_suffixes(&name)
+            .find_map(|extension| self.extensions.get(extension))
+            .cloned();
+        kind.unwrap_or(NameKind::Unknown)
+    }
+}
+
+/// Keeps `affixes` longest first so the most specific rule wins.
+fn insert_affix(affixes: &mut Vec<(String, NameKind)>, affix: String, kind: NameKind) {
+    affixes.retain(|(existing, _)| *existing != affix);
+    let at = affixes.partition_point(|(existing, _)| existing.len() >= affix.len());
+    affixes.insert(at, (affix, kind));
+}
+
+pub(crate) fn builtin() -> &'static NameKindTable {
+    static BUILTIN: OnceLock<NameKindTable> = OnceLock::new();
+    BUILTIN.get_or_init(NameKindTable::default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{builtin, NameKind, NameKindTable};
+    use crate::Language;
+
+    #[test]
+    fn test_kind_of() {
+        let tests = &[
+            ("photos/IMG_0001.JPG", NameKind::Image),
+            ("backup.tar.gz", NameKind::Archive),
+            ("release-1.2.tgz", NameKind::Archive),
+            (
+                "src/main.rs",
+                NameKind::SourceCode {
+                    language: Language::RUST,
+                },
+            ),
+            (
+                "setup.py",
+                NameKind::SourceCode {
+                    language: Language::PYTHON,
+                },
+            ),
+            ("install.sh", NameKind::Executable),
+            ("report.docx", NameKind::Document),
+            ("~$report.docx", NameKind::Lock),
+            ("notes.txt~", NameKind::Temporary),
+            (".main.rs.swp", NameKind::Temporary),
+            ("Cargo.lock", NameKind::Lock),
+            ("Makefile", NameKind::Unknown),
+            (".gitignore", NameKind::Unknown),
+            ("archive.unknown", NameKind::Unknown),
+            ("/", NameKind::Unknown),
+        ];
+
+        for test in tests {
+            assert_eq!(builtin().kind_of(test.0), test.1, "{}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_custom_rules() {
+        let table = NameKindTable::new()
+            .extension(".GZ", NameKind::Archive)
+            .extension("tar.gz", NameKind::Document)
+            .prefix("~", NameKind::Temporary)
+            .prefix("~$", NameKind::Lock)
+            .suffix(".tar.gz", NameKind::Image);
+
+        assert_eq!(table.kind_of("a.tar.gz"), NameKind::Image);
+        assert_eq!(table.kind_of("a.b.gz"), NameKind::Archive);
+        assert_eq!(table.kind_of("~$a"), NameKind::Lock);
+        assert_eq!(table.kind_of("~a"), NameKind::Temporary);
+        assert_eq!(table.kind_of("a.png"), NameKind::Unknown);
+
+        let table = table.suffix(".tar.gz", NameKind::Archive);
+        assert_eq!(table.kind_of("a.tar.gz"), NameKind::Archive);
+    }
+}
@@ -0,0 +1,106 @@
+//! Paths in and relative to the platform's well-known per-user directories.
+
+use std::path::{Path, PathBuf, MAIN_SEPARATOR};
+
+/// The well-known directories and how they are written when contracted, most specific first.
+fn known_dirs() -> Vec<(PathBuf, &'static str)> {
+    let mut dirs = Vec::new();
+    if cfg!(windows) {
+        dirs.extend(dirs::cache_dir().map(|d| (d, "%LOCALAPPDATA%")));
+        dirs.extend(dirs::config_dir().map(|d| (d, "%APPDATA%")));
+    } else if !cfg!(target_vendor = "apple") {
+        dirs.extend(dirs::config_dir().map(|d| (d, "$XDG_CONFIG_HOME")));
+        dirs.extend(dirs::cache_dir().map(|d| (d, "$XDG_CACHE_HOME")));
+        dirs.extend(dirs::data_dir().map(|d| (d, "$XDG_DATA_HOME")));
+    }
+    dirs.extend(dirs::home_dir().map(|d| (d, "~")));
+    dirs
+}
+
+/// Writes `path` relative to the longest of `dirs` containing it.
+pub(crate) fn contract(path: &Path, dirs: &[(PathBuf, &str)]) -> String {
+    let best = dirs
+        .iter()
+        .filter_map(|(dir, label)| Some((path.strip_prefix(dir).ok()?, dir, label)))
+        .max_by_key(|(_, dir, _)| dir.components().count());
+    match best {
+        Some((rest, _, label)) if rest.as_os_str().is_empty() => label.to_string(),
+        Some((rest, _, label)) => format!("{label}{MAIN_SEPARATOR}{}", rest.display()),
+        None => path.display().to_string(),
+    }
+}
+
+pub(crate) fn display_contracted(path: &Path) -> String {
+    contract(path, &known_dirs())
+}
+
+fn under(dir: Option<PathBuf>, app: Option<&Path>, path: &Path) -> Option<PathBuf> {
+    let mut joined = dir?;
+    joined.extend(app);
+    joined.push(path);
+    Some(joined)
+}
+
+pub(crate) fn relative_to_config_dir(path: &Path) -> Option<PathBuf> {
+    under(dirs::config_dir(), None, path)
+}
+
+pub(crate) fn in_cache_dir(path: &Path, app: &Path) -> Option<PathBuf> {
+    under(dirs::cache_dir(), Some(app), path)
+}
+
+pub(crate) fn in_data_dir(path: &Path, app: &Path) -> Option<PathBuf> {
+    under(dirs::data_dir(), Some(app), path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{contract, in_cache_dir, in_data_dir, relative_to_config_dir};
+
+    use std::path::{Path, PathBuf, MAIN_SEPARATOR};
+
+    #[test]
+    fn test_contract() {
+        let dirs = [
+            (PathBuf::from("/home/me/.config"), "$XDG_CONFIG_HOME"),
+            (PathBuf::from("/home/me"), "~"),
+        ];
+        let s = MAIN_SEPARATOR;
+        let tests = &[
+            (
+                "/home/me/.config/app/x.toml",
+                format!("$XDG_CONFIG_HOME{s}app{s}x.toml"),
+            ),
+            ("/home/me/notes.txt", format!("~{s}notes.txt")),
+            ("/home/me", "~".to_string()),
+            ("/home/meee/x", "/home/meee/x".to_string()),
+            ("/etc/hosts", "/etc/hosts".to_string()),
+        ];
+
+        for test in tests {
+            assert_eq!(contract(Path::new(test.0), &dirs), test.1);
+        }
+    }
+
+    #[test]
+    fn test_builders() {
+        if let Some(config) = dirs::config_dir() {
+            assert_eq!(
+                relative_to_config_dir(Path::new("app/settings.toml")),
+                Some(config.join("app").join("settings.toml"))
+            );
+        }
+        if let Some(cache) = dirs::cache_dir() {
+            assert_eq!(
+                in_cache_dir(Path::new("thumbs"), Path::new("app")),
+                Some(cache.join("app").join("thumbs"))
+            );
+        }
+        if let Some(data) = dirs::data_dir() {
+            assert_eq!(
+                in_data_dir(Path::new("db.sqlite"), Path::new("app")),
+                Some(data.join("app").join("db.sqlite"))
+            );
+        }
+    }
+}
@@ -0,0 +1,246 @@
+//! Telling the programming language of a source file from its name.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::name;
+
+/// A programming or build language, named in lowercase. The constants are the ones
+/// [`LanguageRegistry::default`] knows, and [`Language::new`] makes others.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Language(Cow<'static, str>);
+
+impl Language {
+    pub const C: Language = Language::from_static("c");
+    pub const CPP: Language = Language::from_static("c++");
+    pub const CSHARP: Language = Language::from_static("c#");
+    pub const CMAKE: Language = Language::from_static("cmake");
+    pub const DOCKERFILE: Language = Language::from_static("dockerfile");
+    pub const GO: Language = Language::from_static("go");
+    pub const HASKELL: Language = Language::from_static("haskell");
+    pub const JAVA: Language = Language::from_static("java");
+    pub const JAVASCRIPT: Language = Language::from_static("javascript");
+    pub const KOTLIN: Language = Language::from_static("kotlin");
+    pub const LUA: Language = Language::from_static("lua");
+    pub const MAKE: Language = Language::from_static("make");
+    pub const OBJECTIVE_C: Language = Language::from_static("objective-c");
+    pub const PERL: Language = Language::from_static("perl");
+    pub const PHP: Language = Language::from_static("php");
+    pub const PYTHON: Language = Language::from_static("python");
+    pub const RUBY: Language = Language::from_static("ruby");
+    pub const RUST: Language = Language::from_static("rust");
+    pub const SCALA: Language = Language::from_static("scala");
+    pub const SHELL: Language = Language::from_static("shell");
+    pub const SWIFT: Language = Language::from_static("swift");
+    pub const TYPESCRIPT: Language = Language::from_static("typescript");
+    pub const ZIG: Language = Language::from_static("zig");
+
+    const fn from_static(name: &'static str) -> Self {
+        Language(Cow::Borrowed(name))
+    }
+
+    /// A language by name, lowercased so names compare the same however they were written.
+    pub fn new<S: Into<Cow<'static, str>>>(name: S) -> Self {
+        let name = name.into();
+        if name.bytes().any(|b| b.is_ascii_uppercase()) {
+            Language(Cow::Owned(name.to_ascii_lowercase()))
+        } else {
+            Language(name)
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// The extensions [`LanguageRegistry::default`] maps, also the source code rules of
+/// [`NameKindTable::default`](crate::NameKindTable::default).
+pub(crate) const EXTENSIONS: &[(&[&str], Language)] = &[
+    (&["rs"], Language::RUST),
+    (&["c", "h"], Language::C),
+    (&["cc", "cpp", "cxx", "hh", "hpp", "hxx"], Language::CPP),
+    (&["cs"], Language::CSHARP),
+    (&["cmake"], Language::CMAKE),
+    (&["go"], Language::GO),
+    (&["hs"], Language::HASKELL),
+    (&["java"], Language::JAVA),
+    (&["js", "mjs", "cjs", "jsx"], Language::JAVASCRIPT),
+    (&["kt", "kts"], Language::KOTLIN),
+    (&["lua"], Language::LUA),
+    (&["mk", "mak"], Language::MAKE),
+    (&["m", "mm"], Language::OBJECTIVE_C),
+    (&["pl", "pm"], Language::PERL),
+    (&["php"], Language::PHP),
+    (&["py", "pyi"], Language::PYTHON),
+    (&["rb"], Language::RUBY),
+    (&["scala"], Language::SCALA),
+    (&["sh", "bash", "zsh"], Language::SHELL),
+    (&["swift"], Language::SWIFT),
+    (&["ts", "mts", "cts", "tsx"], Language::TYPESCRIPT),
+    (&["zig"], Language::ZIG),
+];
+
+const FILE_NAMES: &[(&[&str], Language)] = &[
+    (&["CMakeLists.txt"], Language::CMAKE),
+    (&["Containerfile", "Dockerfile"], Language::DOCKERFILE),
+    (&["GNUmakefile", "Makefile", "makefile"], Language::MAKE),
+    (&["Gemfile", "Rakefile"], Language::RUBY),
+    (&["SConstruct", "SConscript"], Language::PYTHON),
+    (
+        &[
+            ".bash_profile",
+            ".bashrc",
+            ".profile",
+            ".zprofile",
+            ".zshrc",
+        ],
+        Language::SHELL,
+    ),
+];
+
+/// Which names and extensions mean which [`Language`], for
+/// [`PathStrExt::source_language`](crate::PathStrExt::source_language) and tools that need to
+/// add to or override it.
+///
+/// Whole file names like `Makefile` are matched exactly and win over extensions, which ignore ASCII
+/// case and are tried longest first, so `d.ts` can differ from `ts`. Later rules for the same name
+/// or extension replace earlier ones.
+///
+/// ```rust
+/// use pathext::{Language, LanguageRegistry};
+///
+/// let registry = LanguageRegistry::default()
+///     .extension("h", Language::CPP)
+///     .file_name("Justfile", Language::new("just"));
+/// assert_eq!(registry.language_of("include/vec.h"), Some(Language::CPP));
+/// assert_eq!(registry.language_of("Justfile").unwrap().name(), "just");
+/// assert_eq!(registry.language_of("build/Makefile"), Some(Language::MAKE));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LanguageRegistry {
+    extensions: BTreeMap<String, Language>,
+    file_names: BTreeMap<String, Language>,
+}
+
+impl Default for LanguageRegistry {
+    /// The built-in rules for common languages and build files.
+    fn default() -> Self {
+        let mut registry = LanguageRegistry::new();
+        for (extensions, language) in EXTENSIONS {
+            for extension in *extensions {
+                registry = registry.extension(extension, language.clone());
+            }
+        }
+        for (names, language) in FILE_NAMES {
+            for name in *names {
+                registry = registry.file_name(*name, language.clone());
+            }
+        }
+        registry
+    }
+}
+
+impl LanguageRegistry {
+    /// A registry without any rules.
+    pub fn new() -> Self {
+        LanguageRegistry {
+            extensions: BTreeMap::new(),
+            file_names: BTreeMap::new(),
+        }
+    }
+
+    /// Files ending in `.extension` are in `language`. A leading `.` is ignored, and the extension may
+    /// be compound, like `d.ts`.
+    pub fn extension<S: AsRef<str>>(mut self, extension: S, language: Language) -> Self {
+        let extension = extension.as_ref();
+        let extension = extension.strip_prefix('.').unwrap_or(extension);
+        self.extensions
+            .insert(extension.to_ascii_lowercase(), language);
+        self
+    }
+
+    /// Files named exactly `name` are in `language`, whatever their extension.
+    pub fn file_name<S: Into<String>>(mut self, name: S, language: Language) -> Self {
+        self.file_names.insert(name.into(), language);
+        self
+    }
+
+    /// The language of the path's file name, `None` if no rule matches or it has none.
+    pub fn language_of<P: AsRef<Path>>(&self, path: P) -> Option<Language> {
+        let name = path.as_ref().file_name()?.to_string_lossy();
+        if let Some(language) = self.file_names.get(name.as_ref()) {
+            return Some(language.clone());
+        }
+        let name = name.to_ascii_lowercase();
+        let language = name::extension_suffixes(&name)
+            .find_map(|extension| self.extensions.get(extension))
+            .cloned();
+        language
+    }
+}
+
+pub(crate) fn builtin() -> &'static LanguageRegistry {
+    static BUILTIN: OnceLock<LanguageRegistry> = OnceLock::new();
+    BUILTIN.get_or_init(LanguageRegistry::default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{builtin, Language, LanguageRegistry};
+
+    #[test]
+    fn test_language_of() {
+        let tests = &[
+            ("src/main.rs", Some(Language::RUST)),
+            ("lib/Module.PY", Some(Language::PYTHON)),
+            ("include/vec.hpp", Some(Language::CPP)),
+            ("types/index.d.ts", Some(Language::TYPESCRIPT)),
+            ("Makefile", Some(Language::MAKE)),
+            ("docker/Dockerfile", Some(Language::DOCKERFILE)),
+            ("CMakeLists.txt", Some(Language::CMAKE)),
+            ("notes.txt", None),
+            ("/home/me/.bashrc", Some(Language::SHELL)),
+            ("makefile.bak", None),
+            (".rs", None),
+            ("/", None),
+        ];
+
+        for test in tests {
+            assert_eq!(builtin().language_of(test.0), test.1, "{}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_custom_rules() {
+        let registry = LanguageRegistry::new()
+            .extension(".TS", Language::TYPESCRIPT)
+            .extension("d.ts", Language::new("TypeScript-Declarations"))
+            .file_name("BUILD", Language::new("starlark"));
+
+        assert_eq!(registry.language_of("a.ts"), Some(Language::TYPESCRIPT));
+        assert_eq!(
+            registry.language_of("a.d.ts"),
+            Some(Language::new("typescript-declarations"))
+        );
+        assert_eq!(registry.language_of("BUILD").unwrap().name(), "starlark");
+        assert_eq!(registry.language_of("build"), None);
+        assert_eq!(registry.language_of("main.rs"), None);
+    }
+
+    #[test]
+    fn test_language_new() {
+        assert_eq!(Language::new("Rust"), Language::RUST);
+        assert_eq!(Language::new(String::from("go")), Language::GO);
+        assert_eq!(Language::CPP.to_string(), "c++");
+    }
+}
@@ -1,41 +1,203 @@
 //! # pathext
 //!
-//! A simple extension trait that includes some convenience methods I have found useful.
+//! Extension traits with some convenience methods I have found useful, split into [`PathStrExt`], [`PathComponentExt`]
+//! and the filesystem backed [`PathFsExt`], all of which [`prelude`] imports.
 //!
 
-use std::path::Path;
-
-/// ```rust
-/// use pathext::PathExt;
-/// use std::ops::Not;
-/// use std::path::Path;
-///
-/// assert!("/some/path".has_component("path"));
-/// assert!(Path::new("/some/path").has_component("path"));
-/// assert!("/some/path".has_component("some/path").not());
-/// assert!("/some/path".has_component("so").not());
-///
-/// assert!("/some/path".contains("some/pa"));
-/// assert!(Path::new("/some/path").contains("some/pa"));
-///
-/// assert!("/this/and/that/".starts_or_ends_with("/"));
-/// assert!(Path::new("/this/and/that/").starts_or_ends_with("/"));
-///
-/// assert!("multiple-extensions.tar.gz".strip_extensions() == Some("multiple-extensions"));
-/// assert!(Path::new("multiple-extensions.tar.gz").strip_extensions() == Some("multiple-extensions"));
-///
-/// assert!("archive.tar.gz".ends_with_extensions(".tar.gz"));
-/// assert!(Path::new("archive.tar.gz").ends_with_extensions("tar.gz"));
-/// assert!(Path::new("archive.tar.gz").ends_with_extensions("z")); // it will match any part of the end of the extension
-///
-/// // Unlike std
-/// assert!("archive.tar.gz".ends_with(".tar.gz"));
-/// assert!(Path::new("archive.tar.gz").ends_with(".tar.gz").not());
-/// assert!(Path::new("archive.tar.gz").ends_with("z").not());
-/// ```
-pub trait PathExt {
-    /// Checks if the contained pattern is in the stringified version of the AsRef<Path>
+use std::cmp::Ordering;
+use std::ffi::OsStr;
+#[cfg(feature = "fs")]
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+/// `tracing::debug!` with the `tracing` feature, nothing without.
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($arg)*);
+    };
+}
+
+mod artifact;
+mod bucket;
+mod build_escape;
+mod cache_key;
+#[cfg(feature = "fs")]
+mod canonical_cache;
+#[cfg(feature = "slug")]
+mod case;
+mod classifier;
+#[cfg(feature = "icu")]
+mod collate;
+mod component_strings;
+mod cygpath;
+mod dedupe;
+pub mod depfile;
+mod dotted;
+mod encoded;
+mod entry_name;
+mod env;
+mod fat;
+#[cfg(feature = "fs")]
+mod fs;
+mod functions;
+#[cfg(any(feature = "proptest", feature = "arbitrary"))]
+pub mod generators;
+mod hash;
+mod include;
+mod io_error;
+mod iso9660;
+mod iter;
+mod kind;
+#[cfg(feature = "dirs")]
+mod known_dirs;
+mod language;
+mod limits;
+mod listing;
+mod literal;
+mod location;
+mod lock;
+pub mod manifest;
+#[cfg(feature = "mime")]
+mod mime;
+mod mirror;
+mod name;
+mod new_name;
+mod normalize;
+mod object_key;
+mod order;
+mod overlay;
+mod path_list;
+#[cfg(feature = "fs")]
+mod pid_file;
+mod pipeline;
+mod platform;
+mod posix;
+pub mod prelude;
+mod prompt;
+mod rebase;
+mod redact;
+#[cfg(feature = "remap")]
+mod remap;
+pub mod resolve;
+mod response_file;
+mod router;
+mod runtime_dir;
+mod rust_module;
+mod sanitize;
+mod sequence;
+mod shard;
+#[cfg(feature = "unicode")]
+mod sort_key;
+mod source_map;
+mod suggest;
+pub mod testing;
+#[cfg(feature = "translit")]
+mod translit;
+#[cfg(feature = "trash")]
+mod trash;
+#[cfg(feature = "fs")]
+mod tree;
+mod virtual_path;
+mod volume;
+#[cfg(feature = "notify")]
+mod watch;
+mod windows_path;
+
+pub use artifact::{ArtifactKind, ArtifactRules};
+pub use cache_key::from_cache_key;
+#[cfg(feature = "fs")]
+pub use canonical_cache::CanonicalizeCache;
+pub use classifier::{NameClassifier, NameRule};
+#[cfg(feature = "icu")]
+pub use collate::{sort_collated, PathCollator};
+pub use component_strings::from_component_strings;
+pub use cygpath::{convert_path_with, CygpathOptions, PathStyle};
+pub use dedupe::{dedupe_equivalent, DedupeOptions, Deduped};
+pub use dotted::{
+    dotted_to_path, dotted_to_path_with, path_to_dotted, path_to_dotted_with, DottedOptions,
+};
+pub use encoded::from_encoded_bytes_compat;
+pub use entry_name::EntryNameExt;
+pub use env::{expand_env, expand_path, expand_registry_string, expand_tilde, ExpansionContext};
+#[cfg(feature = "fs")]
+pub use fs::{LinkTarget, PathState, TouchOptions};
+pub use functions::*;
+pub use include::{IncludeOrder, IncludeResolver};
+pub use io_error::{IoResultExt, PathIoError};
+pub use iter::PathIterExt;
+pub use kind::{NameKind, NameKindTable};
+pub use language::{Language, LanguageRegistry};
+pub use limits::{LengthLimits, LengthUnit};
+pub use listing::{compress_listing, expand_listing};
+pub use literal::parse_path_literal;
+pub use location::{parse_path_or_url, parse_remote_spec, PathOrUrl, RemoteSpec};
+#[cfg(feature = "fs")]
+pub use lock::LockFile;
+pub use manifest::read_paths;
+pub use mirror::{MirrorEntry, MirrorOp, MirrorPlan};
+pub use name::StripOptions;
+pub use new_name::{validate_new_name, validate_new_name_for, NameError};
+pub use normalize::NormalizeStyle;
+pub use object_key::ObjectStore;
+pub use overlay::OverlayMap;
+pub use path_list::split_path_list;
+#[cfg(feature = "fs")]
+pub use pid_file::PidFile;
+pub use pipeline::PathPipeline;
+pub use platform::Platform;
+pub use posix::PosixViolation;
+pub use prompt::PromptOptions;
+pub use redact::RedactOptions;
+#[cfg(feature = "remap")]
+pub use remap::{PathRemapper, RemapError, RemapRule};
+#[cfg(feature = "fs")]
+pub use response_file::read_response_file;
+pub use response_file::{write_response_file, ResponseFileStyle};
+pub use router::PathRouter;
+pub use runtime_dir::{runtime_file, runtime_file_with, RuntimeDirSource, RuntimeFile};
+pub use rust_module::{rust_module_files, rust_module_path};
+pub use sanitize::{CharPolicy, CharPreset};
+pub use source_map::resolve_source_map_source;
+#[cfg(feature = "fs")]
+pub use tree::{
+    summarize_tree, verify_tree, verify_tree_with, ExpectedFile, FileTotals, Mismatch, TreeReport,
+    TreeSummary,
+};
+pub use virtual_path::VirtualPath;
+pub use volume::{parse_volume_spec, VolumeSource, VolumeSpec};
+pub use windows_path::{ParsedWindowsPath, WindowsPrefix};
+
+#[cfg(any(feature = "notify", feature = "remap"))]
+pub use globset;
+#[cfg(feature = "icu")]
+pub use icu_locale_core;
+#[cfg(feature = "notify")]
+pub use notify;
+
+/// Operations on the path as a string, lossily converted where it isn't Unicode: extensions, lengths,
+/// and display forms.
+pub trait PathStrExt {
+    /// The path's bytes as the platform encodes them, UTF-8 where the path is Unicode, for byte-level algorithms
+    /// to work the same everywhere. See [`from_encoded_bytes_compat`] for what they may be used for.
+    fn as_encoded_bytes_compat(&self) -> &[u8];
+    /// The length of the path's encoded bytes: the raw bytes on Unix, and on Windows UTF-8 for Unicode paths rather
+    /// than the UTF-16 units the OS counts. Use [`fits_length_limits`](PathStrExt::fits_length_limits) for OS limits.
+    fn byte_len(&self) -> usize;
+    /// The number of characters in the lossy string form, each invalid sequence counting as one `U+FFFD`.
+    fn char_len_lossy(&self) -> usize;
+    /// Checks if the contained pattern is in the stringified version of the AsRef<Path>. The path is taken as
+    /// written, so `/opt/./foo/../bar` doesn't contain `/opt/bar` until [`normalize`](PathComponentExt::normalize)d.
     fn contains<S: AsRef<str>>(&self, pattern: S) -> bool;
+    /// Writes the path relative to the well-known directory containing it, e.g. `$XDG_CONFIG_HOME/app/x.toml`,
+    /// `%APPDATA%\app\x.toml` or `~/notes.txt`, for showing to users.
+    #[cfg(feature = "dirs")]
+    fn display_contracted(&self) -> String;
+    /// The path escaped for a Makefile or depfile rule, as GCC writes depfiles. `None` if it isn't Unicode or
+    /// has a line break, which make can't read back.
+    fn escape_for_make(&self) -> Option<String>;
+    /// The path escaped for a Ninja build file, with `$`. `None` if it isn't Unicode or has a line break.
+    fn escape_for_ninja(&self) -> Option<String>;
     /// This function was created due to the following expectation breaking pattern in std:
     /// `assert!("archive.tar.gz".ends_with(".tar.gz"));`
     /// `assert!(Path::new("archive.tar.gz").ends_with(".tar.gz").not());`
@@ -47,22 +209,628 @@ pub trait PathExt {
     /// Note that the pattern can match a parital extension as long as it ENDS the path.
     /// `assert!(Path::new("archive.tar.gz").ends_with_extensions("z"));` is valid.
     fn ends_with_extensions<S: AsRef<str>>(&self, pattern: S) -> bool;
-    /// Checks if the supplied component is present in total in the path
-    fn has_component<S: AsRef<str>>(&self, component: S) -> bool;
+    /// Whether the final extension is `extension`, which may be given as `gz` or `.gz`.
+    fn extension_is<S: AsRef<str>>(&self, extension: S) -> bool;
+    /// Whether the final extension is any of `extensions`, each given with or without a leading `.`.
+    fn extension_is_any<I: IntoIterator<Item = S>, S: AsRef<str>>(&self, extensions: I) -> bool;
+    /// Like [`extension_is_any`](PathStrExt::extension_is_any), ignoring ASCII case.
+    fn extension_is_any_ignore_case<I: IntoIterator<Item = S>, S: AsRef<str>>(
+        &self,
+        extensions: I,
+    ) -> bool;
+    /// Like [`extension_is`](PathStrExt::extension_is), ignoring ASCII case so `photo.JPG` is a `jpg`.
+    fn extension_is_ignore_case<S: AsRef<str>>(&self, extension: S) -> bool;
+    /// Checks the whole path and each component against `limits`, e.g. `LengthLimits::WINDOWS` for `MAX_PATH`.
+    fn fits_length_limits(&self, limits: LengthLimits) -> bool;
+    /// Formats the path the way shell prompts do: home as `~`, all but the last few components cut to one
+    /// character, and leading components dropped for `…` to fit a maximum width.
+    fn format_for_prompt(&self, options: &PromptOptions) -> String;
+    /// Whether the file name ends in exactly these extensions, `.tar.gz` or `tar.gz`, starting at a `.` after a
+    /// non-empty stem. Unlike [`ends_with_extensions`](PathStrExt::ends_with_extensions), `z` doesn't match `.gz`.
+    fn has_extensions_exact<S: AsRef<str>>(&self, extensions: S) -> bool;
+    /// What the file probably is by its name alone, e.g. [`NameKind::Archive`] for `backup.tar.gz`, following
+    /// [`NameKindTable::default`]. Build a [`NameKindTable`] to change the rules.
+    fn inferred_kind(&self) -> NameKind;
+    /// For a compressed file, the MIME type of what's inside as told by the extension before the compression's,
+    /// so `backup.tar.gz` and `backup.tgz` give `application/x-tar`. `None` if it isn't compressed or that's unknown.
+    #[cfg(feature = "mime")]
+    fn inner_mime_type(&self) -> Option<&'static str>;
+    /// Whether the path is `""`, which unlike `.` names nothing at all.
+    fn is_empty_path(&self) -> bool;
+    /// Whether the path is bookkeeping an OS leaves behind, like `.DS_Store`, `._` resource forks, `Thumbs.db` or
+    /// anything under `__MACOSX`, following [`NameClassifier::os_metadata_junk`].
+    fn is_os_metadata_junk(&self) -> bool;
+    /// Whether the file name is one editors, office suites and downloaders leave while working: swap and temp
+    /// files (`.swp`, `.tmp`), backups (`notes.txt~`), locks (`~$report.docx`, `.lock` but not `Cargo.lock` and
+    /// friends) and partial downloads (`.part`, `.crdownload`). For sync and watch tools to ignore, following
+    /// [`NameClassifier::transient`].
+    fn is_transient_file(&self) -> bool;
+    /// The lock file guarding this file, `file.txt.lock` for `file.txt`, with the name cut down and
+    /// hashed if it would be too long for the platform. `None` if there is no file name.
+    fn lock_path(&self) -> Option<PathBuf>;
+    /// Whether the path likely holds secrets, like keys under `.ssh`, `.env` files or credential
+    /// stores, for tools to warn before backing up or uploading it. The rules are
+    /// [`NameClassifier::sensitive`], to tune and match with directly.
+    fn looks_sensitive(&self) -> bool;
+    /// A byte key that sorts paths identically on every host: `/` and `\` are both separators, components
+    /// are NFC normalized and compared one at a time. Meant for manifests and reproducible builds.
+    #[cfg(feature = "unicode")]
+    fn portable_sort_key(&self) -> Vec<u8>;
+    /// Like `portable_sort_key`, with components lowercased as well.
+    #[cfg(feature = "unicode")]
+    fn portable_sort_key_case_folded(&self) -> Vec<u8>;
+    /// A stable id of `len` lowercase hex digits, at most 16, hashed from the lexically normalized
+    /// path, to label temp dirs, lock files or log lines for a path without showing it. Not secret.
+    fn short_id(&self, len: usize) -> String;
+    /// Shortens the path until it fits `limits`: over-long components first, then the longest intermediate
+    /// directories are cut down to a few characters plus a hash. The file name keeps its extension.
+    /// Deterministic, and `None` if no amount of shortening can make it fit.
+    fn shorten_to_limit(&self, limits: LengthLimits) -> Option<PathBuf>;
+    /// The file `name` in the same directory, so `logs/app.txt` gives `logs/other.txt`. `None` without
+    /// a file name to replace, like for `/` or `a/..`.
+    fn sibling<S: AsRef<OsStr>>(&self, name: S) -> Option<PathBuf>;
+    /// The same stem with `extension` in place of the last extension, in the same directory, so
+    /// `logs/app.txt` gives `logs/app.log`. An empty `extension` drops it. `None` without a file name.
+    fn sibling_with_extension<S: AsRef<OsStr>>(&self, extension: S) -> Option<PathBuf>;
+    /// The MIME type of the last extension from a bundled table, ignoring ASCII case: `text/html` for
+    /// `index.HTML`, and `application/gzip` for `backup.tar.gz`, whose contents [`inner_mime_type`](PathStrExt::inner_mime_type) gives.
+    #[cfg(feature = "mime")]
+    fn mime_type(&self) -> Option<&'static str>;
+    /// Turns the file stem into a lowercase, `-`-separated slug, keeping the extensions.
+    /// `My First Post!.md` becomes `my-first-post.md`.
+    #[cfg(feature = "slug")]
+    fn slugify_stem(&self) -> PathBuf;
+    /// The programming language of the file by its name or extension, e.g. [`Language::MAKE`] for `Makefile`, following
+    /// [`LanguageRegistry::default`]. Build a [`LanguageRegistry`] to change the rules.
+    fn source_language(&self) -> Option<Language>;
     /// Checks if the supplied pattern is at the beginning or end of the stringified version of the AsRef<Path>
     fn starts_or_ends_with<S: AsRef<str>>(&self, pattern: S) -> bool;
+    /// Converts the file stem to `kebab-case`, keeping the extensions.
+    #[cfg(feature = "slug")]
+    fn stem_to_kebab_case(&self) -> PathBuf;
+    /// Converts the file stem to `snake_case`, keeping the extensions.
+    #[cfg(feature = "slug")]
+    fn stem_to_snake_case(&self) -> PathBuf;
     /// Strips all extensions from a pathref. If the path isn't able to be converted to a `str` return `None` instead
     fn strip_extensions(&self) -> Option<&str>;
+    /// Strips the extensions of the final component as `options` say, leaving dots in directory names alone.
+    /// With the defaults `.bashrc` is all stem, unlike with [`strip_extensions`](PathStrExt::strip_extensions).
+    fn strip_extensions_opts(&self, options: StripOptions) -> Option<&str>;
+    /// The whole path as one file name safe on every filesystem, with separators and other bytes
+    /// percent-encoded, for naming cache entries. [`from_cache_key`] gives the path back.
+    fn to_cache_key(&self) -> String;
+    /// Replaces accented, Cyrillic, CJK and other non-ASCII characters in the file name with ASCII
+    /// approximations, keeping the extensions. Only the file name is touched.
+    #[cfg(feature = "translit")]
+    fn transliterate_file_name(&self) -> PathBuf;
+    /// Like `transliterate_file_name`, but a changed name also gets a short hash of the original so
+    /// names like `café` and `cafë` don't collide.
+    #[cfg(feature = "translit")]
+    fn transliterate_file_name_hashed(&self) -> PathBuf;
+    /// Shortens the file name to at most `max_bytes` by trimming the stem on a char boundary, keeping the
+    /// compound extension (or at least the last extension) intact. `None` if even the extension won't fit.
+    fn truncate_file_name(&self, max_bytes: usize) -> Option<PathBuf>;
+    /// Like `truncate_file_name`, but a shortened name also gets a short hash of the original so names
+    /// that only differed after the cut stay distinct.
+    fn truncate_file_name_hashed(&self, max_bytes: usize) -> Option<PathBuf>;
+}
+
+/// Lexical operations on the path's components, never touching the filesystem.
+pub trait PathComponentExt {
+    /// Joins the path onto `base` if it is relative and then normalizes it lexically.
+    /// No filesystem access happens, so symlinks are not resolved and the path doesn't need to exist.
+    fn absolutize_from<P: AsRef<Path>>(&self, base: P) -> PathBuf;
+    /// What kind of build artifact the path looks like: under a directory like `target/`, `node_modules/` or
+    /// `__pycache__/`, or a compiled file like `.o` or `.class`, following [`ArtifactRules::default`]. Build an
+    /// [`ArtifactRules`] to change the rules.
+    fn artifact_kind(&self) -> Option<ArtifactKind>;
+    /// Puts a file named by a numeric ID under directories of its leading digits: the ID is zero-padded to
+    /// `width` digits and every full `group` of them before the last becomes a directory, so `123456.json` with
+    /// 9 and 3 becomes `000/123/000123456.json`. `None` if the name before its extensions isn't all digits,
+    /// needs more than `width` of them, or `group` is zero.
+    fn bucket_by_number(&self, width: usize, group: usize) -> Option<PathBuf>;
+    /// The numeric ID of a file placed by [`bucket_by_number`](PathComponentExt::bucket_by_number), read from
+    /// the name before its extensions. `None` if that isn't all digits or doesn't fit a `u64`.
+    fn bucketed_number(&self) -> Option<u64>;
+    /// The candidate nearest this path, a "did you mean" for a mistyped one, `None` if none is within
+    /// `max_distance` edits. Distance is counted per component, a differing extension costing 1 and differing
+    /// case at most 1.
+    fn closest_match<I: IntoIterator<Item = P>, P: AsRef<Path>>(
+        &self,
+        candidates: I,
+        max_distance: usize,
+    ) -> Option<P>;
+    /// Orders directories (as told by `is_dir`) before everything else, each group in natural order.
+    fn cmp_dirs_first<P: AsRef<Path>, F: Fn(&Path) -> bool>(&self, other: P, is_dir: F)
+        -> Ordering;
+    /// Orders against `other` component by component using `locale`'s collation, so `ä` sorts near `a` in German.
+    /// This builds a collator on every call, use a [`PathCollator`] to sort many paths.
+    #[cfg(feature = "icu")]
+    fn collate_cmp<P: AsRef<Path>>(&self, other: P, locale: &icu_locale_core::Locale) -> Ordering;
+    /// Every run of `n` consecutive components, overlapping, in order, so `a/b/c` gives `[a, b]` and
+    /// `[b, c]` for 2. Nothing if `n` is 0 or more than there are components.
+    fn component_windows(&self, n: usize) -> impl Iterator<Item = Vec<Component<'_>>>;
+    /// The path in `style`'s form as `cygpath` converts it under Cygwin's mounts, so `C:\Users\me` and
+    /// `/cygdrive/c/Users/me` convert into each other. `None` if it has no such form or isn't Unicode.
+    fn convert_path(&self, style: PathStyle) -> Option<PathBuf>;
+    /// Whether the last components are `suffix`, whole components compared, so `/opt/bin/app` ends
+    /// with `["bin", "app"]` but `/opt/bin/myapp` doesn't.
+    fn ends_with_components<I: IntoIterator<Item = S>, S: AsRef<str>>(&self, suffix: I) -> bool;
+    /// Undoes [`fan_out`](PathComponentExt::fan_out), dropping the directories right above the file whose names,
+    /// read down, are two-character chunks from the start of its stem. As many are dropped as match.
+    fn fan_in(&self) -> PathBuf;
+    /// Moves the file into `levels` nested directories named after two-character chunks of its stem, so
+    /// `cache/aabbccdd.bin` becomes `cache/aa/bb/aabbccdd.bin` with two. `None` if the stem is too short.
+    fn fan_out(&self, levels: usize) -> Option<PathBuf>;
+    /// Checks if the supplied component is present in total in the path. `.` and `..` aren't resolved, so
+    /// `/opt/foo/../bar` has `foo`; use [`normalize`](PathComponentExt::normalize) first to match what it names.
+    fn has_component<S: AsRef<str>>(&self, component: S) -> bool;
+    /// Whether the components contain `sequence` in order, not necessarily next to each other, so
+    /// `src/a/tests/b.rs` has `["src", "tests"]`. Compared as [`has_component`](PathComponentExt::has_component) does.
+    fn has_component_sequence<I: IntoIterator<Item = S>, S: AsRef<str>>(&self, sequence: I)
+        -> bool;
+    /// This path inside `app`'s directory in the user's cache dir, `None` if there isn't one.
+    #[cfg(feature = "dirs")]
+    fn in_cache_dir<A: AsRef<Path>>(&self, app: A) -> Option<PathBuf>;
+    /// This path inside `app`'s directory in the user's data dir, `None` if there isn't one.
+    #[cfg(feature = "dirs")]
+    fn in_data_dir<A: AsRef<Path>>(&self, app: A) -> Option<PathBuf>;
+    /// Whether [`artifact_kind`](PathComponentExt::artifact_kind) recognizes the path, for cleanup tools and
+    /// backup excludes.
+    fn is_build_artifact(&self) -> bool;
+    /// Whether every component is a long file name FAT32 and exFAT accept: valid UTF-8, at most 255 UTF-16 units,
+    /// none of `"*/:<>?\|` or control characters, and not ending in `.` or space.
+    fn is_fat_safe(&self) -> bool;
+    /// Whether every component is a name Joliet can store: at most 64 UCS-2 characters, none of `*/:;?\` or
+    /// control characters, and the whole path within Joliet's 240 bytes.
+    fn is_joliet_safe(&self) -> bool;
+    /// Whether [`normalize`](PathComponentExt::normalize) would give the path back unchanged, byte for byte.
+    fn is_normalized(&self) -> bool;
+    /// Whether [`to_object_key`](PathComponentExt::to_object_key) gives a key `store` takes as it is, within its
+    /// length limits and without characters it treats specially.
+    fn is_object_key_safe(&self, store: ObjectStore) -> bool;
+    /// Whether every component sticks to the POSIX portable filename character set, doesn't start with `-`,
+    /// and fits [`LengthLimits::POSIX`]. See [`posix_portability_violations`](PathComponentExt::posix_portability_violations) for why not.
+    fn is_posix_portable(&self) -> bool;
+    /// Compares component by component with digit runs compared by value and letters case-insensitively,
+    /// so `file2` sorts before `file10`.
+    fn natural_cmp<P: AsRef<Path>>(&self, other: P) -> Ordering;
+    /// Resolves `.`, `..` and redundant separators without touching the filesystem, so `/opt/./foo/../bar`
+    /// becomes `/opt/bar`. Symlinks aren't followed, so `a/link/..` becomes `a` even where the OS would
+    /// go elsewhere. `..` right under a root is dropped while leading `..` of a relative path is kept,
+    /// Windows prefixes are kept, and verbatim `\\?\` paths are left as they are.
+    fn normalize(&self) -> PathBuf;
+    /// Normalizes with RFC 3986 dot-segment removal, where `..` that would climb past the start disappears.
+    /// `../../a` becomes `a`, as a web server mapping URL paths onto a directory wants.
+    fn normalize_uri_style(&self) -> PathBuf;
+    /// Lexically normalizes the path in the given style, only differing in how leading `..` is treated.
+    fn normalize_with_style(&self, style: NormalizeStyle) -> PathBuf;
+    /// [`normalize_with_style`](PathComponentExt::normalize_with_style) with
+    /// [`NormalizeStyle::Filesystem`], written into `buf` instead of a new allocation. `buf` is
+    /// cleared first, so one buffer can be reused across a loop over many paths.
+    fn normalize_into(&self, buf: &mut PathBuf);
+    /// [`normalize_with_style`](PathComponentExt::normalize_with_style) written into `buf`, which is
+    /// cleared first.
+    fn normalize_with_style_into(&self, style: NormalizeStyle, buf: &mut PathBuf);
+    /// The ancestor `n` levels up, as [`Path::parent`] called `n` times would give, so `0` is the
+    /// path itself and `/a/b/c` has `/a` two up. Like `parent`, a relative path's last ancestor is
+    /// the empty path, `a/b` giving `""` two up. `None` once `parent` would be, past the root or
+    /// that empty path.
+    fn nth_parent(&self, n: usize) -> Option<&Path>;
+    /// The parent, or the path itself when it has none, like the root or an empty path. As with
+    /// [`Path::parent`], a single relative component's parent is the empty path.
+    fn parent_or_self(&self) -> &Path;
+    /// Everything that keeps the path from being POSIX portable under `limits`, empty if it is. Pass
+    /// [`LengthLimits::POSIX`] for the strict minimums or e.g. [`LengthLimits::UNIX`] for just the character rules.
+    fn posix_portability_violations(&self, limits: LengthLimits) -> Vec<PosixViolation>;
+    /// The path with user names and home directories masked as `options` say, so `/home/alice/x`
+    /// becomes `/home/<user>/x`, for logs and bug reports.
+    fn redact(&self, options: &RedactOptions) -> PathBuf;
+    /// The path moved from under `from` to the same place under `to`, or as it is when it isn't under
+    /// `from`, written into `buf`, which is cleared first. Whole components are compared, as
+    /// [`Path::strip_prefix`] does.
+    fn rebase_into<F: AsRef<Path>, U: AsRef<Path>>(&self, from: F, to: U, buf: &mut PathBuf);
+    /// This path resolved against the user's config dir (absolute paths are kept), `None` if there isn't one.
+    #[cfg(feature = "dirs")]
+    fn relative_to_config_dir(&self) -> Option<PathBuf>;
+    /// Sanitizes every component so Windows (the strictest common target) would accept it, see [`CharPreset::WindowsStrict`].
+    fn sanitize(&self) -> PathBuf;
+    /// Sanitizes every component following `policy`, keeping prefixes, roots and `..` as they are.
+    fn sanitize_with(&self, policy: &CharPolicy) -> PathBuf;
+    /// Whether the first components after any root are `prefix`, whole components compared, so
+    /// `/usr/local/bin` starts with `["usr", "local"]` but `/usr/localized` doesn't.
+    fn starts_with_components<I: IntoIterator<Item = S>, S: AsRef<str>>(&self, prefix: I) -> bool;
     /// Strip the prefix if it's there
-    fn strip_prefix_if_needed<'a, S: AsRef<str>>(&'a self, prefix: S) -> &'a Path;
+    fn strip_prefix_if_needed<S: AsRef<str>>(&self, prefix: S) -> &Path;
+    /// The components as strings that [`from_component_strings`] turns back into a path equal to this one, or
+    /// `None` if any isn't UTF-8. Repeated separators, non-leading `.` and a trailing separator aren't kept.
+    fn to_component_strings(&self) -> Option<Vec<String>>;
+    /// The file name as the 8.3 short name Windows would give it next to `existing`, compared without case:
+    /// uppercased, with spaces and dots dropped and other invalid characters as `_`, and a `~N` tail when the
+    /// name had to change or is taken. `LongFileName.txt` becomes `LONGFI~1.TXT`. `None` without a file name.
+    fn to_fat_8_3<I, S>(&self, existing: I) -> Option<PathBuf>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>;
+    /// The path as ISO 9660 level 1 names: uppercase `A-Z 0-9 _` only, directories cut to eight characters
+    /// and the file name to `NAME.EXT` with eight and three. Names may collide and the depth isn't checked.
+    fn to_iso9660_level1(&self) -> PathBuf;
+    /// The normal components joined with `/` as an object store key, so `C:\backup\a.txt` on Windows and
+    /// `/backup/a.txt` both give `backup/a.txt`. `None` for a `..`, a component that isn't UTF-8, or no components.
+    fn to_object_key(&self) -> Option<String>;
+    /// Swaps `old_root` for `new_root`, keeping the rest of the path, so `/mnt/a/x` from `/mnt/a` to `/mnt/b`
+    /// gives `/mnt/b/x` and on Windows `C:\data\x` from `C:` to `D:` gives `D:\data\x`. The roots are matched
+    /// by whole components, and a path not under `old_root` is returned as it is, as with
+    /// [`rebase_into`](PathComponentExt::rebase_into).
+    fn with_root<O: AsRef<Path>, N: AsRef<Path>>(&self, old_root: O, new_root: N) -> PathBuf;
 }
 
+/// Operations that query or change the filesystem. Their errors carry a [`PathIoError`] saying what
+/// failed on which path.
+#[cfg(feature = "fs")]
+pub trait PathFsExt {
+    /// `absolutize_from` the current directory, unlike `canonicalize` neither requiring the path exist nor resolving symlinks.
+    fn absolutize(&self) -> io::Result<PathBuf>;
+    /// The path with every existing component in the case the filesystem stores it in, `c:\users\ME` giving
+    /// `C:\Users\Me` on Windows and macOS. Elsewhere names only match exactly, so this just keeps them. Components
+    /// from the first missing one on are kept as given.
+    fn actual_case(&self) -> io::Result<PathBuf>;
+    /// Bytes free for the current user, after quotas and reserved blocks, on the filesystem holding the path or
+    /// its closest existing ancestor, so a destination can be checked before it's created.
+    fn available_space(&self) -> io::Result<u64>;
+    /// Canonicalizes like [`Path::canonicalize`], then drops the `\\?\` prefix Windows adds when the path means
+    /// the same without it, as `dunce::canonicalize` does.
+    fn canonicalize_simplified(&self) -> io::Result<PathBuf>;
+    /// `cmp_dirs_first` asking the filesystem which paths are directories.
+    fn cmp_dirs_first_fs<P: AsRef<Path>>(&self, other: P) -> Ordering;
+    /// Creates a directory named like [`unique_child`](PathFsExt::unique_child) does, retrying on collisions, so it
+    /// is ours alone.
+    fn create_unique_child(&self, prefix: &str, suffix: &str) -> io::Result<PathBuf>;
+    /// Creates the directory and any missing parents, returning whether anything had to be created. Errors say
+    /// which path they are about.
+    fn ensure_dir(&self) -> io::Result<bool>;
+    /// Creates the directory the path is in, like [`ensure_dir`](PathFsExt::ensure_dir) on its parent, so the path
+    /// itself can be written.
+    fn ensure_parent_dir(&self) -> io::Result<bool>;
+    /// Looks for `name` in this path and then each of its ancestors, returning the first that exists.
+    fn find_up<N: AsRef<Path>>(&self, name: N) -> io::Result<Option<PathBuf>>;
+    /// How many hard links the file has, 1 for one that has never been linked.
+    fn hardlink_count(&self) -> io::Result<u64>;
+    /// Creates `dest` as another hard link to this file, with errors naming both paths and saying plainly when
+    /// they are on different filesystems.
+    fn hardlink_to<P: AsRef<Path>>(&self, dest: P) -> io::Result<()>;
+    /// Whether the path is a block device, following symlinks. Always false off Unix.
+    fn is_block_device(&self) -> bool;
+    /// Whether the path is a character device, following symlinks. Always false off Unix.
+    fn is_char_device(&self) -> bool;
+    /// Whether the path is a named pipe, following symlinks. Always false off Unix.
+    fn is_fifo(&self) -> bool;
+    /// Whether the path and `other` are the same file, going by its device and inode (volume and file index on
+    /// Windows) rather than the paths.
+    fn is_hardlinked_with<P: AsRef<Path>>(&self, other: P) -> io::Result<bool>;
+    /// Whether the path is a plain file, following symlinks, and not a directory, device, fifo or socket. This is
+    /// what [`Path::is_file`] checks, named to sit alongside the special file checks.
+    fn is_regular_file(&self) -> bool;
+    /// Whether the path is a Unix domain socket, following symlinks. Always false off Unix.
+    fn is_socket(&self) -> bool;
+    /// Whether the current user may write to the path, asking the OS (`access(2)`, so ACLs and read-only mounts
+    /// count) on Unix and going by the read-only attribute on Windows.
+    fn is_writable_by_current_user(&self) -> io::Result<bool>;
+    /// Lets everyone who can read the file execute it, as `chmod +x` does. Windows decides by extension, so there
+    /// this only checks the path exists.
+    fn make_executable(&self) -> io::Result<()>;
+    /// Removes write permission for everyone, or sets the read-only attribute on Windows.
+    fn make_readonly(&self) -> io::Result<()>;
+    /// Moves the file or directory to the Recycle Bin on Windows, the user's `~/.Trash` or `.Trashes` on macOS,
+    /// and the freedesktop.org trash elsewhere, as a safer default than deleting it.
+    #[cfg(feature = "trash")]
+    fn move_to_trash(&self) -> io::Result<()>;
+    /// The process id in this PID file and whether that process still runs, `None` if there is no
+    /// file. Contents that aren't a process id are an [`InvalidData`](io::ErrorKind::InvalidData) error.
+    fn read_pid_file(&self) -> io::Result<Option<PidFile>>;
+    /// Resolves symlinks for as much of the path as exists, appending the missing remainder lexically.
+    /// Unlike `canonicalize` the path doesn't need to exist. `..` is applied in order after the symlinks
+    /// before it, so `link/..` is the parent of the link's target, as the OS has it.
+    fn resolve_lenient(&self) -> io::Result<PathBuf>;
+    /// What is at the path, telling a missing parent from a missing leaf and a dangling symlink from a working
+    /// one, for error messages that say why a path isn't usable.
+    fn state(&self) -> PathState;
+    /// Creates the file if it's missing and sets its modification time to now, like `touch`.
+    fn touch(&self) -> io::Result<()>;
+    /// Touches the file as [`touch`](PathFsExt::touch) does, optionally creating parent directories or setting
+    /// another time.
+    fn touch_with(&self, options: TouchOptions) -> io::Result<()>;
+    /// Takes the lock on this file by creating its [`lock_path`](PathStrExt::lock_path) atomically,
+    /// `None` if another holder already has. The lock lasts until the [`LockFile`] is dropped.
+    fn try_lock(&self) -> io::Result<Option<LockFile>>;
+    /// A child of this directory named `{prefix}{16 random hex digits}{suffix}` that doesn't exist yet, for staging
+    /// next to a final destination, with a PID or timestamp going in `prefix` when leftovers should be traceable.
+    /// Nothing is created, so another process could still take the name.
+    fn unique_child(&self, prefix: &str, suffix: &str) -> io::Result<PathBuf>;
+    /// Recursively watches the path, handing every filesystem event to `callback`.
+    /// The watch lasts as long as the returned watcher is alive.
+    #[cfg(feature = "notify")]
+    fn watch<F>(&self, callback: F) -> notify::Result<notify::RecommendedWatcher>
+    where
+        F: FnMut(notify::Result<notify::Event>) + Send + 'static;
+    /// Like `watch`, but only passes along event paths matching one of `globs`.
+    /// Globs are matched against the event path relative to this path, and then against the full event path.
+    #[cfg(feature = "notify")]
+    fn watch_matching<F>(
+        &self,
+        globs: globset::GlobSet,
+        callback: F,
+    ) -> notify::Result<notify::RecommendedWatcher>
+    where
+        F: FnMut(notify::Result<notify::Event>) + Send + 'static;
+    /// Writes the current process id to this PID file, replacing it atomically so readers never see
+    /// it half written. Check [`read_pid_file`](PathFsExt::read_pid_file) first to not take over from
+    /// a running instance.
+    fn write_pid_file(&self) -> io::Result<()>;
+}
+
+/// All the path extension traits at once, what the single `PathExt` trait was split into. As a bound it
+/// still gives every method, but importing it doesn't bring them into scope; import [`prelude`] instead.
+#[deprecated(
+    since = "0.3.0",
+    note = "import `pathext::prelude::*`, or bound on `PathStrExt`, `PathComponentExt` and `PathFsExt`"
+)]
+#[cfg(feature = "fs")]
+pub trait PathExt: PathStrExt + PathComponentExt + PathFsExt {}
+
+/// All the path extension traits at once, what the single `PathExt` trait was split into. As a bound it
+/// still gives every method, but importing it doesn't bring them into scope; import [`prelude`] instead.
+#[deprecated(
+    since = "0.3.0",
+    note = "import `pathext::prelude::*`, or bound on `PathStrExt` and `PathComponentExt`"
+)]
+#[cfg(not(feature = "fs"))]
+pub trait PathExt: PathStrExt + PathComponentExt {}
+
+#[allow(deprecated)]
+impl<T: AsRef<Path>> PathExt for T {}
+
 /// I think this is the only implementation needed since there is a lot that implements AsRef<Path> in std.
-impl<T: AsRef<Path>> PathExt for T {
+impl<T: AsRef<Path>> PathStrExt for T {
+    fn fits_length_limits(&self, limits: LengthLimits) -> bool {
+        limits::fits_length_limits(self.as_ref(), limits)
+    }
+
+    fn shorten_to_limit(&self, limits: LengthLimits) -> Option<PathBuf> {
+        limits::shorten_to_limit(self.as_ref(), limits)
+    }
+
+    #[cfg(feature = "dirs")]
+    fn display_contracted(&self) -> String {
+        known_dirs::display_contracted(self.as_ref())
+    }
+
     fn contains<S: AsRef<str>>(&self, pattern: S) -> bool {
         self.as_ref()
             .to_str()
-            .map_or(false, |s| s.contains(pattern.as_ref()))
+            .is_some_and(|s| s.contains(pattern.as_ref()))
+    }
+
+    fn format_for_prompt(&self, options: &PromptOptions) -> String {
+        prompt::format_for_prompt(self.as_ref(), options)
+    }
+
+    fn as_encoded_bytes_compat(&self) -> &[u8] {
+        encoded::as_encoded_bytes(self.as_ref())
+    }
+
+    fn byte_len(&self) -> usize {
+        self.as_ref().as_os_str().len()
+    }
+
+    fn char_len_lossy(&self) -> usize {
+        self.as_ref().to_string_lossy().chars().count()
+    }
+
+    fn is_empty_path(&self) -> bool {
+        self.as_ref().as_os_str().is_empty()
+    }
+
+    #[cfg(feature = "unicode")]
+    fn portable_sort_key(&self) -> Vec<u8> {
+        sort_key::portable_sort_key(self.as_ref(), false)
+    }
+
+    #[cfg(feature = "unicode")]
+    fn portable_sort_key_case_folded(&self) -> Vec<u8> {
+        sort_key::portable_sort_key(self.as_ref(), true)
+    }
+
+    #[cfg(feature = "slug")]
+    fn slugify_stem(&self) -> PathBuf {
+        case::map_stem(self.as_ref(), case::slugify)
+    }
+
+    #[cfg(feature = "slug")]
+    fn stem_to_kebab_case(&self) -> PathBuf {
+        case::map_stem(self.as_ref(), case::kebab_case)
+    }
+
+    #[cfg(feature = "slug")]
+    fn stem_to_snake_case(&self) -> PathBuf {
+        case::map_stem(self.as_ref(), case::snake_case)
+    }
+
+    fn starts_or_ends_with<S: AsRef<str>>(&self, pattern: S) -> bool {
+        self.as_ref()
+            .to_str()
+            .is_some_and(|s| s.starts_with(pattern.as_ref()) || s.ends_with(pattern.as_ref()))
+    }
+
+    fn ends_with_extensions<S: AsRef<str>>(&self, pattern: S) -> bool {
+        self.as_ref()
+            .to_str()
+            .is_some_and(|s| s.ends_with(pattern.as_ref()))
+    }
+
+    fn has_extensions_exact<S: AsRef<str>>(&self, extensions: S) -> bool {
+        self.as_ref()
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name::has_extensions_exact(name, extensions.as_ref()))
+    }
+
+    fn extension_is<S: AsRef<str>>(&self, extension: S) -> bool {
+        self.extension_is_any([extension])
+    }
+
+    fn extension_is_ignore_case<S: AsRef<str>>(&self, extension: S) -> bool {
+        self.extension_is_any_ignore_case([extension])
+    }
+
+    fn extension_is_any<I: IntoIterator<Item = S>, S: AsRef<str>>(&self, extensions: I) -> bool {
+        let extension = self.as_ref().extension().and_then(|e| e.to_str());
+        extensions
+            .into_iter()
+            .any(|wanted| name::extension_is(extension, wanted.as_ref(), false))
+    }
+
+    fn extension_is_any_ignore_case<I: IntoIterator<Item = S>, S: AsRef<str>>(
+        &self,
+        extensions: I,
+    ) -> bool {
+        let extension = self.as_ref().extension().and_then(|e| e.to_str());
+        extensions
+            .into_iter()
+            .any(|wanted| name::extension_is(extension, wanted.as_ref(), true))
+    }
+
+    fn inferred_kind(&self) -> NameKind {
+        kind::builtin().kind_of(self)
+    }
+
+    #[cfg(feature = "mime")]
+    fn inner_mime_type(&self) -> Option<&'static str> {
+        mime::inner_mime_type(self.as_ref())
+    }
+
+    #[cfg(feature = "mime")]
+    fn mime_type(&self) -> Option<&'static str> {
+        mime::mime_type(self.as_ref())
+    }
+
+    fn is_os_metadata_junk(&self) -> bool {
+        classifier::os_metadata_junk().matches(self)
+    }
+
+    fn is_transient_file(&self) -> bool {
+        classifier::transient().matches(self)
+    }
+
+    fn lock_path(&self) -> Option<PathBuf> {
+        lock::lock_path(self.as_ref())
+    }
+
+    fn sibling<S: AsRef<OsStr>>(&self, name: S) -> Option<PathBuf> {
+        let path = self.as_ref();
+        path.file_name()?;
+        Some(path.with_file_name(name))
+    }
+
+    fn sibling_with_extension<S: AsRef<OsStr>>(&self, extension: S) -> Option<PathBuf> {
+        let path = self.as_ref();
+        path.file_name()?;
+        Some(path.with_extension(extension))
+    }
+
+    fn looks_sensitive(&self) -> bool {
+        classifier::sensitive().matches(self)
+    }
+
+    fn source_language(&self) -> Option<Language> {
+        language::builtin().language_of(self)
+    }
+
+    fn strip_extensions(&self) -> Option<&str> {
+        name::strip_extensions(self.as_ref())
+    }
+
+    fn strip_extensions_opts(&self, options: StripOptions) -> Option<&str> {
+        name::strip_extensions_opts(self.as_ref(), &options)
+    }
+
+    fn to_cache_key(&self) -> String {
+        cache_key::to_cache_key(self.as_ref())
+    }
+
+    fn short_id(&self, len: usize) -> String {
+        hash::short_id(self.as_ref(), len)
+    }
+
+    fn escape_for_make(&self) -> Option<String> {
+        build_escape::escape_for_make(self.as_ref())
+    }
+
+    fn escape_for_ninja(&self) -> Option<String> {
+        build_escape::escape_for_ninja(self.as_ref())
+    }
+
+    #[cfg(feature = "translit")]
+    fn transliterate_file_name(&self) -> PathBuf {
+        translit::transliterate_file_name(self.as_ref(), false)
+    }
+
+    #[cfg(feature = "translit")]
+    fn transliterate_file_name_hashed(&self) -> PathBuf {
+        translit::transliterate_file_name(self.as_ref(), true)
+    }
+
+    fn truncate_file_name(&self, max_bytes: usize) -> Option<PathBuf> {
+        limits::truncate_file_name(self.as_ref(), max_bytes, false)
+    }
+
+    fn truncate_file_name_hashed(&self, max_bytes: usize) -> Option<PathBuf> {
+        limits::truncate_file_name(self.as_ref(), max_bytes, true)
+    }
+}
+
+impl<T: AsRef<Path>> PathComponentExt for T {
+    fn absolutize_from<P: AsRef<Path>>(&self, base: P) -> PathBuf {
+        normalize::normalize(&base.as_ref().join(self.as_ref()))
+    }
+
+    #[cfg(feature = "icu")]
+    fn collate_cmp<P: AsRef<Path>>(&self, other: P, locale: &icu_locale_core::Locale) -> Ordering {
+        match PathCollator::new(locale) {
+            Ok(collator) => collator.compare(self, other),
+            Err(_) => self.as_ref().cmp(other.as_ref()),
+        }
+    }
+
+    fn cmp_dirs_first<P: AsRef<Path>, F: Fn(&Path) -> bool>(
+        &self,
+        other: P,
+        is_dir: F,
+    ) -> Ordering {
+        order::cmp_dirs_first(self.as_ref(), other.as_ref(), is_dir)
+    }
+
+    #[cfg(feature = "dirs")]
+    fn in_cache_dir<A: AsRef<Path>>(&self, app: A) -> Option<PathBuf> {
+        known_dirs::in_cache_dir(self.as_ref(), app.as_ref())
+    }
+
+    #[cfg(feature = "dirs")]
+    fn in_data_dir<A: AsRef<Path>>(&self, app: A) -> Option<PathBuf> {
+        known_dirs::in_data_dir(self.as_ref(), app.as_ref())
+    }
+
+    #[cfg(feature = "dirs")]
+    fn relative_to_config_dir(&self) -> Option<PathBuf> {
+        known_dirs::relative_to_config_dir(self.as_ref())
+    }
+
+    fn redact(&self, options: &RedactOptions) -> PathBuf {
+        redact::redact(self.as_ref(), options)
+    }
+
+    fn to_component_strings(&self) -> Option<Vec<String>> {
+        component_strings::to_component_strings(self.as_ref())
     }
 
     fn has_component<S: AsRef<str>>(&self, component: S) -> bool {
@@ -71,105 +839,841 @@ impl<T: AsRef<Path>> PathExt for T {
             .any(|c| c.as_os_str().eq(component.as_ref()))
     }
 
-    fn starts_or_ends_with<S: AsRef<str>>(&self, pattern: S) -> bool {
-        self.as_ref().to_str().map_or(false, |s| {
-            s.starts_with(pattern.as_ref()) || s.ends_with(pattern.as_ref())
-        })
+    fn natural_cmp<P: AsRef<Path>>(&self, other: P) -> Ordering {
+        order::natural_cmp(self.as_ref(), other.as_ref())
+    }
+
+    fn component_windows(&self, n: usize) -> impl Iterator<Item = Vec<Component<'_>>> {
+        sequence::component_windows(self.as_ref(), n)
+    }
+
+    fn has_component_sequence<I: IntoIterator<Item = S>, S: AsRef<str>>(
+        &self,
+        sequence: I,
+    ) -> bool {
+        sequence::has_component_sequence(self.as_ref(), sequence)
+    }
+
+    fn starts_with_components<I: IntoIterator<Item = S>, S: AsRef<str>>(&self, prefix: I) -> bool {
+        sequence::starts_with_components(self.as_ref(), prefix)
+    }
+
+    fn ends_with_components<I: IntoIterator<Item = S>, S: AsRef<str>>(&self, suffix: I) -> bool {
+        sequence::ends_with_components(self.as_ref(), suffix)
+    }
+
+    fn closest_match<I: IntoIterator<Item = P>, P: AsRef<Path>>(
+        &self,
+        candidates: I,
+        max_distance: usize,
+    ) -> Option<P> {
+        suggest::closest_match(self.as_ref(), candidates, max_distance)
+    }
+
+    fn normalize(&self) -> PathBuf {
+        normalize::normalize(self.as_ref())
+    }
+
+    fn is_normalized(&self) -> bool {
+        normalize::is_normalized(self.as_ref())
+    }
+
+    fn normalize_uri_style(&self) -> PathBuf {
+        self.normalize_with_style(NormalizeStyle::Uri)
+    }
+
+    fn normalize_with_style(&self, style: NormalizeStyle) -> PathBuf {
+        normalize::normalize_with_style(self.as_ref(), style)
+    }
+
+    fn normalize_into(&self, buf: &mut PathBuf) {
+        normalize::normalize_into(self.as_ref(), NormalizeStyle::Filesystem, buf)
+    }
+
+    fn normalize_with_style_into(&self, style: NormalizeStyle, buf: &mut PathBuf) {
+        normalize::normalize_into(self.as_ref(), style, buf)
+    }
+
+    fn rebase_into<F: AsRef<Path>, U: AsRef<Path>>(&self, from: F, to: U, buf: &mut PathBuf) {
+        rebase::rebase_into(self.as_ref(), from.as_ref(), to.as_ref(), buf)
+    }
+
+    fn is_posix_portable(&self) -> bool {
+        self.posix_portability_violations(LengthLimits::POSIX)
+            .is_empty()
+    }
+
+    fn posix_portability_violations(&self, limits: LengthLimits) -> Vec<PosixViolation> {
+        posix::violations(self.as_ref(), limits)
+    }
+
+    fn artifact_kind(&self) -> Option<ArtifactKind> {
+        artifact::builtin().kind_of(self)
+    }
+
+    fn is_build_artifact(&self) -> bool {
+        self.artifact_kind().is_some()
+    }
+
+    fn bucket_by_number(&self, width: usize, group: usize) -> Option<PathBuf> {
+        bucket::bucket_by_number(self.as_ref(), width, group)
+    }
+
+    fn bucketed_number(&self) -> Option<u64> {
+        bucket::bucketed_number(self.as_ref())
+    }
+
+    fn convert_path(&self, style: PathStyle) -> Option<PathBuf> {
+        cygpath::convert_path(self.as_ref(), style)
+    }
+
+    fn fan_in(&self) -> PathBuf {
+        shard::fan_in(self.as_ref())
+    }
+
+    fn fan_out(&self, levels: usize) -> Option<PathBuf> {
+        shard::fan_out(self.as_ref(), levels)
+    }
+
+    fn is_fat_safe(&self) -> bool {
+        fat::is_fat_safe(self.as_ref())
+    }
+
+    fn to_fat_8_3<I, S>(&self, existing: I) -> Option<PathBuf>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        fat::to_fat_8_3(self.as_ref(), existing)
+    }
+
+    fn is_object_key_safe(&self, store: ObjectStore) -> bool {
+        object_key::to_object_key(self.as_ref()).is_some_and(|key| store.allows(&key))
+    }
+
+    fn to_object_key(&self) -> Option<String> {
+        object_key::to_object_key(self.as_ref())
+    }
+
+    fn is_joliet_safe(&self) -> bool {
+        iso9660::is_joliet_safe(self.as_ref())
+    }
+
+    fn to_iso9660_level1(&self) -> PathBuf {
+        iso9660::to_level1(self.as_ref())
+    }
+
+    fn sanitize(&self) -> PathBuf {
+        self.sanitize_with(&CharPolicy::windows_strict())
+    }
+
+    fn sanitize_with(&self, policy: &CharPolicy) -> PathBuf {
+        sanitize::sanitize_path(self.as_ref(), policy)
+    }
+
+    fn strip_prefix_if_needed<S: AsRef<str>>(&self, prefix: S) -> &Path {
+        if let Ok(stripped) = self.as_ref().strip_prefix(prefix.as_ref()) {
+            stripped
+        } else {
+            self.as_ref()
+        }
+    }
+
+    fn nth_parent(&self, n: usize) -> Option<&Path> {
+        self.as_ref().ancestors().nth(n)
+    }
+
+    fn parent_or_self(&self) -> &Path {
+        let path = self.as_ref();
+        path.parent().unwrap_or(path)
+    }
+
+    fn with_root<O: AsRef<Path>, N: AsRef<Path>>(&self, old_root: O, new_root: N) -> PathBuf {
+        let mut rooted = PathBuf::new();
+        rebase::rebase_into(
+            self.as_ref(),
+            old_root.as_ref(),
+            new_root.as_ref(),
+            &mut rooted,
+        );
+        rooted
+    }
+}
+
+#[cfg(feature = "fs")]
+impl<T: AsRef<Path>> PathFsExt for T {
+    fn absolutize(&self) -> io::Result<PathBuf> {
+        resolve::absolutize_with(self.as_ref(), &resolve::RealFs)
+            .map_err(|e| fs::with_path(e, "absolutize", self.as_ref()))
+    }
+
+    fn cmp_dirs_first_fs<P: AsRef<Path>>(&self, other: P) -> Ordering {
+        order::cmp_dirs_first(self.as_ref(), other.as_ref(), Path::is_dir)
+    }
+
+    fn actual_case(&self) -> io::Result<PathBuf> {
+        fs::actual_case(self.as_ref())
+            .map_err(|e| fs::with_path(e, "find the stored case of", self.as_ref()))
+    }
+
+    fn canonicalize_simplified(&self) -> io::Result<PathBuf> {
+        fs::canonicalize_simplified(self.as_ref())
+            .map_err(|e| fs::with_path(e, "canonicalize", self.as_ref()))
+    }
+
+    fn state(&self) -> PathState {
+        fs::state(self.as_ref())
+    }
+
+    fn ensure_dir(&self) -> io::Result<bool> {
+        fs::ensure_dir(self.as_ref()).map_err(|e| fs::with_path(e, "create dir", self.as_ref()))
+    }
+
+    fn ensure_parent_dir(&self) -> io::Result<bool> {
+        fs::ensure_parent_dir(self.as_ref())
+            .map_err(|e| fs::with_path(e, "create the parent dir of", self.as_ref()))
+    }
+
+    fn touch(&self) -> io::Result<()> {
+        fs::touch(self.as_ref(), TouchOptions::default())
+            .map_err(|e| fs::with_path(e, "touch", self.as_ref()))
+    }
+
+    fn touch_with(&self, options: TouchOptions) -> io::Result<()> {
+        fs::touch(self.as_ref(), options).map_err(|e| fs::with_path(e, "touch", self.as_ref()))
+    }
+
+    fn try_lock(&self) -> io::Result<Option<LockFile>> {
+        lock::try_lock(self.as_ref()).map_err(|e| fs::with_path(e, "lock", self.as_ref()))
+    }
+
+    fn unique_child(&self, prefix: &str, suffix: &str) -> io::Result<PathBuf> {
+        fs::unique_child(self.as_ref(), prefix, suffix)
+            .map_err(|e| fs::with_path(e, "pick a unique child name in", self.as_ref()))
+    }
+
+    fn create_unique_child(&self, prefix: &str, suffix: &str) -> io::Result<PathBuf> {
+        fs::create_unique_child(self.as_ref(), prefix, suffix)
+            .map_err(|e| fs::with_path(e, "create a unique child in", self.as_ref()))
+    }
+
+    fn available_space(&self) -> io::Result<u64> {
+        fs::available_space(self.as_ref())
+            .map_err(|e| fs::with_path(e, "get the available space of", self.as_ref()))
+    }
+
+    fn make_executable(&self) -> io::Result<()> {
+        fs::make_executable(self.as_ref())
+            .map_err(|e| fs::with_path(e, "make executable", self.as_ref()))
+    }
+
+    fn make_readonly(&self) -> io::Result<()> {
+        fs::make_readonly(self.as_ref())
+            .map_err(|e| fs::with_path(e, "make read-only", self.as_ref()))
+    }
+
+    fn is_writable_by_current_user(&self) -> io::Result<bool> {
+        fs::is_writable_by_current_user(self.as_ref())
+            .map_err(|e| fs::with_path(e, "check write access to", self.as_ref()))
+    }
+
+    #[cfg(feature = "trash")]
+    fn move_to_trash(&self) -> io::Result<()> {
+        trash::move_to_trash(self.as_ref())
+            .map_err(|e| fs::with_path(e, "move to trash", self.as_ref()))
+    }
+
+    fn hardlink_count(&self) -> io::Result<u64> {
+        fs::hardlink_count(self.as_ref())
+            .map_err(|e| fs::with_path(e, "read the link count of", self.as_ref()))
+    }
+
+    fn hardlink_to<P: AsRef<Path>>(&self, dest: P) -> io::Result<()> {
+        fs::hardlink_to(self.as_ref(), dest.as_ref())
+            .map_err(|e| fs::with_path(e, "hard link", self.as_ref()))
+    }
+
+    fn is_hardlinked_with<P: AsRef<Path>>(&self, other: P) -> io::Result<bool> {
+        fs::is_hardlinked_with(self.as_ref(), other.as_ref())
+            .map_err(|e| fs::with_path(e, "compare the file ids of", self.as_ref()))
+    }
+
+    fn is_regular_file(&self) -> bool {
+        fs::is_regular_file(self.as_ref())
+    }
+
+    fn is_fifo(&self) -> bool {
+        fs::is_special(self.as_ref(), fs::Special::Fifo)
+    }
+
+    fn is_socket(&self) -> bool {
+        fs::is_special(self.as_ref(), fs::Special::Socket)
+    }
+
+    fn is_block_device(&self) -> bool {
+        fs::is_special(self.as_ref(), fs::Special::BlockDevice)
+    }
+
+    fn is_char_device(&self) -> bool {
+        fs::is_special(self.as_ref(), fs::Special::CharDevice)
+    }
+
+    fn find_up<N: AsRef<Path>>(&self, name: N) -> io::Result<Option<PathBuf>> {
+        resolve::find_up_with(self.as_ref(), name.as_ref(), &resolve::RealFs)
+            .map_err(|e| fs::with_path(e, "search up from", self.as_ref()))
+    }
+
+    fn read_pid_file(&self) -> io::Result<Option<PidFile>> {
+        pid_file::read_pid_file(self.as_ref())
+            .map_err(|e| fs::with_path(e, "read the pid file", self.as_ref()))
+    }
+
+    fn write_pid_file(&self) -> io::Result<()> {
+        pid_file::write_pid_file(self.as_ref())
+            .map_err(|e| fs::with_path(e, "write the pid file", self.as_ref()))
+    }
+
+    fn resolve_lenient(&self) -> io::Result<PathBuf> {
+        resolve::resolve_lenient_with(self.as_ref(), &resolve::RealFs)
+            .map_err(|e| fs::with_path(e, "resolve", self.as_ref()))
+    }
+
+    #[cfg(feature = "notify")]
+    fn watch<F>(&self, callback: F) -> notify::Result<notify::RecommendedWatcher>
+    where
+        F: FnMut(notify::Result<notify::Event>) + Send + 'static,
+    {
+        watch::watch(self.as_ref(), callback)
+    }
+
+    #[cfg(feature = "notify")]
+    fn watch_matching<F>(
+        &self,
+        globs: globset::GlobSet,
+        callback: F,
+    ) -> notify::Result<notify::RecommendedWatcher>
+    where
+        F: FnMut(notify::Result<notify::Event>) + Send + 'static,
+    {
+        watch::watch_matching(self.as_ref(), globs, callback)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::prelude::*;
+    use super::{
+        normalize, ArtifactKind, CharPolicy, Language, NameKind, NormalizeStyle, Platform,
+        StripOptions,
+    };
+
+    use std::cmp::Ordering;
+    use std::ffi::OsStr;
+    use std::ops::Not;
+    use std::path::{Component, Path, PathBuf};
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_path_ext_bound() {
+        fn describe<P: super::PathExt>(path: P) -> (bool, Option<String>) {
+            (
+                path.has_component("src"),
+                path.strip_extensions().map(String::from),
+            )
+        }
+
+        assert_eq!(describe("src/lib.rs"), (true, Some("src/lib".to_string())));
+        assert_eq!(
+            describe(PathBuf::from("a.tar.gz")),
+            (false, Some("a".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_absolutize_from() {
+        let tests = &[
+            ("src/./lib.rs", "/work", "/work/src/lib.rs"),
+            ("../other", "/work/project", "/work/other"),
+            ("/etc/../tmp", "/work", "/tmp"),
+            ("a/../../b", "work", "b"),
+            ("", "/work/", "/work"),
+        ];
+
+        for test in tests {
+            let expected = normalize::normalize(Path::new(test.2));
+            assert_eq!(test.0.absolutize_from(test.1), expected);
+            assert_eq!(Path::new(test.0).absolutize_from(test.1), expected);
+            assert_eq!(PathBuf::from(test.0).absolutize_from(test.1), expected);
+        }
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn test_absolutize() {
+        let cwd = std::env::current_dir().unwrap();
+        assert_eq!("a/./b/..".absolutize().unwrap(), cwd.join("a"));
+    }
+
+    #[test]
+    fn test_ends_with_extensions() {
+        let archive_path = Path::new("archive.tar.gz");
+        assert!(archive_path.ends_with_extensions(".tar.gz"));
+        assert!(archive_path.ends_with_extensions("tar.gz"));
+        assert!(archive_path.ends_with_extensions(".gz"));
+        assert!(archive_path.ends_with_extensions("z"));
+        assert!(archive_path.ends_with_extensions("archive.tar.gz"));
+    }
+
+    // from a playground link I made https://play.rust-lang.org/?version=stable&mode=debug&edition=2021&gist=c3d8a15324eeb911795bf5ac40bd2429
+    #[test]
+    fn is_ends_with_still_ignoring_extensions() {
+        // This doesn't make sense to me. Hence the crate.
+        let archive_path = Path::new("archive.tar.gz");
+        assert!(archive_path.ends_with(".tar.gz").not());
+        assert!(archive_path.ends_with(OsStr::new(".tar.gz")).not());
+        assert!(archive_path.extension() == Some(OsStr::new("gz")));
+        assert!(archive_path.extension() != Some(OsStr::new("tar.gz")));
+    }
+
+    #[test]
+    fn test_strip_extensions() {
+        let tests = &[
+            (".stuff", Some("")),
+            ("something.tar.gz", Some("something")),
+            ("areally-cool.attempt.js", Some("areally-cool")),
+            ("lastdot.", Some("lastdot")),
+        ];
+
+        for test_case in tests {
+            assert_eq!(test_case.0.strip_extensions(), test_case.1);
+        }
+    }
+
+    #[test]
+    fn test_contains() {
+        let tests = &[(
+            "/opt/somewhere/someplace/somehow/",
+            vec![
+                ("opt", true),
+                ("/opt", true),
+                ("somewhere", true),
+                ("/someplace/somehow", true),
+                ("someplace/somehow/", true),
+                ("root", false),
+            ],
+        )];
+
+        for test_case in tests {
+            for test in test_case.1.iter() {
+                assert_eq!(test_case.0.contains(test.0), test.1);
+                let p = Path::new(test_case.0);
+                assert_eq!(p.contains(test.0), test.1);
+                let pb = PathBuf::from(test_case.0);
+                assert_eq!(pb.contains(test.0), test.1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_lengths() {
+        let tests = &[
+            ("", 0, 0, true),
+            (".", 1, 1, false),
+            ("/a/b", 4, 4, false),
+            ("dir/ünï", 9, 7, false),
+            ("名前", 6, 2, false),
+        ];
+
+        for test in tests {
+            let p = Path::new(test.0);
+            let pb = PathBuf::from(test.0);
+            assert_eq!(test.0.byte_len(), test.1);
+            assert_eq!(p.byte_len(), test.1);
+            assert_eq!(pb.char_len_lossy(), test.2);
+            assert_eq!(p.is_empty_path(), test.3);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_lengths_non_utf8() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let p = Path::new(OsStr::from_bytes(b"a\xff\xfeb"));
+        assert_eq!(p.byte_len(), 4);
+        assert_eq!(p.char_len_lossy(), 4);
+    }
+
+    #[test]
+    fn test_has_extensions_exact() {
+        let tests = &[
+            ("dir/archive.tar.gz", ".tar.gz", true),
+            ("dir/archive.tar.gz", "z", false),
+            ("dir.tar.gz/file", "tar.gz", false),
+            ("dir/.gz", "gz", false),
+            ("/", "gz", false),
+        ];
+
+        for test in tests {
+            assert_eq!(test.0.has_extensions_exact(test.1), test.2);
+            assert_eq!(Path::new(test.0).has_extensions_exact(test.1), test.2);
+            assert_eq!(PathBuf::from(test.0).has_extensions_exact(test.1), test.2);
+        }
+    }
+
+    #[test]
+    fn test_extension_is() {
+        let tests = &[
+            ("photo.jpg", "jpg", true, true),
+            ("photo.JPG", ".jpg", false, true),
+            ("archive.tar.gz", "gz", true, true),
+            ("archive.tar.gz", "tar.gz", false, false),
+            (".bashrc", "bashrc", false, false),
+            ("dir.d/file", "d", false, false),
+        ];
+
+        for test in tests {
+            assert_eq!(test.0.extension_is(test.1), test.2);
+            assert_eq!(Path::new(test.0).extension_is_ignore_case(test.1), test.3);
+        }
+
+        let images = ["png", ".jpg", "gif"];
+        assert!("a/b.gif".extension_is_any(images));
+        assert!(PathBuf::from("a/b.PNG").extension_is_any(images).not());
+        assert!(PathBuf::from("a/b.PNG").extension_is_any_ignore_case(images));
+        assert!("a/b.txt".extension_is_any_ignore_case(images).not());
+    }
+
+    #[test]
+    fn test_strip_extensions_opts() {
+        let last = StripOptions {
+            max_extensions: Some(1),
+            ..StripOptions::default()
+        };
+        let windows = StripOptions {
+            platform: Platform::Windows,
+            ..StripOptions::default()
+        };
+        let unix = StripOptions {
+            platform: Platform::Unix,
+            ..StripOptions::default()
+        };
+        let tests = &[
+            (
+                "dir.v2/archive.tar.gz",
+                StripOptions::default(),
+                "dir.v2/archive",
+            ),
+            ("dir.v2/archive.tar.gz", last, "dir.v2/archive.tar"),
+            (
+                "/home/me/.bashrc",
+                StripOptions::default(),
+                "/home/me/.bashrc",
+            ),
+            ("docs.d/", StripOptions::default(), "docs"),
+            ("plain", StripOptions::default(), "plain"),
+            (r"dir.v2\archive.tar.gz", windows, r"dir.v2\archive"),
+            (r"dir.v2\archive", unix, r"dir"),
+        ];
+
+        for test in tests {
+            assert_eq!(test.0.strip_extensions_opts(test.1), Some(test.2));
+            assert_eq!(
+                Path::new(test.0).strip_extensions_opts(test.1),
+                Some(test.2)
+            );
+            assert_eq!(
+                PathBuf::from(test.0).strip_extensions_opts(test.1),
+                Some(test.2)
+            );
+        }
+    }
+
+    #[test]
+    fn test_to_cache_key() {
+        let tests = &["/var/cache/Some File.txt", "src/lib.rs", ".env"];
+
+        for test in tests {
+            let key = test.to_cache_key();
+            assert_eq!(Path::new(test).to_cache_key(), key);
+            assert_eq!(PathBuf::from(test).to_cache_key(), key);
+            assert_eq!(super::from_cache_key(&key).unwrap(), Path::new(test));
+        }
+    }
+
+    #[test]
+    fn test_short_id() {
+        let id = "/tmp/build/out".short_id(12);
+        assert_eq!(id.len(), 12);
+        assert_eq!(Path::new("/tmp/build/./out/").short_id(12), id);
+        assert_eq!(PathBuf::from("/tmp/build/out").short_id(12), id);
+        assert_ne!("/tmp/build/in".short_id(12), id);
+    }
+
+    #[test]
+    fn test_escape_for_build_files() {
+        let tests = &[
+            ("obj/main.o", Some("obj/main.o"), Some("obj/main.o")),
+            (
+                "My Files/$x.o",
+                Some(r"My\ Files/$$x.o"),
+                Some("My$ Files/$$x.o"),
+            ),
+            ("bad\nname", None, None),
+        ];
+
+        for test in tests {
+            assert_eq!(test.0.escape_for_make().as_deref(), test.1);
+            assert_eq!(Path::new(test.0).escape_for_make().as_deref(), test.1);
+            assert_eq!(PathBuf::from(test.0).escape_for_make().as_deref(), test.1);
+            assert_eq!(test.0.escape_for_ninja().as_deref(), test.2);
+            assert_eq!(Path::new(test.0).escape_for_ninja().as_deref(), test.2);
+            assert_eq!(PathBuf::from(test.0).escape_for_ninja().as_deref(), test.2);
+        }
+    }
+
+    #[test]
+    fn test_convert_path() {
+        let tests = &[
+            (
+                r"C:\Users\me",
+                PathStyle::Unix,
+                Some("/cygdrive/c/Users/me"),
+            ),
+            (
+                "/cygdrive/c/Users/me",
+                PathStyle::Windows,
+                Some(r"C:\Users\me"),
+            ),
+            (
+                "/cygdrive/c/Users/me",
+                PathStyle::Mixed,
+                Some("C:/Users/me"),
+            ),
+            ("/usr/bin", PathStyle::Windows, None),
+        ];
+
+        for test in tests {
+            let expected = test.2.map(PathBuf::from);
+            assert_eq!(test.0.convert_path(test.1), expected);
+            assert_eq!(Path::new(test.0).convert_path(test.1), expected);
+            assert_eq!(PathBuf::from(test.0).convert_path(test.1), expected);
+        }
+    }
+
+    #[test]
+    fn test_fan_out_and_in() {
+        let tests = &[
+            ("cache/aabbccdd.bin", 2, Some("cache/aa/bb/aabbccdd.bin")),
+            ("/var/blobs/9f8e7d", 1, Some("/var/blobs/9f/9f8e7d")),
+            ("cache/a.bin", 1, None),
+        ];
+
+        for test in tests {
+            let expected = test.2.map(PathBuf::from);
+            assert_eq!(test.0.fan_out(test.1), expected);
+            assert_eq!(Path::new(test.0).fan_out(test.1), expected);
+            assert_eq!(PathBuf::from(test.0).fan_out(test.1), expected);
+            if let Some(sharded) = expected {
+                assert_eq!(sharded.fan_in(), Path::new(test.0));
+            }
+        }
+    }
+
+    #[test]
+    fn test_bucket_by_number() {
+        let tests = &[
+            ("export/123456.json", Some("export/000/123/000123456.json")),
+            ("/data/987654321", Some("/data/987/654/987654321")),
+            ("export/index.json", None),
+        ];
+
+        for test in tests {
+            let expected = test.1.map(PathBuf::from);
+            assert_eq!(test.0.bucket_by_number(9, 3), expected);
+            assert_eq!(Path::new(test.0).bucket_by_number(9, 3), expected);
+            assert_eq!(PathBuf::from(test.0).bucket_by_number(9, 3), expected);
+            assert_eq!(
+                expected.and_then(|p| p.bucketed_number()),
+                test.0.bucketed_number()
+            );
+        }
     }
 
-    fn ends_with_extensions<S: AsRef<str>>(&self, pattern: S) -> bool {
-        self.as_ref()
-            .to_str()
-            .map_or(false, |s| s.ends_with(pattern.as_ref()))
+    #[test]
+    fn test_inferred_kind() {
+        let tests = &[
+            ("dist/app-1.0.tar.gz", NameKind::Archive),
+            (
+                "src/lib.rs",
+                NameKind::SourceCode {
+                    language: Language::RUST,
+                },
+            ),
+            ("~$budget.xlsx", NameKind::Lock),
+            ("LICENSE", NameKind::Unknown),
+        ];
+
+        for test in tests {
+            assert_eq!(test.0.inferred_kind(), test.1);
+            assert_eq!(Path::new(test.0).inferred_kind(), test.1);
+            assert_eq!(PathBuf::from(test.0).inferred_kind(), test.1);
+        }
     }
 
-    fn strip_extensions(&self) -> Option<&str> {
-        if let Some(path) = self.as_ref().to_str() {
-            if let Some((base, ..)) = path.split_once('.') {
-                Some(base)
-            } else {
-                Some(path)
-            }
-        } else {
-            None
+    #[cfg(feature = "mime")]
+    #[test]
+    fn test_mime_type() {
+        let tests = &[
+            ("site/index.html", Some("text/html"), None),
+            (
+                "dist/app.tar.gz",
+                Some("application/gzip"),
+                Some("application/x-tar"),
+            ),
+            ("README", None, None),
+        ];
+
+        for test in tests {
+            assert_eq!(test.0.mime_type(), test.1);
+            assert_eq!(Path::new(test.0).mime_type(), test.1);
+            assert_eq!(PathBuf::from(test.0).mime_type(), test.1);
+            assert_eq!(test.0.inner_mime_type(), test.2);
         }
     }
 
-    fn strip_prefix_if_needed<'a, S: AsRef<str>>(&'a self, prefix: S) -> &'a Path {
-        if let Ok(stripped) = self.as_ref().strip_prefix(prefix.as_ref()) {
-            stripped
-        } else {
-            self.as_ref()
+    #[test]
+    fn test_source_language() {
+        let tests = &[
+            ("src/lib.rs", Some(Language::RUST)),
+            ("CMakeLists.txt", Some(Language::CMAKE)),
+            ("README.md", None),
+        ];
+
+        for test in tests {
+            assert_eq!(test.0.source_language(), test.1);
+            assert_eq!(Path::new(test.0).source_language(), test.1);
+            assert_eq!(PathBuf::from(test.0).source_language(), test.1);
         }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::PathExt;
+    #[test]
+    fn test_artifact_kind() {
+        let tests = &[
+            ("crate/target/release/app", Some(ArtifactKind::Output)),
+            (
+                "app/node_modules/left-pad/index.js",
+                Some(ArtifactKind::Dependencies),
+            ),
+            ("lib/Util.class", Some(ArtifactKind::Compiled)),
+            ("src/lib.rs", None),
+        ];
 
-    use std::ffi::OsStr;
-    use std::ops::Not;
-    use std::path::{Path, PathBuf};
+        for test in tests {
+            assert_eq!(test.0.artifact_kind(), test.1);
+            assert_eq!(Path::new(test.0).artifact_kind(), test.1);
+            assert_eq!(PathBuf::from(test.0).artifact_kind(), test.1);
+            assert_eq!(test.0.is_build_artifact(), test.1.is_some());
+        }
+    }
 
     #[test]
-    fn test_ends_with_extensions() {
-        let archive_path = Path::new("archive.tar.gz");
-        assert!(archive_path.ends_with_extensions(".tar.gz"));
-        assert!(archive_path.ends_with_extensions("tar.gz"));
-        assert!(archive_path.ends_with_extensions(".gz"));
-        assert!(archive_path.ends_with_extensions("z"));
-        assert!(archive_path.ends_with_extensions("archive.tar.gz"));
+    fn test_is_os_metadata_junk() {
+        let tests = &[
+            ("Music/.DS_Store", true),
+            ("archive/__MACOSX/._song.mp3", true),
+            ("Music/song.mp3", false),
+        ];
+
+        for test in tests {
+            assert_eq!(test.0.is_os_metadata_junk(), test.1, "{}", test.0);
+            assert_eq!(Path::new(test.0).is_os_metadata_junk(), test.1);
+            assert_eq!(PathBuf::from(test.0).is_os_metadata_junk(), test.1);
+        }
     }
 
-    // from a playground link I made https://play.rust-lang.org/?version=stable&mode=debug&edition=2021&gist=c3d8a15324eeb911795bf5ac40bd2429
     #[test]
-    fn is_ends_with_still_ignoring_extensions() {
-        // This doesn't make sense to me. Hence the crate.
-        let archive_path = Path::new("archive.tar.gz");
-        assert!(archive_path.ends_with(".tar.gz").not());
-        assert!(archive_path.ends_with(OsStr::new(".tar.gz")).not());
-        assert!(archive_path.extension() == Some(OsStr::new("gz")));
-        assert!(archive_path.extension() != Some(OsStr::new("tar.gz")));
+    fn test_is_transient_file() {
+        let tests = &[
+            ("src/.lib.rs.swp", true),
+            ("Downloads/movie.mkv.crdownload", true),
+            ("docs/~$spec.docx", true),
+            ("Cargo.lock", false),
+            ("docs/spec.docx", false),
+        ];
+
+        for test in tests {
+            assert_eq!(test.0.is_transient_file(), test.1, "{}", test.0);
+            assert_eq!(Path::new(test.0).is_transient_file(), test.1);
+            assert_eq!(PathBuf::from(test.0).is_transient_file(), test.1);
+        }
     }
 
     #[test]
-    fn test_strip_extensions() {
+    fn test_looks_sensitive() {
         let tests = &[
-            (".stuff", Some("")),
-            ("something.tar.gz", Some("something")),
-            ("areally-cool.attempt.js", Some("areally-cool")),
-            ("lastdot.", Some("lastdot")),
+            ("~/.ssh/id_rsa", true),
+            ("deploy/.env", true),
+            ("README.md", false),
         ];
 
-        for test_case in tests {
-            assert_eq!(test_case.0.strip_extensions(), test_case.1);
+        for test in tests {
+            assert_eq!(test.0.looks_sensitive(), test.1, "{}", test.0);
+            assert_eq!(Path::new(test.0).looks_sensitive(), test.1);
+            assert_eq!(PathBuf::from(test.0).looks_sensitive(), test.1);
         }
     }
 
     #[test]
-    fn test_contains() {
-        let tests = &[(
-            "/opt/somewhere/someplace/somehow/",
-            vec![
-                ("opt", true),
-                ("/opt", true),
-                ("somewhere", true),
-                ("/someplace/somehow", true),
-                ("someplace/somehow/", true),
-                ("root", false),
-            ],
-        )];
+    fn test_lock_path() {
+        let tests = &[("notes.txt", Some("notes.txt.lock")), ("/", None)];
 
-        for test_case in tests {
-            for test in test_case.1.iter() {
-                assert_eq!(test_case.0.contains(test.0), test.1);
-                let p = Path::new(test_case.0);
-                assert_eq!(p.contains(test.0), test.1);
-                let pb = PathBuf::from(test_case.0);
-                assert_eq!(pb.contains(test.0), test.1);
-            }
+        for test in tests {
+            let expected = test.1.map(PathBuf::from);
+            assert_eq!(test.0.lock_path(), expected, "{}", test.0);
+            assert_eq!(Path::new(test.0).lock_path(), expected);
+            assert_eq!(PathBuf::from(test.0).lock_path(), expected);
+        }
+    }
+
+    #[test]
+    fn test_sibling() {
+        let tests = &[
+            ("logs/app.txt", Some("logs/other.txt"), Some("logs/app.log")),
+            ("/app.tar.gz", Some("/other.txt"), Some("/app.tar.log")),
+            ("app", Some("other.txt"), Some("app.log")),
+            (".bashrc", Some("other.txt"), Some(".bashrc.log")),
+            ("/", None, None),
+            ("a/..", None, None),
+            ("", None, None),
+        ];
+
+        for test in tests {
+            let sibling = test.1.map(PathBuf::from);
+            assert_eq!(test.0.sibling("other.txt"), sibling, "{}", test.0);
+            assert_eq!(Path::new(test.0).sibling("other.txt"), sibling);
+            assert_eq!(PathBuf::from(test.0).sibling("other.txt"), sibling);
+            let with_extension = test.2.map(PathBuf::from);
+            assert_eq!(
+                test.0.sibling_with_extension("log"),
+                with_extension,
+                "{}",
+                test.0
+            );
+            assert_eq!(
+                Path::new(test.0).sibling_with_extension("log"),
+                with_extension
+            );
+            assert_eq!(
+                PathBuf::from(test.0).sibling_with_extension("log"),
+                with_extension
+            );
         }
+
+        assert_eq!(
+            "notes.md".sibling_with_extension(""),
+            Some(PathBuf::from("notes"))
+        );
     }
 
     #[test]
@@ -195,6 +1699,64 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_component_windows() {
+        let expected = vec![
+            vec![
+                Component::Normal("src".as_ref()),
+                Component::Normal("a".as_ref()),
+            ],
+            vec![
+                Component::Normal("a".as_ref()),
+                Component::Normal("b.rs".as_ref()),
+            ],
+        ];
+        assert_eq!(
+            "src/a/b.rs".component_windows(2).collect::<Vec<_>>(),
+            expected
+        );
+        let p = Path::new("src/a/b.rs");
+        assert_eq!(p.component_windows(2).collect::<Vec<_>>(), expected);
+        let pb = PathBuf::from("src/a/b.rs");
+        assert_eq!(pb.component_windows(2).collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn test_has_component_sequence() {
+        let tests = &[("src/x/tests/y.rs", true), ("tests/src/y.rs", false)];
+
+        for test in tests {
+            assert_eq!(test.0.has_component_sequence(["src", "tests"]), test.1);
+            assert_eq!(
+                Path::new(test.0).has_component_sequence(["src", "tests"]),
+                test.1
+            );
+            assert_eq!(
+                PathBuf::from(test.0).has_component_sequence(["src", "tests"]),
+                test.1
+            );
+        }
+    }
+
+    #[test]
+    fn test_starts_and_ends_with_components() {
+        let tests = &[
+            ("/usr/local/bin", true, false),
+            ("/usr/localized/bin", false, false),
+            ("usr/local", true, true),
+        ];
+
+        for test in tests {
+            let (p, pb) = (Path::new(test.0), PathBuf::from(test.0));
+            assert_eq!(test.0.starts_with_components(["usr", "local"]), test.1);
+            assert_eq!(p.starts_with_components(["usr", "local"]), test.1);
+            assert_eq!(pb.starts_with_components(["usr", "local"]), test.1);
+            assert_eq!(test.0.ends_with_components(["usr", "local"]), test.2);
+            assert_eq!(p.ends_with_components(["usr", "local"]), test.2);
+            assert_eq!(pb.ends_with_components(["usr", "local"]), test.2);
+        }
+    }
+
     #[test]
     fn test_starts_or_ends_with() {
         let tests = &[(
@@ -242,4 +1804,464 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_nth_parent() {
+        let tests = &[
+            ("/a/b/c", 0, Some("/a/b/c")),
+            ("/a/b/c", 1, Some("/a/b")),
+            ("/a/b/c", 3, Some("/")),
+            ("/a/b/c", 4, None),
+            ("a/b", 1, Some("a")),
+            ("a/b", 2, Some("")),
+            ("a/b", 3, None),
+            ("", 0, Some("")),
+            ("", 1, None),
+        ];
+
+        for test in tests {
+            let expected = test.2.map(Path::new);
+            assert_eq!(test.0.nth_parent(test.1), expected, "{:?}", test);
+            assert_eq!(Path::new(test.0).nth_parent(test.1), expected);
+            assert_eq!(PathBuf::from(test.0).nth_parent(test.1), expected);
+        }
+    }
+
+    #[test]
+    fn test_parent_or_self() {
+        let tests = &[("/a/b", "/a"), ("/a", "/"), ("/", "/"), ("a", ""), ("", "")];
+
+        for test in tests {
+            let expected = Path::new(test.1);
+            assert_eq!(test.0.parent_or_self(), expected, "{}", test.0);
+            assert_eq!(Path::new(test.0).parent_or_self(), expected);
+            assert_eq!(PathBuf::from(test.0).parent_or_self(), expected);
+        }
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn test_find_up() {
+        let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let expected = Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+        assert_eq!(src.find_up("Cargo.toml").unwrap(), Some(expected));
+        assert_eq!(src.find_up("no-such-file.pathext").unwrap(), None);
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn test_resolve_lenient() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let expected = root.canonicalize().unwrap().join("src/not/yet/here.rs");
+        assert_eq!(
+            root.join("src/../src/not/yet/here.rs")
+                .resolve_lenient()
+                .unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_with_root() {
+        let tests = &[
+            ("/mnt/a/x", "/mnt/a", "/mnt/b", "/mnt/b/x"),
+            ("/mnt/a", "/mnt/a", "/mnt/b", "/mnt/b"),
+            ("/data/x", "/", "/mnt/b", "/mnt/b/data/x"),
+            ("/data/x", "/", "", "data/x"),
+            ("relative/x", "", "/srv", "/srv/relative/x"),
+            // whole components only, and a path elsewhere is kept
+            ("/mnt/ab/x", "/mnt/a", "/mnt/b", "/mnt/ab/x"),
+            ("/other/x", "/mnt/a", "/mnt/b", "/other/x"),
+        ];
+
+        for test in tests {
+            let expected = Path::new(test.3);
+            assert_eq!(test.0.with_root(test.1, test.2), expected, "{}", test.0);
+            assert_eq!(Path::new(test.0).with_root(test.1, test.2), expected);
+            assert_eq!(PathBuf::from(test.0).with_root(test.1, test.2), expected);
+        }
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_with_root_windows() {
+        let tests = &[
+            (r"C:\data\x", r"C:\", r"D:\", r"D:\data\x"),
+            (r"C:\data\x", "C:", "D:", r"D:\data\x"),
+            (r"C:data\x", "C:", "D:", r"D:data\x"),
+            (r"\\server\share\x", r"\\server\share", r"E:\", r"E:\x"),
+            (
+                r"\\?\C:\data",
+                r"\\?\C:\",
+                r"\\nas\backup\",
+                r"\\nas\backup\data",
+            ),
+        ];
+
+        for test in tests {
+            assert_eq!(
+                Path::new(test.0).with_root(test.1, test.2),
+                Path::new(test.3),
+                "{}",
+                test.0
+            );
+        }
+    }
+
+    #[test]
+    fn test_normalize() {
+        let tests = &[
+            ("/opt/./foo/../bar", "/opt/bar", false),
+            ("/opt/bar", "/opt/bar", true),
+            ("a//b/", "a/b", false),
+            ("../a", "../a", true),
+            ("a/..", ".", false),
+            ("", "", true),
+        ];
+
+        for test in tests {
+            let expected = Path::new(test.1);
+            assert_eq!(test.0.normalize(), expected);
+            assert_eq!(Path::new(test.0).normalize(), expected);
+            assert_eq!(PathBuf::from(test.0).normalize(), expected);
+            assert_eq!(test.0.is_normalized(), test.2, "{}", test.0);
+            assert_eq!(Path::new(test.0).is_normalized(), test.2);
+            assert_eq!(PathBuf::from(test.0).is_normalized(), test.2);
+        }
+
+        // matching the path as written and what it names
+        assert!("/opt/./foo/../bar".has_component("foo"));
+        assert!(!"/opt/./foo/../bar".normalize().has_component("foo"));
+        assert!("/opt/./foo/../bar".normalize().contains("/opt/bar"));
+    }
+
+    #[test]
+    fn test_normalize_uri_style() {
+        let tests = &[
+            ("../../etc/passwd", "etc/passwd"),
+            ("static/../../index.html", "index.html"),
+            ("/static/./css/../app.js", "/static/app.js"),
+        ];
+
+        for test in tests {
+            let expected = Path::new(test.1);
+            assert_eq!(test.0.normalize_uri_style(), expected);
+            assert_eq!(Path::new(test.0).normalize_uri_style(), expected);
+            assert_eq!(PathBuf::from(test.0).normalize_uri_style(), expected);
+        }
+        assert_eq!(
+            "../a".normalize_with_style(NormalizeStyle::Filesystem),
+            Path::new("../a")
+        );
+    }
+
+    #[test]
+    fn test_write_into() {
+        let mut buf = PathBuf::new();
+        let tests = &[
+            ("/opt/./foo/../bar", "/opt/bar", "/opt/./foo/../bar"),
+            ("src/../lib.rs", "lib.rs", "out/../lib.rs"),
+            ("../a", "../a", "../a"),
+        ];
+
+        for test in tests {
+            let expected = Path::new(test.1);
+            test.0.normalize_into(&mut buf);
+            assert_eq!(buf, expected);
+            Path::new(test.0).normalize_into(&mut buf);
+            assert_eq!(buf, expected);
+            PathBuf::from(test.0).normalize_into(&mut buf);
+            assert_eq!(buf, expected);
+
+            let rebased = Path::new(test.2);
+            test.0.rebase_into("src", "out", &mut buf);
+            assert_eq!(buf, rebased);
+            Path::new(test.0).rebase_into("src", "out", &mut buf);
+            assert_eq!(buf, rebased);
+            PathBuf::from(test.0).rebase_into("src", "out", &mut buf);
+            assert_eq!(buf, rebased);
+        }
+
+        "../a/./b".normalize_with_style_into(NormalizeStyle::Uri, &mut buf);
+        assert_eq!(buf, Path::new("a/b"));
+    }
+
+    #[test]
+    fn test_is_posix_portable() {
+        let tests = &[
+            ("/usr/lib/libc.so.6", true),
+            ("build/out-1.tar", true),
+            ("Program Files", false),
+            ("-n", false),
+            ("fifteen_chars_x", false),
+        ];
+
+        for test in tests {
+            assert_eq!(test.0.is_posix_portable(), test.1, "{}", test.0);
+            assert_eq!(Path::new(test.0).is_posix_portable(), test.1);
+            assert_eq!(PathBuf::from(test.0).is_posix_portable(), test.1);
+        }
+        assert!("fifteen_chars_x"
+            .posix_portability_violations(LengthLimits::UNIX)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_object_key() {
+        let tests = &[
+            (
+                "/srv/backup/photos/beach.jpg",
+                Some("srv/backup/photos/beach.jpg"),
+                true,
+                true,
+            ),
+            (
+                "reports/Q1 [draft].pdf",
+                Some("reports/Q1 [draft].pdf"),
+                false,
+                false,
+            ),
+            ("../outside", None, false, false),
+        ];
+
+        for test in tests {
+            let expected = test.1.map(String::from);
+            assert_eq!(test.0.to_object_key(), expected);
+            assert_eq!(Path::new(test.0).to_object_key(), expected);
+            assert_eq!(PathBuf::from(test.0).to_object_key(), expected);
+            assert_eq!(test.0.is_object_key_safe(ObjectStore::S3), test.2);
+            assert_eq!(test.0.is_object_key_safe(ObjectStore::Gcs), test.3);
+            assert_eq!(
+                test.0.is_object_key_safe(ObjectStore::AzureBlob),
+                test.1.is_some()
+            );
+        }
+    }
+
+    #[test]
+    fn test_fat() {
+        let tests = &[
+            (
+                "/DCIM/IMG_0001.JPG",
+                &[][..],
+                Some("/DCIM/IMG_0001.JPG"),
+                true,
+            ),
+            (
+                "music/Track 01.flac",
+                &[][..],
+                Some("music/TRACK0~1.FLA"),
+                true,
+            ),
+            ("notes.txt", &["NOTES.TXT"][..], Some("NOTES~1.TXT"), true),
+            ("a|b.txt", &[][..], Some("A_B~1.TXT"), false),
+            ("/", &[][..], None, true),
+        ];
+
+        for test in tests {
+            let expected = test.2.map(PathBuf::from);
+            assert_eq!(test.0.to_fat_8_3(test.1), expected);
+            assert_eq!(Path::new(test.0).to_fat_8_3(test.1), expected);
+            assert_eq!(PathBuf::from(test.0).to_fat_8_3(test.1), expected);
+            assert_eq!(test.0.is_fat_safe(), test.3);
+        }
+    }
+
+    #[test]
+    fn test_iso9660_and_joliet() {
+        let tests = &[
+            ("/EFI/boot/bootx64.efi", "/EFI/BOOT/BOOTX64.EFI", true),
+            ("Disc Two/Track 01.flac", "DISC_TWO/TRACK_01.FLA", true),
+            ("a:b", "A_B", false),
+        ];
+
+        for test in tests {
+            let expected = Path::new(test.1);
+            assert_eq!(test.0.to_iso9660_level1(), expected);
+            assert_eq!(Path::new(test.0).to_iso9660_level1(), expected);
+            assert_eq!(PathBuf::from(test.0).to_iso9660_level1(), expected);
+            assert_eq!(test.0.is_joliet_safe(), test.2);
+        }
+    }
+
+    #[test]
+    fn test_redact() {
+        let options = RedactOptions::default().user("alice");
+        let tests = &[
+            ("/home/alice/notes.txt", "/home/<user>/notes.txt"),
+            ("/var/spool/alice", "/var/spool/<user>"),
+        ];
+
+        for test in tests {
+            assert_eq!(test.0.redact(&options), Path::new(test.1));
+            assert_eq!(Path::new(test.0).redact(&options), Path::new(test.1));
+            assert_eq!(PathBuf::from(test.0).redact(&options), Path::new(test.1));
+        }
+    }
+
+    #[test]
+    fn test_sanitize() {
+        let tests = &[
+            ("/data/what?/CON.txt", "/data/what_/CON_.txt"),
+            ("../a:b/c ", "../a_b/c"),
+        ];
+
+        for test in tests {
+            let expected = Path::new(test.1);
+            assert_eq!(test.0.sanitize(), expected);
+            assert_eq!(Path::new(test.0).sanitize(), expected);
+            assert_eq!(PathBuf::from(test.0).sanitize(), expected);
+        }
+        assert_eq!(
+            "/srv/My Post!.md".sanitize_with(&CharPolicy::url_safe().map(' ', "-")),
+            Path::new("/srv/My-Post_.md")
+        );
+    }
+
+    #[test]
+    fn test_truncate_file_name() {
+        let name = format!("/out/{}.tar.gz", "x".repeat(300));
+        for max in [255, 16] {
+            let truncated = name.truncate_file_name(max).unwrap();
+            let file_name = truncated.file_name().unwrap().to_str().unwrap();
+            assert_eq!(file_name.len(), max);
+            assert!(file_name.ends_with(".tar.gz"));
+            assert_eq!(truncated.parent(), Some(Path::new("/out")));
+
+            let hashed = Path::new(&name).truncate_file_name_hashed(max).unwrap();
+            assert_eq!(hashed.file_name().unwrap().len(), max);
+            assert_ne!(hashed, truncated);
+        }
+    }
+
+    #[cfg(feature = "slug")]
+    #[test]
+    fn test_stem_case_conversions() {
+        let tests = &[
+            (
+                "assets/Hero Image (Final).v2.png",
+                "assets/hero-image-final.v2.png",
+                "assets/hero_image_final.v2.png",
+                "assets/hero-image-final.v2.png",
+            ),
+            (
+                "src/MyComponent.test.tsx",
+                "src/mycomponent.test.tsx",
+                "src/my_component.test.tsx",
+                "src/my-component.test.tsx",
+            ),
+        ];
+
+        for test in tests {
+            for p in [Path::new(test.0), &PathBuf::from(test.0)] {
+                assert_eq!(p.slugify_stem(), Path::new(test.1));
+                assert_eq!(p.stem_to_snake_case(), Path::new(test.2));
+                assert_eq!(p.stem_to_kebab_case(), Path::new(test.3));
+            }
+        }
+    }
+
+    #[cfg(feature = "icu")]
+    #[test]
+    fn test_collate_cmp() {
+        use icu_locale_core::locale;
+
+        let de = locale!("de");
+        assert_eq!("docs/äpfel".collate_cmp("docs/birne", &de), Ordering::Less);
+        assert_eq!(
+            Path::new("docs/äpfel").collate_cmp("docs/birne", &locale!("sv")),
+            Ordering::Greater
+        );
+        // plain byte order puts every non-ASCII letter after z
+        assert_eq!(
+            Path::new("docs/äpfel").cmp(Path::new("docs/birne")),
+            Ordering::Greater
+        );
+    }
+
+    #[cfg(feature = "unicode")]
+    #[test]
+    fn test_portable_sort_key() {
+        let mut manifest = vec![
+            PathBuf::from("src\\lib.rs"),
+            PathBuf::from("README.md"),
+            PathBuf::from("src/bin/main.rs"),
+            PathBuf::from("src-extra/x.rs"),
+        ];
+        manifest.sort_by_key(|p| p.portable_sort_key());
+        assert_eq!(
+            manifest,
+            [
+                PathBuf::from("README.md"),
+                PathBuf::from("src/bin/main.rs"),
+                PathBuf::from("src\\lib.rs"),
+                PathBuf::from("src-extra/x.rs"),
+            ]
+        );
+
+        let mut names = vec!["b", "A", "a", "B"];
+        names.sort_by_key(|p| p.portable_sort_key());
+        assert_eq!(names, ["A", "B", "a", "b"]);
+        names.sort_by_key(|p| p.portable_sort_key_case_folded());
+        assert_eq!(names, ["A", "a", "B", "b"]);
+        assert_eq!(
+            "Src".portable_sort_key_case_folded(),
+            "src".portable_sort_key()
+        );
+    }
+
+    #[test]
+    fn test_closest_match() {
+        let files = ["Cargo.toml", "src/lib.rs", "src/main.rs"];
+        assert_eq!("src/mian.rs".closest_match(files, 2), Some("src/main.rs"));
+        assert_eq!(
+            Path::new("cargo.toml").closest_match(files, 1),
+            Some("Cargo.toml")
+        );
+        assert_eq!(PathBuf::from("build.rs").closest_match(files, 2), None);
+    }
+
+    #[test]
+    fn test_natural_cmp() {
+        let mut chapters = vec!["ch10.md", "ch2.md", "ch1.md"];
+        chapters.sort_by(|a, b| a.natural_cmp(b));
+        assert_eq!(chapters, ["ch1.md", "ch2.md", "ch10.md"]);
+        assert_eq!(Path::new("v1.9").natural_cmp("v1.10"), Ordering::Less);
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn test_cmp_dirs_first_fs() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let mut listing = [
+            root.join("README.md"),
+            root.join("src"),
+            root.join("Cargo.toml"),
+        ];
+        listing.sort_by(|a, b| a.cmp_dirs_first_fs(b));
+        assert_eq!(listing[0], root.join("src"));
+        assert_eq!(listing[1], root.join("Cargo.toml"));
+    }
+
+    #[test]
+    fn test_cmp_dirs_first() {
+        let mut listing = vec!["z", "dir2/", "a", "dir10/"];
+        listing.sort_by(|a, b| {
+            a.cmp_dirs_first(b, |p| p.has_component("dir2") || p.has_component("dir10"))
+        });
+        assert_eq!(listing, ["dir2/", "dir10/", "a", "z"]);
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn test_canonicalize_simplified() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let canonical = root
+            .join("src/../Cargo.toml")
+            .canonicalize_simplified()
+            .unwrap();
+        assert!(canonical.is_absolute());
+        assert!(canonical.to_string_lossy().starts_with(r"\\?\").not());
+        assert!(canonical.ends_with("Cargo.toml"));
+    }
 }
@@ -0,0 +1,412 @@
+//! Fitting names and paths within filesystem length limits.
+
+use std::ffi::OsStr;
+use std::path::{Component, Path, PathBuf};
+
+use crate::hash::fnv1a;
+use crate::Platform;
+
+/// What a length limit counts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LengthUnit {
+    /// Bytes of the encoded path, as Unix `PATH_MAX` and `NAME_MAX` count.
+    Bytes,
+    /// UTF-16 code units, as Windows counts.
+    Utf16,
+}
+
+impl LengthUnit {
+    fn of_char(self, c: char) -> usize {
+        match self {
+            LengthUnit::Bytes => c.len_utf8(),
+            LengthUnit::Utf16 => c.len_utf16(),
+        }
+    }
+
+    pub(crate) fn of_str(self, s: &str) -> usize {
+        match self {
+            LengthUnit::Bytes => s.len(),
+            LengthUnit::Utf16 => s.encode_utf16().count(),
+        }
+    }
+
+    pub(crate) fn of_os_str(self, s: &OsStr) -> usize {
+        match self {
+            LengthUnit::Bytes => s.len(),
+            LengthUnit::Utf16 => self.of_str(&s.to_string_lossy()),
+        }
+    }
+}
+
+/// Limits on the length of a whole path and of each of its components.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct LengthLimits {
+    /// The longest a whole path may be, not counting a terminating NUL.
+    pub max_path: usize,
+    /// The longest a single component may be.
+    pub max_component: usize,
+    pub unit: LengthUnit,
+}
+
+impl LengthLimits {
+    /// Linux `PATH_MAX` (4096 including the NUL) and `NAME_MAX`.
+    pub const UNIX: LengthLimits = LengthLimits {
+        max_path: 4095,
+        max_component: 255,
+        unit: LengthUnit::Bytes,
+    };
+    /// macOS `PATH_MAX` (1024 including the NUL) and `NAME_MAX`.
+    pub const MACOS: LengthLimits = LengthLimits {
+        max_path: 1023,
+        max_component: 255,
+        unit: LengthUnit::Bytes,
+    };
+    /// The POSIX minimums every conforming system supports, `_POSIX_PATH_MAX` (256 including the NUL)
+    /// and `_POSIX_NAME_MAX`.
+    pub const POSIX: LengthLimits = LengthLimits {
+        max_path: 255,
+        max_component: 14,
+        unit: LengthUnit::Bytes,
+    };
+    /// Windows `MAX_PATH` (260 including the NUL), which applies unless long paths are enabled.
+    pub const WINDOWS: LengthLimits = LengthLimits {
+        max_path: 259,
+        max_component: 255,
+        unit: LengthUnit::Utf16,
+    };
+    /// Windows with long path support enabled, or any `\\?\` path.
+    pub const WINDOWS_LONG_PATHS: LengthLimits = LengthLimits {
+        max_path: 32_767,
+        max_component: 255,
+        unit: LengthUnit::Utf16,
+    };
+
+    /// The limits of the platform this was compiled for, assuming Windows long paths are not enabled.
+    pub const fn native() -> LengthLimits {
+        LengthLimits::for_platform(Platform::Native)
+    }
+
+    /// The limits of `platform`, assuming Windows long paths are not enabled. [`Platform::Unix`]
+    /// is Linux's, use [`LengthLimits::MACOS`] for macOS.
+    pub const fn for_platform(platform: Platform) -> LengthLimits {
+        match platform {
+            Platform::Native if cfg!(target_vendor = "apple") => LengthLimits::MACOS,
+            _ if platform.is_windows() => LengthLimits::WINDOWS,
+            _ => LengthLimits::UNIX,
+        }
+    }
+
+    /// `\\?\` paths aren't subject to `MAX_PATH` even without long path support.
+    fn for_path(self, path: &Path) -> LengthLimits {
+        let verbatim = matches!(
+            path.components().next(),
+            Some(Component::Prefix(p)) if p.kind().is_verbatim()
+        ) || path.as_os_str().to_string_lossy().starts_with(r"\\?\");
+        if self == LengthLimits::WINDOWS && verbatim {
+            LengthLimits::WINDOWS_LONG_PATHS
+        } else {
+            self
+        }
+    }
+}
+
+/// The longest prefix of `s` that is at most `max` units and ends on a char boundary.
+fn floor_units(s: &str, max: usize, unit: LengthUnit) -> &str {
+    let mut used = 0;
+    for (i, c) in s.char_indices() {
+        used += unit.of_char(c);
+        if used > max {
+            return &s[..i];
+        }
+    }
+    s
+}
+
+/// Byte offsets of the first and last extension dots, ignoring a leading dot.
+fn extension_dots(name: &str) -> Option<(usize, usize)> {
+    let offset = usize::from(name.starts_with('.'));
+    let rest = &name[offset..];
+    let first = rest.find('.')? + offset;
+    let last = rest.rfind('.')? + offset;
+    Some((first, last))
+}
+
+fn short_hash(name: &str) -> String {
+    format!("-{:08x}", fnv1a(name.as_bytes()) as u32)
+}
+
+/// Shortens a file name to `max` units, keeping as much of the extension as fits.
+pub(crate) fn truncate_name(
+    name: &str,
+    max: usize,
+    hashed: bool,
+    unit: LengthUnit,
+) -> Option<String> {
+    if unit.of_str(name) <= max {
+        return Some(name.to_string());
+    }
+
+    let hash = if hashed {
+        short_hash(name)
+    } else {
+        String::new()
+    };
+
+    // prefer keeping the whole compound extension, settling for the last one if that can't fit
+    let splits = match extension_dots(name) {
+        Some((first, last)) if first != last => vec![first, last],
+        Some((first, _)) => vec![first],
+        None => vec![name.len()],
+    };
+    for split in splits {
+        let (stem, extension) = name.split_at(split);
+        let Some(budget) = max.checked_sub(unit.of_str(extension) + hash.len()) else {
+            continue;
+        };
+        let stem = floor_units(stem, budget, unit);
+        if !stem.is_empty() {
+            return Some(format!("{stem}{hash}{extension}"));
+        }
+    }
+    None
+}
+
+pub(crate) fn truncate_file_name(path: &Path, max_bytes: usize, hashed: bool) -> Option<PathBuf> {
+    let name = path.file_name()?.to_str()?;
+    let truncated = truncate_name(name, max_bytes, hashed, LengthUnit::Bytes)?;
+    Some(path.with_file_name(truncated))
+}
+
+pub(crate) fn fits_length_limits(path: &Path, limits: LengthLimits) -> bool {
+    let limits = limits.for_path(path);
+    limits.unit.of_os_str(path.as_os_str()) <= limits.max_path
+        && path.components().all(|c| match c {
+            Component::Normal(name) => limits.unit.of_os_str(name) <= limits.max_component,
+            _ => true,
+        })
+}
+
+/// Shortest form an intermediate directory is cut down to: a few characters and a hash.
+const MIN_DIR_KEEP: usize = 4;
+
+pub(crate) fn shorten_to_limit(path: &Path, limits: LengthLimits) -> Option<PathBuf> {
+    if fits_length_limits(path, limits) {
+        return Some(path.to_path_buf());
+    }
+    let limits = limits.for_path(path);
+    let unit = limits.unit;
+
+    let components: Vec<Component> = path.components().collect();
+    let last_normal = components
+        .iter()
+        .rposition(|c| matches!(c, Component::Normal(_)));
+    let mut parts: Vec<(String, bool)> = Vec::with_capacity(components.len());
+    for (i, component) in components.iter().enumerate() {
+        match component {
+            Component::Normal(name) => {
+                let name = name.to_string_lossy();
+                let is_file_name = Some(i) == last_normal;
+                let name = if is_file_name {
+                    truncate_name(&name, limits.max_component, true, unit)?
+                } else {
+                    shorten_dir(&name, limits.max_component, unit)
+                };
+                parts.push((name, !is_file_name));
+            }
+            other => parts.push((other.as_os_str().to_string_lossy().into_owned(), false)),
+        }
+    }
+
+    let assemble =
+        |parts: &[(String, bool)]| parts.iter().map(|(p, _)| p.as_str()).collect::<PathBuf>();
+    loop {
+        let assembled = assemble(&parts);
+        let excess = match unit
+            .of_os_str(assembled.as_os_str())
+            .checked_sub(limits.max_path)
+        {
+            None | Some(0) => return Some(assembled),
+            Some(excess) => excess,
+        };
+
+        // shorten the longest directory that can still give something up, the leftmost on ties
+        let min_len = MIN_DIR_KEEP + short_hash("").len();
+        let (index, len) = parts
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, is_dir))| *is_dir)
+            .map(|(i, (name, _))| (i, unit.of_str(name)))
+            .filter(|(_, len)| *len > min_len)
+            .fold(None, |best: Option<(usize, usize)>, (i, len)| match best {
+                Some((_, best_len)) if best_len >= len => best,
+                _ => Some((i, len)),
+            })?;
+        let target = len.saturating_sub(excess).max(min_len);
+        parts[index].0 = shorten_dir(&parts[index].0, target, unit);
+    }
+}
+
+/// Cuts a directory name down to `max` units, marking it with a hash of the full name.
+fn shorten_dir(name: &str, max: usize, unit: LengthUnit) -> String {
+    if unit.of_str(name) <= max {
+        return name.to_string();
+    }
+    let hash = short_hash(name);
+    let keep = floor_units(name, max.saturating_sub(hash.len()), unit);
+    format!("{keep}{hash}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        fits_length_limits, floor_units, shorten_to_limit, truncate_file_name, LengthLimits,
+        LengthUnit,
+    };
+
+    use crate::Platform;
+    use std::path::Path;
+
+    #[test]
+    fn test_floor_units() {
+        let tests = &[
+            ("héllo", 2, "h"),
+            ("héllo", 3, "hé"),
+            ("abc", 10, "abc"),
+            ("日本", 4, "日"),
+        ];
+
+        for test in tests {
+            assert_eq!(floor_units(test.0, test.1, LengthUnit::Bytes), test.2);
+        }
+    }
+
+    #[test]
+    fn test_truncate_file_name() {
+        let tests = &[
+            ("dir/short.txt", 20, Some("dir/short.txt")),
+            ("dir/averylongname.tar.gz", 12, Some("dir/avery.tar.gz")),
+            ("averylongname.with.many.parts.gz", 10, Some("averylo.gz")),
+            ("日本語の名前.txt", 11, Some("日本.txt")),
+            ("noextension-at-all", 6, Some("noexte")),
+            (".hidden-config-file", 7, Some(".hidden")),
+            ("a.toolongextension", 5, None),
+        ];
+
+        for test in tests {
+            assert_eq!(
+                truncate_file_name(Path::new(test.0), test.1, false),
+                test.2.map(|p| Path::new(p).to_path_buf()),
+                "{}",
+                test.0
+            );
+        }
+    }
+
+    #[test]
+    fn test_truncate_file_name_hashed() {
+        let a = truncate_file_name(Path::new("report-for-january.tar.gz"), 24, true).unwrap();
+        let b = truncate_file_name(Path::new("report-for-june-2024.tar.gz"), 24, true).unwrap();
+        assert_ne!(a, b);
+        for p in [&a, &b] {
+            let name = p.to_str().unwrap();
+            assert!(name.len() <= 24);
+            assert!(name.ends_with(".tar.gz"));
+            assert!(name.starts_with("report-f"));
+        }
+        assert_eq!(
+            truncate_file_name(Path::new("short.txt"), 24, true).unwrap(),
+            Path::new("short.txt")
+        );
+    }
+
+    #[test]
+    fn test_fits_length_limits() {
+        let long_name = "n".repeat(256);
+        let deep = "/abcdefghi".repeat(30);
+        let tests = &[
+            ("/usr/local/bin", LengthLimits::UNIX, true),
+            (long_name.as_str(), LengthLimits::UNIX, false),
+            (deep.as_str(), LengthLimits::UNIX, true),
+            (deep.as_str(), LengthLimits::WINDOWS, false),
+            (deep.as_str(), LengthLimits::WINDOWS_LONG_PATHS, true),
+            (
+                deep.as_str(),
+                LengthLimits::for_platform(Platform::Windows),
+                false,
+            ),
+            (
+                deep.as_str(),
+                LengthLimits::for_platform(Platform::Unix),
+                true,
+            ),
+        ];
+
+        for test in tests {
+            assert_eq!(fits_length_limits(Path::new(test.0), test.1), test.2);
+        }
+
+        // on Windows limits each of these is one unit per char, on Unix three or four bytes
+        let wide = "日".repeat(100);
+        let utf16 = LengthLimits {
+            max_path: 100,
+            max_component: 100,
+            unit: LengthUnit::Utf16,
+        };
+        let bytes = LengthLimits {
+            unit: LengthUnit::Bytes,
+            ..utf16
+        };
+        assert!(fits_length_limits(Path::new(&wide), utf16));
+        assert!(!fits_length_limits(Path::new(&wide), bytes));
+    }
+
+    #[test]
+    fn test_shorten_to_limit() {
+        let limits = LengthLimits {
+            max_path: 70,
+            max_component: 24,
+            unit: LengthUnit::Bytes,
+        };
+        let tests = &[
+            "/short/path.txt",
+            "/node_modules/some-long-package-name/node_modules/another-long-package/index.js",
+            "/a/b/an-extremely-long-file-name.tar.gz",
+            "relative/directory-names-that-are-long/and-even-longer-directory-names/file.rs",
+        ];
+
+        for test in tests {
+            let shortened = shorten_to_limit(Path::new(test), limits).unwrap();
+            assert!(
+                fits_length_limits(&shortened, limits),
+                "{}",
+                shortened.display()
+            );
+            assert_eq!(
+                shortened.components().count(),
+                Path::new(test).components().count()
+            );
+            // deterministic
+            assert_eq!(
+                shorten_to_limit(Path::new(test), limits).unwrap(),
+                shortened
+            );
+        }
+
+        let untouched = Path::new("/short/path.txt");
+        assert_eq!(shorten_to_limit(untouched, limits).unwrap(), untouched);
+        let shortened =
+            shorten_to_limit(Path::new("/a/b/an-extremely-long-file-name.tar.gz"), limits).unwrap();
+        assert!(shortened.to_str().unwrap().ends_with(".tar.gz"));
+        assert!(shortened.starts_with("/a/b"));
+
+        // nothing left to shorten
+        let tight = LengthLimits {
+            max_path: 10,
+            ..limits
+        };
+        assert_eq!(
+            shorten_to_limit(Path::new("/a/b/c/d/e/f/g.txt"), tight),
+            None
+        );
+    }
+}
@@ -0,0 +1,240 @@
+//! Writing long lists of paths compactly, with shell-style braces.
+
+use std::path::{Path, PathBuf};
+
+/// Condenses `paths` for display by writing the files of each directory as one brace group, as
+/// `src/{lib,main}.rs` when they share an extension and `src/{lib.rs,README.md}` otherwise.
+///
+/// Directories come in the order their first file does, names in input order without duplicates,
+/// and a directory is joined to its names with `/`. `{`, `}`, `,` and `\` in paths are escaped
+/// with `\`, so [`expand_listing`] gives the paths back.
+///
+/// ```rust
+/// use pathext::compress_listing;
+///
+/// let listing = compress_listing(["src/lib.rs", "src/main.rs", "README.md", "src/fs/mod.rs", "src/build.sh"]);
+/// assert_eq!(listing, ["src/{lib.rs,main.rs,build.sh}", "README.md", "src/fs/mod.rs"]);
+/// let listing = compress_listing(["tests/a.rs", "tests/b.rs", "tests/c.rs"]);
+/// assert_eq!(listing, ["tests/{a,b,c}.rs"]);
+/// ```
+pub fn compress_listing<I, P>(paths: I) -> Vec<String>
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    let mut groups: Vec<(Option<PathBuf>, Vec<String>)> = Vec::new();
+    for path in paths {
+        let path = path.as_ref();
+        let (dir, name) = match (path.parent(), path.file_name()) {
+            (Some(dir), Some(name)) => (Some(dir.to_path_buf()), name.to_string_lossy()),
+            _ => (None, path.to_string_lossy()),
+        };
+        let name = escape(&name);
+        let group = match groups
+            .iter_mut()
+            .find(|group| dir.is_some() && group.0 == dir)
+        {
+            Some(group) => &mut group.1,
+            None => {
+                groups.push((dir, Vec::new()));
+                &mut groups.last_mut().expect("just pushed").1
+            }
+        };
+        if !group.contains(&name) {
+            group.push(name);
+        }
+    }
+    groups
+        .into_iter()
+        .map(|(dir, names)| {
+            let prefix = match dir.as_deref().map(Path::to_string_lossy) {
+                Some(dir) if dir.is_empty() => String::new(),
+                Some(dir) if dir.ends_with(std::path::is_separator) => escape(&dir),
+                Some(dir) => format!("{}/", escape(&dir)),
+                None => String::new(),
+            };
+            match names.as_slice() {
+                [name] => prefix + name,
+                _ => match shared_extension(&names) {
+                    Some(extension) => {
+                        let stems: Vec<&str> = names
+                            .iter()
+                            .map(|name| &name[..name.len() - extension.len()])
+                            .collect();
+                        format!("{prefix}{{{}}}{extension}", stems.join(","))
+                    }
+                    None => format!("{prefix}{{{}}}", names.join(",")),
+                },
+            }
+        })
+        .collect()
+}
+
+/// The `.ext` every name has, dotfiles like `.rs` having none.
+fn shared_extension(names: &[String]) -> Option<&str> {
+    let dot = |name: &str| name.rfind('.').filter(|&dot| dot > 0);
+    let first = &names[0];
+    let extension = &first[dot(first)?..];
+    let shared = names
+        .iter()
+        .all(|name| dot(name).is_some_and(|dot| &name[dot..] == extension));
+    shared.then_some(extension)
+}
+
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(c, '{' | '}' | ',' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Expands the brace groups of `entries` as a shell does, `src/{lib,main}.rs` giving `src/lib.rs`
+/// and `src/main.rs`, the inverse of [`compress_listing`]. Groups may nest, `\` escapes the next
+/// character, and braces without a `,` inside are literal.
+///
+/// ```rust
+/// use pathext::expand_listing;
+/// use std::path::PathBuf;
+///
+/// let paths = expand_listing(["src/{lib,bin/{a,b}}.rs", r"odd\,name"]);
+/// assert_eq!(paths, ["src/lib.rs", "src/bin/a.rs", "src/bin/b.rs", "odd,name"].map(PathBuf::from));
+/// ```
+pub fn expand_listing<I, S>(entries: I) -> Vec<PathBuf>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut paths = Vec::new();
+    for entry in entries {
+        expand(entry.as_ref(), &mut paths);
+    }
+    paths
+}
+
+fn expand(entry: &str, paths: &mut Vec<PathBuf>) {
+    let Some((open, commas, close)) = first_group(entry) else {
+        paths.push(PathBuf::from(unescape(entry)));
+        return;
+    };
+    let (prefix, suffix) = (&entry[..open], &entry[close + 1..]);
+    let mut start = open + 1;
+    for end in commas.into_iter().chain([close]) {
+        expand(&format!("{prefix}{}{suffix}", &entry[start..end]), paths);
+        start = end + 1;
+    }
+}
+
+/// The byte offsets of the first `{` with a matching `}` and a `,` at its own level, of those
+/// commas, and of the `}`.
+fn first_group(entry: &str) -> Option<(usize, Vec<usize>, usize)> {
+    let bytes = entry.as_bytes();
+    let mut open = 0;
+    while open < bytes.len() {
+        match bytes[open] {
+            b'\\' => open += 1,
+            b'{' => {
+                let mut depth = 0;
+                let mut commas = Vec::new();
+                let mut i = open + 1;
+                while i < bytes.len() {
+                    match bytes[i] {
+                        b'\\' => i += 1,
+                        b'{' => depth += 1,
+                        b'}' if depth == 0 => break,
+                        b'}' => depth -= 1,
+                        b',' if depth == 0 => commas.push(i),
+                        _ => {}
+                    }
+                    i += 1;
+                }
+                if i >= bytes.len() {
+                    return None;
+                }
+                if !commas.is_empty() {
+                    return Some((open, commas, i));
+                }
+            }
+            _ => {}
+        }
+        open += 1;
+    }
+    None
+}
+
+fn unescape(s: &str) -> String {
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.extend(chars.next()),
+            c => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compress_listing, expand_listing};
+
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_compress_listing() {
+        let tests: &[(&[&str], &[&str])] = &[
+            (&[], &[]),
+            (&["a.rs"], &["a.rs"]),
+            (&["a.rs", "b.rs"], &["{a,b}.rs"]),
+            (&["a.rs", "a.rs", "b.md"], &["{a.rs,b.md}"]),
+            (&["/etc/hosts", "/etc/passwd"], &["/etc/{hosts,passwd}"]),
+            (&["x/.a.rs", "x/.b.rs"], &["x/{.a,.b}.rs"]),
+            (&["x/.rs", "x/a.rs"], &["x/{.rs,a.rs}"]),
+            (&["x/a.tar.gz", "x/b.gz"], &["x/{a.tar,b}.gz"]),
+            (&["x/Makefile", "x/README"], &["x/{Makefile,README}"]),
+            (&["x/a,b.rs", "x/{c}.rs"], &[r"x/{a\,b,\{c\}}.rs"]),
+            (&["/", "/"], &["/", "/"]),
+            (
+                &["a/1", "b/1", "a/2", "a/b/1"],
+                &["a/{1,2}", "b/1", "a/b/1"],
+            ),
+        ];
+
+        for test in tests {
+            let listing = compress_listing(test.0);
+            assert_eq!(listing, test.1, "{:?}", test.0);
+            let mut expected: Vec<PathBuf> = test.0.iter().map(PathBuf::from).collect();
+            let mut expanded = expand_listing(&listing);
+            expected.sort();
+            expected.dedup();
+            expanded.sort();
+            expanded.dedup();
+            assert_eq!(expanded, expected, "{:?}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_expand_listing() {
+        let tests: &[(&str, &[&str])] = &[
+            ("plain", &["plain"]),
+            ("{a,b}", &["a", "b"]),
+            ("x{a,b}y{1,2}", &["xay1", "xay2", "xby1", "xby2"]),
+            ("{a,{b,c}d}", &["a", "bd", "cd"]),
+            ("{a}", &["{a}"]),
+            ("{a}{b,c}", &["{a}b", "{a}c"]),
+            ("{a,", &["{a,"]),
+            ("{,x}.rs", &[".rs", "x.rs"]),
+            (r"\{a,b\}", &["{a,b}"]),
+            (r"{a\,b,c}", &["a,b", "c"]),
+            ("ünï/{ä,ö}", &["ünï/ä", "ünï/ö"]),
+        ];
+
+        for test in tests {
+            let expected: Vec<PathBuf> = test.1.iter().map(PathBuf::from).collect();
+            assert_eq!(expand_listing([test.0]), expected, "{}", test.0);
+        }
+    }
+}
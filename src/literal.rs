@@ -0,0 +1,66 @@
+//! Cleaning up paths pasted into config files.
+
+use std::path::PathBuf;
+
+/// Undoes what happens to a path written into a `.env`, INI or JSON file, especially on Windows: a
+/// leading byte order mark, whitespace and a CRLF's `\r` around it, and one pair of matching `"` or
+/// `'` quotes are removed, and if every run of backslashes has an even length, as in
+/// `C:\\Users\\me`, each is halved.
+///
+/// Whitespace inside the quotes is kept. A path with any single backslash, like `\\server\share`,
+/// has its backslashes kept as they are, but one whose only backslashes are the UNC prefix, like
+/// `\\server`, can't be told from an escaped `\server` and loses one.
+///
+/// ```rust
+/// use pathext::parse_path_literal;
+/// use std::path::Path;
+///
+/// assert_eq!(parse_path_literal("\u{feff}\"C:\\\\Users\\\\me\"\r"), Path::new(r"C:\Users\me"));
+/// assert_eq!(parse_path_literal(r"  '\\server\share\My Files'  "), Path::new(r"\\server\share\My Files"));
+/// assert_eq!(parse_path_literal("/home/me/notes.txt\r"), Path::new("/home/me/notes.txt"));
+/// ```
+pub fn parse_path_literal(literal: &str) -> PathBuf {
+    let literal = literal.strip_prefix('\u{feff}').unwrap_or(literal).trim();
+    let unquoted = ['"', '\'']
+        .into_iter()
+        .find_map(|quote| literal.strip_prefix(quote)?.strip_suffix(quote))
+        .unwrap_or(literal);
+
+    let runs_are_even = unquoted.split(|c| c != '\\').all(|run| run.len() % 2 == 0);
+    if runs_are_even {
+        PathBuf::from(unquoted.replace(r"\\", r"\"))
+    } else {
+        PathBuf::from(unquoted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_path_literal;
+
+    use std::path::Path;
+
+    #[test]
+    fn test_parse_path_literal() {
+        let tests = &[
+            ("/plain/path", "/plain/path"),
+            ("/with/cr\r\n", "/with/cr"),
+            ("\u{feff}C:\\x", r"C:\x"),
+            (r#""C:\\Program Files\\App""#, r"C:\Program Files\App"),
+            (r"C:\\Users\\me", r"C:\Users\me"),
+            (r"C:\Users\me", r"C:\Users\me"),
+            (r"\\\\server\\share", r"\\server\share"),
+            (r"\\server\share", r"\\server\share"),
+            (r"\\?\C:\long", r"\\?\C:\long"),
+            ("' spaced name '", " spaced name "),
+            ("\"unbalanced'", "\"unbalanced'"),
+            ("\"\"", ""),
+            ("\"", "\""),
+            ("  ", ""),
+        ];
+
+        for test in tests {
+            assert_eq!(parse_path_literal(test.0), Path::new(test.1), "{}", test.0);
+        }
+    }
+}
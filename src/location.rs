@@ -0,0 +1,258 @@
+//! Telling filesystem paths apart from URLs in user-supplied locations.
+
+use std::path::PathBuf;
+
+/// A location as given by a user, see [`parse_path_or_url`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PathOrUrl {
+    /// A local path, including ones that were written as `file:` URLs.
+    Path(PathBuf),
+    /// Anything else with a `scheme://`, with the scheme lowercased and the rest after `://`.
+    Url { scheme: String, rest: String },
+}
+
+impl PathOrUrl {
+    /// The local path, if this is one.
+    pub fn as_path(&self) -> Option<&std::path::Path> {
+        match self {
+            PathOrUrl::Path(path) => Some(path),
+            PathOrUrl::Url { .. } => None,
+        }
+    }
+}
+
+/// Classifies a location string as a local path or a URL.
+///
+/// Single letter "schemes" are Windows drives, so `C:\x` and `c:/x` are paths, as are UNC
+/// (`\\server\share`) and relative paths. `file:` URLs are turned into paths, percent-decoded,
+/// with a host becoming a UNC path. Any other `scheme://rest` is a URL.
+///
+/// ```rust
+/// use pathext::{parse_path_or_url, PathOrUrl};
+/// use std::path::PathBuf;
+///
+/// assert_eq!(parse_path_or_url("./rel"), PathOrUrl::Path(PathBuf::from("./rel")));
+/// assert_eq!(parse_path_or_url("file:///etc/hosts"), PathOrUrl::Path(PathBuf::from("/etc/hosts")));
+/// assert_eq!(
+///     parse_path_or_url("s3://bucket/key"),
+///     PathOrUrl::Url { scheme: "s3".into(), rest: "bucket/key".into() }
+/// );
+/// ```
+pub fn parse_path_or_url(location: &str) -> PathOrUrl {
+    let Some((scheme, rest)) = split_scheme(location) else {
+        return PathOrUrl::Path(PathBuf::from(location));
+    };
+
+    if scheme == "file" {
+        if let Some(path) = file_url_to_path(rest) {
+            return PathOrUrl::Path(path);
+        }
+    }
+
+    match rest.strip_prefix("//") {
+        Some(rest) => PathOrUrl::Url {
+            scheme,
+            rest: rest.to_string(),
+        },
+        None => PathOrUrl::Path(PathBuf::from(location)),
+    }
+}
+
+/// A location on another machine in scp/rsync `[user@]host:path` syntax, see [`parse_remote_spec`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RemoteSpec {
+    pub user: Option<String>,
+    /// Without the brackets of an IPv6 address.
+    pub host: String,
+    /// Relative paths are relative to the remote home directory, an empty one being the home itself.
+    pub path: PathBuf,
+}
+
+/// Parses an scp/rsync style `[user@]host:path`, returning `None` for anything that's local.
+///
+/// As with scp, a `/` before the first `:` makes it a local path, and so does a single letter
+/// host, which is a Windows drive. IPv6 hosts go in brackets, `[::1]:/x`. URLs (`host://...`)
+/// and rsync daemon `host::module` locations aren't remote specs in this sense.
+///
+/// ```rust
+/// use pathext::{parse_remote_spec, RemoteSpec};
+/// use std::path::PathBuf;
+///
+/// assert_eq!(
+///     parse_remote_spec("deploy@web1:/var/www"),
+///     Some(RemoteSpec { user: Some("deploy".into()), host: "web1".into(), path: PathBuf::from("/var/www") })
+/// );
+/// assert_eq!(parse_remote_spec(r"C:\var\www"), None);
+/// assert_eq!(parse_remote_spec("./web1:/var/www"), None);
+/// ```
+pub fn parse_remote_spec(location: &str) -> Option<RemoteSpec> {
+    let (user, rest) = match location.split_once('@') {
+        Some((user, rest)) if !user.is_empty() && !user.contains([':', '/', '\\']) => {
+            (Some(user.to_string()), rest)
+        }
+        _ => (None, location),
+    };
+
+    let (host, path) = match rest.strip_prefix('[') {
+        Some(bracketed) => {
+            let (host, after) = bracketed.split_once(']')?;
+            (host, after.strip_prefix(':')?)
+        }
+        None => rest.split_once(':')?,
+    };
+
+    let local = host.len() <= 1 && user.is_none() || host.contains(['/', '\\']);
+    if host.is_empty() || local || path.starts_with("//") || path.starts_with(':') {
+        return None;
+    }
+    Some(RemoteSpec {
+        user,
+        host: host.to_string(),
+        path: PathBuf::from(path),
+    })
+}
+
+/// Splits off an RFC 3986 scheme, ignoring single letters which are Windows drives.
+pub(crate) fn split_scheme(location: &str) -> Option<(String, &str)> {
+    let (scheme, rest) = location.split_once(':')?;
+    let mut chars = scheme.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    if valid && scheme.len() > 1 {
+        Some((scheme.to_ascii_lowercase(), rest))
+    } else {
+        None
+    }
+}
+
+/// Converts what follows `file:` to a path, `None` when it isn't in `//host/path` or `/path` form.
+fn file_url_to_path(rest: &str) -> Option<PathBuf> {
+    let decoded = percent_decode(rest)?;
+    let (host, path) = match decoded.strip_prefix("//") {
+        Some(authority) => match authority.find('/') {
+            Some(i) => authority.split_at(i),
+            None => (authority, ""),
+        },
+        None if decoded.starts_with('/') => ("", decoded.as_str()),
+        None => return None,
+    };
+
+    if !host.is_empty() && !host.eq_ignore_ascii_case("localhost") {
+        return Some(PathBuf::from(format!(
+            r"\\{}{}",
+            host,
+            path.replace('/', "\\")
+        )));
+    }
+
+    // `/C:/x` is a drive path
+    let bytes = path.as_bytes();
+    if bytes.len() >= 3 && bytes[0] == b'/' && bytes[1].is_ascii_alphabetic() && bytes[2] == b':' {
+        return Some(PathBuf::from(&path[1..]));
+    }
+    Some(PathBuf::from(path))
+}
+
+pub(crate) fn percent_decode(s: &str) -> Option<String> {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = s.get(i + 1..i + 3)?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_path_or_url, parse_remote_spec, PathOrUrl, RemoteSpec};
+
+    use std::path::PathBuf;
+
+    fn path(p: &str) -> PathOrUrl {
+        PathOrUrl::Path(PathBuf::from(p))
+    }
+
+    fn url(scheme: &str, rest: &str) -> PathOrUrl {
+        PathOrUrl::Url {
+            scheme: scheme.to_string(),
+            rest: rest.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_path_or_url() {
+        let tests = &[
+            (r"C:\x", path(r"C:\x")),
+            ("c:/x", path("c:/x")),
+            ("C:", path("C:")),
+            (r"\\server\share", path(r"\\server\share")),
+            ("./rel", path("./rel")),
+            ("rel/with:colon", path("rel/with:colon")),
+            ("name:with-colon", path("name:with-colon")),
+            ("/abs/path", path("/abs/path")),
+            ("file:///x", path("/x")),
+            ("file:///C:/x%20y", path("C:/x y")),
+            ("file://localhost/etc", path("/etc")),
+            ("file://server/share/x", path(r"\\server\share\x")),
+            ("file:/etc/hosts", path("/etc/hosts")),
+            ("s3://bucket/key", url("s3", "bucket/key")),
+            ("HTTPS://example.com/a", url("https", "example.com/a")),
+            ("git+ssh://host/repo", url("git+ssh", "host/repo")),
+            ("file:bad%zz", path("file:bad%zz")),
+            ("file://host%zz/x", url("file", "host%zz/x")),
+        ];
+
+        for test in tests {
+            assert_eq!(parse_path_or_url(test.0), test.1, "{}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_parse_remote_spec() {
+        let remote = |user: Option<&str>, host: &str, path: &str| {
+            Some(RemoteSpec {
+                user: user.map(String::from),
+                host: host.to_string(),
+                path: PathBuf::from(path),
+            })
+        };
+        let tests = &[
+            (
+                "user@host:/var/www",
+                remote(Some("user"), "host", "/var/www"),
+            ),
+            ("host:/var/www", remote(None, "host", "/var/www")),
+            ("host:relative/dir", remote(None, "host", "relative/dir")),
+            ("host:", remote(None, "host", "")),
+            ("me@[::1]:/srv", remote(Some("me"), "::1", "/srv")),
+            ("[fe80::1]:x", remote(None, "fe80::1", "x")),
+            (
+                "git@github.com:org/repo.git",
+                remote(Some("git"), "github.com", "org/repo.git"),
+            ),
+            ("me@c:/x", remote(Some("me"), "c", "/x")),
+            (r"C:\var\www", None),
+            ("c:/var/www", None),
+            ("./host:/x", None),
+            ("dir/file:with-colon", None),
+            ("/abs/path", None),
+            ("plain", None),
+            ("ssh://host/x", None),
+            ("host::module/x", None),
+            ("[::1]/x", None),
+            (":/x", None),
+        ];
+
+        for test in tests {
+            assert_eq!(parse_remote_spec(test.0), test.1, "{}", test.0);
+        }
+    }
+}
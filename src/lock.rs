@@ -0,0 +1,161 @@
+//! Lock files next to the files they guard.
+
+use std::ffi::OsString;
+#[cfg(feature = "fs")]
+use std::fs::{self, File};
+#[cfg(feature = "fs")]
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::limits::{self, LengthLimits};
+
+const LOCK_SUFFIX: &str = ".lock";
+
+pub(crate) fn lock_path(path: &Path) -> Option<PathBuf> {
+    let name = path.file_name()?;
+    let limits = LengthLimits::native();
+    let mut locked = OsString::from(name);
+    locked.push(LOCK_SUFFIX);
+    if limits.unit.of_os_str(&locked) <= limits.max_component {
+        return Some(path.with_file_name(locked));
+    }
+    // keep `.lock` whole and cut the rest down, hashed so long names sharing a start don't collide
+    let name = name.to_str()?;
+    let budget = limits.max_component - LOCK_SUFFIX.len();
+    let truncated = limits::truncate_name(name, budget, true, limits.unit)?;
+    Some(path.with_file_name(truncated + LOCK_SUFFIX))
+}
+
+/// A lock file held by this process, removed again when dropped. It holds the process id, so
+/// whoever finds it can tell who has the lock.
+///
+/// The lock is advisory: it only keeps out those who also take it through
+/// [`PathFsExt::try_lock`](crate::PathFsExt::try_lock). A process that dies holding it leaves the
+/// file behind, and the lock held until the file is removed.
+#[cfg(feature = "fs")]
+#[derive(Debug)]
+pub struct LockFile {
+    path: PathBuf,
+    released: bool,
+}
+
+#[cfg(feature = "fs")]
+impl LockFile {
+    /// The lock file itself.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Removes the lock file, as dropping does, but reporting failure.
+    pub fn release(mut self) -> io::Result<()> {
+        self.released = true;
+        fs::remove_file(&self.path).map_err(|e| crate::fs::with_path(e, "remove", &self.path))
+    }
+}
+
+#[cfg(feature = "fs")]
+impl Drop for LockFile {
+    fn drop(&mut self) {
+        if !self.released {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+#[cfg(feature = "fs")]
+pub(crate) fn try_lock(path: &Path) -> io::Result<Option<LockFile>> {
+    let Some(lock_path) = lock_path(path) else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "no file name to derive a lock file from",
+        ));
+    };
+    let mut file = match File::options()
+        .write(true)
+        .create_new(true)
+        .open(&lock_path)
+    {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => return Ok(None),
+        Err(e) => return Err(crate::fs::with_path(e, "create", &lock_path)),
+    };
+    // from here on the guard owns the file, removing it if the write fails
+    let lock = LockFile {
+        path: lock_path,
+        released: false,
+    };
+    writeln!(file, "{}", std::process::id())
+        .map_err(|e| crate::fs::with_path(e, "write", &lock.path))?;
+    Ok(Some(lock))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::lock_path;
+    use crate::LengthLimits;
+
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn test_lock_path() {
+        let tests = &[
+            ("file.txt", Some("file.txt.lock")),
+            ("/var/db/data", Some("/var/db/data.lock")),
+            ("dir/.hidden", Some("dir/.hidden.lock")),
+            ("/", None),
+            ("a/..", None),
+        ];
+
+        for test in tests {
+            assert_eq!(
+                lock_path(Path::new(test.0)),
+                test.1.map(PathBuf::from),
+                "{}",
+                test.0
+            );
+        }
+    }
+
+    #[test]
+    fn test_lock_path_long_name() {
+        let max = LengthLimits::native().max_component;
+        let fits = "x".repeat(max - 5);
+        assert_eq!(
+            lock_path(Path::new(&fits)).unwrap(),
+            Path::new(&format!("{fits}.lock"))
+        );
+
+        let a = lock_path(Path::new(&format!("{}a.txt", "x".repeat(max)))).unwrap();
+        let b = lock_path(Path::new(&format!("{}b.txt", "x".repeat(max)))).unwrap();
+        assert_ne!(a, b);
+        for lock in [a, b] {
+            let name = lock.file_name().unwrap().to_str().unwrap();
+            assert!(name.len() <= max, "{name}");
+            assert!(name.ends_with(".txt.lock"), "{name}");
+        }
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn test_try_lock() {
+        use super::try_lock;
+
+        let dir = tempfile::tempdir().unwrap();
+        let data = dir.path().join("data.db");
+        let lock = try_lock(&data).unwrap().unwrap();
+        assert_eq!(lock.path(), dir.path().join("data.db.lock"));
+        let pid = std::fs::read_to_string(lock.path()).unwrap();
+        assert_eq!(pid.trim(), std::process::id().to_string());
+        assert!(try_lock(&data).unwrap().is_none());
+
+        drop(lock);
+        assert!(!dir.path().join("data.db.lock").exists());
+        let lock = try_lock(&data).unwrap().unwrap();
+        lock.release().unwrap();
+        assert!(try_lock(&data).unwrap().is_some());
+
+        let e = try_lock(&dir.path().join("missing/data.db")).unwrap_err();
+        assert_eq!(e.kind(), std::io::ErrorKind::NotFound);
+        assert!(try_lock(Path::new("/")).is_err());
+    }
+}
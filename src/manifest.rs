@@ -0,0 +1,301 @@
+//! Writing and reading lists of paths, one per line or NUL-terminated, for manifest files and
+//! `xargs`-style pipelines.
+//!
+//! With [`Delimiter::Newline`] every path is escaped onto one line of UTF-8: `\\` for a backslash,
+//! `\n` and `\r` for line breaks and `\xHH` for each byte that isn't UTF-8. A `\r` at the end of a
+//! line is taken for a CRLF line ending and dropped. With [`Delimiter::Nul`] paths are written as
+//! their bytes, each followed by a NUL, as `find -print0` writes and `xargs -0` reads, which needs
+//! no escaping since paths can't contain NUL.
+//!
+//! Off Unix, paths that aren't Unicode can't be written, and what is read must be UTF-8 once
+//! unescaped. Empty entries are skipped when reading, so blank lines don't matter.
+//!
+//! ```rust
+//! use pathext::manifest::{self, Delimiter};
+//! use std::path::PathBuf;
+//!
+//! let paths = ["src/lib.rs", "notes\nand more.txt", r"C:\temp"];
+//! let mut list = Vec::new();
+//! manifest::write(&mut list, paths, Delimiter::Newline).unwrap();
+//! assert_eq!(list, b"src/lib.rs\nnotes\\nand more.txt\nC:\\\\temp\n");
+//!
+//! let read: Vec<PathBuf> = manifest::read(&list[..], Delimiter::Newline)
+//!     .collect::<Result<_, _>>()
+//!     .unwrap();
+//! assert_eq!(read, paths.map(PathBuf::from));
+//! ```
+
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use crate::encoded;
+
+/// What ends each path in a list.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Delimiter {
+    /// A line feed, with paths escaped to fit on their line.
+    #[default]
+    Newline,
+    /// A NUL byte, with paths as they are.
+    Nul,
+}
+
+impl Delimiter {
+    pub fn byte(self) -> u8 {
+        match self {
+            Delimiter::Newline => b'\n',
+            Delimiter::Nul => b'\0',
+        }
+    }
+}
+
+fn not_unicode(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{} isn't Unicode", path.display()),
+    )
+}
+
+fn invalid_entry(entry: &[u8], reason: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{reason} in {:?}", String::from_utf8_lossy(entry)),
+    )
+}
+
+/// The path escaped onto a single line, as [`write`] writes it with [`Delimiter::Newline`].
+pub fn escape<P: AsRef<Path>>(path: P) -> io::Result<String> {
+    let path = path.as_ref();
+    let bytes = encoded::portable_bytes(path).ok_or_else(|| not_unicode(path))?;
+    let mut escaped = String::with_capacity(bytes.len());
+    for chunk in bytes.utf8_chunks() {
+        for c in chunk.valid().chars() {
+            match c {
+                '\\' => escaped.push_str("\\\\"),
+                '\n' => escaped.push_str("\\n"),
+                '\r' => escaped.push_str("\\r"),
+                c => escaped.push(c),
+            }
+        }
+        for byte in chunk.invalid() {
+            escaped.push_str(&format!("\\x{byte:02X}"));
+        }
+    }
+    Ok(escaped)
+}
+
+/// The path back from a line [`escape`] wrote, an [`io::ErrorKind::InvalidData`] error for an
+/// unknown or cut off escape.
+pub fn unescape(line: &[u8]) -> io::Result<PathBuf> {
+    let mut bytes = Vec::with_capacity(line.len());
+    let mut rest = line;
+    while let Some((&byte, tail)) = rest.split_first() {
+        rest = tail;
+        if byte != b'\\' {
+            bytes.push(byte);
+            continue;
+        }
+        let Some((&escape, tail)) = rest.split_first() else {
+            return Err(invalid_entry(line, "trailing backslash"));
+        };
+        rest = tail;
+        match escape {
+            b'\\' => bytes.push(b'\\'),
+            b'n' => bytes.push(b'\n'),
+            b'r' => bytes.push(b'\r'),
+            b'x' => {
+                let hex = rest
+                    .get(..2)
+                    .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+                    .ok_or_else(|| invalid_entry(line, "bad \\x escape"))?;
+                // two ASCII hex digits always parse
+                let hex = std::str::from_utf8(hex).unwrap();
+                bytes.push(u8::from_str_radix(hex, 16).unwrap());
+                rest = &rest[2..];
+            }
+            _ => return Err(invalid_entry(line, "unknown escape")),
+        }
+    }
+    encoded::path_from_bytes(bytes).ok_or_else(|| invalid_entry(line, "not UTF-8"))
+}
+
+/// Writes every path followed by `delimiter`, escaped if that's [`Delimiter::Newline`].
+pub fn write<W, I, P>(mut writer: W, paths: I, delimiter: Delimiter) -> io::Result<()>
+where
+    W: Write,
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    for path in paths {
+        let path = path.as_ref();
+        match delimiter {
+            Delimiter::Newline => writer.write_all(escape(path)?.as_bytes())?,
+            Delimiter::Nul => {
+                let bytes = encoded::portable_bytes(path).ok_or_else(|| not_unicode(path))?;
+                writer.write_all(bytes)?;
+            }
+        }
+        writer.write_all(&[delimiter.byte()])?;
+    }
+    Ok(())
+}
+
+/// The non-empty entries between `delimiter`s, without the `\r` of a CRLF line ending.
+fn entries<R: BufRead>(reader: R, delimiter: u8) -> impl Iterator<Item = io::Result<Vec<u8>>> {
+    reader.split(delimiter).filter_map(move |entry| {
+        let mut entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => return Some(Err(e)),
+        };
+        if delimiter == b'\n' && entry.last() == Some(&b'\r') {
+            entry.pop();
+        }
+        (!entry.is_empty()).then_some(Ok(entry))
+    })
+}
+
+fn path_from_entry(entry: Vec<u8>) -> io::Result<PathBuf> {
+    match encoded::path_from_bytes(entry) {
+        Some(path) => Ok(path),
+        None => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "path isn't UTF-8",
+        )),
+    }
+}
+
+/// Reads the paths [`write`] wrote with the same `delimiter`, as they come.
+pub fn read<R: BufRead>(
+    reader: R,
+    delimiter: Delimiter,
+) -> impl Iterator<Item = io::Result<PathBuf>> {
+    entries(reader, delimiter.byte()).map(move |entry| match delimiter {
+        Delimiter::Newline => unescape(&entry?),
+        Delimiter::Nul => path_from_entry(entry?),
+    })
+}
+
+/// Reads paths as they come from a stream of unescaped paths each ended by `delimiter`, such as
+/// `b'\0'` for `git ls-files -z` or `find -print0` and `b'\n'` for one path per line.
+///
+/// Paths are the bytes between delimiters on Unix and must be UTF-8 elsewhere, an
+/// [`io::ErrorKind::InvalidData`] error otherwise. Empty entries are skipped, and with `b'\n'` a
+/// `\r` ending a line is taken for a CRLF line ending and dropped.
+///
+/// ```rust
+/// use pathext::read_paths;
+/// use std::path::PathBuf;
+///
+/// let listed = b"src/lib.rs\0docs/a b.md\0";
+/// let paths: Vec<PathBuf> = read_paths(&listed[..], b'\0').collect::<Result<_, _>>().unwrap();
+/// assert_eq!(paths, [PathBuf::from("src/lib.rs"), PathBuf::from("docs/a b.md")]);
+/// ```
+pub fn read_paths<R: BufRead>(
+    reader: R,
+    delimiter: u8,
+) -> impl Iterator<Item = io::Result<PathBuf>> {
+    entries(reader, delimiter).map(|entry| path_from_entry(entry?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{escape, read, read_paths, unescape, write, Delimiter};
+
+    use std::io;
+    use std::path::{Path, PathBuf};
+
+    fn read_all(list: &[u8], delimiter: Delimiter) -> io::Result<Vec<PathBuf>> {
+        read(list, delimiter).collect()
+    }
+
+    #[test]
+    fn test_escape() {
+        let tests = &[
+            ("plain/path.txt", "plain/path.txt"),
+            ("two\nlines", "two\\nlines"),
+            ("cr\r", "cr\\r"),
+            (r"C:\dir", r"C:\\dir"),
+            ("tab\there ünï", "tab\there ünï"),
+        ];
+
+        for test in tests {
+            assert_eq!(escape(test.0).unwrap(), test.1, "{:?}", test.0);
+            assert_eq!(unescape(test.1.as_bytes()).unwrap(), Path::new(test.0));
+        }
+    }
+
+    #[test]
+    fn test_unescape_rejects() {
+        let tests: &[&[u8]] = &[b"end\\", b"\\q", b"\\x4", b"\\xZZ", b"\\x+1"];
+
+        for test in tests {
+            let error = unescape(test).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData, "{test:?}");
+        }
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let paths = ["a", "with space", "new\nline", "back\\slash", "\r\n"];
+
+        for delimiter in [Delimiter::Newline, Delimiter::Nul] {
+            let mut list = Vec::new();
+            write(&mut list, paths, delimiter).unwrap();
+            assert_eq!(
+                read_all(&list, delimiter).unwrap(),
+                paths.map(PathBuf::from)
+            );
+        }
+    }
+
+    #[test]
+    fn test_read() {
+        assert_eq!(
+            read_all(b"a\r\n\r\n\nb\\x41", Delimiter::Newline).unwrap(),
+            [Path::new("a"), Path::new("bA")]
+        );
+        assert_eq!(
+            read_all(b"a\nb\0\0c\\n", Delimiter::Nul).unwrap(),
+            [Path::new("a\nb"), Path::new("c\\n")]
+        );
+        assert!(read_all(b"ok\nbad\\", Delimiter::Newline).is_err());
+    }
+
+    #[test]
+    fn test_read_paths() {
+        let read_paths_all =
+            |list: &[u8], delimiter| read_paths(list, delimiter).collect::<io::Result<Vec<_>>>();
+
+        assert_eq!(
+            read_paths_all(b"a\\x41\r\nb c\n\n", b'\n').unwrap(),
+            [Path::new("a\\x41"), Path::new("b c")]
+        );
+        assert_eq!(
+            read_paths_all(b"one\ntwo\0\0three\r", b'\0').unwrap(),
+            [Path::new("one\ntwo"), Path::new("three\r")]
+        );
+        assert!(read_paths_all(b"", b'\0').unwrap().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_utf8() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let path = Path::new(OsStr::from_bytes(b"bad\xff\xfe/\xc3"));
+        assert_eq!(escape(path).unwrap(), "bad\\xFF\\xFE/\\xC3");
+
+        for delimiter in [Delimiter::Newline, Delimiter::Nul] {
+            let mut list = Vec::new();
+            write(&mut list, [path], delimiter).unwrap();
+            assert_eq!(read_all(&list, delimiter).unwrap(), [path]);
+        }
+        let listed = b"\xff\0ok\0";
+        let paths: Vec<PathBuf> = read_paths(&listed[..], b'\0').map(Result::unwrap).collect();
+        assert_eq!(
+            paths,
+            [Path::new(OsStr::from_bytes(b"\xff")), Path::new("ok")]
+        );
+    }
+}
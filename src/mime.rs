@@ -0,0 +1,187 @@
+//! Guessing MIME types from file extensions with a bundled table.
+
+use std::path::Path;
+
+/// Extensions and their MIME types, sorted by extension for binary search.
+const TYPES: &[(&str, &str)] = &[
+    ("7z", "application/x-7z-compressed"),
+    ("aac", "audio/aac"),
+    ("avif", "image/avif"),
+    ("bmp", "image/bmp"),
+    ("br", "application/x-brotli"),
+    ("bz2", "application/x-bzip2"),
+    ("c", "text/x-c"),
+    ("cjs", "text/javascript"),
+    ("css", "text/css"),
+    ("csv", "text/csv"),
+    ("doc", "application/msword"),
+    (
+        "docx",
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    ),
+    ("eot", "application/vnd.ms-fontobject"),
+    ("epub", "application/epub+zip"),
+    ("flac", "audio/flac"),
+    ("gif", "image/gif"),
+    ("gz", "application/gzip"),
+    ("htm", "text/html"),
+    ("html", "text/html"),
+    ("ico", "image/vnd.microsoft.icon"),
+    ("ics", "text/calendar"),
+    ("jar", "application/java-archive"),
+    ("jpeg", "image/jpeg"),
+    ("jpg", "image/jpeg"),
+    ("js", "text/javascript"),
+    ("json", "application/json"),
+    ("jsonld", "application/ld+json"),
+    ("lz4", "application/x-lz4"),
+    ("m4a", "audio/mp4"),
+    ("md", "text/markdown"),
+    ("mjs", "text/javascript"),
+    ("mkv", "video/x-matroska"),
+    ("mov", "video/quicktime"),
+    ("mp3", "audio/mpeg"),
+    ("mp4", "video/mp4"),
+    ("mpeg", "video/mpeg"),
+    ("odp", "application/vnd.oasis.opendocument.presentation"),
+    ("ods", "application/vnd.oasis.opendocument.spreadsheet"),
+    ("odt", "application/vnd.oasis.opendocument.text"),
+    ("oga", "audio/ogg"),
+    ("ogg", "audio/ogg"),
+    ("ogv", "video/ogg"),
+    ("otf", "font/otf"),
+    ("pdf", "application/pdf"),
+    ("png", "image/png"),
+    ("ppt", "application/vnd.ms-powerpoint"),
+    (
+        "pptx",
+        "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+    ),
+    ("rar", "application/vnd.rar"),
+    ("rtf", "application/rtf"),
+    ("sh", "application/x-sh"),
+    ("svg", "image/svg+xml"),
+    ("tar", "application/x-tar"),
+    ("tbz2", "application/x-bzip2"),
+    ("tgz", "application/gzip"),
+    ("tif", "image/tiff"),
+    ("tiff", "image/tiff"),
+    ("toml", "application/toml"),
+    ("ttf", "font/ttf"),
+    ("txt", "text/plain"),
+    ("txz", "application/x-xz"),
+    ("wasm", "application/wasm"),
+    ("wav", "audio/wav"),
+    ("weba", "audio/webm"),
+    ("webm", "video/webm"),
+    ("webmanifest", "application/manifest+json"),
+    ("webp", "image/webp"),
+    ("woff", "font/woff"),
+    ("woff2", "font/woff2"),
+    ("xhtml", "application/xhtml+xml"),
+    ("xls", "application/vnd.ms-excel"),
+    (
+        "xlsx",
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    ),
+    ("xml", "application/xml"),
+    ("xz", "application/x-xz"),
+    ("yaml", "application/yaml"),
+    ("yml", "application/yaml"),
+    ("zip", "application/zip"),
+    ("zst", "application/zstd"),
+];
+
+/// Single extensions that stand for a compressed tarball, like `tgz` for `tar.gz`.
+const TARBALLS: &[&str] = &["tbz2", "tgz", "txz"];
+
+/// Extensions of compression that wraps another file, whose own type comes from the extension before.
+const COMPRESSIONS: &[&str] = &["br", "bz2", "gz", "lz4", "xz", "zst"];
+
+fn lookup(extension: &str) -> Option<&'static str> {
+    let extension = extension.to_ascii_lowercase();
+    TYPES
+        .binary_search_by(|(known, _)| (*known).cmp(extension.as_str()))
+        .ok()
+        .map(|i| TYPES[i].1)
+}
+
+/// The last extension of the file name and the one before it, if any. A leading `.` belongs to
+/// the stem, so `.gz` has no extension.
+fn extensions(path: &Path) -> Option<(&str, Option<&str>)> {
+    let name = path.file_name()?.to_str()?;
+    let name = name.strip_prefix('.').unwrap_or(name);
+    let mut extensions = name.split('.').skip(1);
+    let mut last = extensions.next()?;
+    let mut before = None;
+    for extension in extensions {
+        before = Some(last);
+        last = extension;
+    }
+    Some((last, before))
+}
+
+pub(crate) fn mime_type(path: &Path) -> Option<&'static str> {
+    lookup(extensions(path)?.0)
+}
+
+pub(crate) fn inner_mime_type(path: &Path) -> Option<&'static str> {
+    let (last, inner) = extensions(path)?;
+    let last = last.to_ascii_lowercase();
+    if TARBALLS.contains(&last.as_str()) {
+        return lookup("tar");
+    }
+    if COMPRESSIONS.contains(&last.as_str()) {
+        return lookup(inner?);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{inner_mime_type, mime_type, TYPES};
+
+    use std::path::Path;
+
+    #[test]
+    fn test_types_sorted() {
+        assert!(TYPES.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    fn test_mime_type() {
+        let tests = &[
+            ("index.html", Some("text/html"), None),
+            ("photo.JPG", Some("image/jpeg"), None),
+            (
+                "backup.tar.gz",
+                Some("application/gzip"),
+                Some("application/x-tar"),
+            ),
+            (
+                "release.tgz",
+                Some("application/gzip"),
+                Some("application/x-tar"),
+            ),
+            (
+                "data.json.zst",
+                Some("application/zstd"),
+                Some("application/json"),
+            ),
+            ("notes.txt.gz", Some("application/gzip"), Some("text/plain")),
+            ("plain.gz", Some("application/gzip"), None),
+            ("weird.unknown.gz", Some("application/gzip"), None),
+            ("archive.tar.unknown", None, None),
+            (".gz", None, None),
+            (".config.json", Some("application/json"), None),
+            ("Makefile", None, None),
+            ("trailing.", None, None),
+        ];
+
+        for test in tests {
+            let path = Path::new(test.0);
+            assert_eq!(mime_type(path), test.1, "{}", test.0);
+            assert_eq!(inner_mime_type(path), test.2, "{}", test.0);
+        }
+    }
+}
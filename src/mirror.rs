@@ -0,0 +1,332 @@
+//! Planning how to make one directory tree a copy of another.
+
+use std::collections::BTreeMap;
+#[cfg(feature = "fs")]
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::NameClassifier;
+
+/// A file in one of the trees of a [`MirrorPlan`], at `path` relative to its root. Without a size
+/// and modification time a file is always copied.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct MirrorEntry {
+    pub path: PathBuf,
+    pub size: Option<u64>,
+    pub modified: Option<SystemTime>,
+}
+
+impl From<PathBuf> for MirrorEntry {
+    /// An entry with nothing known about it but its path.
+    fn from(path: PathBuf) -> Self {
+        MirrorEntry {
+            path,
+            ..MirrorEntry::default()
+        }
+    }
+}
+
+/// One step of a [`MirrorPlan`], with full paths under the source and destination roots.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MirrorOp {
+    Copy {
+        from: PathBuf,
+        to: PathBuf,
+    },
+    /// A destination file the source doesn't have.
+    Delete(PathBuf),
+    /// A file whose copy is already up to date.
+    Skip {
+        from: PathBuf,
+        to: PathBuf,
+    },
+}
+
+/// Works out the copies and deletes that make `destination` mirror `source`, leaving running them
+/// to the caller, be that [`std::fs::copy`] or an rsync-like backend.
+///
+/// Planning is lexical: [`plan`](MirrorPlan::plan) takes the files of both trees as lists, and only
+/// [`scan`](MirrorPlan::scan), with the `fs` feature, reads them from disk. Files are matched up by
+/// relative path and filtered by name rules; excluded files are neither copied nor deleted.
+///
+/// ```rust
+/// use pathext::{MirrorEntry, MirrorOp, MirrorPlan, NameClassifier, NameRule};
+/// use std::path::PathBuf;
+///
+/// let plan = MirrorPlan::new("site", "/srv/www")
+///     .exclude(NameClassifier::new().with(NameRule::Extension("tmp".into())))
+///     .delete_extra(true);
+/// let ops = plan.plan(
+///     ["index.html", "draft.tmp"].map(|p| MirrorEntry::from(PathBuf::from(p))),
+///     ["old.html", "cache.tmp"].map(|p| MirrorEntry::from(PathBuf::from(p))),
+/// );
+/// assert_eq!(
+///     ops,
+///     [
+///         MirrorOp::Copy { from: "site/index.html".into(), to: "/srv/www/index.html".into() },
+///         MirrorOp::Delete("/srv/www/old.html".into()),
+///     ]
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MirrorPlan {
+    source: PathBuf,
+    destination: PathBuf,
+    include: Option<NameClassifier>,
+    exclude: NameClassifier,
+    delete_extra: bool,
+}
+
+impl MirrorPlan {
+    /// A plan copying every file and deleting nothing.
+    pub fn new<S: Into<PathBuf>, D: Into<PathBuf>>(source: S, destination: D) -> Self {
+        MirrorPlan {
+            source: source.into(),
+            destination: destination.into(),
+            include: None,
+            exclude: NameClassifier::new(),
+            delete_extra: false,
+        }
+    }
+
+    /// Only mirror files `include` matches.
+    pub fn include(mut self, include: NameClassifier) -> Self {
+        self.include = Some(include);
+        self
+    }
+
+    /// Leave files `exclude` matches alone, on both sides.
+    pub fn exclude(mut self, exclude: NameClassifier) -> Self {
+        self.exclude = exclude;
+        self
+    }
+
+    /// Whether destination files missing from the source are deleted.
+    pub fn delete_extra(mut self, delete_extra: bool) -> Self {
+        self.delete_extra = delete_extra;
+        self
+    }
+
+    pub fn source(&self) -> &Path {
+        &self.source
+    }
+
+    pub fn destination(&self) -> &Path {
+        &self.destination
+    }
+
+    /// Whether the plan covers `path`, relative to either root.
+    pub fn is_included<P: AsRef<Path>>(&self, path: P) -> bool {
+        let path = path.as_ref();
+        self.include
+            .as_ref()
+            .is_none_or(|include| include.matches(path))
+            && !self.exclude.matches(path)
+    }
+
+    /// The steps mirroring the `source` files onto the `destination` ones, sorted by path. A file
+    /// is skipped when its copy has the same size and was modified no earlier.
+    pub fn plan<S, D>(&self, source: S, destination: D) -> Vec<MirrorOp>
+    where
+        S: IntoIterator<Item = MirrorEntry>,
+        D: IntoIterator<Item = MirrorEntry>,
+    {
+        let mut existing: BTreeMap<PathBuf, MirrorEntry> = destination
+            .into_iter()
+            .filter(|entry| self.is_included(&entry.path))
+            .map(|entry| (entry.path.clone(), entry))
+            .collect();
+        let mut ops: Vec<(PathBuf, MirrorOp)> = Vec::new();
+        for entry in source {
+            if !self.is_included(&entry.path) {
+                continue;
+            }
+            let from = self.source.join(&entry.path);
+            let to = self.destination.join(&entry.path);
+            let op = match existing.remove(&entry.path) {
+                Some(copy) if is_up_to_date(&entry, &copy) => MirrorOp::Skip { from, to },
+                _ => MirrorOp::Copy { from, to },
+            };
+            ops.push((entry.path, op));
+        }
+        if self.delete_extra {
+            for path in existing.into_keys() {
+                let op = MirrorOp::Delete(self.destination.join(&path));
+                ops.push((path, op));
+            }
+        }
+        ops.sort_by(|a, b| a.0.cmp(&b.0));
+        ops.into_iter().map(|(_, op)| op).collect()
+    }
+
+    /// [`plan`](MirrorPlan::plan) over the files on disk under both roots, symlinks not followed.
+    /// A missing destination is planned as empty.
+    #[cfg(feature = "fs")]
+    pub fn scan(&self) -> io::Result<Vec<MirrorOp>> {
+        let source = scan_entries(&self.source)?;
+        let destination = match std::fs::symlink_metadata(&self.destination) {
+            Err(e) if crate::fs::is_missing(&e) => Vec::new(),
+            _ => scan_entries(&self.destination)?,
+        };
+        Ok(self.plan(source, destination))
+    }
+}
+
+fn is_up_to_date(source: &MirrorEntry, copy: &MirrorEntry) -> bool {
+    match (source.size, copy.size, source.modified, copy.modified) {
+        (Some(size), Some(copy_size), Some(modified), Some(copy_modified)) => {
+            size == copy_size && modified <= copy_modified
+        }
+        _ => false,
+    }
+}
+
+#[cfg(feature = "fs")]
+fn scan_entries(root: &Path) -> io::Result<Vec<MirrorEntry>> {
+    let files = crate::tree::walk_files(root)?;
+    Ok(files
+        .into_iter()
+        .map(|(path, metadata)| MirrorEntry {
+            path,
+            size: Some(metadata.len()),
+            modified: metadata.modified().ok(),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MirrorEntry, MirrorOp, MirrorPlan};
+    use crate::{NameClassifier, NameRule};
+
+    use std::path::PathBuf;
+    use std::time::{Duration, SystemTime};
+
+    fn entry(path: &str, size: u64, age: u64) -> MirrorEntry {
+        MirrorEntry {
+            path: PathBuf::from(path),
+            size: Some(size),
+            modified: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1000 - age)),
+        }
+    }
+
+    fn copy(path: &str) -> MirrorOp {
+        MirrorOp::Copy {
+            from: PathBuf::from("src").join(path),
+            to: PathBuf::from("dst").join(path),
+        }
+    }
+
+    fn skip(path: &str) -> MirrorOp {
+        MirrorOp::Skip {
+            from: PathBuf::from("src").join(path),
+            to: PathBuf::from("dst").join(path),
+        }
+    }
+
+    #[test]
+    fn test_plan() {
+        let source = [
+            entry("same", 3, 10),
+            entry("bigger", 4, 10),
+            entry("newer", 3, 1),
+            entry("new", 3, 10),
+            MirrorEntry::from(PathBuf::from("unknown")),
+            entry("logs/app.log", 3, 10),
+        ];
+        let destination = [
+            entry("same", 3, 5),
+            entry("bigger", 3, 5),
+            entry("newer", 3, 5),
+            entry("unknown", 3, 5),
+            entry("gone", 3, 5),
+            entry("logs/old.log", 3, 5),
+        ];
+        let plan = MirrorPlan::new("src", "dst");
+        let tests = &[
+            (
+                plan.clone(),
+                vec![
+                    copy("bigger"),
+                    copy("logs/app.log"),
+                    copy("new"),
+                    copy("newer"),
+                    skip("same"),
+                    copy("unknown"),
+                ],
+            ),
+            (
+                plan.clone().delete_extra(true),
+                vec![
+                    copy("bigger"),
+                    MirrorOp::Delete("dst/gone".into()),
+                    copy("logs/app.log"),
+                    MirrorOp::Delete("dst/logs/old.log".into()),
+                    copy("new"),
+                    copy("newer"),
+                    skip("same"),
+                    copy("unknown"),
+                ],
+            ),
+            (
+                plan.clone()
+                    .delete_extra(true)
+                    .exclude(NameClassifier::new().with(NameRule::Directory("logs".into()))),
+                vec![
+                    copy("bigger"),
+                    MirrorOp::Delete("dst/gone".into()),
+                    copy("new"),
+                    copy("newer"),
+                    skip("same"),
+                    copy("unknown"),
+                ],
+            ),
+            (
+                plan.include(NameClassifier::new().with(NameRule::Prefix("n".into())))
+                    .delete_extra(true),
+                vec![copy("new"), copy("newer")],
+            ),
+        ];
+
+        for test in tests {
+            assert_eq!(
+                test.0.plan(source.clone(), destination.clone()),
+                test.1,
+                "{:?}",
+                test.0
+            );
+        }
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn test_scan() {
+        let dir = tempfile::tempdir().unwrap();
+        let (src, dst) = (dir.path().join("src"), dir.path().join("dst"));
+        std::fs::create_dir_all(src.join("a")).unwrap();
+        std::fs::write(src.join("a/b.txt"), "b").unwrap();
+
+        let plan = MirrorPlan::new(&src, &dst).delete_extra(true);
+        let to = dst.join("a/b.txt");
+        let from = src.join("a/b.txt");
+        assert_eq!(
+            plan.scan().unwrap(),
+            [MirrorOp::Copy {
+                from: from.clone(),
+                to: to.clone()
+            }]
+        );
+
+        std::fs::create_dir_all(dst.join("a")).unwrap();
+        std::fs::copy(&from, &to).unwrap();
+        std::fs::write(dst.join("stale"), "").unwrap();
+        assert_eq!(
+            plan.scan().unwrap(),
+            [
+                MirrorOp::Skip { from, to },
+                MirrorOp::Delete(dst.join("stale"))
+            ]
+        );
+    }
+}
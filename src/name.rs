@@ -0,0 +1,248 @@
+//! Pieces of file names: stems and extensions.
+
+use std::path::Path;
+
+use crate::Platform;
+
+/// Splits a file name into its stem and compound extension at the first `.`, so
+/// `archive.tar.gz` gives `archive` and `.tar.gz`. A leading `.` belongs to the stem.
+#[cfg(any(feature = "slug", feature = "translit"))]
+pub(crate) fn split_extensions(name: &str) -> (&str, &str) {
+    let split = name
+        .char_indices()
+        .skip(1)
+        .find(|(_, c)| *c == '.')
+        .map_or(name.len(), |(i, _)| i);
+    name.split_at(split)
+}
+
+/// Every compound extension of `name` without its `.`, longest first, so `a.tar.gz` gives `tar.gz`
+/// then `gz`. A leading `.` belongs to the stem.
+pub(crate) fn extension_suffixes(name: &str) -> impl Iterator<Item = &str> {
+    name.char_indices()
+        .skip(1)
+        .filter(|&(_, c)| c == '.')
+        .map(move |(dot, _)| &name[dot + 1..])
+}
+
+/// Whether `name` ends in the extensions `pattern` (with or without its leading `.`) right after a
+/// `.` that follows a non-empty stem, so neither `z` nor `.bashrc` count for `.gz` or `bashrc`.
+pub(crate) fn has_extensions_exact(name: &str, pattern: &str) -> bool {
+    let pattern = pattern.strip_prefix('.').unwrap_or(pattern);
+    if pattern.is_empty() {
+        return false;
+    }
+    name.strip_suffix(pattern)
+        .and_then(|rest| rest.strip_suffix('.'))
+        .is_some_and(|stem| !stem.is_empty())
+}
+
+/// Whether the final `extension`, as [`Path::extension`](std::path::Path::extension) gives it, is
+/// `wanted` with or without its leading `.`.
+pub(crate) fn extension_is(extension: Option<&str>, wanted: &str, ignore_case: bool) -> bool {
+    let wanted = wanted.strip_prefix('.').unwrap_or(wanted);
+    extension.is_some_and(|extension| {
+        if ignore_case {
+            extension.eq_ignore_ascii_case(wanted)
+        } else {
+            extension == wanted
+        }
+    })
+}
+
+/// What counts as an extension for [`PathStrExt::strip_extensions_opts`](crate::PathStrExt::strip_extensions_opts).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct StripOptions {
+    /// Whether a leading `.` is part of the stem, so `.bashrc` has no extension and `.config.toml` has one.
+    pub leading_dot_is_stem: bool,
+    /// Only strip this many of the last extensions, `Some(1)` turning `a.tar.gz` into `a.tar`.
+    pub max_extensions: Option<usize>,
+    /// Whether trailing dots, as in `file.`, are dropped. Otherwise they're kept and don't start an extension.
+    pub trim_trailing_dots: bool,
+    /// Whose separators end the directories before the file name, so `\` does for [`Platform::Windows`].
+    pub platform: Platform,
+}
+
+impl Default for StripOptions {
+    fn default() -> Self {
+        StripOptions {
+            leading_dot_is_stem: true,
+            max_extensions: None,
+            trim_trailing_dots: false,
+            platform: Platform::Native,
+        }
+    }
+}
+
+/// Where the stem of the file name ends under `options`, as a byte offset.
+pub(crate) fn stem_end(name: &str, options: &StripOptions) -> usize {
+    let trimmed = name.trim_end_matches('.');
+    if trimmed.is_empty() {
+        // `.`, `..` and the like are all stem
+        return name.len();
+    }
+    let body = if options.trim_trailing_dots {
+        trimmed
+    } else {
+        name
+    };
+    let skip = usize::from(options.leading_dot_is_stem && body.starts_with('.'));
+    let dots: Vec<usize> = body[skip..]
+        .match_indices('.')
+        .map(|(i, _)| i + skip)
+        .filter(|&i| i + 1 < body.len())
+        .collect();
+    let cut = match options.max_extensions {
+        Some(n) => dots
+            .len()
+            .checked_sub(n)
+            .map_or(dots.first(), |i| dots.get(i)),
+        None => dots.first(),
+    };
+    cut.copied().unwrap_or(body.len())
+}
+
+/// Everything before the first `.` of the whole path string, `None` if it isn't Unicode.
+pub(crate) fn strip_extensions(path: &Path) -> Option<&str> {
+    let path = path.to_str()?;
+    Some(path.split_once('.').map_or(path, |(base, _)| base))
+}
+
+/// The path string up to where [`stem_end`] puts the end of the file name's stem.
+pub(crate) fn strip_extensions_opts<'a>(path: &'a Path, options: &StripOptions) -> Option<&'a str> {
+    let path = path.to_str()?;
+    let is_separator = |c: char| options.platform.is_separator(c);
+    let trimmed = path.trim_end_matches(is_separator);
+    let name_start = trimmed.rfind(is_separator).map_or(0, |i| i + 1);
+    Some(&path[..name_start + stem_end(&trimmed[name_start..], options)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{extension_is, extension_suffixes, has_extensions_exact, stem_end, StripOptions};
+
+    #[cfg(any(feature = "slug", feature = "translit"))]
+    #[test]
+    fn test_split_extensions() {
+        use super::split_extensions;
+
+        let tests = &[
+            ("archive.tar.gz", ("archive", ".tar.gz")),
+            (".bashrc", (".bashrc", "")),
+            (".config.toml", (".config", ".toml")),
+            ("README", ("README", "")),
+            ("日本.txt", ("日本", ".txt")),
+            ("", ("", "")),
+        ];
+
+        for test in tests {
+            assert_eq!(split_extensions(test.0), test.1);
+        }
+    }
+
+    #[test]
+    fn test_extension_suffixes() {
+        let tests: &[(&str, &[&str])] = &[
+            ("archive.tar.gz", &["tar.gz", "gz"]),
+            (".bashrc", &[]),
+            (".config.toml", &["toml"]),
+            ("trailing.", &[""]),
+            ("README", &[]),
+        ];
+
+        for test in tests {
+            assert_eq!(extension_suffixes(test.0).collect::<Vec<_>>(), test.1);
+        }
+    }
+
+    #[test]
+    fn test_has_extensions_exact() {
+        let tests = &[
+            ("archive.tar.gz", ".tar.gz", true),
+            ("archive.tar.gz", "tar.gz", true),
+            ("archive.tar.gz", ".gz", true),
+            ("archive.tar.gz", "z", false),
+            ("archive.tar.gz", "ar.gz", false),
+            ("archive.tar.gz", "archive.tar.gz", false),
+            (".gz", "gz", false),
+            (".bashrc", "bashrc", false),
+            (".config.toml", "toml", true),
+            ("file.", "", false),
+            ("file.gz", ".", false),
+            ("名前.текст", "текст", true),
+        ];
+
+        for test in tests {
+            assert_eq!(
+                has_extensions_exact(test.0, test.1),
+                test.2,
+                "{} {}",
+                test.0,
+                test.1
+            );
+        }
+    }
+
+    #[test]
+    fn test_extension_is() {
+        let tests = &[
+            (Some("gz"), "gz", false, true),
+            (Some("gz"), ".gz", false, true),
+            (Some("GZ"), ".gz", false, false),
+            (Some("GZ"), ".gz", true, true),
+            (Some("gz"), "tar.gz", false, false),
+            (Some("gz"), "..gz", false, false),
+            (None, "gz", true, false),
+        ];
+
+        for test in tests {
+            assert_eq!(extension_is(test.0, test.1, test.2), test.3, "{test:?}");
+        }
+    }
+
+    #[test]
+    fn test_stem_end() {
+        let default = StripOptions::default();
+        let old = StripOptions {
+            leading_dot_is_stem: false,
+            ..default
+        };
+        let last = StripOptions {
+            max_extensions: Some(1),
+            ..default
+        };
+        let none = StripOptions {
+            max_extensions: Some(0),
+            ..default
+        };
+        let trim = StripOptions {
+            trim_trailing_dots: true,
+            ..default
+        };
+        let tests = &[
+            ("archive.tar.gz", default, "archive"),
+            ("archive.tar.gz", last, "archive.tar"),
+            ("archive.tar.gz", none, "archive.tar.gz"),
+            (".bashrc", default, ".bashrc"),
+            (".bashrc", old, ""),
+            (".config.toml", default, ".config"),
+            ("file.", default, "file."),
+            ("file.", trim, "file"),
+            ("file.tar.gz..", trim, "file"),
+            ("...", trim, "..."),
+            ("..", default, ".."),
+            ("日本.テキスト.txt", last, "日本.テキスト"),
+            ("README", default, "README"),
+        ];
+
+        for test in tests {
+            assert_eq!(
+                &test.0[..stem_end(test.0, &test.1)],
+                test.2,
+                "{} {:?}",
+                test.0,
+                test.1
+            );
+        }
+    }
+}
@@ -0,0 +1,220 @@
+//! Checking a name before creating a file or directory with it.
+
+use std::error::Error;
+use std::ffi::OsStr;
+use std::fmt;
+
+use crate::sanitize::is_windows_reserved;
+use crate::{LengthLimits, Platform};
+
+/// Why [`validate_new_name`] rejected a name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NameError {
+    /// No name at all.
+    Empty,
+    /// `.` or `..`, which already name the directory itself and its parent.
+    CurrentOrParent,
+    /// A NUL byte, which ends the name for the OS.
+    Nul,
+    /// `/`, or on Windows also `\`, which would make the name a path.
+    Separator { character: char },
+    /// A character Windows doesn't allow in names: `<>:"|?*` or a control character.
+    InvalidCharacter { character: char },
+    /// A trailing `.` or space, which Windows silently drops, creating a different name.
+    TrailingDotOrSpace,
+    /// A Windows device name like `CON` or `lpt1.txt`, which opens the device instead.
+    Reserved,
+    /// Longer than the platform's limit for a single name, in its units.
+    TooLong { length: usize, max: usize },
+}
+
+impl fmt::Display for NameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NameError::Empty => write!(f, "the name is empty"),
+            NameError::CurrentOrParent => {
+                write!(f, "`.` and `..` name existing directories")
+            }
+            NameError::Nul => write!(f, "the name contains a NUL byte"),
+            NameError::Separator { character } => {
+                write!(f, "the name contains the separator {character:?}")
+            }
+            NameError::InvalidCharacter { character } => {
+                write!(
+                    f,
+                    "the name contains {character:?}, which Windows doesn't allow"
+                )
+            }
+            NameError::TrailingDotOrSpace => {
+                write!(f, "Windows drops the trailing dot or space")
+            }
+            NameError::Reserved => write!(f, "the name is a Windows device name"),
+            NameError::TooLong { length, max } => {
+                write!(f, "the name is {length} long, over the limit of {max}")
+            }
+        }
+    }
+}
+
+impl Error for NameError {}
+
+/// Checks `name` can be given to a new file or directory on this platform, as
+/// [`validate_new_name_for`] does.
+pub fn validate_new_name(name: &OsStr) -> Result<(), NameError> {
+    validate_new_name_for(name, Platform::Native)
+}
+
+/// Checks `name` can be given to a new file or directory under `platform`'s rules: not empty,
+/// `.` or `..`, with no NUL or separator, and within [`LengthLimits::for_platform`]. Windows adds
+/// its forbidden characters, trailing dots and spaces, and device names. Problems are reported in
+/// that order, the first one found.
+///
+/// ```rust
+/// use pathext::{validate_new_name_for, NameError, Platform};
+/// use std::ffi::OsStr;
+///
+/// assert_eq!(validate_new_name_for(OsStr::new("notes.txt"), Platform::Windows), Ok(()));
+/// assert_eq!(
+///     validate_new_name_for(OsStr::new("a:b"), Platform::Windows),
+///     Err(NameError::InvalidCharacter { character: ':' })
+/// );
+/// assert_eq!(validate_new_name_for(OsStr::new("a:b"), Platform::Unix), Ok(()));
+/// ```
+pub fn validate_new_name_for(name: &OsStr, platform: Platform) -> Result<(), NameError> {
+    if name.is_empty() {
+        return Err(NameError::Empty);
+    }
+    if name == "." || name == ".." {
+        return Err(NameError::CurrentOrParent);
+    }
+    if name.as_encoded_bytes().contains(&0) {
+        return Err(NameError::Nul);
+    }
+    let windows = platform.is_windows();
+    let lossy = name.to_string_lossy();
+    for character in lossy.chars() {
+        if platform.is_separator(character) {
+            return Err(NameError::Separator { character });
+        }
+        if windows
+            && (character.is_control()
+                || matches!(character, '<' | '>' | ':' | '"' | '|' | '?' | '*'))
+        {
+            return Err(NameError::InvalidCharacter { character });
+        }
+    }
+    if windows {
+        if lossy.ends_with(['.', ' ']) {
+            return Err(NameError::TrailingDotOrSpace);
+        }
+        if is_windows_reserved(&lossy) {
+            return Err(NameError::Reserved);
+        }
+    }
+    let limits = LengthLimits::for_platform(platform);
+    let length = limits.unit.of_os_str(name);
+    if length > limits.max_component {
+        return Err(NameError::TooLong {
+            length,
+            max: limits.max_component,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{validate_new_name, validate_new_name_for, NameError};
+    use crate::Platform;
+
+    use std::ffi::OsStr;
+
+    #[test]
+    fn test_validate_new_name_for() {
+        let long = "x".repeat(256);
+        let tests = &[
+            ("notes.txt", Ok(()), Ok(())),
+            (".hidden", Ok(()), Ok(())),
+            ("", Err(NameError::Empty), Err(NameError::Empty)),
+            (
+                "..",
+                Err(NameError::CurrentOrParent),
+                Err(NameError::CurrentOrParent),
+            ),
+            ("a\0b", Err(NameError::Nul), Err(NameError::Nul)),
+            (
+                "a/b",
+                Err(NameError::Separator { character: '/' }),
+                Err(NameError::Separator { character: '/' }),
+            ),
+            (
+                r"a\b",
+                Ok(()),
+                Err(NameError::Separator { character: '\\' }),
+            ),
+            (
+                "what?",
+                Ok(()),
+                Err(NameError::InvalidCharacter { character: '?' }),
+            ),
+            (
+                "tab\there",
+                Ok(()),
+                Err(NameError::InvalidCharacter { character: '\t' }),
+            ),
+            ("name.", Ok(()), Err(NameError::TrailingDotOrSpace)),
+            ("name ", Ok(()), Err(NameError::TrailingDotOrSpace)),
+            ("lpt1.txt", Ok(()), Err(NameError::Reserved)),
+            (
+                long.as_str(),
+                Err(NameError::TooLong {
+                    length: 256,
+                    max: 255,
+                }),
+                Err(NameError::TooLong {
+                    length: 256,
+                    max: 255,
+                }),
+            ),
+        ];
+
+        for test in tests {
+            let name = OsStr::new(test.0);
+            assert_eq!(
+                validate_new_name_for(name, Platform::Unix),
+                test.1,
+                "{:?}",
+                test.0
+            );
+            assert_eq!(
+                validate_new_name_for(name, Platform::Windows),
+                test.2,
+                "{:?}",
+                test.0
+            );
+        }
+
+        // a name Unix counts in bytes and Windows in UTF-16 units
+        let wide = "é".repeat(200);
+        assert_eq!(
+            validate_new_name_for(OsStr::new(&wide), Platform::Unix),
+            Err(NameError::TooLong {
+                length: 400,
+                max: 255
+            })
+        );
+        assert_eq!(
+            validate_new_name_for(OsStr::new(&wide), Platform::Windows),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_validate_new_name() {
+        assert_eq!(validate_new_name(OsStr::new("report.pdf")), Ok(()));
+        assert_eq!(
+            validate_new_name(OsStr::new(".")),
+            Err(NameError::CurrentOrParent)
+        );
+    }
+}
@@ -0,0 +1,218 @@
+//! Lexical normalization: resolving `.` and `..` without touching the filesystem.
+
+use std::path::{Component, Path, PathBuf, Prefix, MAIN_SEPARATOR_STR};
+
+/// How `..` that climbs past the start of a path is treated during normalization.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum NormalizeStyle {
+    /// Leading `..` in a relative path is kept, since it refers to a real parent directory.
+    #[default]
+    Filesystem,
+    /// RFC 3986 remove-dot-segments: `..` that would climb past the start disappears.
+    Uri,
+}
+
+/// Collapses `.`, `..` and redundant separators from the path.
+///
+/// `..` directly under a root is dropped since there is nothing above the root, while leading
+/// `..` in a relative path is kept. Verbatim (`\\?\`) paths are returned untouched because
+/// Windows passes them through without any normalization of its own. A separator is only written
+/// for a root the path spells out, so the implicit root of a bare UNC share (`\\server\share`)
+/// doesn't gain a trailing one.
+pub(crate) fn normalize(path: &Path) -> PathBuf {
+    normalize_with_style(path, NormalizeStyle::Filesystem)
+}
+
+/// Whether [`normalize`] gives `path` back byte for byte.
+pub(crate) fn is_normalized(path: &Path) -> bool {
+    normalize(path).as_os_str() == path.as_os_str()
+}
+
+/// [`normalize`], with `style` deciding what happens to leading `..` in relative paths.
+pub(crate) fn normalize_with_style(path: &Path, style: NormalizeStyle) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    normalize_into(path, style, &mut normalized);
+    normalized
+}
+
+/// [`normalize_with_style`] writing into `buf`, which is cleared first, so a caller looping over
+/// many paths can keep reusing its allocation.
+pub(crate) fn normalize_into(path: &Path, style: NormalizeStyle, buf: &mut PathBuf) {
+    buf.as_mut_os_string().clear();
+    let mut has_root = false;
+    let mut prefix_len = 0;
+    // normal components in `buf`, which `..` may pop, after any `..` that had nothing to pop
+    let mut depth = 0;
+
+    for component in path.components() {
+        match component {
+            Component::Prefix(p) => {
+                if matches!(
+                    p.kind(),
+                    Prefix::Verbatim(_) | Prefix::VerbatimUNC(..) | Prefix::VerbatimDisk(_)
+                ) {
+                    buf.as_mut_os_string().clear();
+                    buf.as_mut_os_string().push(path);
+                    return;
+                }
+                buf.as_mut_os_string().push(p.as_os_str());
+                prefix_len = p.as_os_str().len();
+            }
+            Component::RootDir => {
+                has_root = true;
+                // a UNC prefix implies a root even when no separator follows it
+                let spelled_out = path.as_os_str().as_encoded_bytes().get(prefix_len);
+                if spelled_out.is_some_and(|&b| std::path::is_separator(char::from(b))) {
+                    buf.as_mut_os_string().push(MAIN_SEPARATOR_STR);
+                }
+            }
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if depth > 0 {
+                    buf.pop();
+                    depth -= 1;
+                } else if !has_root && style == NormalizeStyle::Filesystem {
+                    buf.push("..");
+                }
+            }
+            Component::Normal(part) => {
+                buf.push(part);
+                depth += 1;
+            }
+        }
+    }
+
+    if buf.as_os_str().is_empty() && !path.as_os_str().is_empty() {
+        buf.push(".");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_normalized, normalize, normalize_into, normalize_with_style, NormalizeStyle};
+
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn test_normalize() {
+        let tests = &[
+            ("/opt/./foo/../bar", "/opt/bar"),
+            ("/opt//foo/", "/opt/foo"),
+            ("/../foo", "/foo"),
+            ("/..", "/"),
+            ("a/b/../../..", ".."),
+            ("../../a/../b", "../../b"),
+            ("./a", "a"),
+            ("a/..", "."),
+            (".", "."),
+            ("", ""),
+        ];
+
+        for test in tests {
+            assert_eq!(normalize(Path::new(test.0)), Path::new(test.1));
+        }
+    }
+
+    #[test]
+    fn test_is_normalized() {
+        let tests = &[
+            ("/opt/bar", true),
+            ("/", true),
+            ("a", true),
+            ("../../a", true),
+            (".", true),
+            ("", true),
+            ("/opt/./bar", false),
+            ("/opt/bar/", false),
+            ("/opt//bar", false),
+            ("/..", false),
+            ("./a", false),
+            ("a/..", false),
+        ];
+
+        for test in tests {
+            assert_eq!(is_normalized(Path::new(test.0)), test.1, "{}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_normalize_into() {
+        let mut buf = PathBuf::from("/left/over/from/before");
+        let tests = &[
+            ("/opt/./foo/../bar", "/opt/bar"),
+            ("a/b/../../..", ".."),
+            ("", ""),
+            ("x/../y", "y"),
+        ];
+
+        for test in tests {
+            normalize_into(Path::new(test.0), NormalizeStyle::Filesystem, &mut buf);
+            assert_eq!(buf, Path::new(test.1), "{}", test.0);
+        }
+
+        let capacity = buf.capacity();
+        normalize_into(Path::new("/a/b"), NormalizeStyle::Filesystem, &mut buf);
+        assert_eq!(buf.capacity(), capacity);
+        normalize_into(Path::new("../a"), NormalizeStyle::Uri, &mut buf);
+        assert_eq!(buf, Path::new("a"));
+    }
+
+    #[test]
+    fn test_normalize_uri_style() {
+        // RFC 3986 section 5.4 examples, as paths
+        let tests = &[
+            ("/a/b/c/./../../g", "/a/g"),
+            ("mid/content=5/../6", "mid/6"),
+            ("../g", "g"),
+            ("../../../g", "g"),
+            ("a/../../b/./c", "b/c"),
+            ("/../g", "/g"),
+            ("..", "."),
+        ];
+
+        for test in tests {
+            assert_eq!(
+                normalize_with_style(Path::new(test.0), NormalizeStyle::Uri),
+                Path::new(test.1)
+            );
+        }
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_normalize_windows() {
+        let tests = &[
+            (r"C:\a\..\b", r"C:\b"),
+            (r"C:\..\b", r"C:\b"),
+            (r"C:..\b", r"C:..\b"),
+            (r"C:a\.\b", r"C:a\b"),
+            (r"\\server\share\a\..\b", r"\\server\share\b"),
+            (r"\\server\share", r"\\server\share"),
+            (r"\\server\share\", r"\\server\share\"),
+            (r"\\?\C:\a\..\b", r"\\?\C:\a\..\b"),
+            ("C:/a/./b", r"C:\a\b"),
+        ];
+
+        for test in tests {
+            assert_eq!(normalize(Path::new(test.0)), Path::new(test.1));
+        }
+
+        let tests = &[
+            (r"C:", true),
+            (r"C:a", true),
+            (r"C:\a\b", true),
+            // the share's root is implicit, so no separator is added for it
+            (r"\\server\share", true),
+            (r"\\server\share\b", true),
+            (r"\\?\C:\..", true),
+            ("C:/a/b", false),
+            (r"C:\.", false),
+            (r"C:\a\b\", false),
+            (r"\\server\share\a\..\b", false),
+        ];
+
+        for test in tests {
+            assert_eq!(is_normalized(Path::new(test.0)), test.1, "{}", test.0);
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! Turning paths into object store keys, and checking keys against each store's rules.
+
+use std::path::{Component, Path};
+
+/// An object store whose key rules [`is_object_key_safe`](crate::PathComponentExt::is_object_key_safe) checks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ObjectStore {
+    /// Amazon S3 and stores compatible with it: keys up to 1024 bytes, avoiding the characters AWS
+    /// lists as likely to need special handling, like `\ { } ^ % [ ] ~ # | < > "` and `` ` ``.
+    S3,
+    /// Google Cloud Storage: names up to 1024 bytes, no `.` or `..`, nothing under
+    /// `.well-known/acme-challenge/`, and none of `# [ ] * ?`.
+    Gcs,
+    /// Azure Blob Storage: names up to 1024 characters in at most 254 segments, not ending in `.`,
+    /// and no `\`, which Azure turns into `/`.
+    AzureBlob,
+}
+
+impl ObjectStore {
+    fn fits_key_length(self, key: &str) -> bool {
+        match self {
+            ObjectStore::S3 | ObjectStore::Gcs => key.len() <= 1024,
+            ObjectStore::AzureBlob => key.chars().count() <= 1024,
+        }
+    }
+
+    fn is_special(self, c: char) -> bool {
+        if c.is_control() {
+            return true;
+        }
+        match self {
+            ObjectStore::S3 => matches!(
+                c,
+                '\\' | '{' | '}' | '^' | '%' | '`' | '[' | ']' | '"' | '<' | '>' | '~' | '#' | '|'
+            ),
+            ObjectStore::Gcs => matches!(c, '#' | '[' | ']' | '*' | '?'),
+            ObjectStore::AzureBlob => c == '\\',
+        }
+    }
+
+    pub(crate) fn allows(self, key: &str) -> bool {
+        if key.is_empty() || !self.fits_key_length(key) || key.chars().any(|c| self.is_special(c)) {
+            return false;
+        }
+        match self {
+            ObjectStore::S3 => true,
+            ObjectStore::Gcs => {
+                key != "." && key != ".." && !key.starts_with(".well-known/acme-challenge/")
+            }
+            ObjectStore::AzureBlob => key.split('/').count() <= 254 && !key.ends_with('.'),
+        }
+    }
+}
+
+/// The normal components joined with `/`, dropping roots, prefixes and `.`. `None` if a component
+/// isn't UTF-8, there is a `..`, or nothing is left.
+pub(crate) fn to_object_key(path: &Path) -> Option<String> {
+    let mut key = String::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(name) => {
+                if !key.is_empty() {
+                    key.push('/');
+                }
+                key.push_str(name.to_str()?);
+            }
+            Component::ParentDir => return None,
+            Component::Prefix(_) | Component::RootDir | Component::CurDir => {}
+        }
+    }
+    if key.is_empty() {
+        None
+    } else {
+        Some(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{to_object_key, ObjectStore};
+
+    use std::path::Path;
+
+    #[test]
+    fn test_to_object_key() {
+        let tests = &[
+            (
+                "/home/me/photos/2024/beach.jpg",
+                Some("home/me/photos/2024/beach.jpg"),
+            ),
+            ("./backups//daily/", Some("backups/daily")),
+            ("a/../b", None),
+            ("/", None),
+            ("", None),
+        ];
+
+        for test in tests {
+            assert_eq!(
+                to_object_key(Path::new(test.0)).as_deref(),
+                test.1,
+                "{}",
+                test.0
+            );
+        }
+    }
+
+    #[test]
+    fn test_allows() {
+        let long = "k".repeat(1025);
+        let deep = "d/".repeat(254) + "f";
+        let tests = &[
+            ("photos/beach.jpg", true, true, true),
+            ("notes/50%.txt", false, true, true),
+            ("what?.txt", true, false, true),
+            ("back\\slash", false, true, false),
+            ("ends.with.dot.", true, true, false),
+            ("..", true, false, false),
+            (".well-known/acme-challenge/token", true, false, true),
+            ("tab\there", false, false, false),
+            (long.as_str(), false, false, false),
+            (&long[1..], true, true, true),
+            (deep.as_str(), true, true, false),
+            (&deep[2..], true, true, true),
+        ];
+
+        for test in tests {
+            assert_eq!(ObjectStore::S3.allows(test.0), test.1, "S3 {}", test.0);
+            assert_eq!(ObjectStore::Gcs.allows(test.0), test.2, "GCS {}", test.0);
+            assert_eq!(
+                ObjectStore::AzureBlob.allows(test.0),
+                test.3,
+                "Azure {}",
+                test.0
+            );
+        }
+    }
+}
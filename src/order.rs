@@ -0,0 +1,130 @@
+//! Orderings for listing paths the way people expect to read them.
+
+use std::cmp::Ordering;
+use std::path::Path;
+
+/// Compares one component naturally: digit runs by numeric value, everything else case-insensitively.
+fn natural_cmp_str(a: &str, b: &str) -> Ordering {
+    let mut a = a.chars().peekable();
+    let mut b = b.chars().peekable();
+    loop {
+        match (a.peek().copied(), b.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let x = take_digits(&mut a);
+                let y = take_digits(&mut b);
+                let x_trimmed = x.trim_start_matches('0');
+                let y_trimmed = y.trim_start_matches('0');
+                let ordering = x_trimmed
+                    .len()
+                    .cmp(&y_trimmed.len())
+                    .then_with(|| x_trimmed.cmp(y_trimmed))
+                    .then_with(|| x.len().cmp(&y.len()));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let ordering = x.to_lowercase().cmp(y.to_lowercase());
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                a.next();
+                b.next();
+            }
+        }
+    }
+}
+
+fn take_digits(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) -> String {
+    let mut digits = String::new();
+    while let Some(c) = chars.next_if(|c| c.is_ascii_digit()) {
+        digits.push(c);
+    }
+    digits
+}
+
+/// Natural order component by component, falling back to plain path order so only equal paths compare equal.
+pub(crate) fn natural_cmp(a: &Path, b: &Path) -> Ordering {
+    let mut x = a.components();
+    let mut y = b.components();
+    let ordering = loop {
+        match (x.next(), y.next()) {
+            (None, None) => break Ordering::Equal,
+            (None, Some(_)) => break Ordering::Less,
+            (Some(_), None) => break Ordering::Greater,
+            (Some(c), Some(d)) => {
+                let ordering = natural_cmp_str(
+                    &c.as_os_str().to_string_lossy(),
+                    &d.as_os_str().to_string_lossy(),
+                );
+                if ordering != Ordering::Equal {
+                    break ordering;
+                }
+            }
+        }
+    };
+    ordering.then_with(|| a.cmp(b))
+}
+
+/// Directories before everything else, each group in natural order.
+pub(crate) fn cmp_dirs_first<F: Fn(&Path) -> bool>(a: &Path, b: &Path, is_dir: F) -> Ordering {
+    is_dir(b).cmp(&is_dir(a)).then_with(|| natural_cmp(a, b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cmp_dirs_first, natural_cmp, natural_cmp_str};
+
+    use std::cmp::Ordering;
+    use std::path::Path;
+
+    #[test]
+    fn test_natural_cmp_str() {
+        let tests = &[
+            ("file2", "file10", Ordering::Less),
+            ("file10", "file9", Ordering::Greater),
+            ("File1", "file2", Ordering::Less),
+            ("a", "B", Ordering::Less),
+            ("v1.10", "v1.9", Ordering::Greater),
+            ("007", "7", Ordering::Greater),
+            ("x", "x", Ordering::Equal),
+            ("x", "x1", Ordering::Less),
+        ];
+
+        for test in tests {
+            assert_eq!(
+                natural_cmp_str(test.0, test.1),
+                test.2,
+                "{} {}",
+                test.0,
+                test.1
+            );
+        }
+    }
+
+    #[test]
+    fn test_natural_cmp() {
+        let mut paths = vec!["img12.png", "img10.png", "IMG2.png", "img1/x", "img1"];
+        paths.sort_by(|a, b| natural_cmp(Path::new(a), Path::new(b)));
+        assert_eq!(
+            paths,
+            ["img1", "img1/x", "IMG2.png", "img10.png", "img12.png"]
+        );
+        assert_ne!(natural_cmp(Path::new("A"), Path::new("a")), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_cmp_dirs_first() {
+        let dirs = ["src", "docs10", "docs9"];
+        let is_dir = |p: &Path| dirs.iter().any(|d| p == Path::new(d));
+        let mut listing = vec!["b.txt", "src", "a10.txt", "docs10", "a9.txt", "docs9"];
+        listing.sort_by(|a, b| cmp_dirs_first(Path::new(a), Path::new(b), is_dir));
+        assert_eq!(
+            listing,
+            ["docs9", "docs10", "src", "a9.txt", "a10.txt", "b.txt"]
+        );
+    }
+}
@@ -0,0 +1,126 @@
+//! Layered lookups over several root directories, as mod loaders and config layering do.
+
+use std::path::{Component, Path, PathBuf};
+
+/// An ordered stack of root directories where a relative path means the first root that has it.
+///
+/// ```rust
+/// use pathext::OverlayMap;
+/// use std::path::PathBuf;
+///
+/// let overlay = OverlayMap::new(["mods/hd-textures", "mods/fixes", "base"]);
+/// assert_eq!(
+///     overlay.candidates("textures/wall.png"),
+///     [
+///         PathBuf::from("mods/hd-textures/textures/wall.png"),
+///         PathBuf::from("mods/fixes/textures/wall.png"),
+///         PathBuf::from("base/textures/wall.png"),
+///     ]
+/// );
+/// assert!(overlay.candidates("../../escape").is_empty());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OverlayMap {
+    roots: Vec<PathBuf>,
+}
+
+/// `rel` with `.` and `..` resolved, or `None` if it's absolute or climbs out of its root.
+fn contained(rel: &Path) -> Option<PathBuf> {
+    let mut parts = Vec::new();
+    for component in rel.components() {
+        match component {
+            Component::Normal(name) => parts.push(name),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                parts.pop()?;
+            }
+            Component::Prefix(_) | Component::RootDir => return None,
+        }
+    }
+    Some(parts.iter().collect())
+}
+
+impl OverlayMap {
+    /// An overlay of `roots`, the first taking precedence over the rest.
+    pub fn new<I, P>(roots: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        OverlayMap {
+            roots: roots.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Adds a root that takes precedence over all the others.
+    pub fn push_front<P: Into<PathBuf>>(&mut self, root: P) {
+        self.roots.insert(0, root.into());
+    }
+
+    /// Adds a root that only fills in what the others lack.
+    pub fn push_back<P: Into<PathBuf>>(&mut self, root: P) {
+        self.roots.push(root.into());
+    }
+
+    pub fn roots(&self) -> &[PathBuf] {
+        &self.roots
+    }
+
+    /// Where `rel` could be, in order of precedence, without looking at the disk. Empty if `rel` is
+    /// absolute or `..` takes it out of the roots.
+    pub fn candidates<P: AsRef<Path>>(&self, rel: P) -> Vec<PathBuf> {
+        match contained(rel.as_ref()) {
+            Some(rel) => self.roots.iter().map(|root| root.join(&rel)).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// The first candidate for `rel` that exists.
+    #[cfg(feature = "fs")]
+    pub fn resolve<P: AsRef<Path>>(&self, rel: P) -> Option<PathBuf> {
+        self.candidates(rel)
+            .into_iter()
+            .find(|candidate| candidate.exists())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OverlayMap;
+
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_candidates() {
+        let mut overlay = OverlayMap::new(["user", "system"]);
+        overlay.push_front("override");
+        overlay.push_back("defaults");
+        let all = |rel: &str| -> Vec<PathBuf> {
+            ["override", "user", "system", "defaults"]
+                .iter()
+                .map(|root| PathBuf::from(root).join(rel))
+                .collect()
+        };
+        let tests = &[
+            ("app.toml", all("app.toml")),
+            ("./conf.d/../app.toml", all("app.toml")),
+            ("", all("")),
+            ("../app.toml", vec![]),
+            ("/etc/app.toml", vec![]),
+        ];
+
+        for test in tests {
+            assert_eq!(overlay.candidates(test.0), test.1, "{}", test.0);
+        }
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn test_resolve() {
+        let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let overlay = OverlayMap::new([root.join("no-such-layer"), root.join("src"), root.clone()]);
+        assert_eq!(overlay.resolve("lib.rs"), Some(root.join("src/lib.rs")));
+        assert_eq!(overlay.resolve("Cargo.toml"), Some(root.join("Cargo.toml")));
+        assert_eq!(overlay.resolve("missing.rs"), None);
+    }
+}
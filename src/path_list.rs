@@ -0,0 +1,91 @@
+//! Splitting lists of paths written the way a POSIX shell splits words.
+
+use std::path::PathBuf;
+
+/// Splits a whitespace-separated list of paths as a POSIX shell splits words, without expanding
+/// anything: `'...'` keeps everything literally, `"..."` keeps everything but `\` before `"`, `\`,
+/// `$` or `` ` ``, and elsewhere `\` keeps the next character and a `\` ending a line joins the
+/// next one. That's how lists appear in `.desktop` files, Makefiles and variables like `CFLAGS`.
+///
+/// An unclosed quote runs to the end, and words that are empty, like `''`, are dropped. Since `\`
+/// escapes, Windows paths need single quotes. Lists separated by `:` or `;`, like `PATH`, are for
+/// [`std::env::split_paths`].
+///
+/// ```rust
+/// use pathext::split_path_list;
+/// use std::path::PathBuf;
+///
+/// let paths = split_path_list(r#"src/main.c "My Documents/a.c" 'C:\temp\b.c' with\ space.c"#);
+/// assert_eq!(
+///     paths,
+///     ["src/main.c", "My Documents/a.c", r"C:\temp\b.c", "with space.c"].map(PathBuf::from)
+/// );
+/// ```
+pub fn split_path_list(list: &str) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    let mut word = String::new();
+    let mut chars = list.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => word.extend(chars.by_ref().take_while(|&c| c != '\'')),
+            '"' => {
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => word.push(c),
+                            Some('\n') => {}
+                            Some(c) => word.extend(['\\', c]),
+                            None => word.push('\\'),
+                        },
+                        c => word.push(c),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some('\n') | None => {}
+                Some(c) => word.push(c),
+            },
+            c if c.is_whitespace() => {
+                if !word.is_empty() {
+                    paths.push(PathBuf::from(std::mem::take(&mut word)));
+                }
+            }
+            c => word.push(c),
+        }
+    }
+    if !word.is_empty() {
+        paths.push(PathBuf::from(word));
+    }
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::split_path_list;
+
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_split_path_list() {
+        let tests: &[(&str, &[&str])] = &[
+            ("", &[]),
+            ("  a  b\tc\n", &["a", "b", "c"]),
+            ("'a b' \"c d\"", &["a b", "c d"]),
+            (r"a\ b c\\d", &["a b", r"c\d"]),
+            (r#""say \"hi\" \n""#, &[r#"say "hi" \n"#]),
+            ("'it'\\''s'", &["it's"]),
+            ("pre'fix'\"ed\"", &["prefixed"]),
+            ("a \\\nb", &["a", "b"]),
+            ("long\\\nname", &["longname"]),
+            ("'' \"\" x", &["x"]),
+            ("'unclosed rest", &["unclosed rest"]),
+            ("ünï cödé", &["ünï", "cödé"]),
+        ];
+
+        for test in tests {
+            let expected: Vec<PathBuf> = test.1.iter().map(PathBuf::from).collect();
+            assert_eq!(split_path_list(test.0), expected, "{}", test.0);
+        }
+    }
+}
@@ -0,0 +1,172 @@
+//! PID files, written atomically and read back with a check that the process still runs.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::fs::{unique_child, with_path};
+
+/// What a PID file read by [`PathFsExt::read_pid_file`](crate::PathFsExt::read_pid_file) says.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PidFile {
+    pub pid: u32,
+    /// Whether a process with that id runs, `None` where that can't be asked. A running process may
+    /// still be another one that got the id after the writer exited.
+    pub running: Option<bool>,
+}
+
+impl PidFile {
+    /// Whether the process is known to be gone, so the file was left behind and can be replaced.
+    pub fn is_stale(&self) -> bool {
+        self.running == Some(false)
+    }
+}
+
+pub(crate) fn write_pid_file(path: &Path) -> io::Result<()> {
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let name = path.file_name().ok_or_else(|| {
+        let e = io::Error::new(io::ErrorKind::InvalidInput, "no file name");
+        with_path(e, "write the pid file", path)
+    })?;
+    // written beside it and renamed over it, so readers never see a partial file
+    let staging = unique_child(dir, &format!(".{}.", name.to_string_lossy()), ".tmp")?;
+    fs::write(&staging, format!("{}\n", std::process::id()))
+        .map_err(|e| with_path(e, "write", &staging))?;
+    fs::rename(&staging, path).map_err(|e| {
+        let _ = fs::remove_file(&staging);
+        with_path(e, "replace", path)
+    })
+}
+
+pub(crate) fn read_pid_file(path: &Path) -> io::Result<Option<PidFile>> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if crate::fs::is_missing(&e) => return Ok(None),
+        Err(e) => return Err(with_path(e, "read", path)),
+    };
+    let pid = contents
+        .trim()
+        .parse::<u32>()
+        .ok()
+        .filter(|&pid| pid != 0 && i32::try_from(pid).is_ok())
+        .ok_or_else(|| {
+            let e = io::Error::new(io::ErrorKind::InvalidData, "not a process id");
+            with_path(e, "read the pid file", path)
+        })?;
+    Ok(Some(PidFile {
+        pid,
+        running: is_running(pid),
+    }))
+}
+
+#[cfg(unix)]
+fn is_running(pid: u32) -> Option<bool> {
+    if pid == std::process::id() {
+        return Some(true);
+    }
+    // SAFETY: signal 0 only checks whether the process exists and may be signalled.
+    if unsafe { libc::kill(pid as libc::pid_t, 0) } == 0 {
+        return Some(true);
+    }
+    match io::Error::last_os_error().raw_os_error() {
+        Some(libc::EPERM) => Some(true),
+        Some(libc::ESRCH) => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(windows)]
+fn is_running(pid: u32) -> Option<bool> {
+    use std::ffi::c_void;
+
+    const PROCESS_QUERY_LIMITED_INFORMATION: u32 = 0x1000;
+    const STILL_ACTIVE: u32 = 259;
+    const ERROR_ACCESS_DENIED: i32 = 5;
+    const ERROR_INVALID_PARAMETER: i32 = 87;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn OpenProcess(access: u32, inherit: i32, pid: u32) -> *mut c_void;
+        fn GetExitCodeProcess(process: *mut c_void, code: *mut u32) -> i32;
+        fn CloseHandle(handle: *mut c_void) -> i32;
+    }
+
+    if pid == std::process::id() {
+        return Some(true);
+    }
+    // SAFETY: the handle is only used while open and closed once.
+    unsafe {
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if process.is_null() {
+            return match io::Error::last_os_error().raw_os_error() {
+                Some(ERROR_ACCESS_DENIED) => Some(true),
+                Some(ERROR_INVALID_PARAMETER) => Some(false),
+                _ => None,
+            };
+        }
+        let mut code = 0;
+        let ok = GetExitCodeProcess(process, &mut code);
+        CloseHandle(process);
+        (ok != 0).then_some(code == STILL_ACTIVE)
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn is_running(pid: u32) -> Option<bool> {
+    (pid == std::process::id()).then_some(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_pid_file, write_pid_file, PidFile};
+
+    use std::fs;
+    use std::io;
+
+    #[test]
+    fn test_pid_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("daemon.pid");
+        assert_eq!(read_pid_file(&path).unwrap(), None);
+
+        write_pid_file(&path).unwrap();
+        let pid_file = read_pid_file(&path).unwrap().unwrap();
+        assert_eq!(
+            pid_file,
+            PidFile {
+                pid: std::process::id(),
+                running: Some(true)
+            }
+        );
+        assert!(!pid_file.is_stale());
+
+        // replacing leaves nothing staged behind
+        write_pid_file(&path).unwrap();
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        let tests = &["", "abc", "0", "-1", "4294967295"];
+        for test in tests {
+            fs::write(&path, test).unwrap();
+            let e = read_pid_file(&path).unwrap_err();
+            assert_eq!(e.kind(), io::ErrorKind::InvalidData, "{test}");
+        }
+
+        assert!(write_pid_file(&dir.path().join("missing/daemon.pid")).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_stale_pid_file() {
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        let pid = child.id();
+        child.wait().unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("daemon.pid");
+        fs::write(&path, format!("{pid}\n")).unwrap();
+        assert!(read_pid_file(&path).unwrap().unwrap().is_stale());
+    }
+}
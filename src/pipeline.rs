@@ -0,0 +1,382 @@
+//! Chains of path transformations set up once and applied to many paths.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fmt;
+use std::mem;
+use std::path::{is_separator, Component, Path, PathBuf, MAIN_SEPARATOR};
+
+use crate::env::expand_vars;
+use crate::normalize::normalize_into;
+use crate::rebase::rebase_into;
+use crate::sanitize::sanitize_path_into;
+use crate::{CharPolicy, ExpansionContext, NormalizeStyle};
+
+type Custom = Box<dyn Fn(&Path) -> PathBuf + Send + Sync>;
+
+enum Step {
+    ExpandVars(HashMap<String, String>),
+    Normalize(NormalizeStyle),
+    Sanitize(CharPolicy),
+    Rebase { from: PathBuf, to: PathBuf },
+    Map(Custom),
+}
+
+impl fmt::Debug for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Step::ExpandVars(vars) => f.debug_tuple("ExpandVars").field(&vars.len()).finish(),
+            Step::Normalize(style) => f.debug_tuple("Normalize").field(style).finish(),
+            Step::Sanitize(policy) => f.debug_tuple("Sanitize").field(policy).finish(),
+            Step::Rebase { from, to } => f
+                .debug_struct("Rebase")
+                .field("from", from)
+                .field("to", to)
+                .finish(),
+            Step::Map(_) => f.write_str("Map"),
+        }
+    }
+}
+
+/// A sequence of transformations applied to each path in turn.
+///
+/// Everything a step needs, such as a snapshot of the environment or a character policy, is
+/// prepared when the pipeline is built, so running millions of paths through it only does the
+/// per-path work.
+///
+/// ```rust
+/// use pathext::{CharPolicy, PathPipeline};
+/// use std::collections::HashMap;
+/// use std::path::PathBuf;
+///
+/// let vars = HashMap::from([("OUT".to_string(), "/build/out".to_string())]);
+/// let pipeline = PathPipeline::new()
+///     .expand_vars_from(vars)
+///     .normalize()
+///     .sanitize_with(CharPolicy::posix_portable())
+///     .rebase("/build/out", "/srv/www");
+///
+/// assert_eq!(pipeline.apply("$OUT/docs/../My Page.html"), PathBuf::from("/srv/www/My_Page.html"));
+/// let all: Vec<_> = pipeline.apply_all(["$OUT/a", "$OUT/./b"]).collect();
+/// assert_eq!(all, [PathBuf::from("/srv/www/a"), PathBuf::from("/srv/www/b")]);
+/// ```
+#[derive(Debug, Default)]
+pub struct PathPipeline {
+    steps: Vec<Step>,
+}
+
+impl PathPipeline {
+    /// A pipeline that returns paths as they are, until steps are added.
+    pub fn new() -> Self {
+        PathPipeline::default()
+    }
+
+    /// Expands `$NAME`, `${NAME}` and `%NAME%` from the environment as it is now, leaving unknown
+    /// variables alone.
+    pub fn expand_env(self) -> Self {
+        self.expand_vars_from(ExpansionContext::from_process().vars)
+    }
+
+    /// Expands variable references like [`expand_env`](PathPipeline::expand_env), from `vars` instead.
+    pub fn expand_vars_from(mut self, vars: HashMap<String, String>) -> Self {
+        self.steps.push(Step::ExpandVars(vars));
+        self
+    }
+
+    /// Resolves `.` and `..` lexically, see [`PathComponentExt::normalize_with_style`](crate::PathComponentExt::normalize_with_style).
+    pub fn normalize(self) -> Self {
+        self.normalize_with_style(NormalizeStyle::Filesystem)
+    }
+
+    /// Resolves `.` and `..` lexically in the given style, see [`NormalizeStyle`].
+    pub fn normalize_with_style(mut self, style: NormalizeStyle) -> Self {
+        self.steps.push(Step::Normalize(style));
+        self
+    }
+
+    /// Sanitizes every component for Windows, see [`PathComponentExt::sanitize`](crate::PathComponentExt::sanitize).
+    pub fn sanitize(self) -> Self {
+        self.sanitize_with(CharPolicy::default())
+    }
+
+    /// Sanitizes every component following `policy`, see [`PathComponentExt::sanitize_with`](crate::PathComponentExt::sanitize_with).
+    pub fn sanitize_with(mut self, policy: CharPolicy) -> Self {
+        self.steps.push(Step::Sanitize(policy));
+        self
+    }
+
+    /// Moves paths under `from` to the same place under `to`, leaving other paths alone.
+    pub fn rebase<F: Into<PathBuf>, T: Into<PathBuf>>(mut self, from: F, to: T) -> Self {
+        self.steps.push(Step::Rebase {
+            from: from.into(),
+            to: to.into(),
+        });
+        self
+    }
+
+    /// Runs `f` on every path at this point of the pipeline.
+    pub fn map<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&Path) -> PathBuf + Send + Sync + 'static,
+    {
+        self.steps.push(Step::Map(Box::new(f)));
+        self
+    }
+
+    /// Runs `path` through every step in order. The built-in steps write into two buffers that
+    /// take turns, so a path is only allocated for again when it outgrows them.
+    pub fn apply<P: AsRef<Path>>(&self, path: P) -> PathBuf {
+        let mut path = path.as_ref().to_path_buf();
+        run_steps(&self.steps, &mut path, &mut PathBuf::new());
+        path
+    }
+
+    /// Lazily runs each path from `paths` through the pipeline.
+    pub fn apply_all<'a, I>(&'a self, paths: I) -> impl Iterator<Item = PathBuf> + 'a
+    where
+        I: IntoIterator + 'a,
+        I::Item: AsRef<Path>,
+    {
+        paths.into_iter().map(move |path| self.apply(path))
+    }
+
+    /// Runs every path through the pipeline, giving what [`apply`](PathPipeline::apply) would for
+    /// each, in order. The leading normalize, sanitize and rebase steps run once per directory rather
+    /// than once per path, which pays off for sorted listings where files of a directory follow each
+    /// other. Paths that don't end in a plain file name are run through in full.
+    pub fn transform_all<P: AsRef<Path>>(&self, paths: &[P]) -> Vec<PathBuf> {
+        let cached = self
+            .steps
+            .iter()
+            .take_while(|step| step.is_per_component())
+            .count();
+        let (cached, rest) = self.steps.split_at(cached);
+        let mut last_dir: Option<(&Path, Vec<PathBuf>)> = None;
+        let mut scratch = PathBuf::new();
+        paths
+            .iter()
+            .map(|path| {
+                let path = path.as_ref();
+                let transformed =
+                    split_file(path)
+                        .filter(|_| !cached.is_empty())
+                        .and_then(|(dir, name)| {
+                            let dirs = match &last_dir {
+                                Some((last, dirs)) if last.as_os_str() == dir.as_os_str() => dirs,
+                                _ => &last_dir.insert((dir, dir_values(cached, dir))).1,
+                            };
+                            join_transformed(cached, dirs, name)
+                        });
+                match transformed {
+                    Some(mut transformed) => {
+                        run_steps(rest, &mut transformed, &mut scratch);
+                        transformed
+                    }
+                    None => {
+                        let mut path = path.to_path_buf();
+                        run_steps(&self.steps, &mut path, &mut scratch);
+                        path
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+impl Step {
+    /// Whether the step takes `dir/name` to what it takes `dir` to with the name changed on its own,
+    /// the one exception being a rebase of exactly `dir/name`.
+    fn is_per_component(&self) -> bool {
+        matches!(
+            self,
+            Step::Normalize(_) | Step::Sanitize(_) | Step::Rebase { .. }
+        )
+    }
+
+    /// Writes what the step makes of `path` into `out`, replacing what was there.
+    fn apply(&self, path: &Path, out: &mut PathBuf) {
+        match self {
+            Step::ExpandVars(vars) => {
+                out.as_mut_os_string().clear();
+                match path.to_str() {
+                    Some(s) if s.contains(['$', '%']) => out
+                        .as_mut_os_string()
+                        .push(expand_vars(s, |name| vars.get(name).cloned())),
+                    _ => out.as_mut_os_string().push(path),
+                }
+            }
+            Step::Normalize(style) => normalize_into(path, *style, out),
+            Step::Sanitize(policy) => sanitize_path_into(path, policy, out),
+            Step::Rebase { from, to } => rebase_into(path, from, to, out),
+            Step::Map(f) => *out = f(path),
+        }
+    }
+}
+
+/// Runs `path` through `steps` in place, with `scratch` as the other buffer.
+fn run_steps(steps: &[Step], path: &mut PathBuf, scratch: &mut PathBuf) {
+    for step in steps {
+        step.apply(path, scratch);
+        mem::swap(path, scratch);
+    }
+}
+
+/// `path` as its directory and file name, when joining the two gives back exactly `path`.
+fn split_file(path: &Path) -> Option<(&Path, &OsStr)> {
+    let Some(Component::Normal(name)) = path.components().next_back() else {
+        return None;
+    };
+    let dir = path.parent()?;
+    let bytes = path.as_os_str().as_encoded_bytes();
+    let dir_len = dir.as_os_str().len();
+    let joined_len = match dir_len.checked_sub(1).map(|last| bytes[last]) {
+        Some(last) if is_separator(char::from(last)) => dir_len + name.len(),
+        Some(_) if bytes.get(dir_len) == Some(&(MAIN_SEPARATOR as u8)) => dir_len + 1 + name.len(),
+        Some(_) => return None,
+        None => name.len(),
+    };
+    (joined_len == bytes.len()).then_some((dir, name))
+}
+
+/// What each of `steps` is given and, last, what they give for `dir`.
+fn dir_values(steps: &[Step], dir: &Path) -> Vec<PathBuf> {
+    let mut values = vec![dir.to_path_buf()];
+    for step in steps {
+        let mut value = PathBuf::new();
+        step.apply(&values[values.len() - 1], &mut value);
+        // a directory normalizing away entirely leaves just the name, not `./name`
+        if matches!(step, Step::Normalize(_)) && value.as_os_str() == "." {
+            value.as_mut_os_string().clear();
+        }
+        values.push(value);
+    }
+    values
+}
+
+/// The name run through `steps` and joined onto the directory's result, `None` if a rebase moves
+/// the path itself, which has to be run through in full.
+fn join_transformed(steps: &[Step], dirs: &[PathBuf], name: &OsStr) -> Option<PathBuf> {
+    let mut name = Cow::Borrowed(name);
+    for (step, dir) in steps.iter().zip(dirs) {
+        match step {
+            Step::Sanitize(policy) => {
+                name = Cow::Owned(policy.sanitize_name(&name.to_string_lossy()).into());
+            }
+            Step::Rebase { from, .. }
+                if from.parent() == Some(dir) && from.file_name() == Some(&*name) =>
+            {
+                return None;
+            }
+            _ => {}
+        }
+    }
+    Some(dirs[steps.len()].join(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PathPipeline;
+
+    use std::collections::HashMap;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn test_pipeline_order() {
+        let vars = HashMap::from([("ROOT".to_string(), "/data/../srv".to_string())]);
+        let expand_then_normalize = PathPipeline::new()
+            .expand_vars_from(vars.clone())
+            .normalize();
+        let normalize_then_expand = PathPipeline::new().normalize().expand_vars_from(vars);
+        assert_eq!(expand_then_normalize.apply("$ROOT/x"), Path::new("/srv/x"));
+        assert_eq!(
+            normalize_then_expand.apply("$ROOT/x"),
+            Path::new("/data/../srv/x")
+        );
+    }
+
+    #[test]
+    fn test_pipeline_steps() {
+        let pipeline = PathPipeline::new()
+            .sanitize()
+            .rebase("in", "out")
+            .map(|p| p.with_extension("bak"));
+        let tests = &[
+            ("in/a:b.txt", "out/a_b.bak"),
+            ("elsewhere/c.txt", "elsewhere/c.bak"),
+            ("in", "out.bak"),
+        ];
+
+        for test in tests {
+            assert_eq!(pipeline.apply(test.0), PathBuf::from(test.1), "{}", test.0);
+        }
+        assert_eq!(PathPipeline::new().apply("as/./is"), Path::new("as/./is"));
+        assert_eq!(
+            format!(
+                "{:?}",
+                PathPipeline::new().normalize().map(|p| p.to_path_buf())
+            ),
+            "PathPipeline { steps: [Normalize(Filesystem), Map] }"
+        );
+    }
+
+    #[test]
+    fn test_transform_all() {
+        let paths = [
+            "in/a/../b/one.txt",
+            "in/a/../b/two?.txt",
+            "in/c/three.txt",
+            "in",
+            "in/x",
+            "in/",
+            "a//b",
+            "a/b/",
+            "./x",
+            "a/../x",
+            "..",
+            ".",
+            "",
+            "/",
+            "/top",
+            "elsewhere/CON.txt",
+        ];
+        let pipelines = [
+            PathPipeline::new(),
+            PathPipeline::new().normalize(),
+            PathPipeline::new().rebase("in", "out"),
+            PathPipeline::new().rebase("in/x", "moved"),
+            PathPipeline::new()
+                .normalize()
+                .sanitize()
+                .rebase("in/b", "/srv"),
+            PathPipeline::new()
+                .sanitize()
+                .normalize_with_style(crate::NormalizeStyle::Uri)
+                .map(|p| p.with_extension("bak"))
+                .normalize(),
+        ];
+
+        for pipeline in &pipelines {
+            let expected: Vec<PathBuf> = pipeline.apply_all(paths).collect();
+            assert_eq!(pipeline.transform_all(&paths), expected, "{:?}", pipeline);
+        }
+    }
+
+    /// `transform_all` agrees with mapping `apply` over a listing shaped like a source tree, where
+    /// it reuses its buffers across thousands of paths.
+    #[test]
+    fn test_transform_all_large_listing() {
+        let paths: Vec<String> = (0..200)
+            .flat_map(|dir| {
+                (0..100).map(move |file| format!("in/./pkg{dir}/src/../lib/mod {file}.rs"))
+            })
+            .collect();
+        let pipeline = PathPipeline::new()
+            .normalize()
+            .sanitize()
+            .rebase("in", "/srv/out");
+
+        let naive: Vec<PathBuf> = paths.iter().map(|path| pipeline.apply(path)).collect();
+        assert_eq!(pipeline.transform_all(&paths), naive);
+    }
+}
@@ -0,0 +1,65 @@
+//! Which OS's rules path operations follow, so one platform's behavior can be checked on another.
+
+/// The OS whose rules an operation follows, for the options and constructors that would otherwise
+/// use the one this was compiled for. A Linux CI job can check what Windows would do with
+/// [`Platform::Windows`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Platform {
+    /// Linux, the BSDs and other Unixes: `/` separates, case matters.
+    Unix,
+    /// Windows: `\` separates as well as `/`, case is ignored.
+    Windows,
+    /// The platform this was compiled for, which for macOS is Unix with case ignored.
+    #[default]
+    Native,
+}
+
+impl Platform {
+    /// Whether Windows rules apply.
+    pub const fn is_windows(self) -> bool {
+        match self {
+            Platform::Unix => false,
+            Platform::Windows => true,
+            Platform::Native => cfg!(windows),
+        }
+    }
+
+    /// Whether the platform's filesystems ignore case by default, as Windows and macOS ones do.
+    pub const fn ignores_case(self) -> bool {
+        match self {
+            Platform::Native => cfg!(any(windows, target_vendor = "apple")),
+            _ => self.is_windows(),
+        }
+    }
+
+    /// Whether `c` separates components, `\` only doing so on Windows.
+    pub const fn is_separator(self, c: char) -> bool {
+        c == '/' || c == '\\' && self.is_windows()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Platform;
+
+    #[test]
+    fn test_platform() {
+        let tests = &[
+            (Platform::Unix, false, false, false),
+            (Platform::Windows, true, true, true),
+            (
+                Platform::Native,
+                cfg!(windows),
+                cfg!(any(windows, target_vendor = "apple")),
+                cfg!(windows),
+            ),
+        ];
+
+        for test in tests {
+            assert_eq!(test.0.is_windows(), test.1, "{:?}", test.0);
+            assert_eq!(test.0.ignores_case(), test.2, "{:?}", test.0);
+            assert_eq!(test.0.is_separator('\\'), test.3, "{:?}", test.0);
+            assert!(test.0.is_separator('/'));
+        }
+    }
+}
@@ -0,0 +1,142 @@
+//! Checking paths against the POSIX portable filename rules.
+
+use std::fmt;
+use std::path::{Component, Path};
+
+use crate::{CharPolicy, LengthLimits};
+
+/// One way a path breaks the POSIX portability rules, see
+/// [`PathComponentExt::posix_portability_violations`](crate::PathComponentExt::posix_portability_violations).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PosixViolation {
+    /// A character outside the portable filename set `A-Z a-z 0-9 . _ -`. Bytes that aren't UTF-8
+    /// show up as `U+FFFD`.
+    Character { component: String, character: char },
+    /// A component starting with `-`, which utilities would take for an option.
+    LeadingHyphen { component: String },
+    /// A component longer than the limit, in the limit's units.
+    ComponentTooLong { component: String, length: usize },
+    /// The whole path is longer than the limit, in the limit's units.
+    PathTooLong { length: usize },
+}
+
+impl fmt::Display for PosixViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PosixViolation::Character {
+                component,
+                character,
+            } => write!(
+                f,
+                "{component:?} contains the non-portable character {character:?}"
+            ),
+            PosixViolation::LeadingHyphen { component } => {
+                write!(f, "{component:?} starts with a hyphen")
+            }
+            PosixViolation::ComponentTooLong { component, length } => {
+                write!(f, "{component:?} is {length} long")
+            }
+            PosixViolation::PathTooLong { length } => write!(f, "the path is {length} long"),
+        }
+    }
+}
+
+/// Every violation in the order found, each character reported once per component.
+pub(crate) fn violations(path: &Path, limits: LengthLimits) -> Vec<PosixViolation> {
+    let policy = CharPolicy::posix_portable();
+    let mut violations = Vec::new();
+    for component in path.components() {
+        let Component::Normal(name) = component else {
+            continue;
+        };
+        let name = name.to_string_lossy();
+        if name.starts_with('-') {
+            violations.push(PosixViolation::LeadingHyphen {
+                component: name.to_string(),
+            });
+        }
+        let mut seen = Vec::new();
+        for c in name.chars().filter(|&c| !policy.is_allowed(c)) {
+            if !seen.contains(&c) {
+                seen.push(c);
+                violations.push(PosixViolation::Character {
+                    component: name.to_string(),
+                    character: c,
+                });
+            }
+        }
+        let length = limits.unit.of_str(&name);
+        if length > limits.max_component {
+            violations.push(PosixViolation::ComponentTooLong {
+                component: name.to_string(),
+                length,
+            });
+        }
+    }
+    let length = limits.unit.of_str(&path.to_string_lossy());
+    if length > limits.max_path {
+        violations.push(PosixViolation::PathTooLong { length });
+    }
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{violations, PosixViolation};
+    use crate::LengthLimits;
+
+    use std::path::Path;
+
+    #[test]
+    fn test_violations() {
+        let character = |component: &str, character| PosixViolation::Character {
+            component: component.to_string(),
+            character,
+        };
+        let tests = &[
+            ("/usr/lib/libfoo.so.1", vec![]),
+            ("./a/../b_c-d", vec![]),
+            (
+                "docs/my file(1).c",
+                vec![
+                    character("my file(1).c", ' '),
+                    character("my file(1).c", '('),
+                    character("my file(1).c", ')'),
+                ],
+            ),
+            ("naïve", vec![character("naïve", 'ï')]),
+            (
+                "-rf",
+                vec![PosixViolation::LeadingHyphen {
+                    component: "-rf".to_string(),
+                }],
+            ),
+            (
+                "a_long_file_name.txt",
+                vec![PosixViolation::ComponentTooLong {
+                    component: "a_long_file_name.txt".to_string(),
+                    length: 20,
+                }],
+            ),
+        ];
+
+        for test in tests {
+            assert_eq!(
+                violations(Path::new(test.0), LengthLimits::POSIX),
+                test.1,
+                "{}",
+                test.0
+            );
+        }
+    }
+
+    #[test]
+    fn test_path_too_long() {
+        let path = "abc/".repeat(64);
+        assert_eq!(
+            violations(Path::new(&path), LengthLimits::POSIX),
+            [PosixViolation::PathTooLong { length: 256 }]
+        );
+        assert!(violations(Path::new(&path), LengthLimits::UNIX).is_empty());
+    }
+}
@@ -0,0 +1,43 @@
+//! The path extension traits and the types their methods take, for a single glob import. [`EntryNameExt`](crate::EntryNameExt) is left out
+//! since it changes what the methods mean for strings.
+//!
+//! ```rust
+//! use pathext::prelude::*;
+//! use std::ops::Not;
+//! use std::path::Path;
+//!
+//! assert!("/some/path".has_component("path"));
+//! assert!(Path::new("/some/path").has_component("path"));
+//! assert!("/some/path".has_component("some/path").not());
+//! assert!("/some/path".has_component("so").not());
+//!
+//! assert!("/some/path".contains("some/pa"));
+//! assert!(Path::new("/some/path").contains("some/pa"));
+//!
+//! assert!("/this/and/that/".starts_or_ends_with("/"));
+//! assert!(Path::new("/this/and/that/").starts_or_ends_with("/"));
+//!
+//! assert!("multiple-extensions.tar.gz".strip_extensions() == Some("multiple-extensions"));
+//! assert!(Path::new("multiple-extensions.tar.gz").strip_extensions() == Some("multiple-extensions"));
+//!
+//! assert!("archive.tar.gz".ends_with_extensions(".tar.gz"));
+//! assert!(Path::new("archive.tar.gz").ends_with_extensions("tar.gz"));
+//! assert!(Path::new("archive.tar.gz").ends_with_extensions("z")); // it will match any part of the end of the extension
+//!
+//! assert_eq!("src/../lib.rs".absolutize_from("/work"), Path::new("/work/lib.rs"));
+//!
+//! // Unlike std
+//! assert!("archive.tar.gz".ends_with(".tar.gz"));
+//! assert!(Path::new("archive.tar.gz").ends_with(".tar.gz").not());
+//! assert!(Path::new("archive.tar.gz").ends_with("z").not());
+//! ```
+
+pub use crate::{
+    CharPolicy, CharPreset, IoResultExt, LengthLimits, LengthUnit, NormalizeStyle, ObjectStore,
+    OverlayMap, PathComponentExt, PathIterExt, PathPipeline, PathRouter, PathStrExt, PathStyle,
+    PromptOptions, RedactOptions, StripOptions, VirtualPath,
+};
+#[cfg(feature = "fs")]
+pub use crate::{LinkTarget, PathFsExt, PathState, TouchOptions};
+#[cfg(feature = "remap")]
+pub use crate::{PathRemapper, RemapRule};
@@ -0,0 +1,146 @@
+//! Shell-prompt style shortening of paths.
+
+use std::path::{Component, Path, PathBuf, MAIN_SEPARATOR, MAIN_SEPARATOR_STR};
+
+/// How [`PathStrExt::format_for_prompt`](crate::PathStrExt::format_for_prompt) shortens a path.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PromptOptions {
+    /// Contracted to `~` when the path is inside it.
+    pub home: Option<PathBuf>,
+    /// How many trailing components are kept in full, the rest being cut to their first character.
+    pub full_components: usize,
+    /// The most characters the result may have, with leading components dropped for `…` to fit.
+    pub max_width: Option<usize>,
+}
+
+impl Default for PromptOptions {
+    fn default() -> Self {
+        PromptOptions {
+            home: None,
+            full_components: 1,
+            max_width: None,
+        }
+    }
+}
+
+/// The first character, or the first two for a hidden `.name` as fish does.
+fn abbreviate(component: &str) -> String {
+    let take = if component.starts_with('.') { 2 } else { 1 };
+    component.chars().take(take).collect()
+}
+
+pub(crate) fn format_for_prompt(path: &Path, options: &PromptOptions) -> String {
+    let (anchor, rest) = match &options.home {
+        Some(home) if path.starts_with(home) => (
+            format!("~{MAIN_SEPARATOR}"),
+            path.strip_prefix(home).unwrap_or(path),
+        ),
+        _ => (String::new(), path),
+    };
+
+    let mut anchor = anchor;
+    let mut parts: Vec<String> = Vec::new();
+    for component in rest.components() {
+        match component {
+            Component::Prefix(_) | Component::RootDir => {
+                anchor.push_str(&component.as_os_str().to_string_lossy())
+            }
+            other => parts.push(other.as_os_str().to_string_lossy().into_owned()),
+        }
+    }
+    if anchor.ends_with(MAIN_SEPARATOR) && parts.is_empty() && anchor.len() > 1 {
+        anchor.pop();
+    }
+
+    let abbreviate_before = parts.len().saturating_sub(options.full_components);
+    for part in &mut parts[..abbreviate_before] {
+        *part = abbreviate(part);
+    }
+
+    let render = |skipped: bool, parts: &[String]| {
+        let mut rendered = anchor.clone();
+        if skipped {
+            rendered.push('…');
+            rendered.push(MAIN_SEPARATOR);
+        }
+        rendered.push_str(&parts.join(MAIN_SEPARATOR_STR));
+        rendered
+    };
+
+    let Some(max_width) = options.max_width else {
+        return render(false, &parts);
+    };
+    let mut first = 0;
+    let mut rendered = render(false, &parts);
+    while rendered.chars().count() > max_width && first + 1 < parts.len() {
+        first += 1;
+        rendered = render(true, &parts[first..]);
+    }
+    let width = rendered.chars().count();
+    if width > max_width {
+        // even the last component alone is too wide, keep its end
+        let tail: String = rendered.chars().skip(width + 1 - max_width).collect();
+        rendered = format!("…{tail}");
+    }
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_for_prompt, PromptOptions};
+
+    use std::path::{Path, PathBuf, MAIN_SEPARATOR_STR};
+
+    fn native(s: &str) -> String {
+        s.replace('/', MAIN_SEPARATOR_STR)
+    }
+
+    #[test]
+    fn test_format_for_prompt() {
+        let home = PromptOptions {
+            home: Some(PathBuf::from("/home/me")),
+            ..PromptOptions::default()
+        };
+        let two_full = PromptOptions {
+            full_components: 2,
+            ..home.clone()
+        };
+        let tests = &[
+            ("/home/me/projects/rust/pathext", &home, "~/p/r/pathext"),
+            ("/home/me/.config/nvim/lua", &home, "~/.c/n/lua"),
+            ("/home/me", &home, "~"),
+            ("/usr/local/bin", &home, "/u/l/bin"),
+            ("/usr/local/bin", &two_full, "/u/local/bin"),
+            ("/", &home, "/"),
+            ("relative/dir", &PromptOptions::default(), "r/dir"),
+        ];
+
+        for test in tests {
+            assert_eq!(format_for_prompt(Path::new(test.0), test.1), native(test.2));
+        }
+    }
+
+    #[test]
+    fn test_format_for_prompt_max_width() {
+        let options = |max_width| PromptOptions {
+            home: Some(PathBuf::from("/home/me")),
+            full_components: 2,
+            max_width: Some(max_width),
+        };
+        let path = Path::new("/home/me/aa/bb/cc/project-dir/src");
+        let tests = &[
+            (100, "~/a/b/c/project-dir/src"),
+            (21, "~/…/c/project-dir/src"),
+            (20, "~/…/project-dir/src"),
+            (10, "~/…/src"),
+            (4, "…src"),
+            (3, "…rc"),
+        ];
+
+        for test in tests {
+            let formatted = format_for_prompt(path, &options(test.0));
+            assert_eq!(formatted, native(test.1), "{}", test.0);
+            assert!(formatted.chars().count() <= test.0);
+        }
+    }
+}
@@ -0,0 +1,47 @@
+//! Moving paths from under one directory to under another.
+
+use std::path::{Path, PathBuf};
+
+/// `path` moved from under `from` to the same place under `to`, or as it is when it isn't under
+/// `from`, written into `buf`, which is cleared first.
+pub(crate) fn rebase_into(path: &Path, from: &Path, to: &Path, buf: &mut PathBuf) {
+    buf.as_mut_os_string().clear();
+    match path.strip_prefix(from) {
+        Ok(rest) => {
+            buf.push(to);
+            if !rest.as_os_str().is_empty() {
+                buf.push(rest);
+            }
+        }
+        Err(_) => buf.push(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::rebase_into;
+
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn test_rebase_into() {
+        let mut buf = PathBuf::from("/left/over");
+        let tests = &[
+            ("src/lib.rs", "out/lib.rs"),
+            ("src", "out"),
+            ("srcs/x.rs", "srcs/x.rs"),
+            ("/abs/x", "/abs/x"),
+            ("", ""),
+        ];
+
+        for test in tests {
+            rebase_into(
+                Path::new(test.0),
+                Path::new("src"),
+                Path::new("out"),
+                &mut buf,
+            );
+            assert_eq!(buf, Path::new(test.1), "{}", test.0);
+        }
+    }
+}
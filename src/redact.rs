@@ -0,0 +1,169 @@
+//! Masking what identifies a user in paths headed for logs, telemetry or bug reports.
+
+use std::ffi::OsStr;
+use std::path::{Component, Path, PathBuf};
+
+use crate::hash::fnv1a;
+
+/// What [`PathComponentExt::redact`](crate::PathComponentExt::redact) masks.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RedactOptions {
+    /// Mask the user's directory under `/home`, `/Users` or `C:\Users`, so `/home/alice/notes`
+    /// becomes `/home/<user>/notes`.
+    pub home_dirs: bool,
+    /// User names masked wherever they are a whole component, like in `/var/mail/alice`.
+    pub users: Vec<String>,
+    /// Replace the file name's stem with a hash, keeping its last extension, so reports show the
+    /// kind of file and whether two paths name the same one without the name itself.
+    ///
+    /// This hides names from a casual reader but doesn't anonymize them: the hash is a fast 32-bit
+    /// one, so anyone who can guess a name, like `passwords.txt`, can hash it and compare. Leave
+    /// it off where the names themselves must stay private.
+    pub hash_file_names: bool,
+    /// What masked components become.
+    pub placeholder: String,
+}
+
+impl Default for RedactOptions {
+    /// Masking home directories as `<user>`, and nothing else.
+    fn default() -> Self {
+        RedactOptions {
+            home_dirs: true,
+            users: Vec::new(),
+            hash_file_names: false,
+            placeholder: "<user>".to_string(),
+        }
+    }
+}
+
+impl RedactOptions {
+    /// The options with `user` masked too.
+    pub fn user<S: Into<String>>(mut self, user: S) -> Self {
+        self.users.push(user.into());
+        self
+    }
+}
+
+/// Whether `name` is a directory whose children are users' homes, right below the root.
+fn holds_homes(name: &OsStr) -> bool {
+    name == "home" || name.eq_ignore_ascii_case("users")
+}
+
+pub(crate) fn redact(path: &Path, options: &RedactOptions) -> PathBuf {
+    let components: Vec<Component> = path.components().collect();
+    let last_normal = components
+        .iter()
+        .rposition(|c| matches!(c, Component::Normal(_)));
+    let mut redacted = PathBuf::new();
+    // how many components after the root the current one is, to spot `/home/<user>`
+    let mut depth: Option<usize> = None;
+    for (i, component) in components.iter().enumerate() {
+        let Component::Normal(name) = component else {
+            depth = match component {
+                Component::RootDir => Some(0),
+                _ => None,
+            };
+            redacted.push(component);
+            continue;
+        };
+        let in_home = options.home_dirs
+            && depth == Some(1)
+            && components.get(i - 1).is_some_and(
+                |previous| matches!(previous, Component::Normal(parent) if holds_homes(parent)),
+            );
+        depth = depth.map(|depth| depth + 1);
+        let is_user = options
+            .users
+            .iter()
+            .any(|user| name.to_str() == Some(user.as_str()));
+        if in_home || is_user {
+            redacted.push(&options.placeholder);
+        } else if options.hash_file_names && Some(i) == last_normal {
+            redacted.push(hashed_name(name));
+        } else {
+            redacted.push(name);
+        }
+    }
+    redacted
+}
+
+fn hashed_name(name: &OsStr) -> String {
+    let hash = format!("{:08x}", fnv1a(name.as_encoded_bytes()) as u32);
+    match Path::new(name).extension() {
+        Some(extension) => format!("{hash}.{}", extension.to_string_lossy()),
+        None => hash,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{redact, RedactOptions};
+
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn test_redact() {
+        let options = RedactOptions::default();
+        let tests = &[
+            (
+                "/home/alice/src/app/main.rs",
+                "/home/<user>/src/app/main.rs",
+            ),
+            ("/Users/alice", "/Users/<user>"),
+            ("/home", "/home"),
+            ("/srv/home/alice", "/srv/home/alice"),
+            ("home/alice", "home/alice"),
+            ("/var/mail/alice", "/var/mail/alice"),
+            ("relative/path.txt", "relative/path.txt"),
+        ];
+
+        for test in tests {
+            assert_eq!(
+                redact(Path::new(test.0), &options),
+                PathBuf::from(test.1),
+                "{}",
+                test.0
+            );
+        }
+    }
+
+    #[test]
+    fn test_redact_options() {
+        let options = RedactOptions {
+            home_dirs: false,
+            placeholder: "USER".to_string(),
+            ..RedactOptions::default()
+        }
+        .user("alice");
+        let tests = &[
+            ("/home/alice/x", "/home/USER/x"),
+            ("/home/bob/x", "/home/bob/x"),
+            ("/var/mail/alice", "/var/mail/USER"),
+            ("/tmp/alice2", "/tmp/alice2"),
+        ];
+
+        for test in tests {
+            assert_eq!(
+                redact(Path::new(test.0), &options),
+                PathBuf::from(test.1),
+                "{}",
+                test.0
+            );
+        }
+
+        let hashing = RedactOptions {
+            hash_file_names: true,
+            ..RedactOptions::default()
+        };
+        let a = redact(Path::new("/home/alice/taxes.2024.pdf"), &hashing);
+        assert_eq!(a.parent(), Some(Path::new("/home/<user>")));
+        assert_eq!(a.extension().unwrap(), "pdf");
+        assert!(!a.to_string_lossy().contains("taxes"));
+        assert_eq!(a, redact(Path::new("/home/bob/taxes.2024.pdf"), &hashing));
+        assert_ne!(a, redact(Path::new("/home/alice/other.pdf"), &hashing));
+        assert_eq!(
+            redact(Path::new("/home/alice/"), &hashing),
+            Path::new("/home/<user>")
+        );
+    }
+}
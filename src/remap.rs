@@ -0,0 +1,423 @@
+//! Rule-driven rewriting of paths, behind the `remap` feature.
+
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use globset::{Glob, GlobMatcher};
+use regex_automata::meta::Regex;
+use regex_automata::util::captures::Captures;
+
+use crate::Platform;
+
+/// One rule of a [`PathRemapper`].
+///
+/// `Glob` and `Regex` targets are templates: `{path}`, `{parent}`, `{name}`, `{stem}` and `{ext}`
+/// stand for the matched path (`/`-separated) and its parts, and for regexes `{1}` or `{group}`
+/// for a capture group. `{{` and `}}` are literal braces.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(tag = "kind", rename_all = "snake_case")
+)]
+pub enum RemapRule {
+    /// Only `from` itself, which becomes `to`.
+    Exact { from: PathBuf, to: PathBuf },
+    /// `from` and everything under it, moved under `to` keeping what follows the prefix.
+    Prefix { from: PathBuf, to: PathBuf },
+    /// Paths matching the glob `pattern`, see [`globset::Glob`].
+    Glob { pattern: String, to: String },
+    /// Paths the regex `pattern` matches in full.
+    Regex { pattern: String, to: String },
+}
+
+/// Why a [`RemapRule`] couldn't be compiled.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RemapError {
+    /// The index of the rule.
+    pub rule: usize,
+    pub message: String,
+}
+
+impl fmt::Display for RemapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "remap rule {}: {}", self.rule, self.message)
+    }
+}
+
+impl Error for RemapError {}
+
+#[derive(Clone, Debug)]
+enum Piece {
+    Literal(String),
+    Path,
+    Parent,
+    Name,
+    Stem,
+    Ext,
+    Group(usize),
+    NamedGroup(String),
+}
+
+#[derive(Clone, Debug)]
+enum Matcher {
+    Exact(PathBuf),
+    Prefix(PathBuf),
+    Glob(GlobMatcher),
+    Regex(Regex),
+}
+
+#[derive(Clone, Debug)]
+struct Compiled {
+    matcher: Matcher,
+    template: Vec<Piece>,
+    to: PathBuf,
+}
+
+/// Maps paths through an ordered list of [`RemapRule`]s, the first matching rule deciding the result.
+///
+/// With the `serde` feature a remapper (de)serializes as its list of rules, tagged by `kind`,
+/// so it can come straight from a config file. Its [`platform`](PathRemapper::platform) isn't
+/// part of that and comes back as [`Platform::Native`].
+///
+/// ```rust
+/// use pathext::{PathRemapper, RemapRule};
+/// use std::path::{Path, PathBuf};
+///
+/// let remapper = PathRemapper::new([
+///     RemapRule::Exact { from: "README.md".into(), to: "docs/index.md".into() },
+///     RemapRule::Prefix { from: "assets".into(), to: "/srv/static".into() },
+///     RemapRule::Glob { pattern: "**/*.scss".into(), to: "css/{stem}.css".into() },
+///     RemapRule::Regex { pattern: r"posts/(\d{4})-(?<slug>.+)\.md".into(), to: "blog/{1}/{slug}/index.html".into() },
+/// ])
+/// .unwrap();
+///
+/// assert_eq!(remapper.remap("README.md"), Some(PathBuf::from("docs/index.md")));
+/// assert_eq!(remapper.remap("assets/img/logo.png"), Some(PathBuf::from("/srv/static/img/logo.png")));
+/// assert_eq!(remapper.remap("theme/main.scss"), Some(PathBuf::from("css/main.css")));
+/// assert_eq!(remapper.remap("posts/2024-hello.md"), Some(PathBuf::from("blog/2024/hello/index.html")));
+/// assert_eq!(remapper.remap(Path::new("src/lib.rs")), None);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(try_from = "Vec<RemapRule>", into = "Vec<RemapRule>")
+)]
+pub struct PathRemapper {
+    rules: Vec<RemapRule>,
+    compiled: Vec<Compiled>,
+    platform: Platform,
+}
+
+fn parse_template(template: &str, regex: Option<&Regex>) -> Result<Vec<Piece>, String> {
+    let mut pieces = Vec::new();
+    let mut literal = String::new();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '}' => return Err("unmatched `}` in target".to_string()),
+            '{' => {
+                let name: String = chars.by_ref().take_while(|&c| c != '}').collect();
+                let piece = match name.as_str() {
+                    "path" => Piece::Path,
+                    "parent" => Piece::Parent,
+                    "name" => Piece::Name,
+                    "stem" => Piece::Stem,
+                    "ext" => Piece::Ext,
+                    group => match (regex, group.parse::<usize>()) {
+                        (Some(regex), Ok(i)) if i < regex.group_info().group_len(0.into()) => {
+                            Piece::Group(i)
+                        }
+                        (Some(regex), Err(_))
+                            if regex.group_info().to_index(0.into(), group).is_some() =>
+                        {
+                            Piece::NamedGroup(group.to_string())
+                        }
+                        _ => return Err(format!("unknown placeholder `{{{group}}}` in target")),
+                    },
+                };
+                pieces.push(Piece::Literal(std::mem::take(&mut literal)));
+                pieces.push(piece);
+            }
+            c => literal.push(c),
+        }
+    }
+    pieces.push(Piece::Literal(literal));
+    Ok(pieces)
+}
+
+fn compile(rule: &RemapRule) -> Result<Compiled, String> {
+    let (matcher, template, to) = match rule {
+        RemapRule::Exact { from, to } => (Matcher::Exact(from.clone()), Vec::new(), to.clone()),
+        RemapRule::Prefix { from, to } => (Matcher::Prefix(from.clone()), Vec::new(), to.clone()),
+        RemapRule::Glob { pattern, to } => {
+            let glob = Glob::new(pattern).map_err(|e| e.to_string())?;
+            (
+                Matcher::Glob(glob.compile_matcher()),
+                parse_template(to, None)?,
+                PathBuf::new(),
+            )
+        }
+        RemapRule::Regex { pattern, to } => {
+            let regex = Regex::new(&format!("^(?:{pattern})$")).map_err(|e| e.to_string())?;
+            let template = parse_template(to, Some(&regex))?;
+            (Matcher::Regex(regex), template, PathBuf::new())
+        }
+    };
+    Ok(Compiled {
+        matcher,
+        template,
+        to,
+    })
+}
+
+/// The path as matched by globs and regexes, `/`-separated on every platform.
+fn slash_form(path: &Path, platform: Platform) -> String {
+    let lossy = path.to_string_lossy();
+    if platform.is_windows() {
+        lossy.replace('\\', "/")
+    } else {
+        lossy.into_owned()
+    }
+}
+
+fn expand(
+    template: &[Piece],
+    path: &Path,
+    haystack: &str,
+    captures: Option<&Captures>,
+    platform: Platform,
+) -> PathBuf {
+    let lossy = |s: Option<&std::ffi::OsStr>| {
+        s.map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    };
+    let group =
+        |span: Option<regex_automata::Span>| span.map_or("", |span| &haystack[span.range()]);
+    let mut expanded = String::new();
+    for piece in template {
+        match piece {
+            Piece::Literal(literal) => expanded.push_str(literal),
+            Piece::Path => expanded.push_str(haystack),
+            Piece::Parent => expanded.push_str(&slash_form(
+                path.parent().unwrap_or(Path::new("")),
+                platform,
+            )),
+            Piece::Name => expanded.push_str(&lossy(path.file_name())),
+            Piece::Stem => expanded.push_str(&lossy(path.file_stem())),
+            Piece::Ext => expanded.push_str(&lossy(path.extension())),
+            Piece::Group(i) => expanded.push_str(group(captures.and_then(|c| c.get_group(*i)))),
+            Piece::NamedGroup(name) => {
+                expanded.push_str(group(captures.and_then(|c| c.get_group_by_name(name))))
+            }
+        }
+    }
+    PathBuf::from(expanded)
+}
+
+impl PathRemapper {
+    /// Compiles `rules`, failing on the first invalid glob, regex or target template.
+    pub fn new<I: IntoIterator<Item = RemapRule>>(rules: I) -> Result<Self, RemapError> {
+        let rules: Vec<RemapRule> = rules.into_iter().collect();
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("compile remap rules", rules = rules.len()).entered();
+        let compiled = rules
+            .iter()
+            .enumerate()
+            .map(|(rule, r)| compile(r).map_err(|message| RemapError { rule, message }))
+            .collect::<Result<_, _>>();
+        if let Err(_error) = &compiled {
+            debug!(error = %_error, "remap rule didn't compile");
+        }
+        let compiled = compiled?;
+        Ok(PathRemapper {
+            rules,
+            compiled,
+            platform: Platform::Native,
+        })
+    }
+
+    /// Matches paths with `platform`'s separators, so with [`Platform::Windows`] `a\b` matches
+    /// rules for `a/b` on every host.
+    pub fn platform(mut self, platform: Platform) -> Self {
+        self.platform = platform;
+        self
+    }
+
+    pub fn rules(&self) -> &[RemapRule] {
+        &self.rules
+    }
+
+    /// Where the first matching rule sends `path`, or `None` if no rule matches.
+    pub fn remap<P: AsRef<Path>>(&self, path: P) -> Option<PathBuf> {
+        // std already splits on `\` on Windows, elsewhere it needs swapping for `/`
+        let path = match path.as_ref() {
+            path if self.platform.is_windows() && !cfg!(windows) => {
+                Cow::Owned(PathBuf::from(slash_form(path, self.platform)))
+            }
+            path => Cow::Borrowed(path),
+        };
+        let path = path.as_ref();
+        self.compiled.iter().find_map(|rule| match &rule.matcher {
+            Matcher::Exact(from) => (path == from).then(|| rule.to.clone()),
+            Matcher::Prefix(from) => path.strip_prefix(from).ok().map(|rest| rule.to.join(rest)),
+            Matcher::Glob(glob) => glob.is_match(path).then(|| {
+                let haystack = slash_form(path, self.platform);
+                expand(&rule.template, path, &haystack, None, self.platform)
+            }),
+            Matcher::Regex(regex) => {
+                let haystack = slash_form(path, self.platform);
+                let mut captures = regex.create_captures();
+                regex.captures(&haystack, &mut captures);
+                captures.is_match().then(|| {
+                    expand(
+                        &rule.template,
+                        path,
+                        &haystack,
+                        Some(&captures),
+                        self.platform,
+                    )
+                })
+            }
+        })
+    }
+}
+
+impl TryFrom<Vec<RemapRule>> for PathRemapper {
+    type Error = RemapError;
+
+    fn try_from(rules: Vec<RemapRule>) -> Result<Self, Self::Error> {
+        PathRemapper::new(rules)
+    }
+}
+
+impl From<PathRemapper> for Vec<RemapRule> {
+    fn from(remapper: PathRemapper) -> Self {
+        remapper.rules
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PathRemapper, RemapRule};
+    use crate::Platform;
+
+    use std::path::PathBuf;
+
+    fn glob(pattern: &str, to: &str) -> RemapRule {
+        RemapRule::Glob {
+            pattern: pattern.to_string(),
+            to: to.to_string(),
+        }
+    }
+
+    fn regex(pattern: &str, to: &str) -> RemapRule {
+        RemapRule::Regex {
+            pattern: pattern.to_string(),
+            to: to.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_remap() {
+        let remapper = PathRemapper::new([
+            RemapRule::Exact {
+                from: "a/b".into(),
+                to: "exact".into(),
+            },
+            RemapRule::Prefix {
+                from: "a".into(),
+                to: "/prefixed".into(),
+            },
+            glob("*.{jpg,png}", "img/{parent}/{{{name}}}"),
+            regex(r"(\w+)/v(?<v>\d+)/(.*)", "{3}@{v}/{1}.{ext}"),
+        ])
+        .unwrap();
+        let tests = &[
+            ("a/b", Some("exact")),
+            ("a/./b/", Some("exact")),
+            ("a/b/c", Some("/prefixed/b/c")),
+            ("a", Some("/prefixed")),
+            ("ab/c", None),
+            ("photos/cat.png", Some("img/photos/{cat.png}")),
+            ("api/v2/users.json", Some("users.json@2/api.json")),
+            ("xapi/v2", None),
+        ];
+
+        for test in tests {
+            assert_eq!(
+                remapper.remap(test.0),
+                test.1.map(PathBuf::from),
+                "{}",
+                test.0
+            );
+        }
+    }
+
+    #[test]
+    fn test_remap_platform() {
+        let rules = [
+            RemapRule::Prefix {
+                from: "assets".into(),
+                to: "static".into(),
+            },
+            glob("src/**/*.c", "obj/{stem}.o"),
+        ];
+        let windows = PathRemapper::new(rules.clone())
+            .unwrap()
+            .platform(Platform::Windows);
+        let unix = PathRemapper::new(rules).unwrap().platform(Platform::Unix);
+
+        assert_eq!(
+            windows.remap(r"assets\img.png"),
+            Some(PathBuf::from("static/img.png"))
+        );
+        assert_eq!(
+            windows.remap(r"src\net\tcp.c"),
+            Some(PathBuf::from("obj/tcp.o"))
+        );
+        if !cfg!(windows) {
+            assert_eq!(unix.remap(r"assets\img.png"), None);
+            assert_eq!(unix.remap(r"src\net\tcp.c"), None);
+        }
+        assert_eq!(
+            unix.remap("src/net/tcp.c"),
+            Some(PathBuf::from("obj/tcp.o"))
+        );
+    }
+
+    #[test]
+    fn test_remap_errors() {
+        let tests = &[
+            (glob("a[", "x"), 0),
+            (regex("(", "x"), 0),
+            (glob("*", "{nope}"), 0),
+            (glob("*", "{1}"), 0),
+            (regex("(a)", "{2}"), 0),
+            (regex("(?<n>a)", "{m}"), 0),
+            (glob("*", "x}"), 0),
+        ];
+
+        for test in tests {
+            let e = PathRemapper::new([test.0.clone()]).unwrap_err();
+            assert_eq!(e.rule, test.1, "{:?}", test.0);
+        }
+        let e =
+            PathRemapper::new([glob("*", "ok"), regex("(?<n>a)", "{n}/{0}/{bad}")]).unwrap_err();
+        assert_eq!(e.rule, 1);
+        assert!(e
+            .to_string()
+            .starts_with("remap rule 1: unknown placeholder `{bad}`"));
+    }
+}
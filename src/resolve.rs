@@ -0,0 +1,533 @@
+//! Path resolution against a pluggable view of the filesystem.
+//!
+//! The helpers here only ask the filesystem three questions: what the current directory is,
+//! where a symlink points and whether a path exists. [`PathResolver`] abstracts those so the
+//! same logic runs against the real disk ([`RealFs`], with the `fs` feature) or an in-memory
+//! [`MockFs`] in tests.
+
+use std::collections::BTreeMap;
+use std::collections::VecDeque;
+use std::ffi::OsString;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+#[cfg(feature = "fs")]
+use crate::fs::is_missing;
+use crate::normalize::normalize;
+use crate::PathComponentExt;
+
+/// Symlinks followed before resolution gives up, matching Linux's `MAXSYMLINKS`.
+const MAX_SYMLINK_HOPS: usize = 40;
+
+/// The filesystem lookups needed to resolve paths.
+pub trait PathResolver {
+    /// The directory relative paths are resolved against.
+    fn current_dir(&self) -> io::Result<PathBuf>;
+    /// Where the symlink at `path` points, or `None` if `path` is not a symlink or does not exist.
+    fn symlink_target(&self, path: &Path) -> io::Result<Option<PathBuf>>;
+    /// Whether `path` exists, following symlinks.
+    fn exists(&self, path: &Path) -> io::Result<bool>;
+}
+
+impl<R: PathResolver + ?Sized> PathResolver for &R {
+    fn current_dir(&self) -> io::Result<PathBuf> {
+        (**self).current_dir()
+    }
+
+    fn symlink_target(&self, path: &Path) -> io::Result<Option<PathBuf>> {
+        (**self).symlink_target(path)
+    }
+
+    fn exists(&self, path: &Path) -> io::Result<bool> {
+        (**self).exists(path)
+    }
+}
+
+/// The real filesystem and process current directory.
+#[cfg(feature = "fs")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RealFs;
+
+#[cfg(feature = "fs")]
+impl PathResolver for RealFs {
+    fn current_dir(&self) -> io::Result<PathBuf> {
+        std::env::current_dir()
+    }
+
+    fn symlink_target(&self, path: &Path) -> io::Result<Option<PathBuf>> {
+        match std::fs::symlink_metadata(path) {
+            Ok(metadata) if metadata.file_type().is_symlink() => std::fs::read_link(path).map(Some),
+            Ok(_) => Ok(None),
+            Err(e) if is_missing(&e) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn exists(&self, path: &Path) -> io::Result<bool> {
+        match path.try_exists() {
+            Err(e) if is_missing(&e) => Ok(false),
+            other => other,
+        }
+    }
+}
+
+/// An entry in a [`MockFs`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MockEntry {
+    File,
+    Dir,
+    Symlink(PathBuf),
+}
+
+/// An in-memory filesystem for exercising resolution logic without touching the disk.
+///
+/// Paths are made absolute against the mock's current directory, then walked a component at a
+/// time as the OS does, so `..` after a symlink goes to the parent of its target. Entries are
+/// added at their lexically normalized paths.
+#[derive(Clone, Debug)]
+pub struct MockFs {
+    cwd: PathBuf,
+    entries: BTreeMap<PathBuf, MockEntry>,
+}
+
+impl MockFs {
+    /// Starts building a mock fixture, with `/` as the current directory unless [`MockFsBuilder::cwd`] says otherwise.
+    ///
+    /// ```rust
+    /// use pathext::resolve::{MockEntry, MockFs, PathResolver};
+    /// use std::path::Path;
+    ///
+    /// let fs = MockFs::builder()
+    ///     .file("/a/b.txt")
+    ///     .symlink("/l", "/a")
+    ///     .build();
+    /// assert!(fs.exists(Path::new("/l/b.txt")).unwrap());
+    /// assert_eq!(fs.entry("/a"), Some(&MockEntry::Dir));
+    /// ```
+    pub fn builder() -> MockFsBuilder {
+        MockFsBuilder::default()
+    }
+
+    /// Creates an empty mock whose current directory is `cwd`, which is added as a directory.
+    pub fn new<P: AsRef<Path>>(cwd: P) -> Self {
+        let mut fs = MockFs {
+            cwd: normalize(cwd.as_ref()),
+            entries: BTreeMap::new(),
+        };
+        let cwd = fs.cwd.clone();
+        fs.add_dir(cwd);
+        fs
+    }
+
+    /// Adds a file, along with any missing parent directories.
+    pub fn add_file<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
+        self.insert(path.as_ref(), MockEntry::File)
+    }
+
+    /// Adds a directory, along with any missing parent directories.
+    pub fn add_dir<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
+        self.insert(path.as_ref(), MockEntry::Dir)
+    }
+
+    /// Adds a symlink at `link` pointing to `target`, which need not exist.
+    pub fn add_symlink<P: AsRef<Path>, T: AsRef<Path>>(&mut self, link: P, target: T) -> &mut Self {
+        self.insert(
+            link.as_ref(),
+            MockEntry::Symlink(target.as_ref().to_path_buf()),
+        )
+    }
+
+    /// The entry stored at `path`, without following a symlink at the final component.
+    pub fn entry<P: AsRef<Path>>(&self, path: P) -> Option<&MockEntry> {
+        let path = self.resolve(&self.absolute(path.as_ref()), false, 0)?;
+        self.entries.get(&path)
+    }
+
+    /// The entries directly inside `dir`, in sorted order, for walking the mock like a directory tree.
+    pub fn children<P: AsRef<Path>>(&self, dir: P) -> impl Iterator<Item = (&Path, &MockEntry)> {
+        let dir = self
+            .resolve(&self.absolute(dir.as_ref()), true, 0)
+            .unwrap_or_default();
+        self.entries
+            .iter()
+            .filter(move |(path, _)| path.parent() == Some(dir.as_path()))
+            .map(|(path, entry)| (path.as_path(), entry))
+    }
+
+    fn insert(&mut self, path: &Path, entry: MockEntry) -> &mut Self {
+        let path = normalize(&self.absolute(path));
+        for ancestor in path.ancestors().skip(1) {
+            self.entries
+                .entry(ancestor.to_path_buf())
+                .or_insert(MockEntry::Dir);
+        }
+        self.entries.insert(path, entry);
+        self
+    }
+
+    fn absolute(&self, path: &Path) -> PathBuf {
+        self.cwd.join(path)
+    }
+
+    /// Follows symlinks through every component of an absolute path in order, `..` going to the
+    /// parent of what the path so far resolved to.
+    fn resolve(&self, path: &Path, follow_last: bool, hops: usize) -> Option<PathBuf> {
+        if hops > MAX_SYMLINK_HOPS {
+            return None;
+        }
+        let components: Vec<Component> = path.components().collect();
+        let mut current = PathBuf::new();
+        for (i, component) in components.iter().enumerate() {
+            match component {
+                Component::CurDir => continue,
+                Component::ParentDir => {
+                    current.pop();
+                    continue;
+                }
+                component => current.push(component),
+            }
+            let is_last = i + 1 == components.len();
+            if let Some(MockEntry::Symlink(target)) = self.entries.get(&current) {
+                if !is_last || follow_last {
+                    let target = current.parent().unwrap_or(Path::new("")).join(target);
+                    current = self.resolve(&target, true, hops + 1)?;
+                }
+            }
+        }
+        Some(current)
+    }
+}
+
+/// Builds a [`MockFs`] fixture, see [`MockFs::builder`].
+///
+/// Relative paths are resolved against the builder's current directory, wherever in the chain it is set.
+#[derive(Clone, Debug, Default)]
+pub struct MockFsBuilder {
+    cwd: Option<PathBuf>,
+    entries: Vec<(PathBuf, MockEntry)>,
+}
+
+impl MockFsBuilder {
+    /// Sets the mock's current directory, which is created as a directory.
+    pub fn cwd<P: AsRef<Path>>(mut self, cwd: P) -> Self {
+        self.cwd = Some(cwd.as_ref().to_path_buf());
+        self
+    }
+
+    /// Adds a file, along with any missing parent directories.
+    pub fn file<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.entries
+            .push((path.as_ref().to_path_buf(), MockEntry::File));
+        self
+    }
+
+    /// Adds a directory, along with any missing parent directories.
+    pub fn dir<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.entries
+            .push((path.as_ref().to_path_buf(), MockEntry::Dir));
+        self
+    }
+
+    /// Adds a symlink at `link` pointing to `target`, which need not exist.
+    pub fn symlink<P: AsRef<Path>, T: AsRef<Path>>(mut self, link: P, target: T) -> Self {
+        self.entries.push((
+            link.as_ref().to_path_buf(),
+            MockEntry::Symlink(target.as_ref().to_path_buf()),
+        ));
+        self
+    }
+
+    /// Creates the mock with every entry added so far.
+    pub fn build(self) -> MockFs {
+        let mut fs = MockFs::new(self.cwd.unwrap_or_else(|| PathBuf::from("/")));
+        for (path, entry) in self.entries {
+            fs.insert(&path, entry);
+        }
+        fs
+    }
+}
+
+impl PathResolver for MockFs {
+    fn current_dir(&self) -> io::Result<PathBuf> {
+        Ok(self.cwd.clone())
+    }
+
+    fn symlink_target(&self, path: &Path) -> io::Result<Option<PathBuf>> {
+        match self.entry(path) {
+            Some(MockEntry::Symlink(target)) => Ok(Some(target.clone())),
+            _ => Ok(None),
+        }
+    }
+
+    fn exists(&self, path: &Path) -> io::Result<bool> {
+        let absolute = self.absolute(path);
+        match self.resolve(&absolute, true, 0) {
+            Some(resolved) => Ok(matches!(
+                self.entries.get(&resolved),
+                Some(MockEntry::File | MockEntry::Dir)
+            )),
+            None => Err(too_many_links(path)),
+        }
+    }
+}
+
+fn too_many_links(path: &Path) -> io::Error {
+    io::Error::other(format!(
+        "too many levels of symbolic links resolving {}",
+        path.display()
+    ))
+}
+
+/// Makes the path absolute against the resolver's current directory and normalizes it lexically.
+pub fn absolutize_with<R: PathResolver>(path: &Path, resolver: &R) -> io::Result<PathBuf> {
+    if path.is_absolute() {
+        Ok(normalize(path))
+    } else {
+        Ok(path.absolutize_from(resolver.current_dir()?))
+    }
+}
+
+/// Resolves symlinks for as much of the path as exists, keeping the missing remainder as written.
+///
+/// Unlike `canonicalize` this succeeds for paths that don't exist yet, such as an output file
+/// inside a symlinked directory. Components are resolved in order as the OS does, so `..` after a
+/// symlink goes to the parent of its target. Below a missing component the path is only
+/// normalized lexically, until a `..` climbs back out of it.
+pub fn resolve_lenient_with<R: PathResolver>(path: &Path, resolver: &R) -> io::Result<PathBuf> {
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        resolver.current_dir()?.join(path)
+    };
+    let mut pending: VecDeque<OsString> = absolute
+        .components()
+        .map(|c| c.as_os_str().to_os_string())
+        .collect();
+    let mut resolved = PathBuf::new();
+    // how many components at the end of `resolved` are from the first missing one on
+    let mut missing = 0usize;
+    let mut hops = 0;
+
+    while let Some(part) = pending.pop_front() {
+        if part == "." {
+            continue;
+        }
+        if part == ".." {
+            resolved.pop();
+            missing = missing.saturating_sub(1);
+            continue;
+        }
+        let candidate = resolved.join(&part);
+        if missing > 0 {
+            // nothing below a missing component exists to be a symlink
+            missing += 1;
+            resolved = candidate;
+            continue;
+        }
+        if resolved.as_os_str().is_empty() || candidate.parent().is_none() {
+            // prefixes and roots are kept as they are
+            resolved = candidate;
+            continue;
+        }
+
+        if let Some(target) = resolver.symlink_target(&candidate)? {
+            hops += 1;
+            if hops > MAX_SYMLINK_HOPS {
+                return Err(too_many_links(path));
+            }
+            // relative targets continue from the link's directory, absolute ones replace it
+            for part in target.components().rev() {
+                pending.push_front(part.as_os_str().to_os_string());
+            }
+        } else {
+            missing = usize::from(!resolver.exists(&candidate)?);
+            resolved = candidate;
+        }
+    }
+
+    Ok(resolved)
+}
+
+/// Looks for `name` in the path and each of its ancestors, returning the first match.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(start = %start.display(), name = %name.display())
+    )
+)]
+pub fn find_up_with<R: PathResolver>(
+    start: &Path,
+    name: &Path,
+    resolver: &R,
+) -> io::Result<Option<PathBuf>> {
+    let start = absolutize_with(start, resolver)?;
+    for dir in start.ancestors() {
+        let candidate = dir.join(name);
+        if resolver.exists(&candidate)? {
+            debug!(found = %candidate.display());
+            return Ok(Some(candidate));
+        }
+    }
+    debug!("not found in any ancestor");
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock() -> MockFs {
+        let mut fs = MockFs::new("/work/project");
+        fs.add_file("/work/project/src/lib.rs")
+            .add_file("/work/Cargo.toml")
+            .add_dir("/data/real")
+            .add_symlink("/work/project/out", "/data/real")
+            .add_symlink("/work/project/rel", "src")
+            .add_symlink("/work/project/dangling", "/nowhere")
+            .add_symlink("/work/project/deep", "/data/real")
+            .add_symlink("/loop/a", "/loop/b")
+            .add_symlink("/loop/b", "/loop/a");
+        fs
+    }
+
+    #[test]
+    fn test_mock_fs() {
+        let fs = mock();
+        assert_eq!(fs.current_dir().unwrap(), Path::new("/work/project"));
+        assert!(fs.exists(Path::new("src/lib.rs")).unwrap());
+        assert!(fs.exists(Path::new("/work")).unwrap());
+        assert!(fs.exists(Path::new("out")).unwrap());
+        assert!(fs.exists(Path::new("rel/lib.rs")).unwrap());
+        assert!(!fs.exists(Path::new("dangling")).unwrap());
+        assert!(!fs.exists(Path::new("missing")).unwrap());
+        assert!(fs.exists(Path::new("/loop/a")).is_err());
+        assert_eq!(
+            fs.symlink_target(Path::new("out")).unwrap(),
+            Some(PathBuf::from("/data/real"))
+        );
+        assert_eq!(fs.symlink_target(Path::new("src")).unwrap(), None);
+        assert_eq!(fs.entry("/work/project/src"), Some(&MockEntry::Dir));
+
+        // `..` after a symlink leaves its target, as `resolve_lenient_with` has it
+        assert!(fs.exists(Path::new("deep/../real")).unwrap());
+        assert!(!fs.exists(Path::new("deep/../src")).unwrap());
+        assert!(fs.exists(Path::new("rel/../src/lib.rs")).unwrap());
+        assert_eq!(
+            resolve_lenient_with(Path::new("deep/../real"), &fs).unwrap(),
+            Path::new("/data/real")
+        );
+    }
+
+    #[test]
+    fn test_mock_fs_builder() {
+        let fs = MockFs::builder()
+            .file("b.txt")
+            .cwd("/a")
+            .dir("/empty")
+            .symlink("/l", "/a")
+            .build();
+        assert_eq!(fs.current_dir().unwrap(), normalize(Path::new("/a")));
+        assert!(fs.exists(Path::new("/l/b.txt")).unwrap());
+        assert!(fs.exists(Path::new("/empty")).unwrap());
+
+        let root: Vec<_> = fs.children("/").collect();
+        assert_eq!(
+            root,
+            vec![
+                (normalize(Path::new("/a")).as_path(), &MockEntry::Dir),
+                (normalize(Path::new("/empty")).as_path(), &MockEntry::Dir),
+                (
+                    normalize(Path::new("/l")).as_path(),
+                    &MockEntry::Symlink(PathBuf::from("/a"))
+                ),
+            ]
+        );
+        let through_link: Vec<_> = fs.children("/l").map(|(p, _)| p).collect();
+        assert_eq!(through_link, vec![normalize(Path::new("/a/b.txt"))]);
+    }
+
+    #[test]
+    fn test_mock_fs_builder_dotdot_after_link() {
+        let fs = MockFs::builder()
+            .dir("/a/sub")
+            .file("/x/f")
+            .symlink("/x/l", "/a/sub")
+            .build();
+        // `/x/l/..` is `/a`, not the lexical `/x`
+        assert_eq!(fs.entry("/x/l/.."), Some(&MockEntry::Dir));
+        assert!(fs.exists(Path::new("/x/l/../sub")).unwrap());
+        assert!(!fs.exists(Path::new("/x/l/../f")).unwrap());
+        assert_eq!(fs.entry("/x/l/../f"), None);
+        assert_eq!(
+            fs.entry("/x/l"),
+            Some(&MockEntry::Symlink(PathBuf::from("/a/sub")))
+        );
+    }
+
+    #[test]
+    fn test_absolutize_with() {
+        let fs = mock();
+        let tests = &[
+            ("src/./lib.rs", "/work/project/src/lib.rs"),
+            ("../Cargo.toml", "/work/Cargo.toml"),
+            ("/etc/../tmp", "/tmp"),
+            ("", "/work/project"),
+        ];
+
+        for test in tests {
+            assert_eq!(
+                absolutize_with(Path::new(test.0), &fs).unwrap(),
+                normalize(Path::new(test.1))
+            );
+        }
+    }
+
+    #[test]
+    fn test_resolve_lenient_with() {
+        let fs = mock();
+        let tests = &[
+            ("out/new/file.txt", "/data/real/new/file.txt"),
+            ("rel/lib.rs", "/work/project/src/lib.rs"),
+            ("src/missing/../lib.rs", "/work/project/src/lib.rs"),
+            // climbing back out of a missing directory resolves symlinks again
+            ("src/missing/../../out/x", "/data/real/x"),
+            ("src/a/b/../../../rel/lib.rs", "/work/project/src/lib.rs"),
+            ("src/a/b/../c", "/work/project/src/a/c"),
+            ("dangling/x", "/nowhere/x"),
+            ("/not/there", "/not/there"),
+            // `..` after a symlink leaves its target, not the link's directory
+            ("deep/..", "/data"),
+            ("rel/../Cargo.toml", "/work/project/Cargo.toml"),
+            ("./src/./lib.rs", "/work/project/src/lib.rs"),
+        ];
+
+        for test in tests {
+            assert_eq!(
+                resolve_lenient_with(Path::new(test.0), &fs).unwrap(),
+                normalize(Path::new(test.1)),
+                "{}",
+                test.0
+            );
+        }
+        assert!(resolve_lenient_with(Path::new("/loop/a"), &fs).is_err());
+    }
+
+    #[test]
+    fn test_find_up_with() {
+        let fs = mock();
+        let tests = &[
+            ("src", "Cargo.toml", Some("/work/Cargo.toml")),
+            ("src", "src", Some("/work/project/src")),
+            ("/data/real", "Cargo.toml", None),
+        ];
+
+        for test in tests {
+            assert_eq!(
+                find_up_with(Path::new(test.0), Path::new(test.1), &fs).unwrap(),
+                test.2.map(|p| normalize(Path::new(p)))
+            );
+        }
+    }
+}
@@ -0,0 +1,330 @@
+//! Response files, the `@file` arguments compilers and linkers take to get past command line length
+//! limits.
+
+#[cfg(feature = "fs")]
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+#[cfg(feature = "fs")]
+use std::path::PathBuf;
+
+use crate::Platform;
+
+/// Whose quoting rules a response file follows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ResponseFileStyle {
+    /// GCC, Clang and binutils: `'...'` and `"..."` group, and `\` escapes the next character
+    /// everywhere, so Windows paths have their backslashes doubled.
+    Gnu,
+    /// MSVC's `cl` and `link`, splitting like `CommandLineToArgvW`: `"..."` groups, and backslashes
+    /// are literal unless a `"` follows them.
+    Msvc,
+}
+
+impl Default for ResponseFileStyle {
+    /// [`Msvc`](ResponseFileStyle::Msvc) on Windows, [`Gnu`](ResponseFileStyle::Gnu) elsewhere.
+    fn default() -> Self {
+        ResponseFileStyle::for_platform(Platform::Native)
+    }
+}
+
+impl ResponseFileStyle {
+    /// The style of `platform`'s usual toolchain, [`Msvc`](ResponseFileStyle::Msvc) for Windows.
+    pub fn for_platform(platform: Platform) -> Self {
+        if platform.is_windows() {
+            ResponseFileStyle::Msvc
+        } else {
+            ResponseFileStyle::Gnu
+        }
+    }
+}
+
+#[cfg(feature = "fs")]
+fn split_gnu(text: &str) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    let mut word = String::new();
+    let mut quote = None;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match (c, quote) {
+            ('\\', _) => word.extend(chars.next()),
+            ('\'' | '"', None) => quote = Some(c),
+            (c, Some(open)) if c == open => quote = None,
+            (c, None) if c.is_whitespace() => {
+                if !word.is_empty() {
+                    paths.push(PathBuf::from(std::mem::take(&mut word)));
+                }
+            }
+            (c, _) => word.push(c),
+        }
+    }
+    if !word.is_empty() {
+        paths.push(PathBuf::from(word));
+    }
+    paths
+}
+
+#[cfg(feature = "fs")]
+fn split_msvc(text: &str) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    let mut word = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                let mut count = 1;
+                while chars.next_if_eq(&'\\').is_some() {
+                    count += 1;
+                }
+                if chars.peek() == Some(&'"') {
+                    word.extend(std::iter::repeat_n('\\', count / 2));
+                    if count % 2 == 1 {
+                        word.extend(chars.next());
+                    }
+                } else {
+                    word.extend(std::iter::repeat_n('\\', count));
+                }
+            }
+            // `""` inside quotes is a literal `"`
+            '"' if quoted && chars.next_if_eq(&'"').is_some() => word.push('"'),
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !word.is_empty() {
+                    paths.push(PathBuf::from(std::mem::take(&mut word)));
+                }
+            }
+            c => word.push(c),
+        }
+    }
+    if !word.is_empty() {
+        paths.push(PathBuf::from(word));
+    }
+    paths
+}
+
+#[cfg(feature = "fs")]
+fn decode(bytes: Vec<u8>) -> io::Result<String> {
+    if let Some(utf16) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+        let units: Vec<u16> = utf16
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        return String::from_utf16(&units)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "response file isn't UTF-16"));
+    }
+    let bytes = match bytes.strip_prefix(b"\xEF\xBB\xBF") {
+        Some(rest) => rest.to_vec(),
+        None => bytes,
+    };
+    String::from_utf8(bytes)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "response file isn't UTF-8"))
+}
+
+/// Reads the paths listed in a response file, split by `style`'s quoting rules. The file may be
+/// UTF-8, with or without a byte order mark, or UTF-16LE with one, as MSVC tools write them.
+/// Nested `@file` references are returned as they are, and empty arguments like `""` dropped.
+///
+/// ```rust
+/// use pathext::{read_response_file, write_response_file, ResponseFileStyle};
+/// use std::path::PathBuf;
+///
+/// let paths = [r"C:\My Project\main.c", r"C:\src\util.c"].map(PathBuf::from);
+/// let mut contents = Vec::new();
+/// write_response_file(&mut contents, &paths, ResponseFileStyle::Msvc).unwrap();
+/// assert_eq!(contents, b"\"C:\\My Project\\main.c\"\nC:\\src\\util.c\n");
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// let file = dir.path().join("args.rsp");
+/// std::fs::write(&file, &contents).unwrap();
+/// assert_eq!(read_response_file(&file, ResponseFileStyle::Msvc).unwrap(), paths);
+/// ```
+#[cfg(feature = "fs")]
+pub fn read_response_file<P: AsRef<Path>>(
+    path: P,
+    style: ResponseFileStyle,
+) -> io::Result<Vec<PathBuf>> {
+    let path = path.as_ref();
+    let bytes = fs::read(path).map_err(|e| crate::fs::with_path(e, "read", path))?;
+    let text = decode(bytes).map_err(|e| crate::fs::with_path(e, "decode", path))?;
+    Ok(match style {
+        ResponseFileStyle::Gnu => split_gnu(&text),
+        ResponseFileStyle::Msvc => split_msvc(&text),
+    })
+}
+
+fn quote_gnu(path: &str) -> String {
+    let mut quoted = String::with_capacity(path.len());
+    for c in path.chars() {
+        if c.is_whitespace() || matches!(c, '\\' | '\'' | '"') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted
+}
+
+fn quote_msvc(path: &str) -> Option<String> {
+    if path.contains(['"', '\n', '\r']) {
+        return None;
+    }
+    if !path.is_empty() && !path.contains(char::is_whitespace) {
+        return Some(path.to_string());
+    }
+    // backslashes before the closing quote would escape it
+    let trailing = path.len() - path.trim_end_matches('\\').len();
+    Some(format!("\"{path}{}\"", "\\".repeat(trailing)))
+}
+
+/// Writes the paths one per line, quoted for `style`, for passing as `@file`. Paths that aren't
+/// Unicode, and for [`Msvc`](ResponseFileStyle::Msvc) paths with `"` or line breaks, which it has
+/// no way to write, are an [`io::ErrorKind::InvalidData`] error.
+pub fn write_response_file<W, I, P>(
+    mut writer: W,
+    paths: I,
+    style: ResponseFileStyle,
+) -> io::Result<()>
+where
+    W: Write,
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    for path in paths {
+        let path = path.as_ref();
+        let quoted = path.to_str().and_then(|path| match style {
+            ResponseFileStyle::Gnu => Some(quote_gnu(path)),
+            ResponseFileStyle::Msvc => quote_msvc(path),
+        });
+        let quoted = quoted.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{} can't be written to a response file", path.display()),
+            )
+        })?;
+        writeln!(writer, "{quoted}")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "fs")]
+    use super::{decode, read_response_file, split_gnu, split_msvc};
+    use super::{write_response_file, ResponseFileStyle};
+
+    use crate::Platform;
+
+    #[cfg(feature = "fs")]
+    use std::path::PathBuf;
+
+    #[cfg(feature = "fs")]
+    fn paths(paths: &[&str]) -> Vec<PathBuf> {
+        paths.iter().map(PathBuf::from).collect()
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn test_split_gnu() {
+        let tests: &[(&str, &[&str])] = &[
+            ("a.o b.o\n\tc.o\n", &["a.o", "b.o", "c.o"]),
+            (
+                "'my file.o' \"other file.o\"",
+                &["my file.o", "other file.o"],
+            ),
+            (
+                r"C:\\src\\a.o with\ space.o",
+                &[r"C:\src\a.o", "with space.o"],
+            ),
+            (r#""say \"hi\"" 'it\'s'"#, &[r#"say "hi""#, "it's"]),
+            ("'' x", &["x"]),
+        ];
+
+        for test in tests {
+            assert_eq!(split_gnu(test.0), paths(test.1), "{}", test.0);
+        }
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn test_split_msvc() {
+        let tests: &[(&str, &[&str])] = &[
+            ("a.obj b.obj\r\nc.obj", &["a.obj", "b.obj", "c.obj"]),
+            (r"C:\src\a.obj", &[r"C:\src\a.obj"]),
+            (r#""C:\My Files\a.obj""#, &[r"C:\My Files\a.obj"]),
+            (r#""C:\dir\\" next"#, &[r"C:\dir\", "next"]),
+            (r#"a\"b "c""d""#, &[r#"a"b"#, r#"c"d"#]),
+            (r#"pre"fix ed"post"#, &["prefix edpost"]),
+            (r#""" x"#, &["x"]),
+        ];
+
+        for test in tests {
+            assert_eq!(split_msvc(test.0), paths(test.1), "{}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_for_platform() {
+        assert_eq!(
+            ResponseFileStyle::for_platform(Platform::Windows),
+            ResponseFileStyle::Msvc
+        );
+        assert_eq!(
+            ResponseFileStyle::for_platform(Platform::Unix),
+            ResponseFileStyle::Gnu
+        );
+        assert_eq!(
+            ResponseFileStyle::for_platform(Platform::Native),
+            ResponseFileStyle::default()
+        );
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn test_decode() {
+        assert_eq!(decode(b"\xEF\xBB\xBFa.o".to_vec()).unwrap(), "a.o");
+        assert_eq!(decode(b"\xFF\xFEa\0.\0o\0".to_vec()).unwrap(), "a.o");
+        assert!(decode(b"\xFF".to_vec()).is_err());
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn test_write_roundtrip() {
+        let written = paths(&[
+            "plain.o",
+            "with space.o",
+            r"C:\src\a.obj",
+            r"C:\My Files\",
+            "tab\there.o",
+        ]);
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("args.rsp");
+
+        for style in [ResponseFileStyle::Gnu, ResponseFileStyle::Msvc] {
+            let mut contents = Vec::new();
+            write_response_file(&mut contents, &written, style).unwrap();
+            std::fs::write(&file, contents).unwrap();
+            assert_eq!(read_response_file(&file, style).unwrap(), written);
+        }
+
+        std::fs::write(&file, b"\xFF").unwrap();
+        let error = read_response_file(&file, ResponseFileStyle::Gnu).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        let context = crate::PathIoError::of(&error).unwrap();
+        assert_eq!(
+            (context.operation, context.path.as_path()),
+            ("decode", file.as_path())
+        );
+        let missing = dir.path().join("missing.rsp");
+        let error = read_response_file(&missing, ResponseFileStyle::Gnu).unwrap_err();
+        assert_eq!(crate::PathIoError::of(&error).unwrap().operation, "read");
+    }
+
+    #[test]
+    fn test_write_response_file() {
+        let mut contents = Vec::new();
+        write_response_file(&mut contents, ["it's \"quoted\""], ResponseFileStyle::Gnu).unwrap();
+        assert_eq!(contents, b"it\\'s\\ \\\"quoted\\\"\n");
+        let error = write_response_file(Vec::new(), ["a\"b"], ResponseFileStyle::Msvc).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,190 @@
+//! Longest-prefix lookup of paths, for mount tables, static file servers and the like.
+
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+
+/// Maps path prefixes to values, routing a path to the value of the longest prefix containing it.
+///
+/// Prefixes match whole components, so `/static` routes `/static/app.js` but not `/statics`, and
+/// `.` components and trailing separators don't matter. An empty prefix catches every relative path.
+///
+/// Paths aren't normalized: one with a `..` after its prefix isn't routed at all, since
+/// `/static/../etc/passwd` would otherwise hand `../etc/passwd` to whatever serves `/static`.
+///
+/// ```rust
+/// use pathext::PathRouter;
+/// use std::path::Path;
+///
+/// let mut router = PathRouter::new();
+/// router.insert("/", "root");
+/// router.insert("/static", "assets");
+/// router.insert("/static/vendor", "third-party");
+///
+/// assert_eq!(router.route("/static/app.js"), Some((&"assets", Path::new("app.js"))));
+/// assert_eq!(router.route("/static/vendor/x.js"), Some((&"third-party", Path::new("x.js"))));
+/// assert_eq!(router.route("/statics"), Some((&"root", Path::new("statics"))));
+/// assert_eq!(router.route("relative"), None);
+/// assert_eq!(router.route("/static/../etc/passwd"), None);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PathRouter<T> {
+    routes: BTreeMap<PathBuf, T>,
+}
+
+impl<T> Default for PathRouter<T> {
+    fn default() -> Self {
+        PathRouter {
+            routes: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T> PathRouter<T> {
+    pub fn new() -> Self {
+        PathRouter::default()
+    }
+
+    /// Registers `prefix`, returning the value it replaced if it was already registered.
+    pub fn insert<P: AsRef<Path>>(&mut self, prefix: P, value: T) -> Option<T> {
+        self.routes.insert(prefix.as_ref().to_path_buf(), value)
+    }
+
+    pub fn remove<P: AsRef<Path>>(&mut self, prefix: P) -> Option<T> {
+        self.routes.remove(prefix.as_ref())
+    }
+
+    /// The value registered for exactly `prefix`.
+    pub fn get<P: AsRef<Path>>(&self, prefix: P) -> Option<&T> {
+        self.routes.get(prefix.as_ref())
+    }
+
+    /// The value of the longest registered prefix of `path`, and what's left of `path` after it.
+    /// `None` if nothing matches or what's left has a `..` component, which could climb out of the
+    /// prefix.
+    pub fn route<'p, P: AsRef<Path> + ?Sized>(&self, path: &'p P) -> Option<(&T, &'p Path)> {
+        let path = path.as_ref();
+        let (value, remainder) = path.ancestors().find_map(|ancestor| {
+            let value = self.routes.get(ancestor)?;
+            Some((value, path.strip_prefix(ancestor).ok()?))
+        })?;
+        contained(remainder).then_some((value, remainder))
+    }
+
+    /// Like [`route`](PathRouter::route), with the value mutable.
+    pub fn route_mut<'p, P: AsRef<Path> + ?Sized>(
+        &mut self,
+        path: &'p P,
+    ) -> Option<(&mut T, &'p Path)> {
+        let path = path.as_ref();
+        let prefix = path
+            .ancestors()
+            .find(|ancestor| self.routes.contains_key(*ancestor))?;
+        let remainder = path.strip_prefix(prefix).ok()?;
+        if !contained(remainder) {
+            return None;
+        }
+        Some((self.routes.get_mut(prefix)?, remainder))
+    }
+
+    /// The prefixes and their values, in path order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Path, &T)> {
+        self.routes
+            .iter()
+            .map(|(prefix, value)| (prefix.as_path(), value))
+    }
+
+    pub fn len(&self) -> usize {
+        self.routes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+}
+
+/// Whether `remainder` stays under the prefix it was routed by.
+fn contained(remainder: &Path) -> bool {
+    !remainder
+        .components()
+        .any(|component| component == Component::ParentDir)
+}
+
+impl<P: AsRef<Path>, T> FromIterator<(P, T)> for PathRouter<T> {
+    fn from_iter<I: IntoIterator<Item = (P, T)>>(iter: I) -> Self {
+        let mut router = PathRouter::new();
+        router.extend(iter);
+        router
+    }
+}
+
+impl<P: AsRef<Path>, T> Extend<(P, T)> for PathRouter<T> {
+    fn extend<I: IntoIterator<Item = (P, T)>>(&mut self, iter: I) {
+        for (prefix, value) in iter {
+            self.insert(prefix, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PathRouter;
+
+    use std::path::Path;
+
+    #[test]
+    fn test_route() {
+        let router: PathRouter<u32> = [
+            ("/", 0),
+            ("/srv", 1),
+            ("/srv/www/", 2),
+            ("/srv/www/private", 3),
+            ("", 4),
+            ("src/./gen", 5),
+        ]
+        .into_iter()
+        .collect();
+        let tests = &[
+            ("/srv/www/index.html", Some((2, "index.html"))),
+            ("/srv/www", Some((2, ""))),
+            ("/srv/www/private/key", Some((3, "key"))),
+            ("/srv/wwwroot/x", Some((1, "wwwroot/x"))),
+            ("/srv/./www/x", Some((2, "x"))),
+            ("/etc/hosts", Some((0, "etc/hosts"))),
+            ("src/gen/mod.rs", Some((5, "mod.rs"))),
+            ("src/lib.rs", Some((4, "src/lib.rs"))),
+            ("/srv/www/../../etc/passwd", None),
+            ("/srv/www/private/../key", None),
+            ("src/gen/..", None),
+            ("../outside", None),
+        ];
+
+        for test in tests {
+            let routed = router.route(test.0).map(|(v, rest)| (*v, rest));
+            assert_eq!(
+                routed,
+                test.1.map(|(v, rest)| (v, Path::new(rest))),
+                "{}",
+                test.0
+            );
+        }
+    }
+
+    #[test]
+    fn test_router_editing() {
+        let mut router = PathRouter::new();
+        assert!(router.is_empty());
+        assert_eq!(router.insert("/a", 1), None);
+        assert_eq!(router.insert("/a/", 2), Some(1));
+        assert_eq!(router.len(), 1);
+        assert_eq!(router.route("/b"), None);
+
+        if let Some((value, _)) = router.route_mut("/a/b") {
+            *value += 10;
+        }
+        assert_eq!(router.get("/a"), Some(&12));
+        assert!(router.route_mut("/a/../b").is_none());
+        assert_eq!(router.iter().collect::<Vec<_>>(), [(Path::new("/a"), &12)]);
+        assert_eq!(router.remove("/a"), Some(12));
+        assert!(router.is_empty());
+    }
+}
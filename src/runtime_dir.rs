@@ -0,0 +1,304 @@
+//! Where a per-user daemon keeps its sockets, PID and lock files.
+
+#[cfg(feature = "fs")]
+use std::io;
+use std::path::PathBuf;
+
+use crate::{ExpansionContext, Platform};
+
+/// Which tier of the fallback chain of [`runtime_file`] was used.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RuntimeDirSource {
+    /// The directory in this environment variable, like `XDG_RUNTIME_DIR`, `TMPDIR` or `TEMP`.
+    Var(&'static str),
+    /// The system's temp directory, `/tmp` or `C:\Windows\Temp`, for when no variable was set.
+    SystemTemp,
+}
+
+/// A file in the runtime directory, as [`runtime_file`] resolved it.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RuntimeFile {
+    pub path: PathBuf,
+    /// The app's directory holding the file, which has to be created first.
+    pub dir: PathBuf,
+    pub source: RuntimeDirSource,
+    /// Whether other users can write where `dir` goes, so it has to be created owner-only and
+    /// checked to be ours, or someone else could have made it first. Such a `dir` is named
+    /// `{app}-{user}` so users don't clash.
+    pub shared: bool,
+}
+
+/// The file `name` in `app`'s runtime directory, trying `$XDG_RUNTIME_DIR`, then `$TMPDIR`, then
+/// `/tmp`, and on Windows `%TMP%`, `%TEMP%`, `%USERPROFILE%` and then `%SystemRoot%\Temp`, as
+/// [`runtime_file_with`] does for the process's environment.
+pub fn runtime_file(app: &str, name: &str) -> RuntimeFile {
+    runtime_file_with(
+        app,
+        name,
+        &ExpansionContext::from_process(),
+        Platform::Native,
+    )
+}
+
+/// [`runtime_file`] for `platform`, reading variables from `context`. Only variables holding an
+/// absolute path count.
+///
+/// `XDG_RUNTIME_DIR` is private to the user by specification, and the Windows variables point into
+/// the user's profile; the others are [`shared`](RuntimeFile::shared). The user for naming a shared
+/// directory comes from `USER`, `LOGNAME` or `USERNAME`.
+///
+/// ```rust
+/// use pathext::{runtime_file_with, ExpansionContext, Platform, RuntimeDirSource};
+/// use std::path::Path;
+///
+/// let mut context = ExpansionContext::new();
+/// context.vars.insert("XDG_RUNTIME_DIR".into(), "/run/user/1000".into());
+/// let file = runtime_file_with("mydaemon", "control.sock", &context, Platform::Unix);
+/// assert_eq!(file.path, Path::new("/run/user/1000/mydaemon/control.sock"));
+/// assert_eq!(file.source, RuntimeDirSource::Var("XDG_RUNTIME_DIR"));
+///
+/// context.vars.clear();
+/// context.vars.insert("USER".into(), "ann".into());
+/// let file = runtime_file_with("mydaemon", "control.sock", &context, Platform::Unix);
+/// assert_eq!(file.dir, Path::new("/tmp/mydaemon-ann"));
+/// assert!(file.shared);
+/// ```
+pub fn runtime_file_with(
+    app: &str,
+    name: &str,
+    context: &ExpansionContext,
+    platform: Platform,
+) -> RuntimeFile {
+    let windows = platform.is_windows();
+    let tiers: &[(&str, bool)] = if windows {
+        &[("TMP", false), ("TEMP", false), ("USERPROFILE", false)]
+    } else {
+        &[("XDG_RUNTIME_DIR", false), ("TMPDIR", true)]
+    };
+    let found = tiers.iter().find_map(|&(var, shared)| {
+        let value = context.vars.get(var)?;
+        is_absolute(value, windows).then_some((RuntimeDirSource::Var(var), value.clone(), shared))
+    });
+    let (source, base, shared) = found.unwrap_or_else(|| {
+        let base = if windows {
+            let root = context
+                .vars
+                .get("SystemRoot")
+                .filter(|root| is_absolute(root, true));
+            format!(r"{}\Temp", root.map_or(r"C:\Windows", String::as_str))
+        } else {
+            "/tmp".to_string()
+        };
+        (RuntimeDirSource::SystemTemp, base, true)
+    });
+
+    let user = ["USER", "LOGNAME", "USERNAME"]
+        .iter()
+        .find_map(|var| context.vars.get(*var).filter(|user| !user.is_empty()));
+    let app_dir = match user {
+        Some(user) if shared => format!("{app}-{user}"),
+        _ => app.to_string(),
+    };
+    let separator = if windows { '\\' } else { '/' };
+    let base = base.trim_end_matches(|c| platform.is_separator(c));
+    let dir = format!("{base}{separator}{app_dir}");
+    let path = format!("{dir}{separator}{name}");
+    RuntimeFile {
+        path: PathBuf::from(path),
+        dir: PathBuf::from(dir),
+        source,
+        shared,
+    }
+}
+
+fn is_absolute(path: &str, windows: bool) -> bool {
+    if windows {
+        let bytes = path.as_bytes();
+        path.starts_with(r"\\")
+            || bytes.len() >= 3
+                && bytes[0].is_ascii_alphabetic()
+                && bytes[1] == b':'
+                && matches!(bytes[2], b'\\' | b'/')
+    } else {
+        path.starts_with('/')
+    }
+}
+
+impl RuntimeFile {
+    /// Creates [`dir`](RuntimeFile::dir) readable and writable by the owner only, or accepts it if
+    /// it's already there, then checks it's a directory, not a symlink, owned by the current user
+    /// and closed to others, failing with [`PermissionDenied`](io::ErrorKind::PermissionDenied) if
+    /// not. That matters most for a [`shared`](RuntimeFile::shared) one, which someone else could
+    /// have made first. Off Unix it only creates it.
+    #[cfg(feature = "fs")]
+    pub fn create_dir(&self) -> io::Result<()> {
+        create_private_dir(self)
+            .map_err(|e| crate::fs::with_path(e, "create the runtime dir", &self.dir))
+    }
+}
+
+#[cfg(all(feature = "fs", unix))]
+fn create_private_dir(file: &RuntimeFile) -> io::Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+
+    match std::fs::DirBuilder::new().mode(0o700).create(&file.dir) {
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+        result => result?,
+    }
+    let metadata = std::fs::symlink_metadata(&file.dir)?;
+    // SAFETY: getuid has no preconditions and can't fail.
+    let uid = unsafe { libc::getuid() };
+    if !metadata.is_dir() || metadata.uid() != uid || metadata.mode() & 0o077 != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "exists but isn't a directory private to the current user",
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(all(feature = "fs", not(unix)))]
+fn create_private_dir(file: &RuntimeFile) -> io::Result<()> {
+    crate::fs::ensure_dir(&file.dir).map(drop)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{runtime_file_with, RuntimeDirSource};
+    use crate::{ExpansionContext, Platform};
+
+    use std::path::PathBuf;
+
+    fn context(vars: &[(&str, &str)]) -> ExpansionContext {
+        let mut context = ExpansionContext::new();
+        for (name, value) in vars {
+            context.vars.insert(name.to_string(), value.to_string());
+        }
+        context
+    }
+
+    type Vars = &'static [(&'static str, &'static str)];
+
+    #[test]
+    fn test_runtime_file_with() {
+        let tests: &[(Vars, Platform, &str, RuntimeDirSource, bool)] = &[
+            (
+                &[
+                    ("XDG_RUNTIME_DIR", "/run/user/1000/"),
+                    ("TMPDIR", "/var/tmp"),
+                ],
+                Platform::Unix,
+                "/run/user/1000/app/app.pid",
+                RuntimeDirSource::Var("XDG_RUNTIME_DIR"),
+                false,
+            ),
+            (
+                &[
+                    ("XDG_RUNTIME_DIR", "relative"),
+                    ("TMPDIR", "/var/tmp"),
+                    ("USER", "ann"),
+                ],
+                Platform::Unix,
+                "/var/tmp/app-ann/app.pid",
+                RuntimeDirSource::Var("TMPDIR"),
+                true,
+            ),
+            (
+                &[("TMPDIR", ""), ("LOGNAME", "bob")],
+                Platform::Unix,
+                "/tmp/app-bob/app.pid",
+                RuntimeDirSource::SystemTemp,
+                true,
+            ),
+            (
+                &[],
+                Platform::Unix,
+                "/tmp/app/app.pid",
+                RuntimeDirSource::SystemTemp,
+                true,
+            ),
+            (
+                &[
+                    ("TEMP", r"C:\Users\ann\AppData\Local\Temp"),
+                    ("USERNAME", "ann"),
+                ],
+                Platform::Windows,
+                r"C:\Users\ann\AppData\Local\Temp\app\app.pid",
+                RuntimeDirSource::Var("TEMP"),
+                false,
+            ),
+            (
+                &[("TMP", "temp"), ("USERPROFILE", r"\\server\home\ann")],
+                Platform::Windows,
+                r"\\server\home\ann\app\app.pid",
+                RuntimeDirSource::Var("USERPROFILE"),
+                false,
+            ),
+            (
+                &[("SystemRoot", r"D:\Win"), ("USERNAME", "ann")],
+                Platform::Windows,
+                r"D:\Win\Temp\app-ann\app.pid",
+                RuntimeDirSource::SystemTemp,
+                true,
+            ),
+        ];
+
+        for test in tests {
+            let file = runtime_file_with("app", "app.pid", &context(test.0), test.1);
+            assert_eq!(file.path, PathBuf::from(test.2), "{:?}", test.0);
+            let dir = file.dir.to_str().unwrap();
+            assert_eq!(test.2.rsplit_once(['/', '\\']).unwrap().0, dir);
+            assert_eq!(file.source, test.3, "{:?}", test.0);
+            assert_eq!(file.shared, test.4, "{:?}", test.0);
+        }
+    }
+
+    #[cfg(all(feature = "fs", unix))]
+    #[test]
+    fn test_create_dir() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = tempfile::tempdir().unwrap();
+        let tmpdir = root.path().to_str().unwrap();
+        let context = context(&[("TMPDIR", tmpdir), ("USER", "me")]);
+        let file = runtime_file_with("app", "app.sock", &context, Platform::Unix);
+        assert!(file.shared);
+
+        file.create_dir().unwrap();
+        let mode = std::fs::metadata(&file.dir).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+        // already there and ours
+        file.create_dir().unwrap();
+
+        std::fs::set_permissions(&file.dir, std::fs::Permissions::from_mode(0o777)).unwrap();
+        let e = file.create_dir().unwrap_err();
+        assert_eq!(e.kind(), std::io::ErrorKind::PermissionDenied);
+    }
+
+    #[cfg(all(feature = "fs", unix))]
+    #[test]
+    fn test_create_dir_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = tempfile::tempdir().unwrap();
+        let runtime_dir = root.path().to_str().unwrap();
+        let context = context(&[("XDG_RUNTIME_DIR", runtime_dir)]);
+        let file = runtime_file_with("app", "app.sock", &context, Platform::Unix);
+        assert!(!file.shared);
+
+        file.create_dir().unwrap();
+        let mode = std::fs::metadata(&file.dir).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+        // a second start finds it there
+        file.create_dir().unwrap();
+
+        std::fs::set_permissions(&file.dir, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let e = file.create_dir().unwrap_err();
+        assert_eq!(e.kind(), std::io::ErrorKind::PermissionDenied);
+
+        std::fs::remove_dir(&file.dir).unwrap();
+        std::os::unix::fs::symlink(root.path(), &file.dir).unwrap();
+        let e = file.create_dir().unwrap_err();
+        assert_eq!(e.kind(), std::io::ErrorKind::PermissionDenied);
+    }
+}
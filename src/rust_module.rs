@@ -0,0 +1,192 @@
+//! Mapping Rust module paths to the files that hold them and back.
+
+use std::path::{Component, Path, PathBuf};
+
+/// Keywords a module can only be named with `r#`.
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let",
+    "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+    "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+    "virtual", "where", "while", "yield",
+];
+
+/// Keywords that can't be module names at all, not even raw.
+const PATH_KEYWORDS: &[&str] = &["crate", "self", "super", "Self"];
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    let starts_well = chars.next().is_some_and(|c| c.is_alphabetic() || c == '_');
+    starts_well && chars.all(|c| c.is_alphanumeric() || c == '_') && name != "_"
+}
+
+/// The file name a module segment is stored under, `r#` dropped, `None` if it can't name a module.
+fn segment_file_name(segment: &str) -> Option<&str> {
+    let name = match segment.strip_prefix("r#") {
+        Some(raw) => raw,
+        None if KEYWORDS.contains(&segment) => return None,
+        None => segment,
+    };
+    (is_identifier(name) && !PATH_KEYWORDS.contains(&name)).then_some(name)
+}
+
+/// The files `module` may be in, in the order rustc looks: `src_dir/foo/bar.rs` then
+/// `src_dir/foo/bar/mod.rs` for `crate::foo::bar`, and `lib.rs` then `main.rs` for `crate` itself.
+/// `src_dir` is the directory of the crate root, usually `src`. The `crate::` is optional.
+///
+/// `None` for paths that don't name a module by its place in the crate, like ones starting with
+/// `self` or `super` or holding something that isn't an identifier. `#[path]` attributes and
+/// inline `mod` blocks can put modules elsewhere, which only the source says.
+///
+/// ```rust
+/// use pathext::{rust_module_files, rust_module_path};
+/// use std::path::PathBuf;
+///
+/// assert_eq!(
+///     rust_module_files("src", "crate::net::r#type").unwrap(),
+///     [PathBuf::from("src/net/type.rs"), PathBuf::from("src/net/type/mod.rs")]
+/// );
+/// assert_eq!(rust_module_path("src", "src/net/type/mod.rs").as_deref(), Some("crate::net::r#type"));
+/// ```
+pub fn rust_module_files<P: AsRef<Path>>(src_dir: P, module: &str) -> Option<Vec<PathBuf>> {
+    let src_dir = src_dir.as_ref();
+    let module = module.strip_prefix("::").unwrap_or(module);
+    if module == "crate" {
+        return Some(vec![src_dir.join("lib.rs"), src_dir.join("main.rs")]);
+    }
+    let rest = module.strip_prefix("crate::").unwrap_or(module);
+    let mut dir = src_dir.to_path_buf();
+    let mut segments = rest.split("::").peekable();
+    while let Some(segment) = segments.next() {
+        let name = segment_file_name(segment.trim())?;
+        if segments.peek().is_none() {
+            return Some(vec![
+                dir.join(format!("{name}.rs")),
+                dir.join(name).join("mod.rs"),
+            ]);
+        }
+        dir.push(name);
+    }
+    None
+}
+
+/// The module path, starting with `crate`, that rustc gives the file at `file` in the crate whose
+/// root is in `src_dir`, as [`rust_module_files`] maps it. Keywords come back raw, like `r#type`.
+///
+/// `None` for files outside `src_dir`, without the `.rs` extension, or with a name that can't be a
+/// module. Since `src/bin`, `src/main.rs` next to `src/lib.rs` and similar are roots of other crates,
+/// which files are in the crate is for the caller to know.
+pub fn rust_module_path<S: AsRef<Path>, F: AsRef<Path>>(src_dir: S, file: F) -> Option<String> {
+    let relative = file.as_ref().strip_prefix(src_dir.as_ref()).ok()?;
+    let mut names = Vec::new();
+    for component in relative.components() {
+        match component {
+            Component::Normal(name) => names.push(name.to_str()?),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    let last = names.pop()?.strip_suffix(".rs")?;
+    match (names.is_empty(), last) {
+        (true, "lib" | "main") => return Some("crate".to_string()),
+        (_, "mod") if names.is_empty() => return None,
+        (_, "mod") => {}
+        _ => names.push(last),
+    }
+    let mut module = String::from("crate");
+    for name in names {
+        if !is_identifier(name) || PATH_KEYWORDS.contains(&name) {
+            return None;
+        }
+        module.push_str("::");
+        if KEYWORDS.contains(&name) {
+            module.push_str("r#");
+        }
+        module.push_str(name);
+    }
+    Some(module)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{rust_module_files, rust_module_path};
+
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_rust_module_files() {
+        let tests: &[(&str, Option<[&str; 2]>)] = &[
+            ("crate", Some(["src/lib.rs", "src/main.rs"])),
+            ("crate::foo", Some(["src/foo.rs", "src/foo/mod.rs"])),
+            (
+                "crate::foo::bar",
+                Some(["src/foo/bar.rs", "src/foo/bar/mod.rs"]),
+            ),
+            ("foo::bar", Some(["src/foo/bar.rs", "src/foo/bar/mod.rs"])),
+            ("::crate::foo", Some(["src/foo.rs", "src/foo/mod.rs"])),
+            ("crate::r#async", Some(["src/async.rs", "src/async/mod.rs"])),
+            ("crate::ünï", Some(["src/ünï.rs", "src/ünï/mod.rs"])),
+            ("crate::async", None),
+            ("crate::r#foo", Some(["src/foo.rs", "src/foo/mod.rs"])),
+            ("crate::r#self", None),
+            ("crate::r#", None),
+            ("self::foo", None),
+            ("super::foo", None),
+            ("crate::foo::", None),
+            ("crate::foo-bar", None),
+            ("crate::1st", None),
+            (
+                "crates::foo",
+                Some(["src/crates/foo.rs", "src/crates/foo/mod.rs"]),
+            ),
+            ("", None),
+        ];
+
+        for test in tests {
+            let expected = test.1.map(|files| files.map(PathBuf::from).to_vec());
+            assert_eq!(rust_module_files("src", test.0), expected, "{}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_rust_module_path() {
+        let tests = &[
+            ("src/lib.rs", Some("crate")),
+            ("src/main.rs", Some("crate")),
+            ("src/foo.rs", Some("crate::foo")),
+            ("src/foo/mod.rs", Some("crate::foo")),
+            ("src/foo/bar.rs", Some("crate::foo::bar")),
+            ("src/./foo/lib.rs", Some("crate::foo::lib")),
+            ("src/type.rs", Some("crate::r#type")),
+            ("src/mod.rs", None),
+            ("src/foo/bar.txt", None),
+            ("src/foo-bar.rs", None),
+            ("src/self.rs", None),
+            ("tests/foo.rs", None),
+            ("src/../x.rs", None),
+        ];
+
+        for test in tests {
+            assert_eq!(
+                rust_module_path("src", test.0).as_deref(),
+                test.1,
+                "{}",
+                test.0
+            );
+        }
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let modules = ["crate::a", "crate::a::b::c", "crate::r#match::x"];
+
+        for module in modules {
+            for file in rust_module_files("/work/src", module).unwrap() {
+                assert_eq!(
+                    rust_module_path("/work/src", &file).as_deref(),
+                    Some(module)
+                );
+            }
+        }
+    }
+}
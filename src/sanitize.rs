@@ -0,0 +1,273 @@
+//! Turning arbitrary strings into names a target filesystem or URL will accept.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Component, Path, PathBuf};
+
+/// The built-in character rules a [`CharPolicy`] starts from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum CharPreset {
+    /// Everything Windows accepts: no `<>:"/\|?*` or control characters, no trailing dots or
+    /// spaces, and no device names like `CON` or `lpt1.txt`.
+    #[default]
+    WindowsStrict,
+    /// Only the POSIX portable filename character set, `A-Z a-z 0-9 . _ -`.
+    PosixPortable,
+    /// Only RFC 3986 unreserved characters, `A-Z a-z 0-9 - . _ ~`.
+    UrlSafe,
+}
+
+/// Which characters may appear in a name and what replaces those that may not.
+///
+/// ```rust
+/// use pathext::{CharPolicy, CharPreset};
+///
+/// let policy = CharPolicy::new(CharPreset::UrlSafe).map(' ', "-").map('&', "and");
+/// assert_eq!(policy.sanitize_name("Tom & Jerry.mp4"), "Tom-and-Jerry.mp4");
+/// assert_eq!(CharPolicy::default().sanitize_name("what?.txt"), "what_.txt");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CharPolicy {
+    preset: CharPreset,
+    replacements: BTreeMap<char, String>,
+    forbidden: BTreeSet<char>,
+    fallback: Option<String>,
+}
+
+impl CharPolicy {
+    /// A policy following `preset`, replacing disallowed characters with `_`.
+    pub fn new(preset: CharPreset) -> Self {
+        CharPolicy {
+            preset,
+            ..CharPolicy::default()
+        }
+    }
+
+    pub fn windows_strict() -> Self {
+        CharPolicy::new(CharPreset::WindowsStrict)
+    }
+
+    pub fn posix_portable() -> Self {
+        CharPolicy::new(CharPreset::PosixPortable)
+    }
+
+    pub fn url_safe() -> Self {
+        CharPolicy::new(CharPreset::UrlSafe)
+    }
+
+    pub fn preset(&self) -> CharPreset {
+        self.preset
+    }
+
+    /// Always replaces `from` with `to`, whether or not the preset allows `from`.
+    /// The replacement is used as is, so it should itself be allowed.
+    pub fn map<S: Into<String>>(mut self, from: char, to: S) -> Self {
+        self.replacements.insert(from, to.into());
+        self
+    }
+
+    /// Disallows `c` on top of what the preset disallows.
+    pub fn forbid(mut self, c: char) -> Self {
+        self.forbidden.insert(c);
+        self
+    }
+
+    /// What replaces a disallowed character that has no mapping, `_` unless set. May be empty to drop them,
+    /// in which case `_` still stands in for a name left empty and marks a reserved one like `CON`.
+    pub fn fallback<S: Into<String>>(mut self, fallback: S) -> Self {
+        self.fallback = Some(fallback.into());
+        self
+    }
+
+    fn fallback_str(&self) -> &str {
+        self.fallback.as_deref().unwrap_or("_")
+    }
+
+    /// The fallback where something has to be added, `_` when it is empty.
+    fn marker(&self) -> &str {
+        match self.fallback_str() {
+            "" => "_",
+            fallback => fallback,
+        }
+    }
+
+    /// Whether `c` may appear in a name unchanged.
+    pub fn is_allowed(&self, c: char) -> bool {
+        if c == '/'
+            || c == '\0'
+            || self.forbidden.contains(&c)
+            || self.replacements.contains_key(&c)
+        {
+            return false;
+        }
+        match self.preset {
+            CharPreset::WindowsStrict => {
+                !c.is_control() && !matches!(c, '<' | '>' | ':' | '"' | '\\' | '|' | '?' | '*')
+            }
+            CharPreset::PosixPortable => c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'),
+            CharPreset::UrlSafe => c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~'),
+        }
+    }
+
+    /// Sanitizes a single name, which never comes back empty, `.` or `..`.
+    pub fn sanitize_name(&self, name: &str) -> String {
+        let mut sanitized = String::with_capacity(name.len());
+        for c in name.chars() {
+            if let Some(replacement) = self.replacements.get(&c) {
+                sanitized.push_str(replacement);
+            } else if self.is_allowed(c) {
+                sanitized.push(c);
+            } else {
+                sanitized.push_str(self.fallback_str());
+            }
+        }
+
+        if self.preset == CharPreset::WindowsStrict {
+            let trimmed = sanitized.trim_end_matches(['.', ' ']).len();
+            sanitized.truncate(trimmed);
+            if is_windows_reserved(&sanitized) {
+                let stem_end = sanitized.find('.').unwrap_or(sanitized.len());
+                sanitized.insert_str(stem_end, self.marker());
+            }
+        }
+
+        if sanitized.is_empty() || sanitized == "." || sanitized == ".." {
+            sanitized = self.marker().to_string();
+        }
+        sanitized
+    }
+}
+
+/// Whether Windows treats the name as a device, which it does regardless of case or extension.
+pub(crate) fn is_windows_reserved(name: &str) -> bool {
+    let stem = name.split('.').next().unwrap_or(name).trim_end_matches(' ');
+    let upper = stem.to_ascii_uppercase();
+    match upper.as_str() {
+        "CON" | "PRN" | "AUX" | "NUL" | "CONIN$" | "CONOUT$" => true,
+        _ => {
+            let (kind, digit) = (upper.get(..3), upper.get(3..).unwrap_or_default());
+            matches!(kind, Some("COM" | "LPT"))
+                && matches!(
+                    digit,
+                    "1" | "2" | "3" | "4" | "5" | "6" | "7" | "8" | "9" | "¹" | "²" | "³"
+                )
+        }
+    }
+}
+
+/// Sanitizes every normal component of the path, leaving prefixes, roots, `.` and `..` alone.
+pub(crate) fn sanitize_path(path: &Path, policy: &CharPolicy) -> PathBuf {
+    let mut buf = PathBuf::new();
+    sanitize_path_into(path, policy, &mut buf);
+    buf
+}
+
+/// [`sanitize_path`] written into `buf`, which is cleared first.
+pub(crate) fn sanitize_path_into(path: &Path, policy: &CharPolicy, buf: &mut PathBuf) {
+    buf.as_mut_os_string().clear();
+    for component in path.components() {
+        match component {
+            Component::Normal(name) => buf.push(policy.sanitize_name(&name.to_string_lossy())),
+            other => buf.push(other.as_os_str()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_windows_reserved, CharPolicy, CharPreset};
+
+    #[test]
+    fn test_windows_strict() {
+        let policy = CharPolicy::windows_strict();
+        let tests = &[
+            ("normal name.txt", "normal name.txt"),
+            ("a<b>c:d\"e|f?g*h\\i", "a_b_c_d_e_f_g_h_i"),
+            ("tab\there", "tab_here"),
+            ("trailing. . ", "trailing"),
+            ("CON", "CON_"),
+            ("con.tar.gz", "con_.tar.gz"),
+            ("lpt9", "lpt9_"),
+            ("console", "console"),
+            ("COM10", "COM10"),
+            ("...", "_"),
+            ("", "_"),
+            ("ünïcödé", "ünïcödé"),
+        ];
+
+        for test in tests {
+            assert_eq!(policy.sanitize_name(test.0), test.1, "{:?}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_posix_portable() {
+        let policy = CharPolicy::posix_portable();
+        let tests = &[
+            ("report-2024_v1.pdf", "report-2024_v1.pdf"),
+            ("with space~", "with_space_"),
+            ("naïve", "na_ve"),
+            ("CON", "CON"),
+        ];
+
+        for test in tests {
+            assert_eq!(policy.sanitize_name(test.0), test.1);
+        }
+    }
+
+    #[test]
+    fn test_url_safe() {
+        let policy = CharPolicy::url_safe();
+        let tests = &[("a~b.c", "a~b.c"), ("a b/c", "a_b_c"), ("100%", "100_")];
+
+        for test in tests {
+            assert_eq!(policy.sanitize_name(test.0), test.1);
+        }
+    }
+
+    #[test]
+    fn test_user_defined() {
+        let policy = CharPolicy::new(CharPreset::UrlSafe)
+            .map(' ', "-")
+            .map('é', "e")
+            .forbid('~')
+            .fallback("");
+        assert_eq!(
+            policy.sanitize_name("café au lait~!.txt"),
+            "cafe-au-lait.txt"
+        );
+        assert_eq!(policy.sanitize_name("!!!"), "_");
+        assert!(!policy.is_allowed('~'));
+        assert!(!policy.is_allowed(' '));
+
+        // an empty fallback still marks reserved names
+        let policy = CharPolicy::windows_strict().fallback("");
+        let tests = &[("CON", "CON_"), ("con.txt", "con_.txt"), ("a?b", "ab")];
+        for test in tests {
+            assert_eq!(policy.sanitize_name(test.0), test.1, "{}", test.0);
+        }
+
+        // mappings win over the preset
+        let policy = CharPolicy::windows_strict().map('a', "4");
+        assert_eq!(policy.sanitize_name("banana"), "b4n4n4");
+    }
+
+    #[test]
+    fn test_is_windows_reserved() {
+        let tests = &[
+            ("CON", true),
+            ("con.txt", true),
+            ("Nul.tar.gz", true),
+            ("COM1", true),
+            ("LPT0", false),
+            ("COMPUTER", false),
+            ("CON ", true),
+            ("xCON", false),
+            ("ää", false),
+            ("COM²", true),
+        ];
+
+        for test in tests {
+            assert_eq!(is_windows_reserved(test.0), test.1, "{}", test.0);
+        }
+    }
+}
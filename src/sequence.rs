@@ -0,0 +1,152 @@
+//! Matching runs and ordered sequences of components, for rules like "a `tests` dir under `src`".
+
+use std::path::{Component, Path};
+
+pub(crate) fn component_windows(path: &Path, n: usize) -> impl Iterator<Item = Vec<Component<'_>>> {
+    let components: Vec<Component> = path.components().collect();
+    let count = if n == 0 {
+        0
+    } else {
+        (components.len() + 1).saturating_sub(n)
+    };
+    (0..count).map(move |start| components[start..start + n].to_vec())
+}
+
+/// The components that name something, leaving out the root, any prefix and `.`.
+fn named(path: &Path) -> impl DoubleEndedIterator<Item = Component<'_>> {
+    path.components()
+        .filter(|component| matches!(component, Component::Normal(_) | Component::ParentDir))
+}
+
+/// Whether `wanted` lines up with `components` from their start, all of it.
+fn lines_up<'a, C, I, S>(mut components: C, wanted: I) -> bool
+where
+    C: Iterator<Item = Component<'a>>,
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    wanted.into_iter().all(|wanted| {
+        components
+            .next()
+            .is_some_and(|component| component.as_os_str() == wanted.as_ref())
+    })
+}
+
+pub(crate) fn starts_with_components<I, S>(path: &Path, prefix: I) -> bool
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    lines_up(named(path), prefix)
+}
+
+pub(crate) fn ends_with_components<I, S>(path: &Path, suffix: I) -> bool
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let suffix: Vec<S> = suffix.into_iter().collect();
+    lines_up(named(path).rev(), suffix.iter().rev())
+}
+
+pub(crate) fn has_component_sequence<I, S>(path: &Path, sequence: I) -> bool
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut components = path.components();
+    sequence.into_iter().all(|wanted| {
+        components
+            .by_ref()
+            .any(|component| component.as_os_str() == wanted.as_ref())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        component_windows, ends_with_components, has_component_sequence, starts_with_components,
+    };
+
+    use std::path::Path;
+
+    #[test]
+    fn test_component_windows() {
+        let tests: &[(&str, usize, &[&str])] = &[
+            ("a/b/c/d", 2, &["a/b", "b/c", "c/d"]),
+            ("a/b/c", 3, &["a/b/c"]),
+            ("a/b/c", 4, &[]),
+            ("a/b/c", 0, &[]),
+            ("/a/./b", 2, &["/a", "a/b"]),
+            ("a", 1, &["a"]),
+        ];
+
+        for test in tests {
+            let windows: Vec<String> = component_windows(Path::new(test.0), test.1)
+                .map(|window| {
+                    let path: std::path::PathBuf = window.iter().collect();
+                    path.to_string_lossy().into_owned()
+                })
+                .collect();
+            assert_eq!(windows, test.2, "{} {}", test.0, test.1);
+        }
+    }
+
+    #[test]
+    fn test_has_component_sequence() {
+        let tests: &[(&str, &[&str], bool)] = &[
+            ("src/a/tests/b.rs", &["src", "tests"], true),
+            ("src/tests", &["src", "tests"], true),
+            ("tests/src", &["src", "tests"], false),
+            ("src/testsuite/x", &["src", "tests"], false),
+            ("src/a/src/tests", &["src", "src", "tests"], true),
+            ("src/tests", &["src", "src"], false),
+            ("/x/src", &["/", "src"], true),
+            ("anything", &[], true),
+        ];
+
+        for test in tests {
+            assert_eq!(
+                has_component_sequence(Path::new(test.0), test.1),
+                test.2,
+                "{} {:?}",
+                test.0,
+                test.1
+            );
+        }
+    }
+
+    #[test]
+    fn test_anchored_components() {
+        let tests: &[(&str, &[&str], bool, bool)] = &[
+            ("/usr/local/bin/app", &["usr", "local"], true, false),
+            ("/usr/localized/bin", &["usr", "local"], false, false),
+            ("usr/local", &["usr", "local"], true, true),
+            ("./usr/local/", &["usr", "local"], true, true),
+            ("/opt/bin/app", &["bin", "app"], false, true),
+            ("/opt/bin/myapp", &["bin", "app"], false, false),
+            ("../lib/x", &["..", "lib"], true, false),
+            ("bin", &["usr", "bin"], false, false),
+            ("/", &[], true, true),
+            ("/a", &["/"], false, false),
+        ];
+
+        for test in tests {
+            let path = Path::new(test.0);
+            assert_eq!(
+                starts_with_components(path, test.1),
+                test.2,
+                "{} {:?}",
+                test.0,
+                test.1
+            );
+            assert_eq!(
+                ends_with_components(path, test.1),
+                test.3,
+                "{} {:?}",
+                test.0,
+                test.1
+            );
+        }
+    }
+}
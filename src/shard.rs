@@ -0,0 +1,116 @@
+//! Spreading a flat directory over nested ones named after the start of each file name.
+
+use std::path::{Path, PathBuf};
+
+/// How many characters of the stem name each directory level, a byte in hex.
+const CHUNK: usize = 2;
+
+fn chunks(name: &str) -> impl Iterator<Item = &str> {
+    let mut rest = name;
+    std::iter::from_fn(move || {
+        let end = rest
+            .char_indices()
+            .nth(CHUNK)
+            .map_or(rest.len(), |(i, _)| i);
+        let (chunk, tail) = rest.split_at(end);
+        rest = tail;
+        (chunk.chars().count() == CHUNK).then_some(chunk)
+    })
+}
+
+pub(crate) fn fan_out(path: &Path, levels: usize) -> Option<PathBuf> {
+    let name = path.file_name()?.to_str()?;
+    let stem = Path::new(name).file_stem()?.to_str()?;
+    let mut sharded = path.parent().map(Path::to_path_buf).unwrap_or_default();
+    let mut added = 0;
+    for chunk in chunks(stem).take(levels) {
+        sharded.push(chunk);
+        added += 1;
+    }
+    if added < levels {
+        return None;
+    }
+    sharded.push(name);
+    Some(sharded)
+}
+
+/// Drops the most directories right above the file whose names, read down, are its leading chunks.
+pub(crate) fn fan_in(path: &Path) -> PathBuf {
+    let (Some(name), Some(parent)) = (path.file_name(), path.parent()) else {
+        return path.to_path_buf();
+    };
+    let Some(stem) = Path::new(name).file_stem().and_then(|s| s.to_str()) else {
+        return path.to_path_buf();
+    };
+    let leading: Vec<&str> = chunks(stem).collect();
+    let dirs: Vec<&Path> = parent.ancestors().collect();
+    let matches = |levels: usize| {
+        (0..levels).all(|level| {
+            dirs[levels - 1 - level]
+                .file_name()
+                .and_then(|n| n.to_str())
+                == Some(leading[level])
+        })
+    };
+    let levels = (1..=leading.len().min(dirs.len() - 1))
+        .rev()
+        .find(|&levels| matches(levels))
+        .unwrap_or(0);
+    dirs[levels].join(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fan_in, fan_out};
+
+    use std::path::Path;
+
+    #[test]
+    fn test_fan_out() {
+        let tests = &[
+            ("cache/aabbccdd.bin", 2, Some("cache/aa/bb/aabbccdd.bin")),
+            ("cache/aabbccdd.bin", 0, Some("cache/aabbccdd.bin")),
+            ("/objects/0123456789", 1, Some("/objects/01/0123456789")),
+            ("deadbeef", 3, Some("de/ad/be/deadbeef")),
+            ("cache/abc.bin", 2, None),
+            ("cache/ünïcode", 2, Some("cache/ün/ïc/ünïcode")),
+            ("/", 1, None),
+        ];
+
+        for test in tests {
+            assert_eq!(
+                fan_out(Path::new(test.0), test.1).as_deref(),
+                test.2.map(Path::new),
+                "{} {}",
+                test.0,
+                test.1
+            );
+        }
+    }
+
+    #[test]
+    fn test_fan_in() {
+        let tests = &[
+            ("cache/aa/bb/aabbccdd.bin", "cache/aabbccdd.bin"),
+            ("cache/aa/aabbccdd.bin", "cache/aabbccdd.bin"),
+            ("/objects/01/23/45/0123456789", "/objects/0123456789"),
+            ("de/ad/be/deadbeef", "deadbeef"),
+            ("cache/bb/cc/aabbccdd.bin", "cache/bb/cc/aabbccdd.bin"),
+            ("aa/cache/aabbccdd.bin", "aa/cache/aabbccdd.bin"),
+            ("cache/aabbccdd.bin", "cache/aabbccdd.bin"),
+            ("aabbccdd.bin", "aabbccdd.bin"),
+        ];
+
+        for test in tests {
+            assert_eq!(fan_in(Path::new(test.0)), Path::new(test.1), "{}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_fan_roundtrip() {
+        for levels in 0..4 {
+            let path = Path::new("store/c0ffee42.blob");
+            assert_eq!(fan_in(&fan_out(path, levels).unwrap()), path);
+        }
+    }
+}
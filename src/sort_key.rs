@@ -0,0 +1,72 @@
+//! Host-independent byte keys for ordering paths.
+
+use std::path::Path;
+
+use unicode_normalization::UnicodeNormalization;
+
+/// Builds a key whose byte order is the same on every host.
+///
+/// Both `/` and `\` separate components, empty and `.` components are dropped, and each
+/// component is NFC normalized (macOS tends to hand out NFD names) and optionally lowercased.
+/// Components are joined with a NUL, which can't appear in names and sorts below everything,
+/// so keys compare component by component: `a/b` sorts before `a-b`.
+pub(crate) fn portable_sort_key(path: &Path, case_fold: bool) -> Vec<u8> {
+    let lossy = path.as_os_str().to_string_lossy();
+    let mut key = Vec::with_capacity(lossy.len());
+    let components = lossy
+        .split(['/', '\\'])
+        .filter(|c| !c.is_empty() && *c != ".");
+    for (i, component) in components.enumerate() {
+        if i > 0 {
+            key.push(0);
+        }
+        let normalized: String = component.nfc().collect();
+        if case_fold {
+            key.extend(normalized.to_lowercase().as_bytes());
+        } else {
+            key.extend(normalized.as_bytes());
+        }
+    }
+    if lossy.starts_with(['/', '\\']) {
+        key.insert(0, 0);
+    }
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::portable_sort_key;
+
+    use std::path::Path;
+
+    fn key(p: &str) -> Vec<u8> {
+        portable_sort_key(Path::new(p), false)
+    }
+
+    #[test]
+    fn test_portable_sort_key_equivalence() {
+        let tests = &[
+            ("a/b/c", "a\\b\\c"),
+            ("a//b/./c/", "a/b/c"),
+            // "é" precomposed and decomposed
+            ("caf\u{e9}/x", "cafe\u{301}/x"),
+        ];
+
+        for test in tests {
+            assert_eq!(key(test.0), key(test.1), "{} {}", test.0, test.1);
+        }
+        assert_ne!(key("/a"), key("a"));
+        assert_ne!(key("A/b"), key("a/b"));
+        assert_eq!(
+            portable_sort_key(Path::new("A/B"), true),
+            portable_sort_key(Path::new("a/b"), true)
+        );
+    }
+
+    #[test]
+    fn test_portable_sort_key_order() {
+        let mut paths = vec!["a-b", "a/b", "a.b", "a", "B", "a/b/c", "/z"];
+        paths.sort_by_key(|p| key(p));
+        assert_eq!(paths, ["/z", "B", "a", "a/b", "a/b/c", "a-b", "a.b"]);
+    }
+}
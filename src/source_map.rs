@@ -0,0 +1,121 @@
+//! Where the `sources` of a JavaScript source map are.
+
+use std::path::Path;
+
+use crate::location::{percent_decode, split_scheme};
+use crate::{normalize, parse_path_or_url, PathOrUrl};
+
+/// Resolves an entry of a source map's `sources` to the file it names, as browsers and bundlers do
+/// rather than as [`Path::join`] would.
+///
+/// A non-empty `source_root` is put in front of `source` with a `/` between them unless it ends in
+/// one. Sources that are URLs of their own, like `webpack:///src/app.ts`, ignore it and come back
+/// as [`PathOrUrl::Url`], as do roots that are. Otherwise the result is a URL reference relative to
+/// the map: `?query` and `#fragment` are dropped, `%XX` decoded (kept as written if that isn't
+/// valid UTF-8), `\` read as `/`, and the reference joined onto `map_dir`, the directory holding the
+/// `.map` file, and normalized. `file:` URLs and absolute references don't use `map_dir`.
+///
+/// ```rust
+/// use pathext::{resolve_source_map_source, PathOrUrl};
+/// use std::path::PathBuf;
+///
+/// let resolved = resolve_source_map_source("/app/dist", Some("../src"), "util/my%20file.ts");
+/// assert_eq!(resolved, PathOrUrl::Path(PathBuf::from("/app/src/util/my file.ts")));
+///
+/// let bundled = resolve_source_map_source("/app/dist", None, "webpack:///./src/index.ts");
+/// assert!(bundled.as_path().is_none());
+/// ```
+pub fn resolve_source_map_source<P: AsRef<Path>>(
+    map_dir: P,
+    source_root: Option<&str>,
+    source: &str,
+) -> PathOrUrl {
+    let reference = match source_root.filter(|root| !root.is_empty()) {
+        Some(root) if split_scheme(source).is_none() => {
+            let separator = if root.ends_with('/') { "" } else { "/" };
+            format!("{root}{separator}{source}")
+        }
+        _ => source.to_string(),
+    };
+    if split_scheme(&reference).is_some() {
+        match parse_path_or_url(&reference) {
+            PathOrUrl::Path(path) if reference.starts_with("file:") => {
+                return PathOrUrl::Path(normalize::normalize(&path));
+            }
+            url @ PathOrUrl::Url { .. } => return url,
+            // `name:x` without `//` is taken for a path
+            PathOrUrl::Path(_) => {}
+        }
+    }
+    let reference = reference.split(['?', '#']).next().unwrap_or_default();
+    let reference = percent_decode(reference).unwrap_or_else(|| reference.to_string());
+    let joined = map_dir.as_ref().join(reference.replace('\\', "/"));
+    PathOrUrl::Path(normalize::normalize(&joined))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::resolve_source_map_source;
+    use crate::PathOrUrl;
+
+    use std::path::PathBuf;
+
+    fn path(p: &str) -> PathOrUrl {
+        PathOrUrl::Path(PathBuf::from(p))
+    }
+
+    fn url(scheme: &str, rest: &str) -> PathOrUrl {
+        PathOrUrl::Url {
+            scheme: scheme.into(),
+            rest: rest.into(),
+        }
+    }
+
+    #[test]
+    fn test_resolve_source_map_source() {
+        let tests = &[
+            ("/app/dist", None, "../src/a.ts", path("/app/src/a.ts")),
+            ("/app/dist", Some(""), "a.ts", path("/app/dist/a.ts")),
+            ("/app/dist", Some("src"), "a.ts", path("/app/dist/src/a.ts")),
+            (
+                "/app/dist",
+                Some("src/"),
+                "./a.ts",
+                path("/app/dist/src/a.ts"),
+            ),
+            ("/app/dist", Some("/abs/"), "a.ts", path("/abs/a.ts")),
+            ("/app/dist", None, "/abs/a.ts", path("/abs/a.ts")),
+            ("dist", None, "../../up/a.ts", path("../up/a.ts")),
+            ("/app/dist", None, "a.ts?v=3#top", path("/app/dist/a.ts")),
+            (
+                "/app/dist",
+                None,
+                "bad%zzname.ts",
+                path("/app/dist/bad%zzname.ts"),
+            ),
+            ("/app/dist", None, r"..\src\a.ts", path("/app/src/a.ts")),
+            ("/app/dist", None, "file:///srv/a.ts", path("/srv/a.ts")),
+            (
+                "/app/dist",
+                Some("src"),
+                "webpack:///./a.ts",
+                url("webpack", "/./a.ts"),
+            ),
+            (
+                "/app/dist",
+                Some("https://cdn.example.com/src"),
+                "a.ts",
+                url("https", "cdn.example.com/src/a.ts"),
+            ),
+        ];
+
+        for test in tests {
+            assert_eq!(
+                resolve_source_map_source(test.0, test.1, test.2),
+                test.3,
+                "{}",
+                test.2
+            );
+        }
+    }
+}
@@ -0,0 +1,155 @@
+//! Edit distances between paths, for suggesting the file a user probably meant.
+
+use std::path::{Component, Path};
+
+/// Levenshtein distance over chars.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &b) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(a != b);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Splits off the extension the way [`Path::extension`] does, dotfiles having none.
+fn split_extension(name: &str) -> (&str, &str) {
+    match name.rfind('.').filter(|&dot| dot > 0) {
+        Some(dot) => (&name[..dot], &name[dot..]),
+        None => (name, ""),
+    }
+}
+
+/// The stems' edit distance plus at most 1 for differing extensions.
+fn stem_and_extension_distance(a: &str, b: &str) -> usize {
+    let (a_stem, a_extension) = split_extension(a);
+    let (b_stem, b_extension) = split_extension(b);
+    levenshtein(a_stem, b_stem) + usize::from(a_extension != b_extension)
+}
+
+/// Between two names, the stems' edit distance plus at most 1 for differing extensions, so
+/// `main.rs` is as near `main.rc` as `main.c`. Case is compared apart, differences in it costing
+/// at most 1, so `readme.md` is 1 from `README.md`.
+fn name_distance(a: &str, b: &str) -> usize {
+    let folded = stem_and_extension_distance(&a.to_lowercase(), &b.to_lowercase());
+    folded + usize::from(stem_and_extension_distance(a, b) > folded)
+}
+
+/// The edit distance over components, where changing one costs the [`name_distance`] between the
+/// names and adding or dropping one costs its length.
+pub(crate) fn path_distance(a: &Path, b: &Path) -> usize {
+    let names = |path: &Path| -> Vec<String> {
+        path.components()
+            .filter(|component| !matches!(component, Component::CurDir))
+            .map(|component| component.as_os_str().to_string_lossy().into_owned())
+            .collect()
+    };
+    let (a, b) = (names(a), names(b));
+    let weight = |name: &String| name.chars().count().max(1);
+    let mut row: Vec<usize> = std::iter::once(0)
+        .chain(b.iter().scan(0, |total, name| {
+            *total += weight(name);
+            Some(*total)
+        }))
+        .collect();
+    for a in &a {
+        let mut diagonal = row[0];
+        row[0] += weight(a);
+        for (j, b) in b.iter().enumerate() {
+            let substitution = diagonal + name_distance(a, b);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution
+                .min(row[j] + weight(b))
+                .min(diagonal + weight(a));
+        }
+    }
+    row[b.len()]
+}
+
+pub(crate) fn closest_match<I, P>(path: &Path, candidates: I, max_distance: usize) -> Option<P>
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    let mut best: Option<(usize, P)> = None;
+    for candidate in candidates {
+        let distance = path_distance(path, candidate.as_ref());
+        if distance <= max_distance && best.as_ref().is_none_or(|best| distance < best.0) {
+            best = Some((distance, candidate));
+        }
+    }
+    best.map(|(_, candidate)| candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{closest_match, levenshtein, path_distance};
+
+    use std::path::Path;
+
+    #[test]
+    fn test_levenshtein() {
+        let tests = &[
+            ("", "", 0),
+            ("abc", "", 3),
+            ("kitten", "sitting", 3),
+            ("flaw", "lawn", 2),
+            ("ünï", "uni", 2),
+        ];
+
+        for test in tests {
+            assert_eq!(levenshtein(test.0, test.1), test.2, "{:?}", test);
+            assert_eq!(levenshtein(test.1, test.0), test.2, "{:?}", test);
+        }
+    }
+
+    #[test]
+    fn test_path_distance() {
+        let tests = &[
+            ("src/main.rs", "src/main.rs", 0),
+            ("./src/main.rs", "src/main.rs", 0),
+            ("src/mian.rs", "src/main.rs", 2),
+            ("src/main.rc", "src/main.rs", 1),
+            ("src/main.c", "src/main.rs", 1),
+            ("src/main", "src/main.rs", 1),
+            ("sr/main.rs", "src/main.rs", 1),
+            ("main.rs", "src/main.rs", 3),
+            ("src/lib.rs", "src/main.rs", 3),
+            ("/a", "a", 1),
+            ("readme.md", "README.md", 1),
+            ("SRC/Main.RS", "src/main.rs", 2),
+            ("src/Mian.rs", "src/main.rs", 3),
+        ];
+
+        for test in tests {
+            let (a, b) = (Path::new(test.0), Path::new(test.1));
+            assert_eq!(path_distance(a, b), test.2, "{:?}", test);
+            assert_eq!(path_distance(b, a), test.2, "{:?}", test);
+        }
+    }
+
+    #[test]
+    fn test_closest_match() {
+        let candidates = ["src/lib.rs", "src/main.rs", "src/mains.rs", "README.md"];
+        let tests = &[
+            ("src/main.rs", 0, Some("src/main.rs")),
+            ("src/mian.rs", 2, Some("src/main.rs")),
+            ("src/mian.rs", 1, None),
+            ("src/lib.rc", 3, Some("src/lib.rs")),
+            ("readme.md", 1, Some("README.md")),
+            ("Src/Lib.rs", 2, Some("src/lib.rs")),
+            ("nothing/like/it", 3, None),
+        ];
+
+        for test in tests {
+            let best = closest_match(Path::new(test.0), candidates, test.1);
+            assert_eq!(best, test.2, "{:?}", test);
+        }
+    }
+}
@@ -0,0 +1,169 @@
+//! Assertion macros for tests that deal in paths.
+//!
+//! `assert_path_eq!` and `assert_under!` compare paths after lexical normalization with `\`
+//! and `/` treated alike, so the same assertion holds on every platform.
+//!
+//! ```rust
+//! use pathext::{assert_has_component, assert_path_eq, assert_under};
+//!
+//! assert_path_eq!("target\\debug\\..\\release", "target/release");
+//! assert_under!("/srv/www/./static/app.js", "/srv/www");
+//! assert_has_component!("/srv/www/static", "www");
+//! ```
+
+use std::path::{Component, Path, PathBuf};
+
+use crate::normalize::normalize;
+
+/// The form paths are compared in: `\` turned into `/` and then lexically normalized.
+pub fn comparable<P: AsRef<Path>>(path: P) -> PathBuf {
+    let unified = path.as_ref().to_string_lossy().replace('\\', "/");
+    normalize(Path::new(&unified))
+}
+
+/// Checks whether `path` is `base` or lies below it, comparing in the [`comparable`] form.
+pub fn is_under<P: AsRef<Path>, B: AsRef<Path>>(path: P, base: B) -> bool {
+    let path = comparable(path);
+    let base = comparable(base);
+    if base == Path::new(".") {
+        return !path
+            .components()
+            .any(|c| matches!(c, Component::ParentDir | Component::RootDir));
+    }
+    path.starts_with(base)
+}
+
+/// Asserts that a path contains the given component, like [`PathComponentExt::has_component`](crate::PathComponentExt::has_component).
+#[macro_export]
+macro_rules! assert_has_component {
+    ($path:expr, $component:expr $(,)?) => {
+        $crate::assert_has_component!($path, $component, "")
+    };
+    ($path:expr, $component:expr, $($arg:tt)+) => {{
+        let path = &$path;
+        let component = &$component;
+        if !$crate::PathComponentExt::has_component(path, component) {
+            panic!(
+                "assertion `path has component` failed: {}\n     path: `{}`\ncomponent: `{}`\n",
+                format_args!($($arg)+),
+                ::std::path::Path::new(path).display(),
+                component,
+            );
+        }
+    }};
+}
+
+/// Asserts that two paths are equal once normalized and separator-insensitive.
+#[macro_export]
+macro_rules! assert_path_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::assert_path_eq!($left, $right, "")
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {{
+        let left = &$left;
+        let right = &$right;
+        let left_cmp = $crate::testing::comparable(left);
+        let right_cmp = $crate::testing::comparable(right);
+        if left_cmp != right_cmp {
+            panic!(
+                "assertion `left == right` failed (normalized paths): {}\n left: `{}` (as `{}`)\nright: `{}` (as `{}`)\n",
+                format_args!($($arg)+),
+                ::std::path::Path::new(left).display(),
+                left_cmp.display(),
+                ::std::path::Path::new(right).display(),
+                right_cmp.display(),
+            );
+        }
+    }};
+}
+
+/// Asserts that a path is the base path or lies below it, once both are normalized and separator-insensitive.
+#[macro_export]
+macro_rules! assert_under {
+    ($path:expr, $base:expr $(,)?) => {
+        $crate::assert_under!($path, $base, "")
+    };
+    ($path:expr, $base:expr, $($arg:tt)+) => {{
+        let path = &$path;
+        let base = &$base;
+        if !$crate::testing::is_under(path, base) {
+            panic!(
+                "assertion `path is under base` failed: {}\npath: `{}` (as `{}`)\nbase: `{}` (as `{}`)\n",
+                format_args!($($arg)+),
+                ::std::path::Path::new(path).display(),
+                $crate::testing::comparable(path).display(),
+                ::std::path::Path::new(base).display(),
+                $crate::testing::comparable(base).display(),
+            );
+        }
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{comparable, is_under};
+
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn test_comparable() {
+        let tests = &[
+            ("a\\b\\..\\c", "a/c"),
+            ("a/./b/", "a/b"),
+            ("/x\\y//z", "/x/y/z"),
+        ];
+
+        for test in tests {
+            assert_eq!(comparable(test.0), comparable(test.1));
+        }
+    }
+
+    #[test]
+    fn test_is_under() {
+        let tests = &[
+            ("/srv/www/static", "/srv/www", true),
+            ("/srv/www", "/srv/www", true),
+            ("/srv/www/../etc", "/srv/www", false),
+            ("/srv/wwwroot", "/srv/www", false),
+            ("src\\lib.rs", "src/", true),
+            ("lib.rs", ".", true),
+            ("../lib.rs", ".", false),
+        ];
+
+        for test in tests {
+            assert_eq!(
+                is_under(test.0, test.1),
+                test.2,
+                "{} under {}",
+                test.0,
+                test.1
+            );
+        }
+    }
+
+    #[test]
+    fn test_macros() {
+        crate::assert_path_eq!(PathBuf::from("a\\b"), "a/b");
+        crate::assert_path_eq!("a/b/../c", Path::new("a/c"), "with a message {}", 1);
+        crate::assert_under!("/a/b/c", "/a");
+        crate::assert_has_component!(Path::new("/a/b/c"), "b");
+    }
+
+    #[test]
+    #[should_panic(expected = "left: `a\\b\\..\\c`")]
+    fn test_assert_path_eq_message() {
+        crate::assert_path_eq!("a\\b\\..\\c", "a/d");
+    }
+
+    #[test]
+    #[should_panic(expected = "assertion `path is under base` failed")]
+    fn test_assert_under_message() {
+        crate::assert_under!("/a/../b", "/a");
+    }
+
+    #[test]
+    #[should_panic(expected = "component: `x`")]
+    fn test_assert_has_component_message() {
+        crate::assert_has_component!("/a/b", "x");
+    }
+}
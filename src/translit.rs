@@ -0,0 +1,77 @@
+//! ASCII approximations of non-ASCII file names.
+
+use std::path::{Path, PathBuf};
+
+use crate::hash::fnv1a;
+use crate::name::split_extensions;
+
+/// Transliterates with `deunicode`, never letting an approximation like `½` → `1/2` add a separator.
+fn to_ascii(s: &str) -> String {
+    deunicode::deunicode_with_tofu(s, "_")
+        .trim()
+        .replace(['/', '\\'], "-")
+}
+
+pub(crate) fn transliterate_file_name(path: &Path, hashed: bool) -> PathBuf {
+    let Some(name) = path.file_name() else {
+        return path.to_path_buf();
+    };
+    let name = name.to_string_lossy();
+    if name.is_ascii() {
+        return path.to_path_buf();
+    }
+
+    let (stem, extension) = split_extensions(&name);
+    let mut stem = to_ascii(stem);
+    if stem.is_empty() || stem == "." {
+        stem.push('_');
+    }
+    if hashed {
+        stem.push_str(&format!("-{:08x}", fnv1a(name.as_bytes()) as u32));
+    }
+    path.with_file_name(format!("{stem}{}", to_ascii(extension)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::transliterate_file_name;
+
+    use std::path::Path;
+
+    #[test]
+    fn test_transliterate_file_name() {
+        let tests = &[
+            ("docs/café résumé.pdf", "docs/cafe resume.pdf"),
+            ("Привет.txt", "Privet.txt"),
+            ("北京.tar.gz", "Bei Jing.tar.gz"),
+            ("½ size.png", "1-2 size.png"),
+            (".ßkript", ".sskript"),
+            ("ascii-only.txt", "ascii-only.txt"),
+            ("/ünïcödé/dir/plain", "/ünïcödé/dir/plain"),
+        ];
+
+        for test in tests {
+            assert_eq!(
+                transliterate_file_name(Path::new(test.0), false),
+                Path::new(test.1)
+            );
+        }
+    }
+
+    #[test]
+    fn test_transliterate_file_name_hashed() {
+        let a = transliterate_file_name(Path::new("café.txt"), true);
+        let b = transliterate_file_name(Path::new("cafë.txt"), true);
+        assert_ne!(a, b);
+        for p in [&a, &b] {
+            let name = p.to_str().unwrap();
+            assert!(name.is_ascii());
+            assert!(name.starts_with("cafe-"));
+            assert!(name.ends_with(".txt"));
+        }
+        assert_eq!(
+            transliterate_file_name(Path::new("plain.txt"), true),
+            Path::new("plain.txt")
+        );
+    }
+}
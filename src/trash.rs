@@ -0,0 +1,318 @@
+//! Moving paths to the platform's trash instead of deleting them, behind the `trash` feature.
+//!
+//! Windows uses the Recycle Bin through the shell. Elsewhere this follows the freedesktop.org
+//! trash specification: paths go to `$XDG_DATA_HOME/Trash` (`~/.local/share/Trash`) with a
+//! `.trashinfo` file recording where they came from, or to `.Trash-$UID` at the top of their
+//! own filesystem when they can't be renamed across to the home trash. macOS trashes are plain
+//! directories, `~/.Trash` or `.Trashes/$UID` at the top of other volumes, that paths are moved
+//! straight into. Finder keeps its own record of where they came from, so its "Put Back" doesn't
+//! know about paths trashed here.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+#[cfg(unix)]
+use std::path::PathBuf;
+
+use crate::fs::with_path;
+
+/// The `Path=` of a `.trashinfo`, percent-encoding all but unreserved characters and `/`.
+#[cfg(unix)]
+fn encode_info_path(path: &Path) -> String {
+    use std::os::unix::ffi::OsStrExt;
+
+    let mut encoded = String::new();
+    for &b in path.as_os_str().as_bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'/' | b'-' | b'_' | b'.' | b'~') {
+            encoded.push(b as char);
+        } else {
+            encoded.push_str(&format!("%{b:02X}"));
+        }
+    }
+    encoded
+}
+
+/// The local time as `YYYY-MM-DDThh:mm:ss`, as `DeletionDate=` wants.
+#[cfg(unix)]
+fn deletion_date() -> String {
+    // SAFETY: `time(NULL)` only returns, and `tm` is only read after `localtime_r` filled it in.
+    let tm = unsafe {
+        let now = libc::time(std::ptr::null_mut());
+        let mut tm = std::mem::MaybeUninit::<libc::tm>::uninit();
+        if libc::localtime_r(&now, tm.as_mut_ptr()).is_null() {
+            return String::new();
+        }
+        tm.assume_init()
+    };
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        tm.tm_year + 1900,
+        tm.tm_mon + 1,
+        tm.tm_mday,
+        tm.tm_hour,
+        tm.tm_min,
+        tm.tm_sec
+    )
+}
+
+/// Moves `path` into the trash directory `trash`. A `freedesktop` trash gets it in `files/`, with
+/// its name reserved by creating the info file in `info/` first; otherwise it goes straight into
+/// `trash`, as macOS has it.
+#[cfg(unix)]
+pub(crate) fn move_into(path: &Path, trash: &Path, freedesktop: bool) -> io::Result<PathBuf> {
+    let files = if freedesktop {
+        trash.join("files")
+    } else {
+        trash.to_path_buf()
+    };
+    let info = trash.join("info");
+    let dirs = if freedesktop {
+        &[&files, &info][..]
+    } else {
+        &[&files][..]
+    };
+    for dir in dirs {
+        fs::create_dir_all(dir).map_err(|e| with_path(e, "create dir", dir))?;
+    }
+    let name = path
+        .file_name()
+        .ok_or_else(|| with_path(io::ErrorKind::InvalidInput.into(), "move to trash", path))?;
+
+    for n in 1.. {
+        let mut candidate = name.to_os_string();
+        if n > 1 {
+            candidate.push(format!(".{n}"));
+        }
+        let destination = files.join(&candidate);
+        if freedesktop {
+            candidate.push(".trashinfo");
+            let info_file = info.join(&candidate);
+            let created = fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&info_file);
+            let mut file = match created {
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                other => other.map_err(|e| with_path(e, "create", &info_file))?,
+            };
+            let contents = format!(
+                "[Trash Info]\nPath={}\nDeletionDate={}\n",
+                encode_info_path(path),
+                deletion_date()
+            );
+            io::Write::write_all(&mut file, contents.as_bytes())
+                .and_then(|()| fs::rename(path, &destination))
+                .map_err(|e| {
+                    let _ = fs::remove_file(&info_file);
+                    with_path(e, "move to trash", path)
+                })?;
+        } else {
+            if fs::symlink_metadata(&destination).is_ok() {
+                continue;
+            }
+            fs::rename(path, &destination).map_err(|e| with_path(e, "move to trash", path))?;
+        }
+        return Ok(destination);
+    }
+    unreachable!()
+}
+
+/// The trash of user `uid` on the volume whose top directory is `top`: `.Trashes/$UID` on macOS
+/// and `.Trash-$UID` elsewhere.
+#[cfg(unix)]
+fn volume_trash(top: &Path, uid: u32, apple: bool) -> PathBuf {
+    if apple {
+        top.join(".Trashes").join(uid.to_string())
+    } else {
+        top.join(format!(".Trash-{uid}"))
+    }
+}
+
+/// The directory at the top of the filesystem `path` is on, where the per-volume trash goes.
+#[cfg(unix)]
+fn mount_top(path: &Path) -> io::Result<PathBuf> {
+    use std::os::unix::fs::MetadataExt;
+
+    let device = fs::symlink_metadata(path)?.dev();
+    let mut top = path;
+    for ancestor in path.ancestors().skip(1) {
+        if ancestor.as_os_str().is_empty() || fs::metadata(ancestor)?.dev() != device {
+            break;
+        }
+        top = ancestor;
+    }
+    Ok(top.to_path_buf())
+}
+
+#[cfg(unix)]
+pub(crate) fn move_to_trash(path: &Path) -> io::Result<()> {
+    // not `absolutize`, which resolves `..` lexically and so could trash something other than what
+    // `link/../name` names
+    let path = &std::path::absolute(path)?;
+    let home = std::env::var_os("HOME").map(PathBuf::from).ok_or_else(|| {
+        io::Error::new(io::ErrorKind::NotFound, "no home directory for the trash")
+    })?;
+    let apple = cfg!(target_vendor = "apple");
+    let home_trash = if apple {
+        home.join(".Trash")
+    } else {
+        std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+            .unwrap_or_else(|| home.join(".local/share"))
+            .join("Trash")
+    };
+    match move_into(path, &home_trash, !apple) {
+        Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {
+            // SAFETY: getuid can't fail.
+            let uid = unsafe { libc::getuid() };
+            let top = mount_top(path).map_err(|e| with_path(e, "find the mount point of", path))?;
+            move_into(path, &volume_trash(&top, uid, apple), !apple).map(drop)
+        }
+        other => other.map(drop),
+    }
+}
+
+#[cfg(windows)]
+pub(crate) fn move_to_trash(path: &Path) -> io::Result<()> {
+    use std::ffi::c_void;
+    use std::os::windows::ffi::OsStrExt;
+
+    /// `SHFILEOPSTRUCTW`
+    #[repr(C)]
+    #[allow(non_snake_case)]
+    struct ShFileOpStruct {
+        hwnd: *mut c_void,
+        wFunc: u32,
+        pFrom: *const u16,
+        pTo: *const u16,
+        fFlags: u16,
+        fAnyOperationsAborted: i32,
+        hNameMappings: *mut c_void,
+        lpszProgressTitle: *const u16,
+    }
+
+    #[link(name = "shell32")]
+    extern "system" {
+        fn SHFileOperationW(operation: *mut ShFileOpStruct) -> i32;
+    }
+
+    const FO_DELETE: u32 = 3;
+    const FOF_SILENT: u16 = 0x4;
+    const FOF_NOCONFIRMATION: u16 = 0x10;
+    const FOF_ALLOWUNDO: u16 = 0x40;
+    const FOF_NOERRORUI: u16 = 0x400;
+
+    let path = std::path::absolute(path)?;
+    fs::symlink_metadata(&path).map_err(|e| with_path(e, "read the metadata of", &path))?;
+    // a list of paths, each NUL terminated, ending with an empty one
+    let from: Vec<u16> = path.as_os_str().encode_wide().chain([0, 0]).collect();
+    let mut operation = ShFileOpStruct {
+        hwnd: std::ptr::null_mut(),
+        wFunc: FO_DELETE,
+        pFrom: from.as_ptr(),
+        pTo: std::ptr::null(),
+        fFlags: FOF_ALLOWUNDO | FOF_NOCONFIRMATION | FOF_SILENT | FOF_NOERRORUI,
+        fAnyOperationsAborted: 0,
+        hNameMappings: std::ptr::null_mut(),
+        lpszProgressTitle: std::ptr::null(),
+    };
+    // SAFETY: `from` is double NUL terminated and outlives the call, the rest may be null.
+    let result = unsafe { SHFileOperationW(&mut operation) };
+    if result != 0 || operation.fAnyOperationsAborted != 0 {
+        return Err(with_path(
+            io::Error::other(format!("SHFileOperationW failed with {result:#x}")),
+            "move to trash",
+            &path,
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(not(any(unix, windows)))]
+pub(crate) fn move_to_trash(path: &Path) -> io::Result<()> {
+    Err(with_path(
+        io::ErrorKind::Unsupported.into(),
+        "move to trash",
+        path,
+    ))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::{encode_info_path, move_into, volume_trash};
+
+    use std::ops::Not;
+    use std::path::Path;
+
+    #[test]
+    fn test_encode_info_path() {
+        let tests = &[
+            ("/home/me/file.txt", "/home/me/file.txt"),
+            ("/home/me/with space", "/home/me/with%20space"),
+            ("/tmp/ä%", "/tmp/%C3%A4%25"),
+        ];
+
+        for test in tests {
+            assert_eq!(encode_info_path(Path::new(test.0)), test.1);
+        }
+    }
+
+    #[test]
+    fn test_move_into() {
+        let dir = tempfile::tempdir().unwrap();
+        let trash = dir.path().join("Trash");
+        let victim = dir.path().join("victim.txt");
+
+        std::fs::write(&victim, "first").unwrap();
+        let first = move_into(&victim, &trash, true).unwrap();
+        assert_eq!(first, trash.join("files/victim.txt"));
+        let info = std::fs::read_to_string(trash.join("info/victim.txt.trashinfo")).unwrap();
+        assert!(info.starts_with("[Trash Info]\n"));
+        assert!(info.contains(&format!("Path={}\n", encode_info_path(&victim))));
+        assert!(info.contains("DeletionDate=20"));
+
+        std::fs::write(&victim, "second").unwrap();
+        let second = move_into(&victim, &trash, true).unwrap();
+        assert_eq!(second, trash.join("files/victim.txt.2"));
+        assert!(trash.join("info/victim.txt.2.trashinfo").is_file());
+        assert!(victim.exists().not());
+        assert_eq!(std::fs::read_to_string(second).unwrap(), "second");
+
+        assert!(move_into(&victim, &trash, true).is_err());
+        assert!(trash.join("info/victim.txt.3.trashinfo").exists().not());
+    }
+
+    #[test]
+    fn test_move_into_plain_trash() {
+        let dir = tempfile::tempdir().unwrap();
+        let trash = dir.path().join(".Trash");
+        let victim = dir.path().join("victim.txt");
+
+        std::fs::write(&victim, "first").unwrap();
+        assert_eq!(
+            move_into(&victim, &trash, false).unwrap(),
+            trash.join("victim.txt")
+        );
+        std::fs::write(&victim, "second").unwrap();
+        assert_eq!(
+            move_into(&victim, &trash, false).unwrap(),
+            trash.join("victim.txt.2")
+        );
+        assert!(trash.join("files").exists().not());
+        assert!(trash.join("info").exists().not());
+    }
+
+    #[test]
+    fn test_volume_trash() {
+        let top = Path::new("/Volumes/Backup");
+        assert_eq!(
+            volume_trash(top, 501, true),
+            Path::new("/Volumes/Backup/.Trashes/501")
+        );
+        assert_eq!(
+            volume_trash(top, 1000, false),
+            Path::new("/Volumes/Backup/.Trash-1000")
+        );
+    }
+}
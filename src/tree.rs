@@ -0,0 +1,412 @@
+//! Checking and summarizing the files of a directory tree, behind the `fs` feature.
+
+use std::collections::BTreeMap;
+use std::fs::{self, Metadata};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::fs::with_path;
+use crate::NameClassifier;
+
+/// A file a tree should hold, at `path` relative to the root, and what it should be like.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ExpectedFile {
+    pub path: PathBuf,
+    /// The size in bytes it should have, unchecked if `None`.
+    pub size: Option<u64>,
+    /// Its hash as the hasher given to [`verify_tree_with`] writes it, unchecked if `None`.
+    pub hash: Option<String>,
+}
+
+impl From<PathBuf> for ExpectedFile {
+    /// A file that only has to exist.
+    fn from(path: PathBuf) -> Self {
+        ExpectedFile {
+            path,
+            ..ExpectedFile::default()
+        }
+    }
+}
+
+/// How a file in the tree differs from what was expected of it.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Mismatch {
+    /// Something other than a file is there, like a directory.
+    NotAFile { path: PathBuf },
+    Size {
+        path: PathBuf,
+        expected: u64,
+        actual: u64,
+    },
+    Hash {
+        path: PathBuf,
+        expected: String,
+        actual: String,
+    },
+}
+
+/// What [`verify_tree`] found, every list sorted by path, relative to the root.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TreeReport {
+    /// Expected files that aren't there.
+    pub missing: Vec<PathBuf>,
+    /// Files in the tree that weren't expected.
+    pub extra: Vec<PathBuf>,
+    pub mismatched: Vec<Mismatch>,
+}
+
+impl TreeReport {
+    /// Whether the tree holds exactly the expected files, as they were expected.
+    pub fn is_ok(&self) -> bool {
+        self.missing.is_empty() && self.extra.is_empty() && self.mismatched.is_empty()
+    }
+}
+
+/// A number of files and their total size in bytes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FileTotals {
+    pub files: u64,
+    pub bytes: u64,
+}
+
+impl FileTotals {
+    fn add(&mut self, bytes: u64) {
+        self.files += 1;
+        self.bytes += bytes;
+    }
+}
+
+/// What [`summarize_tree`] counted.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TreeSummary {
+    pub total: FileTotals,
+    /// The files under every directory holding any, nested ones included, by path relative to the
+    /// root. The root itself is the empty path.
+    pub by_directory: BTreeMap<PathBuf, FileTotals>,
+    /// The files by lowercased extension, the empty string for files without one.
+    pub by_extension: BTreeMap<String, FileTotals>,
+}
+
+/// Counts the files under `root` and their sizes, in total, by directory and by extension, leaving
+/// out what `ignore` matches, for the summary line of a CLI tool. Ignored directories aren't
+/// descended into, and symlinks count as themselves rather than what they point at.
+///
+/// `ignore` is given each entry's path relative to `root`, so [`NameRule::Glob`](crate::NameRule::Glob)
+/// patterns like `/target` or `docs/**/*.tmp` are anchored at the root. `.gitignore` files aren't
+/// read: their patterns can be added as rules, but negation and per-directory files aren't
+/// supported.
+///
+/// ```rust
+/// use pathext::{summarize_tree, FileTotals, NameClassifier, NameRule};
+/// use std::path::Path;
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// let root = dir.path();
+/// std::fs::create_dir_all(root.join("src")).unwrap();
+/// std::fs::create_dir_all(root.join("target")).unwrap();
+/// std::fs::write(root.join("src/lib.rs"), "pub mod a;").unwrap();
+/// std::fs::write(root.join("src/a.rs"), "").unwrap();
+/// std::fs::write(root.join("target/big.rlib"), [0; 4096]).unwrap();
+///
+/// let ignore = NameClassifier::new().with(NameRule::Glob("/target".into()));
+/// let summary = summarize_tree(root, &ignore).unwrap();
+/// assert_eq!(summary.total, FileTotals { files: 2, bytes: 10 });
+/// assert_eq!(summary.by_directory[Path::new("src")].files, 2);
+/// assert_eq!(summary.by_extension["rs"].bytes, 10);
+/// ```
+pub fn summarize_tree<P: AsRef<Path>>(root: P, ignore: &NameClassifier) -> io::Result<TreeSummary> {
+    let files = walk_files_except(root.as_ref(), |path| ignore.matches(path))?;
+    let mut summary = TreeSummary::default();
+    for (path, metadata) in files {
+        let bytes = metadata.len();
+        summary.total.add(bytes);
+        for dir in path.ancestors().skip(1) {
+            summary
+                .by_directory
+                .entry(dir.to_path_buf())
+                .or_default()
+                .add(bytes);
+        }
+        let extension = path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        summary
+            .by_extension
+            .entry(extension)
+            .or_default()
+            .add(bytes);
+    }
+    Ok(summary)
+}
+
+/// Every entry under `root` that isn't a directory, relative to it and sorted, symlinks not
+/// followed.
+pub(crate) fn walk_files(root: &Path) -> io::Result<Vec<(PathBuf, Metadata)>> {
+    walk_files_except(root, |_| false)
+}
+
+/// [`walk_files`] leaving out entries `skip` is true for, given their relative paths, and not
+/// descending into skipped directories.
+pub(crate) fn walk_files_except<F>(root: &Path, skip: F) -> io::Result<Vec<(PathBuf, Metadata)>>
+where
+    F: Fn(&Path) -> bool,
+{
+    let mut files = Vec::new();
+    let mut pending = vec![PathBuf::new()];
+    while let Some(dir) = pending.pop() {
+        let full = root.join(&dir);
+        for entry in fs::read_dir(&full).map_err(|e| with_path(e, "read dir", &full))? {
+            let entry = entry.map_err(|e| with_path(e, "read dir", &full))?;
+            let relative = dir.join(entry.file_name());
+            if skip(&relative) {
+                continue;
+            }
+            let metadata = entry
+                .metadata()
+                .map_err(|e| with_path(e, "read the metadata of", &entry.path()))?;
+            if metadata.is_dir() {
+                pending.push(relative);
+            } else {
+                files.push((relative, metadata));
+            }
+        }
+    }
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(files)
+}
+
+/// [`verify_tree_with`] without a hasher, so expected hashes aren't checked.
+pub fn verify_tree<P, I>(root: P, manifest: I) -> io::Result<TreeReport>
+where
+    P: AsRef<Path>,
+    I: IntoIterator<Item = ExpectedFile>,
+{
+    verify_tree_with(root, manifest, None::<fn(&Path) -> io::Result<String>>)
+}
+
+/// Compares the files under `root` with `manifest`: which expected files are missing, which files
+/// are there without being expected, and which differ in kind, size or, given a `hasher` that
+/// reads a file's hash, in hash. Symlinks are compared as themselves rather than followed.
+///
+/// ```rust
+/// use pathext::{verify_tree, ExpectedFile, Mismatch};
+/// use std::path::PathBuf;
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// let root = dir.path();
+/// std::fs::create_dir_all(root.join("bin")).unwrap();
+/// std::fs::write(root.join("bin/tool"), "12345").unwrap();
+/// std::fs::write(root.join("stray.log"), "").unwrap();
+///
+/// let manifest = [
+///     ExpectedFile { path: "bin/tool".into(), size: Some(4), hash: None },
+///     PathBuf::from("share/README").into(),
+/// ];
+/// let report = verify_tree(root, manifest).unwrap();
+/// assert_eq!(report.missing, [PathBuf::from("share/README")]);
+/// assert_eq!(report.extra, [PathBuf::from("stray.log")]);
+/// assert_eq!(
+///     report.mismatched,
+///     [Mismatch::Size { path: "bin/tool".into(), expected: 4, actual: 5 }]
+/// );
+/// ```
+pub fn verify_tree_with<P, I, H>(root: P, manifest: I, hasher: Option<H>) -> io::Result<TreeReport>
+where
+    P: AsRef<Path>,
+    I: IntoIterator<Item = ExpectedFile>,
+    H: Fn(&Path) -> io::Result<String>,
+{
+    let root = root.as_ref();
+    let mut found: BTreeMap<PathBuf, Metadata> = walk_files(root)?.into_iter().collect();
+    let mut report = TreeReport::default();
+    for expected in manifest {
+        let full = root.join(&expected.path);
+        let metadata = match found.remove(&expected.path) {
+            Some(metadata) => metadata,
+            None => match fs::symlink_metadata(&full) {
+                Ok(metadata) if metadata.is_dir() => {
+                    let path = expected.path;
+                    report.mismatched.push(Mismatch::NotAFile { path });
+                    continue;
+                }
+                // listed twice, or spelled differently than the walk found it
+                Ok(metadata) => metadata,
+                Err(e) if crate::fs::is_missing(&e) => {
+                    report.missing.push(expected.path);
+                    continue;
+                }
+                Err(e) => return Err(with_path(e, "read the metadata of", &full)),
+            },
+        };
+        if let Some(size) = expected.size.filter(|&size| size != metadata.len()) {
+            report.mismatched.push(Mismatch::Size {
+                path: expected.path,
+                expected: size,
+                actual: metadata.len(),
+            });
+            continue;
+        }
+        if let (Some(hash), Some(hasher)) = (expected.hash, &hasher) {
+            let actual = hasher(&full).map_err(|e| with_path(e, "hash", &full))?;
+            if actual != hash {
+                report.mismatched.push(Mismatch::Hash {
+                    path: expected.path,
+                    expected: hash,
+                    actual,
+                });
+            }
+        }
+    }
+    report.extra = found.into_keys().collect();
+    report.missing.sort();
+    report
+        .mismatched
+        .sort_by(|a, b| mismatch_path(a).cmp(mismatch_path(b)));
+    Ok(report)
+}
+
+fn mismatch_path(mismatch: &Mismatch) -> &Path {
+    match mismatch {
+        Mismatch::NotAFile { path } | Mismatch::Size { path, .. } | Mismatch::Hash { path, .. } => {
+            path
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        summarize_tree, verify_tree, verify_tree_with, walk_files, ExpectedFile, FileTotals,
+        Mismatch,
+    };
+    use crate::{NameClassifier, NameRule};
+
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    fn expected(path: &str, size: Option<u64>, hash: Option<&str>) -> ExpectedFile {
+        ExpectedFile {
+            path: PathBuf::from(path),
+            size,
+            hash: hash.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_walk_files() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("a/b")).unwrap();
+        fs::create_dir(dir.path().join("empty")).unwrap();
+        fs::write(dir.path().join("a/b/c.txt"), "").unwrap();
+        fs::write(dir.path().join("z.txt"), "").unwrap();
+        fs::write(dir.path().join("a/x.txt"), "").unwrap();
+
+        let files: Vec<PathBuf> = walk_files(dir.path())
+            .unwrap()
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+        assert_eq!(files, ["a/b/c.txt", "a/x.txt", "z.txt"].map(PathBuf::from));
+    }
+
+    #[test]
+    fn test_verify_tree() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("lib/sub")).unwrap();
+        fs::write(dir.path().join("lib/a.so"), "aaaa").unwrap();
+        fs::write(dir.path().join("lib/b.so"), "bb").unwrap();
+        fs::write(dir.path().join("extra.tmp"), "").unwrap();
+        let hasher = |path: &Path| fs::read_to_string(path).map(|s| s.to_uppercase());
+
+        let manifest = [
+            expected("lib/a.so", Some(4), Some("AAAA")),
+            expected("lib/b.so", None, Some("XX")),
+            expected("lib/sub", None, None),
+            expected("missing.txt", Some(1), None),
+        ];
+        let report = verify_tree_with(dir.path(), manifest.clone(), Some(hasher)).unwrap();
+        assert_eq!(report.missing, [PathBuf::from("missing.txt")]);
+        assert_eq!(report.extra, [PathBuf::from("extra.tmp")]);
+        assert_eq!(
+            report.mismatched,
+            [
+                Mismatch::Hash {
+                    path: "lib/b.so".into(),
+                    expected: "XX".to_string(),
+                    actual: "BB".to_string(),
+                },
+                Mismatch::NotAFile {
+                    path: "lib/sub".into()
+                },
+            ]
+        );
+        assert!(!report.is_ok());
+
+        // without a hasher only the kind and the size count
+        let report = verify_tree(dir.path(), manifest).unwrap();
+        assert_eq!(
+            report.mismatched,
+            [Mismatch::NotAFile {
+                path: "lib/sub".into()
+            }]
+        );
+
+        let exact = ["lib/a.so", "lib/b.so", "extra.tmp"].map(|p| PathBuf::from(p).into());
+        assert!(verify_tree(dir.path(), exact).unwrap().is_ok());
+    }
+
+    #[test]
+    fn test_summarize_tree() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("a/b")).unwrap();
+        fs::create_dir_all(dir.path().join("node_modules/x")).unwrap();
+        fs::write(dir.path().join("README"), "1").unwrap();
+        fs::write(dir.path().join("a/one.TXT"), "22").unwrap();
+        fs::write(dir.path().join("a/b/two.txt"), "333").unwrap();
+        fs::write(dir.path().join("a/b/three.md"), "4444").unwrap();
+        fs::write(dir.path().join("a/b/skip.bak"), "55555").unwrap();
+        fs::write(dir.path().join("node_modules/x/y.js"), "666666").unwrap();
+
+        let ignore = NameClassifier::new()
+            .with(NameRule::Directory("node_modules".into()))
+            .with(NameRule::Extension("bak".into()));
+        let summary = summarize_tree(dir.path(), &ignore).unwrap();
+        let totals = |files, bytes| FileTotals { files, bytes };
+        assert_eq!(summary.total, totals(4, 10));
+
+        let tests = &[
+            ("", totals(4, 10)),
+            ("a", totals(3, 9)),
+            ("a/b", totals(2, 7)),
+        ];
+        for test in tests {
+            assert_eq!(
+                summary.by_directory[Path::new(test.0)],
+                test.1,
+                "{}",
+                test.0
+            );
+        }
+        assert_eq!(summary.by_directory.len(), 3);
+
+        let tests = &[
+            ("", totals(1, 1)),
+            ("txt", totals(2, 5)),
+            ("md", totals(1, 4)),
+        ];
+        for test in tests {
+            assert_eq!(summary.by_extension[test.0], test.1, "{}", test.0);
+        }
+        assert_eq!(summary.by_extension.len(), 3);
+
+        let everything = summarize_tree(dir.path(), &NameClassifier::new()).unwrap();
+        assert_eq!(everything.total, totals(6, 21));
+
+        let globs = NameClassifier::new()
+            .with(NameRule::Glob("/node_modules".into()))
+            .with(NameRule::Glob("a/**/*.md".into()));
+        let summary = summarize_tree(dir.path(), &globs).unwrap();
+        assert_eq!(summary.total, totals(4, 11));
+    }
+}
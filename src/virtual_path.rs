@@ -0,0 +1,258 @@
+//! Separator-generic path operations for paths that aren't OS paths: archive entry names,
+//! object store keys, the path part of URLs.
+
+use std::fmt;
+
+/// A borrowed path string split on a separator of your choosing instead of the host's.
+///
+/// ```rust
+/// use pathext::VirtualPath;
+///
+/// let key = VirtualPath::new("backups/2024/db.tar.gz", '/');
+/// assert!(key.has_component("2024"));
+/// assert_eq!(key.file_name(), Some("db.tar.gz"));
+/// assert_eq!(key.extension(), Some("gz"));
+/// assert_eq!(key.strip_extensions(), "backups/2024/db");
+/// assert_eq!(key.parent().map(|p| p.as_str()), Some("backups/2024"));
+///
+/// let ns = VirtualPath::new("crate::module::Item", ':');
+/// assert_eq!(ns.components().collect::<Vec<_>>(), ["crate", "module", "Item"]);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct VirtualPath<'a> {
+    path: &'a str,
+    separator: char,
+}
+
+impl<'a> VirtualPath<'a> {
+    pub fn new(path: &'a str, separator: char) -> Self {
+        VirtualPath { path, separator }
+    }
+
+    /// A `/`-separated path, the common case for keys and URLs.
+    pub fn slash(path: &'a str) -> Self {
+        VirtualPath::new(path, '/')
+    }
+
+    pub fn as_str(&self) -> &'a str {
+        self.path
+    }
+
+    pub fn separator(&self) -> char {
+        self.separator
+    }
+
+    /// The non-empty segments between separators, so repeated and trailing separators are ignored.
+    pub fn components(&self) -> impl DoubleEndedIterator<Item = &'a str> + 'a {
+        self.path.split(self.separator).filter(|c| !c.is_empty())
+    }
+
+    /// Whether the path begins with a separator.
+    pub fn is_absolute(&self) -> bool {
+        self.path.starts_with(self.separator)
+    }
+
+    /// The last component, if any.
+    pub fn file_name(&self) -> Option<&'a str> {
+        self.components().next_back()
+    }
+
+    /// The path without its last component and any separators that ended it.
+    pub fn parent(&self) -> Option<VirtualPath<'a>> {
+        let trimmed = self.path.trim_end_matches(self.separator);
+        let end = trimmed.rfind(self.separator)?;
+        let parent = trimmed[..end].trim_end_matches(self.separator);
+        if parent.is_empty() && self.is_absolute() {
+            let root = &self.path[..self.separator.len_utf8()];
+            return Some(VirtualPath::new(root, self.separator));
+        }
+        Some(VirtualPath::new(parent, self.separator))
+    }
+
+    /// The file name up to its last `.`, with a leading `.` not counting as an extension.
+    pub fn file_stem(&self) -> Option<&'a str> {
+        let name = self.file_name()?;
+        match name.rfind('.') {
+            Some(0) | None => Some(name),
+            Some(i) => Some(&name[..i]),
+        }
+    }
+
+    /// The file name after its last `.`, with a leading `.` not counting as an extension.
+    pub fn extension(&self) -> Option<&'a str> {
+        let name = self.file_name()?;
+        match name.rfind('.') {
+            Some(0) | None => None,
+            Some(i) => Some(&name[i + 1..]),
+        }
+    }
+
+    /// Checks if the supplied component is present in total in the path.
+    pub fn has_component<S: AsRef<str>>(&self, component: S) -> bool {
+        self.components().any(|c| c == component.as_ref())
+    }
+
+    /// Checks if the pattern is anywhere in the path string.
+    pub fn contains<S: AsRef<str>>(&self, pattern: S) -> bool {
+        self.path.contains(pattern.as_ref())
+    }
+
+    /// Checks if the pattern begins or ends the path string.
+    pub fn starts_or_ends_with<S: AsRef<str>>(&self, pattern: S) -> bool {
+        self.path.starts_with(pattern.as_ref()) || self.path.ends_with(pattern.as_ref())
+    }
+
+    /// Checks if the path string ends with the pattern, like [`PathStrExt::ends_with_extensions`](crate::PathStrExt::ends_with_extensions).
+    pub fn ends_with_extensions<S: AsRef<str>>(&self, pattern: S) -> bool {
+        self.path.ends_with(pattern.as_ref())
+    }
+
+    /// The path with every extension of the final component removed.
+    /// Only the final component is looked at, so dots in directory names are left alone.
+    pub fn strip_extensions(&self) -> &'a str {
+        let trimmed = self.path.trim_end_matches(self.separator);
+        let name_start = trimmed
+            .rfind(self.separator)
+            .map_or(0, |i| i + self.separator.len_utf8());
+        match trimmed[name_start..].find('.') {
+            Some(i) => &trimmed[..name_start + i],
+            None => trimmed,
+        }
+    }
+
+    /// Removes `prefix` if the path begins with all of its components.
+    pub fn strip_prefix(&self, prefix: &str) -> Option<VirtualPath<'a>> {
+        let mut rest = self.path;
+        for expected in prefix.split(self.separator).filter(|c| !c.is_empty()) {
+            rest = rest.trim_start_matches(self.separator);
+            let end = rest.find(self.separator).unwrap_or(rest.len());
+            if &rest[..end] != expected {
+                return None;
+            }
+            rest = &rest[end..];
+        }
+        Some(VirtualPath::new(
+            rest.trim_start_matches(self.separator),
+            self.separator,
+        ))
+    }
+
+    /// Appends `other` with exactly one separator between them, `other` replacing the path if absolute.
+    pub fn join(&self, other: &str) -> String {
+        if other.starts_with(self.separator) || self.path.is_empty() {
+            return other.to_string();
+        }
+        let mut joined = self.path.trim_end_matches(self.separator).to_string();
+        if !joined.is_empty() || self.is_absolute() {
+            joined.push(self.separator);
+        }
+        joined.push_str(other);
+        joined
+    }
+}
+
+impl fmt::Display for VirtualPath<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VirtualPath;
+
+    #[test]
+    fn test_components() {
+        let tests = &[
+            ("a/b/c", '/', vec!["a", "b", "c"]),
+            ("/a//b/", '/', vec!["a", "b"]),
+            ("a\\b", '/', vec!["a\\b"]),
+            ("a\\b", '\\', vec!["a", "b"]),
+            ("", '/', vec![]),
+        ];
+
+        for test in tests {
+            let components: Vec<_> = VirtualPath::new(test.0, test.1).components().collect();
+            assert_eq!(components, test.2);
+        }
+    }
+
+    #[test]
+    fn test_parent_and_file_name() {
+        let tests = &[
+            ("a/b/c.txt", Some("a/b"), Some("c.txt")),
+            ("a/b/", Some("a"), Some("b")),
+            ("/a", Some("/"), Some("a")),
+            ("a", None, Some("a")),
+            ("", None, None),
+        ];
+
+        for test in tests {
+            let p = VirtualPath::slash(test.0);
+            assert_eq!(p.parent().map(|p| p.as_str()), test.1, "{}", test.0);
+            assert_eq!(p.file_name(), test.2);
+        }
+    }
+
+    #[test]
+    fn test_extensions() {
+        let tests = &[
+            (
+                "dir.d/archive.tar.gz",
+                Some("archive.tar"),
+                Some("gz"),
+                "dir.d/archive",
+            ),
+            ("dir.d/.bashrc", Some(".bashrc"), None, "dir.d/"),
+            ("README", Some("README"), None, "README"),
+            ("a/b.txt/", Some("b"), Some("txt"), "a/b"),
+        ];
+
+        for test in tests {
+            let p = VirtualPath::slash(test.0);
+            assert_eq!(p.file_stem(), test.1);
+            assert_eq!(p.extension(), test.2);
+            assert_eq!(p.strip_extensions(), test.3);
+        }
+    }
+
+    #[test]
+    fn test_matching() {
+        let p = VirtualPath::slash("bucket/logs/2024/app.log.gz");
+        assert!(p.has_component("logs"));
+        assert!(!p.has_component("log"));
+        assert!(p.contains("logs/20"));
+        assert!(p.starts_or_ends_with("bucket/"));
+        assert!(p.ends_with_extensions(".log.gz"));
+    }
+
+    #[test]
+    fn test_strip_prefix() {
+        let tests = &[
+            ("a/b/c", "a/b", Some("c")),
+            ("/a/b/c", "a/", Some("b/c")),
+            ("a/bc", "a/b", None),
+            ("a/b", "a/b", Some("")),
+        ];
+
+        for test in tests {
+            let stripped = VirtualPath::slash(test.0).strip_prefix(test.1);
+            assert_eq!(stripped.map(|p| p.as_str()), test.2);
+        }
+    }
+
+    #[test]
+    fn test_join() {
+        let tests = &[
+            ("a/b", "c", "a/b/c"),
+            ("a/b/", "c", "a/b/c"),
+            ("a", "/c", "/c"),
+            ("", "c", "c"),
+            ("/", "c", "/c"),
+        ];
+
+        for test in tests {
+            assert_eq!(VirtualPath::slash(test.0).join(test.1), test.2);
+        }
+    }
+}
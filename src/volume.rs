@@ -0,0 +1,185 @@
+//! Docker-style `host:container:options` volume specs.
+
+use std::path::{Path, PathBuf};
+
+/// Where a volume's contents come from, see [`VolumeSpec`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VolumeSource {
+    /// A host path bind-mounted into the container.
+    Path(PathBuf),
+    /// A named volume managed by the container engine.
+    Named(String),
+}
+
+/// A volume as given to `docker run -v`, see [`parse_volume_spec`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VolumeSpec {
+    /// `None` for an anonymous volume, given as just the container path.
+    pub source: Option<VolumeSource>,
+    pub target: PathBuf,
+    /// The comma separated options after the last `:`, such as `ro` or `z`.
+    pub options: Vec<String>,
+}
+
+impl VolumeSpec {
+    /// The host path, if this is a bind mount.
+    pub fn host_path(&self) -> Option<&Path> {
+        match &self.source {
+            Some(VolumeSource::Path(path)) => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Whether it was mounted with the `ro` option.
+    pub fn is_read_only(&self) -> bool {
+        self.options.iter().any(|option| option == "ro")
+    }
+}
+
+/// A drive letter is a single ASCII letter followed by `:` and a separator (or nothing).
+fn is_drive_colon(field: &str, after: &str) -> bool {
+    field.len() == 1
+        && field.as_bytes()[0].is_ascii_alphabetic()
+        && (after.is_empty() || after.starts_with(['/', '\\']))
+}
+
+/// Splits on `:`, except after a drive letter, so `C:\x:/data` is two fields.
+fn split_fields(spec: &str) -> Vec<&str> {
+    let mut fields = Vec::new();
+    let mut start = 0;
+    for (i, _) in spec.match_indices(':') {
+        if !is_drive_colon(&spec[start..i], &spec[i + 1..]) {
+            fields.push(&spec[start..i]);
+            start = i + 1;
+        }
+    }
+    fields.push(&spec[start..]);
+    fields
+}
+
+fn is_absolute_anywhere(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    path.starts_with(['/', '\\'])
+        || (bytes.len() >= 3
+            && bytes[0].is_ascii_alphabetic()
+            && bytes[1] == b':'
+            && matches!(bytes[2], b'/' | b'\\'))
+}
+
+fn parse_source(source: &str) -> Option<VolumeSource> {
+    if is_absolute_anywhere(source) || source.starts_with(['.', '~']) {
+        return Some(VolumeSource::Path(PathBuf::from(source)));
+    }
+    let mut chars = source.chars();
+    let valid_name = chars.next().is_some_and(|c| c.is_ascii_alphanumeric())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'));
+    valid_name.then(|| VolumeSource::Named(source.to_string()))
+}
+
+/// Parses a `docker run -v` style volume spec.
+///
+/// `C:\x:/data` is split after the drive letter's colon rather than at it, including in the
+/// container path, so Windows hosts and Windows containers both work. A source starting with a
+/// separator, drive, `.` or `~` is a path, anything else has to be a valid volume name. The
+/// container path must be absolute on some platform, whatever the host. Returns `None` for
+/// specs that don't have that shape, including empty fields.
+///
+/// ```rust
+/// use pathext::{parse_volume_spec, VolumeSource};
+/// use std::path::Path;
+///
+/// let spec = parse_volume_spec(r"C:\src:/app:ro").unwrap();
+/// assert_eq!(spec.host_path(), Some(Path::new(r"C:\src")));
+/// assert_eq!(spec.target, Path::new("/app"));
+/// assert!(spec.is_read_only());
+///
+/// let spec = parse_volume_spec("cache:/root/.cache").unwrap();
+/// assert_eq!(spec.source, Some(VolumeSource::Named("cache".into())));
+/// ```
+pub fn parse_volume_spec(spec: &str) -> Option<VolumeSpec> {
+    let fields = split_fields(spec);
+    if fields.iter().any(|field| field.is_empty()) {
+        return None;
+    }
+    let (source, target, options) = match fields[..] {
+        [target] => (None, target, None),
+        [source, target] => (Some(source), target, None),
+        [source, target, options] => (Some(source), target, Some(options)),
+        _ => return None,
+    };
+
+    if !is_absolute_anywhere(target) {
+        return None;
+    }
+    let source = match source {
+        Some(source) => Some(parse_source(source)?),
+        None => None,
+    };
+    let options = options
+        .map(|options| options.split(',').map(String::from).collect())
+        .unwrap_or_default();
+    Some(VolumeSpec {
+        source,
+        target: PathBuf::from(target),
+        options,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_volume_spec, VolumeSource, VolumeSpec};
+
+    use std::path::PathBuf;
+
+    fn spec(source: Option<VolumeSource>, target: &str, options: &[&str]) -> Option<VolumeSpec> {
+        Some(VolumeSpec {
+            source,
+            target: PathBuf::from(target),
+            options: options.iter().map(|o| o.to_string()).collect(),
+        })
+    }
+
+    fn host(path: &str) -> Option<VolumeSource> {
+        Some(VolumeSource::Path(PathBuf::from(path)))
+    }
+
+    fn named(name: &str) -> Option<VolumeSource> {
+        Some(VolumeSource::Named(name.to_string()))
+    }
+
+    #[test]
+    fn test_parse_volume_spec() {
+        let tests = &[
+            ("/host:/container", spec(host("/host"), "/container", &[])),
+            (
+                "/host:/container:ro",
+                spec(host("/host"), "/container", &["ro"]),
+            ),
+            ("./src:/app:ro,z", spec(host("./src"), "/app", &["ro", "z"])),
+            ("~/data:/data", spec(host("~/data"), "/data", &[])),
+            (r"C:\x:/data", spec(host(r"C:\x"), "/data", &[])),
+            ("c:/x:/data:rw", spec(host("c:/x"), "/data", &["rw"])),
+            (r"C:\x:C:\data", spec(host(r"C:\x"), r"C:\data", &[])),
+            (
+                r"\\server\share:/mnt",
+                spec(host(r"\\server\share"), "/mnt", &[]),
+            ),
+            (
+                "cache:/root/.cache",
+                spec(named("cache"), "/root/.cache", &[]),
+            ),
+            ("/anonymous", spec(None, "/anonymous", &[])),
+            ("C:/anonymous", spec(None, "C:/anonymous", &[])),
+            ("/host:relative", None),
+            ("bad name:/data", None),
+            ("/a:/b:ro:extra", None),
+            ("/a::ro", None),
+            ("", None),
+            ("C:", None),
+        ];
+
+        for test in tests {
+            assert_eq!(parse_volume_spec(test.0), test.1, "{}", test.0);
+        }
+    }
+}
@@ -0,0 +1,110 @@
+//! Filesystem watching, filtered through glob patterns before events reach the callback.
+
+use std::path::Path;
+
+use globset::GlobSet;
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+
+pub(crate) fn watch<F>(path: &Path, callback: F) -> notify::Result<RecommendedWatcher>
+where
+    F: FnMut(notify::Result<Event>) + Send + 'static,
+{
+    let mut watcher = notify::recommended_watcher(callback)?;
+    watcher.watch(path, RecursiveMode::Recursive)?;
+    Ok(watcher)
+}
+
+pub(crate) fn watch_matching<F>(
+    path: &Path,
+    globs: GlobSet,
+    mut callback: F,
+) -> notify::Result<RecommendedWatcher>
+where
+    F: FnMut(notify::Result<Event>) + Send + 'static,
+{
+    let root = path.to_path_buf();
+    let canonical_root = path.canonicalize().ok();
+    watch(path, move |res: notify::Result<Event>| match res {
+        Ok(event) => {
+            if let Some(event) = filter_event(&root, canonical_root.as_deref(), &globs, event) {
+                callback(Ok(event))
+            }
+        }
+        Err(e) => callback(Err(e)),
+    })
+}
+
+/// Keeps only the event paths matching `globs`, relative to the watched root when possible.
+/// Events that never carried a path (rescan notices and the like) are always passed through.
+fn filter_event(
+    root: &Path,
+    canonical_root: Option<&Path>,
+    globs: &GlobSet,
+    mut event: Event,
+) -> Option<Event> {
+    if event.paths.is_empty() {
+        return Some(event);
+    }
+
+    event.paths.retain(|p| {
+        let relative = p
+            .strip_prefix(root)
+            .ok()
+            .or_else(|| canonical_root.and_then(|r| p.strip_prefix(r).ok()));
+        relative.is_some_and(|r| globs.is_match(r)) || globs.is_match(p)
+    });
+
+    if event.paths.is_empty() {
+        debug!(kind = ?event.kind, "watch event filtered out");
+        None
+    } else {
+        Some(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::filter_event;
+
+    use globset::{Glob, GlobSetBuilder};
+    use notify::event::{CreateKind, EventKind};
+    use notify::Event;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn test_filter_event() {
+        let mut builder = GlobSetBuilder::new();
+        builder.add(Glob::new("*.rs").unwrap());
+        builder.add(Glob::new("docs/**").unwrap());
+        let globs = builder.build().unwrap();
+        let root = Path::new("/project");
+
+        let tests = &[
+            (vec!["/project/main.rs"], vec!["/project/main.rs"]),
+            (vec!["/project/docs/a/b.md"], vec!["/project/docs/a/b.md"]),
+            (
+                vec!["/project/main.rs", "/project/README.md"],
+                vec!["/project/main.rs"],
+            ),
+            (vec!["/project/README.md"], vec![]),
+            (vec!["/elsewhere/docs/x.md"], vec![]),
+        ];
+
+        for test in tests {
+            let event = test.0.iter().fold(
+                Event::new(EventKind::Create(CreateKind::File)),
+                |event, p| event.add_path(PathBuf::from(p)),
+            );
+            let filtered = filter_event(root, None, &globs, event).map(|e| e.paths);
+            let expected: Vec<PathBuf> = test.1.iter().map(PathBuf::from).collect();
+            if expected.is_empty() {
+                assert_eq!(filtered, None);
+            } else {
+                assert_eq!(filtered, Some(expected));
+            }
+        }
+
+        let rescan = Event::new(EventKind::Other);
+        assert!(filter_event(root, None, &globs, rescan).is_some());
+    }
+}
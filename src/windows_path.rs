@@ -0,0 +1,332 @@
+//! Windows path grammar, parsed the same on every host.
+
+use std::fmt;
+use std::path::Path;
+
+/// The prefix of a Windows path, mirroring [`std::path::Prefix`] but owned and available everywhere.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WindowsPrefix {
+    /// `\\?\name`
+    Verbatim(String),
+    /// `\\?\UNC\server\share`
+    VerbatimUnc { server: String, share: String },
+    /// `\\?\C:`
+    VerbatimDisk(char),
+    /// `\\.\name`, a device namespace path such as `\\.\COM42`.
+    DeviceNs(String),
+    /// `\\server\share`
+    Unc { server: String, share: String },
+    /// `C:`
+    Disk(char),
+}
+
+impl WindowsPrefix {
+    /// Whether this is one of the `\\?\` prefixes, after which `/` and `.` are not special.
+    pub fn is_verbatim(&self) -> bool {
+        matches!(
+            self,
+            WindowsPrefix::Verbatim(_)
+                | WindowsPrefix::VerbatimUnc { .. }
+                | WindowsPrefix::VerbatimDisk(_)
+        )
+    }
+}
+
+impl fmt::Display for WindowsPrefix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WindowsPrefix::Verbatim(name) => write!(f, r"\\?\{name}"),
+            WindowsPrefix::VerbatimUnc { server, share } => write!(f, r"\\?\UNC\{server}\{share}"),
+            WindowsPrefix::VerbatimDisk(drive) => write!(f, r"\\?\{drive}:"),
+            WindowsPrefix::DeviceNs(name) => write!(f, r"\\.\{name}"),
+            WindowsPrefix::Unc { server, share } => write!(f, r"\\{server}\{share}"),
+            WindowsPrefix::Disk(drive) => write!(f, "{drive}:"),
+        }
+    }
+}
+
+/// A path split up by the rules Windows uses, whatever the host.
+///
+/// ```rust
+/// use pathext::{ParsedWindowsPath, WindowsPrefix};
+///
+/// let parsed = ParsedWindowsPath::parse(r"\\server\share\dir/file.txt");
+/// assert_eq!(parsed.server(), Some("server"));
+/// assert_eq!(parsed.share(), Some("share"));
+/// assert!(parsed.has_root);
+/// assert_eq!(parsed.components, ["dir", "file.txt"]);
+///
+/// let parsed = ParsedWindowsPath::parse("C:relative");
+/// assert_eq!(parsed.prefix, Some(WindowsPrefix::Disk('C')));
+/// assert!(!parsed.is_absolute());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParsedWindowsPath {
+    pub prefix: Option<WindowsPrefix>,
+    /// Whether the path has a root, which every prefix but a bare drive implies.
+    pub has_root: bool,
+    /// The normal components with `..` kept, and `.` only where std keeps it: in verbatim paths
+    /// and at the start of a relative one.
+    pub components: Vec<String>,
+}
+
+fn is_separator(c: char, verbatim: bool) -> bool {
+    c == '\\' || (!verbatim && c == '/')
+}
+
+/// The text up to the next separator and what follows that separator.
+fn next_component(s: &str, verbatim: bool) -> (&str, &str) {
+    match s.find(|c| is_separator(c, verbatim)) {
+        Some(i) => (&s[..i], &s[i + 1..]),
+        None => (s, ""),
+    }
+}
+
+fn drive(s: &str) -> Option<char> {
+    let mut chars = s.chars();
+    let letter = chars.next().filter(char::is_ascii_alphabetic)?;
+    (chars.next() == Some(':')).then_some(letter)
+}
+
+/// The prefix and the text after it, by the same rules as std's Windows `parse_prefix`: only
+/// `\\?\` is verbatim, but `/` works as well as `\` in the other prefixes.
+fn parse_prefix(s: &str) -> (Option<WindowsPrefix>, &str) {
+    if let Some(rest) = s.strip_prefix(r"\\?\") {
+        if let Some(rest) = rest.strip_prefix(r"UNC\") {
+            let (server, rest) = next_component(rest, true);
+            let (share, rest) = next_component(rest, true);
+            let prefix = WindowsPrefix::VerbatimUnc {
+                server: server.to_string(),
+                share: share.to_string(),
+            };
+            return (Some(prefix), rest);
+        }
+        let (name, after) = next_component(rest, true);
+        let prefix = match drive(name).filter(|_| name.len() == 2) {
+            Some(letter) => WindowsPrefix::VerbatimDisk(letter),
+            None => WindowsPrefix::Verbatim(name.to_string()),
+        };
+        return (Some(prefix), after);
+    }
+    let mut chars = s.chars();
+    if chars.next().is_some_and(|c| is_separator(c, false))
+        && chars.next().is_some_and(|c| is_separator(c, false))
+    {
+        let rest = &s[2..];
+        let (first, after_first) = next_component(rest, false);
+        if first == "." && after_first.len() < rest.len() - 1 {
+            let (name, rest) = next_component(after_first, false);
+            return (Some(WindowsPrefix::DeviceNs(name.to_string())), rest);
+        }
+        let (share, rest) = next_component(after_first, false);
+        if !first.is_empty() && !share.is_empty() {
+            let prefix = WindowsPrefix::Unc {
+                server: first.to_string(),
+                share: share.to_string(),
+            };
+            return (Some(prefix), rest);
+        }
+    } else if let Some(letter) = drive(s) {
+        return (Some(WindowsPrefix::Disk(letter)), &s[2..]);
+    }
+    (None, s)
+}
+
+impl ParsedWindowsPath {
+    /// Parses the path, lossily converted to a string, as Windows would.
+    pub fn parse<P: AsRef<Path>>(path: P) -> Self {
+        let s = path.as_ref().to_string_lossy();
+        let (prefix, mut rest) = parse_prefix(&s);
+        let verbatim = prefix.as_ref().is_some_and(WindowsPrefix::is_verbatim);
+        // a bare drive is the only prefix that needs a separator for a root, the rest have already
+        // consumed the one after them
+        let has_root = match &prefix {
+            Some(WindowsPrefix::Disk(_)) | None => {
+                let rooted = rest.starts_with(|c| is_separator(c, verbatim));
+                if rooted {
+                    rest = &rest[1..];
+                }
+                rooted
+            }
+            Some(_) => true,
+        };
+        let mut components = Vec::new();
+        for component in rest.split(|c| is_separator(c, verbatim)) {
+            let leading_cur_dir = prefix.is_none() && !has_root && components.is_empty();
+            match component {
+                "" => {}
+                "." if !verbatim && !leading_cur_dir => {}
+                _ => components.push(component.to_string()),
+            }
+        }
+        ParsedWindowsPath {
+            prefix,
+            has_root,
+            components,
+        }
+    }
+
+    /// The drive letter of a `C:` or `\\?\C:` prefix, as written.
+    pub fn drive(&self) -> Option<char> {
+        match self.prefix {
+            Some(WindowsPrefix::Disk(drive) | WindowsPrefix::VerbatimDisk(drive)) => Some(drive),
+            _ => None,
+        }
+    }
+
+    /// The server of a UNC prefix.
+    pub fn server(&self) -> Option<&str> {
+        match &self.prefix {
+            Some(WindowsPrefix::Unc { server, .. } | WindowsPrefix::VerbatimUnc { server, .. }) => {
+                Some(server)
+            }
+            _ => None,
+        }
+    }
+
+    /// The share of a UNC prefix.
+    pub fn share(&self) -> Option<&str> {
+        match &self.prefix {
+            Some(WindowsPrefix::Unc { share, .. } | WindowsPrefix::VerbatimUnc { share, .. }) => {
+                Some(share)
+            }
+            _ => None,
+        }
+    }
+
+    /// Absolute as Windows means it: with both a prefix and a root, so `\dir` and `C:dir` aren't.
+    pub fn is_absolute(&self) -> bool {
+        self.prefix.is_some() && self.has_root
+    }
+}
+
+/// The path written back with `\` separators.
+impl fmt::Display for ParsedWindowsPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(prefix) = &self.prefix {
+            write!(f, "{prefix}")?;
+        }
+        let implied_root = !matches!(self.prefix, None | Some(WindowsPrefix::Disk(_)));
+        if self.has_root && !(implied_root && self.components.is_empty()) {
+            f.write_str("\\")?;
+        }
+        f.write_str(&self.components.join("\\"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ParsedWindowsPath, WindowsPrefix};
+
+    fn unc(server: &str, share: &str) -> WindowsPrefix {
+        WindowsPrefix::Unc {
+            server: server.to_string(),
+            share: share.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let verbatim_unc = WindowsPrefix::VerbatimUnc {
+            server: "server".to_string(),
+            share: "share".to_string(),
+        };
+        let tests: &[(&str, Option<WindowsPrefix>, bool, &[&str])] = &[
+            (
+                r"C:\Users\me",
+                Some(WindowsPrefix::Disk('C')),
+                true,
+                &["Users", "me"],
+            ),
+            (
+                "c:/Users/./me/",
+                Some(WindowsPrefix::Disk('c')),
+                true,
+                &["Users", "me"],
+            ),
+            (
+                "C:relative",
+                Some(WindowsPrefix::Disk('C')),
+                false,
+                &["relative"],
+            ),
+            (r"\rooted\..\x", None, true, &["rooted", "..", "x"]),
+            (r".\here", None, false, &[".", "here"]),
+            (
+                r"\\server\share\dir",
+                Some(unc("server", "share")),
+                true,
+                &["dir"],
+            ),
+            ("//server/share", Some(unc("server", "share")), true, &[]),
+            (r"\\server", None, true, &["server"]),
+            (
+                r"\\?\C:\dir/with/slash\.",
+                Some(WindowsPrefix::VerbatimDisk('C')),
+                true,
+                &["dir/with/slash", "."],
+            ),
+            (
+                r"\\?\pictures\x",
+                Some(WindowsPrefix::Verbatim("pictures".to_string())),
+                true,
+                &["x"],
+            ),
+            (r"\\?\UNC\server\share\x", Some(verbatim_unc), true, &["x"]),
+            (
+                r"\\.\COM42",
+                Some(WindowsPrefix::DeviceNs("COM42".to_string())),
+                true,
+                &[],
+            ),
+            (
+                "//./pipe/name",
+                Some(WindowsPrefix::DeviceNs("pipe".to_string())),
+                true,
+                &["name"],
+            ),
+            ("", None, false, &[]),
+        ];
+
+        for test in tests {
+            let parsed = ParsedWindowsPath::parse(test.0);
+            assert_eq!(parsed.prefix, test.1, "{}", test.0);
+            assert_eq!(parsed.has_root, test.2, "{}", test.0);
+            assert_eq!(parsed.components, test.3, "{}", test.0);
+        }
+    }
+
+    #[test]
+    fn test_accessors_and_display() {
+        let tests = &[
+            (r"C:/dir\file", Some('C'), None, None, true, r"C:\dir\file"),
+            (
+                r"\\?\UNC\srv\data\x",
+                None,
+                Some("srv"),
+                Some("data"),
+                true,
+                r"\\?\UNC\srv\data\x",
+            ),
+            (
+                r"\\srv\data",
+                None,
+                Some("srv"),
+                Some("data"),
+                true,
+                r"\\srv\data",
+            ),
+            (r"\dir", None, None, None, false, r"\dir"),
+            ("C:dir", Some('C'), None, None, false, "C:dir"),
+        ];
+
+        for test in tests {
+            let parsed = ParsedWindowsPath::parse(test.0);
+            assert_eq!(parsed.drive(), test.1);
+            assert_eq!(parsed.server(), test.2);
+            assert_eq!(parsed.share(), test.3);
+            assert_eq!(parsed.is_absolute(), test.4);
+            assert_eq!(parsed.to_string(), test.5);
+        }
+    }
+}